@@ -0,0 +1,75 @@
+//! Measures the overhead of the conversion boundary.
+//!
+//! Run with `cargo bench --features bench`. Criterion keeps the results of the last run in
+//! `target/criterion` and reports the change against them, so regressions in the bridge show
+//! up as statistically significant slowdowns. Use `--save-baseline` and `--baseline` to
+//! compare against a named run, such as one taken on the main branch.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use tokio::runtime::Runtime;
+use warpdrive::{WarpService, bench};
+
+const BODY_SIZES: [usize; 3] = [0, 1024, 64 * 1024];
+
+fn runtime() -> Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to build runtime")
+}
+
+fn conversion(c: &mut Criterion) {
+    let runtime = runtime();
+    let mut group = c.benchmark_group("convert");
+
+    for body_size in BODY_SIZES {
+        group.throughput(Throughput::Bytes(body_size as u64));
+        group.bench_with_input(
+            BenchmarkId::new("request", body_size),
+            &body_size,
+            |b, &size| {
+                b.to_async(&runtime)
+                    .iter(|| bench::convert_request(bench::request(size)))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("response", body_size),
+            &body_size,
+            |b, &size| b.iter(|| bench::convert_response(bench::response(size))),
+        );
+    }
+
+    group.finish();
+}
+
+fn round_trip(c: &mut Criterion) {
+    let runtime = runtime();
+    let filter = bench::echo_filter();
+    let service = WarpService::new(bench::echo_filter());
+    let mut group = c.benchmark_group("round_trip");
+
+    for body_size in BODY_SIZES {
+        group.throughput(Throughput::Bytes(body_size as u64));
+        group.bench_with_input(
+            BenchmarkId::new("warp_direct", body_size),
+            &body_size,
+            |b, &size| {
+                b.to_async(&runtime)
+                    .iter(|| bench::call_warp(&filter, bench::warp_request(size)))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("warp_service", body_size),
+            &body_size,
+            |b, &size| {
+                b.to_async(&runtime)
+                    .iter(|| bench::call_service(&service, bench::request(size)))
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, conversion, round_trip);
+criterion_main!(benches);
@@ -2,7 +2,7 @@
 //!
 //! To run this example:
 //! ```bash
-//! cargo run --example cors
+//! cargo run --example cors --features cors
 //! ```
 //!
 //! ```bash
@@ -36,14 +36,13 @@ use std::{convert::Infallible, net::SocketAddr};
 use axum::{
     Router,
     extract::Json,
-    http::{HeaderValue, Method},
+    http::{Method, header},
     routing::post,
 };
 use serde::{Deserialize, Serialize};
 use tokio::net::TcpListener;
-use tower_http::cors::CorsLayer;
 use warp::Filter;
-use warpdrive::WarpService;
+use warpdrive::{WarpService, cors::CorsConfig};
 
 #[derive(Debug, Serialize, Deserialize)]
 struct Message {
@@ -66,42 +65,24 @@ async fn warp_handler(payload: Message) -> Result<impl warp::Reply, Infallible>
 
 #[tokio::main]
 async fn main() {
-    let allowed_origins = vec!["http://example.com"];
-    let allowed_methods = vec![Method::GET, Method::POST, Method::OPTIONS];
-    let allowed_headers = vec![
-        axum::http::header::CONTENT_TYPE,
-        axum::http::header::ACCEPT,
-        axum::http::header::ORIGIN,
-    ];
-
-    let axum_cors = CorsLayer::new()
-        .allow_origin(
-            allowed_origins
-                .iter()
-                .map(|origin| HeaderValue::from_str(origin).unwrap())
-                .collect::<Vec<_>>(),
-        )
-        .allow_methods(allowed_methods.clone())
-        .allow_headers(allowed_headers.clone());
-
-    let warp_cors = warp::cors()
-        .allow_origins(allowed_origins.iter().copied())
-        .allow_methods(allowed_methods.iter().map(|method| method.as_str()))
-        .allow_headers(allowed_headers.iter().map(|header| header.as_str()));
+    let cors = CorsConfig::new()
+        .allow_origin("http://example.com")
+        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+        .allow_headers([header::CONTENT_TYPE, header::ACCEPT, header::ORIGIN]);
 
     let warp_routes = warp::path("warp")
         .and(warp::path("data"))
         .and(warp::post())
         .and(warp::body::json())
         .and_then(warp_handler)
-        .with(warp_cors)
+        .with(cors.warp())
         .boxed();
 
     let warp_service = WarpService::new(warp_routes);
 
     let app = Router::new()
         .route("/axum/data", post(axum_handler))
-        .layer(axum_cors)
+        .layer(cors.layer())
         .fallback_service(warp_service);
 
     // Start the server
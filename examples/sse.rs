@@ -26,25 +26,25 @@ use tokio::net::TcpListener;
 use tokio_stream::StreamExt;
 use tokio_stream::wrappers::IntervalStream;
 use warp::{Filter, filters::sse::Event as WarpEvent};
-use warpdrive::WarpService;
+use warpdrive::{
+    WarpService,
+    sse::{SseEvent, axum_event_stream, warp_event_stream},
+};
 
-fn timestamp_stream() -> impl Stream<Item = String> {
-    IntervalStream::new(tokio::time::interval(Duration::from_secs(1)))
-        .map(|_| Local::now().format("%Y-%m-%d %H:%M:%S").to_string())
+// Shared event stream, usable from both frameworks.
+fn timestamp_stream(source: &'static str) -> impl Stream<Item = Result<SseEvent, Infallible>> {
+    IntervalStream::new(tokio::time::interval(Duration::from_secs(1))).map(move |_| {
+        let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S");
+        Ok(SseEvent::default().data(format!("{} time: {}", source, timestamp)))
+    })
 }
 
 async fn axum_stream() -> Sse<impl Stream<Item = Result<AxumEvent, Infallible>>> {
-    let stream = timestamp_stream()
-        .map(|timestamp| Ok(AxumEvent::default().data(format!("Axum time: {}", timestamp))));
-
-    Sse::new(stream).keep_alive(KeepAlive::default())
+    Sse::new(axum_event_stream(timestamp_stream("Axum"))).keep_alive(KeepAlive::default())
 }
 
 fn warp_stream() -> impl Stream<Item = Result<WarpEvent, Infallible>> {
-    let stream = timestamp_stream()
-        .map(|timestamp| Ok(WarpEvent::default().data(format!("Warp time: {}", timestamp))));
-
-    stream
+    warp_event_stream(timestamp_stream("Warp"))
 }
 
 #[tokio::main]
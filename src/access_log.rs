@@ -0,0 +1,292 @@
+use std::{
+    fmt,
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{ConnectInfo, Request},
+    http::{
+        HeaderMap, StatusCode,
+        header::{REFERER, USER_AGENT},
+    },
+    response::Response,
+};
+use http_body::{Body as HttpBody, Frame, SizeHint};
+
+use crate::error::Error;
+
+type LogWriter = Arc<dyn Fn(&str) + Send + Sync>;
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Access logs for a [`WarpService`](crate::WarpService) in the Common or Combined Log
+/// Format, for log pipelines that parse the output of servers such as Apache or nginx.
+///
+/// Each request produces one line, passed to a writer function once the response body has
+/// been sent:
+///
+/// ```text
+/// 10.0.0.1 - - [10/Oct/2000:13:55:36 +0000] "GET /users?page=2 HTTP/1.1" 200 2326
+/// ```
+///
+/// The Combined format appends the quoted `Referer` and `User-Agent` headers, and
+/// [`latency`](AccessLog::latency) appends the time taken in microseconds, up to the end of
+/// the response body. The remote address is read from Axum's `ConnectInfo<SocketAddr>`, so
+/// the router must be served with `into_make_service_with_connect_info::<SocketAddr>()`;
+/// otherwise it is logged as `-`. Times are in UTC.
+///
+/// Access logs are applied with
+/// [`WarpService::with_access_log`](crate::WarpService::with_access_log), and can be
+/// disabled for noisy routes, such as health checks, with
+/// [`PrefixConfig::access_log`](crate::PrefixConfig::access_log).
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::{AccessLog, PrefixConfig, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("users").map(|| "Users").boxed();
+///
+/// let access_log = AccessLog::combined(|line| println!("{}", line)).latency();
+///
+/// let service = WarpService::new(filter)
+///     .with_access_log(access_log)
+///     .with_prefix_config("/health", PrefixConfig::new().access_log(false));
+/// ```
+#[derive(Clone)]
+pub struct AccessLog {
+    writer: LogWriter,
+    combined: bool,
+    latency: bool,
+}
+
+/// A request whose log line is written when its response is complete.
+pub(crate) struct PendingLine {
+    log: AccessLog,
+    prefix: String,
+    suffix: String,
+    started: Instant,
+}
+
+/// A response body that counts the bytes sent, writing the log line when it ends.
+struct CountingBody {
+    inner: Body,
+    bytes: u64,
+    line: Option<PendingLine>,
+    status: StatusCode,
+}
+
+impl AccessLog {
+    /// Creates access logs in the Common Log Format.
+    pub fn common<F>(writer: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        AccessLog {
+            writer: Arc::new(writer),
+            combined: false,
+            latency: false,
+        }
+    }
+
+    /// Creates access logs in the Combined Log Format.
+    pub fn combined<F>(writer: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        AccessLog {
+            combined: true,
+            ..AccessLog::common(writer)
+        }
+    }
+
+    /// Appends the time taken to serve each request, in microseconds.
+    pub fn latency(mut self) -> Self {
+        self.latency = true;
+        self
+    }
+
+    /// Starts logging a request.
+    pub(crate) fn start(&self, req: &Request) -> PendingLine {
+        let remote = req
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map_or("-".to_string(), |ConnectInfo(addr)| addr.ip().to_string());
+        let target = req
+            .uri()
+            .path_and_query()
+            .map_or(req.uri().path(), |path_and_query| path_and_query.as_str());
+
+        let prefix = format!(
+            "{} - - [{}] \"{} {} {:?}\"",
+            remote,
+            clf_time(SystemTime::now()),
+            req.method(),
+            escape(target),
+            req.version()
+        );
+        let suffix = if self.combined {
+            format!(
+                " \"{}\" \"{}\"",
+                header(req.headers(), REFERER.as_str()),
+                header(req.headers(), USER_AGENT.as_str())
+            )
+        } else {
+            String::new()
+        };
+
+        PendingLine {
+            log: self.clone(),
+            prefix,
+            suffix,
+            started: Instant::now(),
+        }
+    }
+}
+
+impl PendingLine {
+    /// Writes the line once the response body has been sent.
+    pub(crate) fn finish(self, result: Result<Response, Error>) -> Result<Response, Error> {
+        match result {
+            Ok(response) => {
+                let (parts, body) = response.into_parts();
+                let body = Body::new(CountingBody {
+                    inner: body,
+                    bytes: 0,
+                    line: Some(self),
+                    status: parts.status,
+                });
+                Ok(Response::from_parts(parts, body))
+            }
+            Err(err) => {
+                self.write(err.status(), 0);
+                Err(err)
+            }
+        }
+    }
+
+    fn write(self, status: StatusCode, bytes: u64) {
+        let mut line = format!("{} {} ", self.prefix, status.as_u16());
+        if bytes == 0 {
+            line.push('-');
+        } else {
+            line.push_str(&bytes.to_string());
+        }
+        line.push_str(&self.suffix);
+        if self.log.latency {
+            line.push_str(&format!(" {}", self.started.elapsed().as_micros()));
+        }
+
+        (self.log.writer)(&line);
+    }
+}
+
+impl HttpBody for CountingBody {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+        let frame = std::task::ready!(Pin::new(&mut this.inner).poll_frame(cx));
+
+        match &frame {
+            Some(Ok(frame)) => {
+                this.bytes += frame.data_ref().map_or(0, |data| data.len() as u64);
+            }
+            Some(Err(_)) | None => {
+                if let Some(line) = this.line.take() {
+                    line.write(this.status, this.bytes);
+                }
+            }
+        }
+
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl Drop for CountingBody {
+    fn drop(&mut self) {
+        // The body was not read to the end, such as for an empty body or a disconnect.
+        if let Some(line) = self.line.take() {
+            line.write(self.status, self.bytes);
+        }
+    }
+}
+
+/// Returns a header value for the Combined format, or `-` if it is missing.
+fn header(headers: &HeaderMap, name: &str) -> String {
+    headers.get(name).map_or("-".to_string(), |value| {
+        escape(&String::from_utf8_lossy(value.as_bytes()))
+    })
+}
+
+/// Escapes quotes, backslashes, and control characters, as Apache does.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\x{:02x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Formats a time as in the Common Log Format, such as `10/Oct/2000:13:55:36 +0000`.
+pub(crate) fn clf_time(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+
+    // Converts days since 1970-01-01 to a civil date, from Howard Hinnant's algorithms.
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:02}/{}/{}:{:02}:{:02}:{:02} +0000",
+        day,
+        MONTHS[month as usize - 1],
+        year,
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60
+    )
+}
+
+impl fmt::Debug for AccessLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccessLog")
+            .field("combined", &self.combined)
+            .field("latency", &self.latency)
+            .finish()
+    }
+}
@@ -0,0 +1,342 @@
+//! A runtime admin API for inspecting and controlling the migration.
+//!
+//! [`AdminRouter`] builds an Axum router, usually nested at `/_warpdrive`, that exposes the
+//! boundary settings of registered [`WarpService`]s, [`UsageReport`]s, and runtime controls.
+//! The controls are [`Switch`]es, such as kill switches, and [`Percentage`]s, such as the
+//! share of traffic sent to a canary. They are cheaply cloneable handles: the application
+//! reads them while handling requests, and the admin API changes them without a restart.
+//!
+//! The router requires an authentication layer, since the controls change how production
+//! traffic is served.
+//!
+//! | Method | Path | Description |
+//! |--------|------|-------------|
+//! | `GET` | `/config` | The boundary settings of each service. |
+//! | `GET` | `/usage` | Each usage report, as written by [`UsageExport::json`](crate::UsageExport::json). |
+//! | `GET` | `/switches` | The state of each switch. |
+//! | `PUT` | `/switches/{name}` | Turns a switch on or off, with a JSON `true` or `false` body. |
+//! | `GET` | `/percentages` | The value of each percentage. |
+//! | `PUT` | `/percentages/{name}` | Sets a percentage, with a JSON number body from 0 to 100. |
+//! | `GET` | `/captures/{name}` | The exchanges kept by a [`Capture`](crate::Capture), oldest first. |
+//! | `DELETE` | `/captures/{name}` | Discards the exchanges kept by a capture. |
+//! | `GET` | `/migrations/{name}/shadow` | The [`ShadowCounts`](crate::ShadowCounts) of a [`Migration`](crate::Migration), by route. |
+//! | `GET` | `/migrations/{name}/canary` | The [`CanaryMetrics`](crate::CanaryMetrics) of a migration, by route. |
+//!
+//! # Example
+//!
+//! ```rust
+//! use axum::{
+//!     Router,
+//!     extract::Request,
+//!     http::StatusCode,
+//!     middleware::{self, Next},
+//!     response::Response,
+//! };
+//! use warpdrive::{
+//!     WarpService,
+//!     admin::{AdminRouter, Percentage, Switch},
+//! };
+//! use warp::Filter;
+//!
+//! async fn require_token(req: Request, next: Next) -> Result<Response, StatusCode> {
+//!     match req.headers().get("authorization") {
+//!         Some(token) if token == "Bearer secret" => Ok(next.run(req).await),
+//!         _ => Err(StatusCode::UNAUTHORIZED),
+//!     }
+//! }
+//!
+//! let service = WarpService::new(warp::path("users").map(|| "Users").boxed());
+//!
+//! let users_on_axum = Percentage::new(5);
+//! let legacy_enabled = Switch::new(true);
+//!
+//! let admin = AdminRouter::new()
+//!     .service("users", &service)
+//!     .percentage("users-canary", users_on_axum.clone())
+//!     .switch("legacy-enabled", legacy_enabled.clone())
+//!     .into_router(middleware::from_fn(require_token));
+//!
+//! let app: Router = Router::new()
+//!     .nest("/_warpdrive", admin)
+//!     .fallback_service(service);
+//! ```
+
+use std::{collections::BTreeMap, convert::Infallible, fmt, sync::Arc};
+
+use axum::{
+    Json, Router,
+    extract::{Path, Request, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{Route, get, put},
+};
+use serde_json::{Value, json};
+use tower::{Layer, Service};
+
+pub use crate::control::{Percentage, Switch};
+use crate::{
+    canary::CanaryStats, capture::Capture, migration::Migration, usage::UsageReport,
+    warp_service::WarpService,
+};
+
+type ConfigFn = Arc<dyn Fn() -> Value + Send + Sync>;
+
+/// A builder for the admin API router.
+///
+/// See the [module documentation](self) for the endpoints.
+#[derive(Clone, Default)]
+pub struct AdminRouter {
+    services: BTreeMap<String, ConfigFn>,
+    usage: BTreeMap<String, UsageReport>,
+    switches: BTreeMap<String, Switch>,
+    percentages: BTreeMap<String, Percentage>,
+    captures: BTreeMap<String, Capture>,
+    migrations: BTreeMap<String, Migration>,
+}
+
+impl AdminRouter {
+    /// Creates an admin API with nothing registered.
+    pub fn new() -> Self {
+        AdminRouter::default()
+    }
+
+    /// Exposes the boundary settings of a service under the given name.
+    pub fn service<T>(mut self, name: impl Into<String>, service: &WarpService<T>) -> Self {
+        self.services
+            .insert(name.into(), Arc::new(service.config()));
+        self
+    }
+
+    /// Exposes a usage report under the given name.
+    pub fn usage_report(mut self, name: impl Into<String>, report: UsageReport) -> Self {
+        self.usage.insert(name.into(), report);
+        self
+    }
+
+    /// Exposes a switch under the given name, so it can be flipped at runtime.
+    pub fn switch(mut self, name: impl Into<String>, switch: Switch) -> Self {
+        self.switches.insert(name.into(), switch);
+        self
+    }
+
+    /// Exposes a percentage under the given name, so it can be changed at runtime.
+    pub fn percentage(mut self, name: impl Into<String>, percentage: Percentage) -> Self {
+        self.percentages.insert(name.into(), percentage);
+        self
+    }
+
+    /// Exposes the exchanges kept by a capture under the given name.
+    pub fn capture(mut self, name: impl Into<String>, capture: Capture) -> Self {
+        self.captures.insert(name.into(), capture);
+        self
+    }
+
+    /// Exposes the shadow comparison counts and canary metrics of a migration under the given
+    /// name.
+    pub fn migration(mut self, name: impl Into<String>, migration: Migration) -> Self {
+        self.migrations.insert(name.into(), migration);
+        self
+    }
+
+    /// Builds the router, with every endpoint behind the given authentication layer, such
+    /// as `ValidateRequestHeaderLayer` or `axum::middleware::from_fn`.
+    pub fn into_router<L, S>(self, auth: L) -> Router<S>
+    where
+        L: Layer<Route> + Clone + Send + Sync + 'static,
+        L::Service: Service<Request> + Clone + Send + Sync + 'static,
+        <L::Service as Service<Request>>::Response: IntoResponse + 'static,
+        <L::Service as Service<Request>>::Error: Into<Infallible> + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+        S: Clone + Send + Sync + 'static,
+    {
+        Router::new()
+            .route("/config", get(config))
+            .route("/usage", get(usage))
+            .route("/switches", get(switches))
+            .route("/switches/{name}", put(set_switch))
+            .route("/percentages", get(percentages))
+            .route("/percentages/{name}", put(set_percentage))
+            .route("/captures/{name}", get(captures).delete(clear_captures))
+            .route("/migrations/{name}/shadow", get(shadow))
+            .route("/migrations/{name}/canary", get(canary))
+            .layer(auth)
+            .with_state(Arc::new(self))
+    }
+}
+
+impl fmt::Debug for AdminRouter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AdminRouter")
+            .field("services", &self.services.keys().collect::<Vec<_>>())
+            .field("usage", &self.usage.keys().collect::<Vec<_>>())
+            .field("switches", &self.switches)
+            .field("percentages", &self.percentages)
+            .field("captures", &self.captures)
+            .field("migrations", &self.migrations.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+type AdminState = State<Arc<AdminRouter>>;
+
+async fn config(State(admin): AdminState) -> Json<Value> {
+    let services: BTreeMap<_, _> = admin
+        .services
+        .iter()
+        .map(|(name, config)| (name, config()))
+        .collect();
+
+    Json(json!({ "services": services }))
+}
+
+async fn usage(State(admin): AdminState) -> Json<Value> {
+    let reports: BTreeMap<_, Value> = admin
+        .usage
+        .iter()
+        .map(|(name, report)| {
+            let report = serde_json::from_str(&report.to_json()).unwrap_or(Value::Null);
+            (name, report)
+        })
+        .collect();
+
+    Json(json!({ "usage": reports }))
+}
+
+async fn switches(State(admin): AdminState) -> Json<Value> {
+    let switches: BTreeMap<_, _> = admin
+        .switches
+        .iter()
+        .map(|(name, switch)| (name, switch.is_on()))
+        .collect();
+
+    Json(json!({ "switches": switches }))
+}
+
+async fn set_switch(
+    State(admin): AdminState,
+    Path(name): Path<String>,
+    Json(on): Json<bool>,
+) -> Response {
+    match admin.switches.get(&name) {
+        Some(switch) => {
+            switch.set(on);
+            Json(json!({ name: on })).into_response()
+        }
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn percentages(State(admin): AdminState) -> Json<Value> {
+    let percentages: BTreeMap<_, _> = admin
+        .percentages
+        .iter()
+        .map(|(name, percentage)| (name, percentage.get()))
+        .collect();
+
+    Json(json!({ "percentages": percentages }))
+}
+
+async fn set_percentage(
+    State(admin): AdminState,
+    Path(name): Path<String>,
+    Json(value): Json<u8>,
+) -> Response {
+    match admin.percentages.get(&name) {
+        Some(percentage) if percentage.set(value) => Json(json!({ name: value })).into_response(),
+        Some(_) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Percentage must be at most 100",
+        )
+            .into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn captures(State(admin): AdminState, Path(name): Path<String>) -> Response {
+    match admin.captures.get(&name) {
+        Some(capture) => {
+            let exchanges: Vec<_> = capture
+                .exchanges()
+                .iter()
+                .map(|exchange| exchange.to_json())
+                .collect();
+            Json(json!({ "exchanges": exchanges })).into_response()
+        }
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn clear_captures(State(admin): AdminState, Path(name): Path<String>) -> StatusCode {
+    match admin.captures.get(&name) {
+        Some(capture) => {
+            capture.clear();
+            StatusCode::NO_CONTENT
+        }
+        None => StatusCode::NOT_FOUND,
+    }
+}
+
+async fn shadow(State(admin): AdminState, Path(name): Path<String>) -> Response {
+    match admin.migrations.get(&name) {
+        Some(migration) => {
+            let routes: Vec<_> = migration
+                .shadow_counts()
+                .into_iter()
+                .map(|counts| {
+                    json!({
+                        "route": counts.route,
+                        "compared": counts.compared,
+                        "status": counts.status,
+                        "header": counts.header,
+                        "body": counts.body,
+                    })
+                })
+                .collect();
+            Json(json!({ "shadow": routes })).into_response()
+        }
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn canary(State(admin): AdminState, Path(name): Path<String>) -> Response {
+    match admin.migrations.get(&name) {
+        Some(migration) => {
+            let routes: Vec<_> = migration
+                .canary_metrics()
+                .into_iter()
+                .map(|metrics| {
+                    json!({
+                        "route": metrics.route,
+                        "warp": canary_stats(&metrics.warp),
+                        "axum": canary_stats(&metrics.axum),
+                    })
+                })
+                .collect();
+            Json(json!({ "canary": routes })).into_response()
+        }
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+fn canary_stats(stats: &CanaryStats) -> Value {
+    let ms = |quantile| {
+        stats
+            .latency
+            .quantile(quantile)
+            .map(|latency| latency.as_secs_f64() * 1000.0)
+    };
+
+    json!({
+        "requests": stats.requests,
+        "successes": stats.successes,
+        "success_ratio": stats.success_ratio(),
+        "statuses": stats.statuses,
+        "latency": {
+            "count": stats.latency.count(),
+            "sum_ms": stats.latency.sum().as_secs_f64() * 1000.0,
+            "p50_ms": ms(0.5),
+            "p90_ms": ms(0.9),
+            "p99_ms": ms(0.99),
+        },
+    })
+}
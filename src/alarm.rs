@@ -0,0 +1,263 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{extract::Request, response::Response};
+
+use crate::{
+    bounded::{BoundedMap, DEFAULT_MAX_KEYS},
+    control::Switch,
+    error::Error,
+    route::{RouteKeyFn, route_failed, route_of},
+};
+
+/// The number of slots the rolling window is divided into.
+const SLOTS: u32 = 10;
+type AlarmHook = Arc<dyn Fn(&str, f64, bool) + Send + Sync>;
+
+/// An in-process alarm on the error rate of routes served by a
+/// [`WarpService`](crate::WarpService).
+///
+/// Each route has a rolling error rate over the [window](ErrorRateAlarm::window), counting
+/// requests that reach the Warp filter and are answered with a `5xx` status or fail at the
+/// boundary. By default, the whole service is tracked as one route, reported as `*`; use
+/// [`route_key`](ErrorRateAlarm::route_key) to track routes separately. Once at least
+/// [`min_requests`](ErrorRateAlarm::min_requests) are in the window and the error rate
+/// reaches the threshold, the route's alarm is raised; it clears once the rate falls below
+/// the threshold again. Each change calls the [`on_alarm`](ErrorRateAlarm::on_alarm) hook, and
+/// a [`Switch`] can be kept on while any alarm is raised, for example to expose the alarm
+/// through the admin API.
+///
+/// Alarms are only re-evaluated as requests are recorded, so they latch: a route that stops
+/// receiving requests keeps its alarm raised until its next request. For the same reason, the
+/// switch should not engage anything that stops requests from reaching the Warp filter, such
+/// as a [`KillSwitch`](crate::KillSwitch), as the alarm would then never clear.
+///
+/// At most [`max_routes`](ErrorRateAlarm::max_routes) routes are tracked. Once that many are
+/// tracked, routes whose alarm is not raised and that have no requests in the window are
+/// forgotten, at most once per window, and new routes that still do not fit are tracked
+/// together as `(other)`.
+///
+/// Unlike a [`CircuitBreaker`](crate::CircuitBreaker), the alarm never changes how requests
+/// are served. It is applied with
+/// [`WarpService::with_error_rate_alarm`](crate::WarpService::with_error_rate_alarm).
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use warpdrive::{ErrorRateAlarm, WarpService, admin::Switch};
+/// use warp::Filter;
+///
+/// let filter = warp::path("users").map(|| "Users").boxed();
+///
+/// let alarmed = Switch::new(false);
+/// let alarm = ErrorRateAlarm::new(0.05)
+///     .window(Duration::from_secs(60))
+///     .on_alarm(|route, rate, raised| {
+///         if raised {
+///             eprintln!("{} is failing {:.1}% of requests", route, rate * 100.0);
+///         }
+///     })
+///     .switch(alarmed.clone());
+///
+/// let service = WarpService::new(filter).with_error_rate_alarm(alarm);
+/// ```
+pub struct ErrorRateAlarm {
+    route_key: Option<RouteKeyFn>,
+    threshold: f64,
+    window: Duration,
+    min_requests: u32,
+    on_alarm: Option<AlarmHook>,
+    switch: Option<Switch>,
+    routes: Mutex<BoundedMap<RouteWindow>>,
+}
+
+/// The requests and errors of a route, counted in slots covering the window.
+#[derive(Debug)]
+struct RouteWindow {
+    /// The start of each slot, and its request and error counts.
+    slots: Vec<(Instant, u32, u32)>,
+    raised: bool,
+}
+
+impl RouteWindow {
+    fn record(&mut self, now: Instant, window: Duration, failed: bool) {
+        let slot_width = window / SLOTS;
+        self.slots
+            .retain(|(start, _, _)| now.duration_since(*start) < window);
+
+        match self.slots.last_mut() {
+            Some((start, requests, errors)) if now.duration_since(*start) < slot_width => {
+                *requests += 1;
+                *errors += u32::from(failed);
+            }
+            _ => self.slots.push((now, 1, u32::from(failed))),
+        }
+    }
+
+    fn counts(&self, now: Instant, window: Duration) -> (u32, u32) {
+        self.slots
+            .iter()
+            .filter(|(start, _, _)| now.duration_since(*start) < window)
+            .fold((0, 0), |(requests, errors), (_, r, e)| {
+                (requests + r, errors + e)
+            })
+    }
+}
+
+impl ErrorRateAlarm {
+    /// Creates an alarm that is raised once the error rate of at least 20 requests in a
+    /// 60 second window reaches `threshold`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threshold` is not between `0.0` and `1.0`.
+    pub fn new(threshold: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&threshold),
+            "threshold must be between 0.0 and 1.0"
+        );
+        ErrorRateAlarm {
+            route_key: None,
+            threshold,
+            window: Duration::from_secs(60),
+            min_requests: 20,
+            on_alarm: None,
+            switch: None,
+            routes: Mutex::new(BoundedMap::new(DEFAULT_MAX_KEYS, Duration::from_secs(60))),
+        }
+    }
+
+    /// Tracks the error rate of each route separately, identified by the given function, such
+    /// as the method and the path with ids replaced by placeholders.
+    ///
+    /// Keys should come from a bounded set of routes, so that a burst of errors for unknown
+    /// paths or a single id does not raise an alarm while the route as a whole is healthy.
+    pub fn route_key<F>(mut self, route_key: F) -> Self
+    where
+        F: Fn(&Request) -> String + Send + Sync + 'static,
+    {
+        self.route_key = Some(Arc::new(route_key));
+        self
+    }
+
+    /// Sets the largest number of routes tracked, after which new routes are tracked together
+    /// as `(other)`. Defaults to 10,000.
+    pub fn max_routes(mut self, max_routes: usize) -> Self {
+        self.routes.get_mut().unwrap().set_max_keys(max_routes);
+        self
+    }
+
+    /// Sets the rolling window over which the error rate is computed. Defaults to 60 seconds.
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self.routes.get_mut().unwrap().set_prune_interval(window);
+        self
+    }
+
+    /// Sets the number of requests in the window below which the alarm is never raised.
+    /// Defaults to 20.
+    pub fn min_requests(mut self, min_requests: u32) -> Self {
+        self.min_requests = min_requests;
+        self
+    }
+
+    /// Calls a function with the route and error rate whenever a route's alarm is raised or
+    /// cleared.
+    pub fn on_alarm<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&str, f64, bool) + Send + Sync + 'static,
+    {
+        self.on_alarm = Some(Arc::new(hook));
+        self
+    }
+
+    /// Turns a switch on while any route's alarm is raised, and off once every alarm has
+    /// cleared.
+    ///
+    /// Alarms only clear as requests are recorded, so the switch should not stop requests
+    /// from reaching the Warp filter.
+    pub fn switch(mut self, switch: Switch) -> Self {
+        self.switch = Some(switch);
+        self
+    }
+
+    /// Returns the error rate of a route over the window, or `None` if it has no requests in
+    /// the window.
+    pub fn error_rate(&self, route: &str) -> Option<f64> {
+        let routes = self.routes.lock().unwrap();
+        let (requests, errors) = routes.get(route)?.counts(Instant::now(), self.window);
+        (requests > 0).then(|| errors as f64 / requests as f64)
+    }
+
+    /// Returns the routes whose alarm is raised, sorted by route.
+    pub fn raised_routes(&self) -> Vec<String> {
+        let mut routes: Vec<_> = self
+            .routes
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, window)| window.raised)
+            .map(|(route, _)| route.clone())
+            .collect();
+        routes.sort();
+        routes
+    }
+
+    /// Returns the route of a request.
+    pub(crate) fn route(&self, req: &Request) -> String {
+        route_of(self.route_key.as_ref(), req)
+    }
+
+    /// Records the result of a request served by the Warp filter.
+    pub(crate) fn record(&self, route: String, result: &Result<Response, Error>) {
+        let Some(failed) = route_failed(result) else {
+            return;
+        };
+
+        let now = Instant::now();
+        let mut routes = self.routes.lock().unwrap();
+        let route = routes.admit(route, now, |_, window| {
+            !window.raised && window.counts(now, self.window).0 == 0
+        });
+        let window = routes.entry(route.clone()).or_insert_with(|| RouteWindow {
+            slots: Vec::new(),
+            raised: false,
+        });
+        window.record(now, self.window, failed);
+
+        let (requests, errors) = window.counts(now, self.window);
+        let rate = errors as f64 / requests as f64;
+        let raised = requests >= self.min_requests && rate >= self.threshold;
+        if raised == window.raised {
+            return;
+        }
+        window.raised = raised;
+        let any_raised = routes.values().any(|window| window.raised);
+        drop(routes);
+
+        if let Some(switch) = &self.switch {
+            switch.set(any_raised);
+        }
+        if let Some(hook) = &self.on_alarm {
+            hook(&route, rate, raised);
+        }
+    }
+}
+
+impl fmt::Debug for ErrorRateAlarm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErrorRateAlarm")
+            .field("threshold", &self.threshold)
+            .field("window", &self.window)
+            .field("min_requests", &self.min_requests)
+            .field("on_alarm", &self.on_alarm.is_some())
+            .field("switch", &self.switch.is_some())
+            .field("routes", &self.routes.lock().unwrap().len())
+            .finish()
+    }
+}
@@ -0,0 +1,222 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::Request,
+    http::{HeaderMap, HeaderName, Method, StatusCode, Uri},
+    response::Response,
+};
+
+use crate::excerpt::{Excerpt, ExcerptBody};
+
+/// The largest body excerpt recorded by default, in bytes.
+const DEFAULT_MAX_EXCERPT: usize = 4 * 1024;
+
+type AuditHook = Arc<dyn Fn(&AuditRecord) + Send + Sync>;
+
+/// An audit hook for requests served by the Warp filter, for compliance logging on legacy
+/// endpoints.
+///
+/// The hook is called once for each request that reaches the Warp filter, when the response
+/// body has been sent or dropped, with an [`AuditRecord`] of the request method, URI,
+/// selected request headers, response status, and excerpts of both bodies. Bodies are not
+/// buffered: the excerpts are the first bytes that pass through, up to
+/// [`max_excerpt`](Audit::max_excerpt), so the request excerpt only covers what the Warp
+/// filter read. Only the headers selected with [`header`](Audit::header) are recorded, so
+/// credentials are not logged by accident.
+///
+/// Requests that time out are recorded with `504 Gateway Timeout`. Requests answered at the
+/// boundary without reaching the Warp filter, such as by a [`Denylist`](crate::Denylist) or a
+/// [`RateLimit`](crate::RateLimit), are not audited. The hook is applied with
+/// [`WarpService::with_audit`](crate::WarpService::with_audit).
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::{Audit, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("accounts").map(|| "Accounts").boxed();
+///
+/// let audit = Audit::new(|record| {
+///     eprintln!(
+///         "{} {} {} user={:?} body={:?}",
+///         record.method,
+///         record.uri,
+///         record.status,
+///         record.headers.get("x-user-id"),
+///         String::from_utf8_lossy(&record.request_body),
+///     );
+/// })
+/// .header("x-user-id")
+/// .max_excerpt(1024);
+///
+/// let service = WarpService::new(filter).with_audit(audit);
+/// ```
+#[derive(Clone)]
+pub struct Audit {
+    hook: AuditHook,
+    headers: Vec<HeaderName>,
+    max_excerpt: usize,
+}
+
+/// A request audited by an [`Audit`] hook.
+#[derive(Debug, Clone)]
+pub struct AuditRecord {
+    /// The request method.
+    pub method: Method,
+    /// The request URI.
+    pub uri: Uri,
+    /// The request headers selected with [`Audit::header`].
+    pub headers: HeaderMap,
+    /// The response status.
+    pub status: StatusCode,
+    /// The start of the request body, as read by the Warp filter.
+    pub request_body: Bytes,
+    /// Whether the request body was longer than the excerpt.
+    pub request_body_truncated: bool,
+    /// The start of the response body, as sent to the client.
+    pub response_body: Bytes,
+    /// Whether the response body was longer than the excerpt.
+    pub response_body_truncated: bool,
+}
+
+/// Calls the audit hook when dropped.
+struct Finish {
+    hook: AuditHook,
+    record: AuditRecord,
+    request_excerpt: Arc<Mutex<Excerpt>>,
+    response_excerpt: Arc<Mutex<Excerpt>>,
+}
+
+impl Drop for Finish {
+    fn drop(&mut self) {
+        (self.record.request_body, self.record.request_body_truncated) =
+            self.request_excerpt.lock().unwrap().take();
+        (
+            self.record.response_body,
+            self.record.response_body_truncated,
+        ) = self.response_excerpt.lock().unwrap().take();
+        (self.hook)(&self.record);
+    }
+}
+
+/// An audited request, whose response is yet to be recorded.
+pub(crate) struct AuditedRequest {
+    audit: Audit,
+    record: AuditRecord,
+    request_excerpt: Arc<Mutex<Excerpt>>,
+}
+
+impl Audit {
+    /// Creates an audit hook that calls the given function for each request.
+    pub fn new<F>(hook: F) -> Self
+    where
+        F: Fn(&AuditRecord) + Send + Sync + 'static,
+    {
+        Audit {
+            hook: Arc::new(hook),
+            headers: Vec::new(),
+            max_excerpt: DEFAULT_MAX_EXCERPT,
+        }
+    }
+
+    /// Records a request header. Can be called more than once to record several headers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid header name.
+    pub fn header(mut self, name: &str) -> Self {
+        self.headers
+            .push(HeaderName::try_from(name).expect("invalid header name"));
+        self
+    }
+
+    /// Sets the largest excerpt recorded from each body, in bytes. Defaults to 4 KiB.
+    pub fn max_excerpt(mut self, max_excerpt: usize) -> Self {
+        self.max_excerpt = max_excerpt;
+        self
+    }
+
+    /// Starts auditing a request, capturing the start of its body.
+    pub(crate) fn start(&self, req: Request) -> (Request, AuditedRequest) {
+        let mut headers = HeaderMap::new();
+        for name in &self.headers {
+            for value in req.headers().get_all(name) {
+                headers.append(name, value.clone());
+            }
+        }
+
+        let record = AuditRecord {
+            method: req.method().clone(),
+            uri: req.uri().clone(),
+            headers,
+            status: StatusCode::OK,
+            request_body: Bytes::new(),
+            request_body_truncated: false,
+            response_body: Bytes::new(),
+            response_body_truncated: false,
+        };
+        let request_excerpt = Arc::new(Mutex::new(Excerpt::default()));
+
+        let (parts, body) = req.into_parts();
+        let body = Body::new(ExcerptBody::<Finish>::new(
+            body,
+            Arc::clone(&request_excerpt),
+            self.max_excerpt,
+            None,
+        ));
+
+        let audited = AuditedRequest {
+            audit: self.clone(),
+            record,
+            request_excerpt,
+        };
+        (Request::from_parts(parts, body), audited)
+    }
+}
+
+impl AuditedRequest {
+    /// Records the response, calling the hook once its body has been sent or dropped.
+    pub(crate) fn finish(self, response: Response) -> Response {
+        let (parts, body) = response.into_parts();
+        let response_excerpt = Arc::new(Mutex::new(Excerpt::default()));
+        let limit = self.audit.max_excerpt;
+        let finish = self.into_finish(parts.status, Arc::clone(&response_excerpt));
+
+        let body = Body::new(ExcerptBody::new(
+            body,
+            response_excerpt,
+            limit,
+            Some(finish),
+        ));
+        Response::from_parts(parts, body)
+    }
+
+    /// Records a request that failed at the boundary, calling the hook immediately.
+    pub(crate) fn fail(self, status: StatusCode) {
+        drop(self.into_finish(status, Arc::default()));
+    }
+
+    fn into_finish(mut self, status: StatusCode, response_excerpt: Arc<Mutex<Excerpt>>) -> Finish {
+        self.record.status = status;
+        Finish {
+            hook: self.audit.hook,
+            record: self.record,
+            request_excerpt: self.request_excerpt,
+            response_excerpt,
+        }
+    }
+}
+
+impl fmt::Debug for Audit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Audit")
+            .field("headers", &self.headers)
+            .field("max_excerpt", &self.max_excerpt)
+            .finish()
+    }
+}
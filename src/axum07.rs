@@ -0,0 +1,100 @@
+//! Compatibility with Axum 0.7.
+//!
+//! Axum 0.7 and 0.8 share the `http` 1.0 request and response types, but have distinct body
+//! types. [`Axum07Service`] adapts a service built for Axum 0.8, such as a [`WarpService`],
+//! so it can be mounted in an Axum 0.7 router, and converts bodies without buffering.
+//!
+//! The conversion layer itself is built on Axum 0.8, which remains a dependency with this
+//! feature, so a workspace on Axum 0.7 compiles both versions.
+//!
+//! # Example
+//!
+//! ```rust
+//! use axum07::{Router, routing::get};
+//! use warpdrive::WarpService;
+//! use warp::Filter;
+//!
+//! let warp_routes = warp::path("api").map(|| "Hello from Warp!").boxed();
+//!
+//! let app: Router = Router::new()
+//!     .route("/", get(|| async { "Hello from Axum 0.7!" }))
+//!     .fallback_service(WarpService::new(warp_routes).into_axum07());
+//! ```
+
+use std::{
+    convert::Infallible,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{body::Body, extract::Request, response::Response};
+use futures::Future;
+use tower::{Service, ServiceExt};
+
+use crate::WarpService;
+
+/// A request with an Axum 0.7 body.
+pub type Axum07Request = axum07::extract::Request;
+
+/// A response with an Axum 0.7 body.
+pub type Axum07Response = axum07::response::Response;
+
+/// An adapter that serves Axum 0.7 requests with a service built for Axum 0.8.
+///
+/// Created with [`WarpService::into_axum07`] or [`Axum07Service::new`].
+#[derive(Debug, Clone)]
+pub struct Axum07Service<S> {
+    inner: S,
+}
+
+impl<S> Axum07Service<S> {
+    /// Wraps a service that accepts Axum 0.8 requests.
+    pub fn new(inner: S) -> Self {
+        Axum07Service { inner }
+    }
+
+    /// Returns the wrapped service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Service<Axum07Request> for Axum07Service<S>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Axum07Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Axum07Request) -> Self::Future {
+        let inner = self.inner.clone();
+        let req = req.map(Body::new);
+
+        Box::pin(async move {
+            let response = match inner.oneshot(req).await {
+                Ok(response) => response,
+                Err(never) => match never {},
+            };
+
+            Ok(response.map(axum07::body::Body::new))
+        })
+    }
+}
+
+impl<T> WarpService<T>
+where
+    T: warp::Reply + Send + Sync + 'static,
+{
+    /// Adapts this service to be mounted in an Axum 0.7 router.
+    ///
+    /// Requires the `axum07` feature. See the [`axum07`](crate::axum07) module for details.
+    pub fn into_axum07(self) -> Axum07Service<Self> {
+        Axum07Service::new(self)
+    }
+}
@@ -0,0 +1,150 @@
+//! Workloads for benchmarking the conversion boundary.
+//!
+//! These helpers are used by this crate's criterion suite in `benches/`, and are exported so
+//! downstream crates can measure the bridge's overhead for their own routes with the benchmark
+//! harness of their choice. Each workload is a plain function or future, so it can be wrapped
+//! in a harness's iteration loop directly, such as with criterion's `to_async`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use warpdrive::{WarpService, bench};
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let service = WarpService::new(bench::echo_filter());
+//!
+//! // Through the bridge.
+//! let body = bench::call_service(&service, bench::request(1024)).await;
+//! assert_eq!(body.len(), 1024);
+//!
+//! // Warp alone, for comparison.
+//! let body = bench::call_warp(&bench::echo_filter(), bench::warp_request(1024)).await;
+//! assert_eq!(body.len(), 1024);
+//! # }
+//! ```
+
+use std::convert::Infallible;
+
+use axum::{
+    body::{Body, Bytes},
+    http::{Request, Response},
+};
+use tower::{Service, ServiceExt};
+use warp::{Filter, filters::BoxedFilter};
+
+use crate::{
+    convert_request::into_warp_request,
+    convert_response::{into_axum_response, into_warp_response},
+};
+
+/// The URI requested by the benchmark fixtures.
+pub const ECHO_PATH: &str = "/bench/echo";
+
+/// Builds a `POST` request to [`ECHO_PATH`] with typical headers and a body of the given
+/// size.
+pub fn request(body_size: usize) -> Request<Body> {
+    Request::post(format!("{}?format=raw&trace=1", ECHO_PATH))
+        .header("host", "localhost")
+        .header("user-agent", "warpdrive-bench/1.0")
+        .header("accept", "*/*")
+        .header("content-type", "application/octet-stream")
+        .header("x-request-id", "00000000-0000-0000-0000-000000000000")
+        .body(Body::from(vec![b'x'; body_size]))
+        .expect("valid benchmark request")
+}
+
+/// Builds the same request as [`request`], using Warp's `http` 0.2 types.
+pub fn warp_request(body_size: usize) -> warp::http::Request<warp::hyper::Body> {
+    warp::http::Request::post(format!("{}?format=raw&trace=1", ECHO_PATH))
+        .header("host", "localhost")
+        .header("user-agent", "warpdrive-bench/1.0")
+        .header("accept", "*/*")
+        .header("content-type", "application/octet-stream")
+        .header("x-request-id", "00000000-0000-0000-0000-000000000000")
+        .body(warp::hyper::Body::from(vec![b'x'; body_size]))
+        .expect("valid benchmark request")
+}
+
+/// Builds a `200 OK` response with typical headers and a body of the given size.
+pub fn response(body_size: usize) -> Response<Body> {
+    Response::builder()
+        .header("content-type", "application/octet-stream")
+        .header("cache-control", "no-store")
+        .header("x-request-id", "00000000-0000-0000-0000-000000000000")
+        .body(Body::from(vec![b'x'; body_size]))
+        .expect("valid benchmark response")
+}
+
+/// A Warp filter that echoes the body of requests to [`ECHO_PATH`].
+pub fn echo_filter() -> BoxedFilter<(warp::reply::Response,)> {
+    warp::path!("bench" / "echo")
+        .and(warp::post())
+        .and(warp::body::bytes())
+        .map(|body: Bytes| warp::reply::Reply::into_response(body.to_vec()))
+        .boxed()
+}
+
+/// Converts a request into a Warp request, without reading the body.
+///
+/// # Panics
+///
+/// Panics if the conversion fails.
+pub async fn convert_request(request: Request<Body>) -> warp::http::Request<warp::hyper::Body> {
+    into_warp_request(request)
+        .await
+        .expect("request conversion failed")
+}
+
+/// Converts a response into a Warp response and back, without reading the body.
+///
+/// # Panics
+///
+/// Panics if either conversion fails.
+pub fn convert_response(response: Response<Body>) -> Response<Body> {
+    let warp_response = into_warp_response(response).expect("response conversion failed");
+    into_axum_response(warp_response).expect("response conversion failed")
+}
+
+/// Calls a service, such as a [`WarpService`](crate::WarpService), and reads the whole
+/// response body.
+///
+/// # Panics
+///
+/// Panics if the response body cannot be read.
+pub async fn call_service<S>(service: &S, request: Request<Body>) -> Bytes
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = Infallible> + Clone,
+{
+    let response = match service.clone().oneshot(request).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    };
+
+    axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body")
+}
+
+/// Calls a Warp filter directly, without the bridge, and reads the whole response body.
+///
+/// # Panics
+///
+/// Panics if the response body cannot be read.
+pub async fn call_warp<T>(
+    filter: &BoxedFilter<(T,)>,
+    request: warp::http::Request<warp::hyper::Body>,
+) -> Bytes
+where
+    T: warp::Reply + 'static,
+{
+    let mut service = warp::service(filter.clone());
+    let response = match service.call(request).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    };
+
+    warp::hyper::body::to_bytes(response.into_body())
+        .await
+        .expect("failed to read response body")
+}
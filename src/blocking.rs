@@ -0,0 +1,50 @@
+use std::task::{Context, Poll};
+
+use axum::{extract::Request, response::Response};
+use futures::future::BoxFuture;
+use tokio::runtime::Handle;
+use tower::{Service, ServiceExt, util::BoxCloneSyncService};
+
+use crate::error::Error;
+
+/// Runs a service on Tokio's blocking thread pool, so CPU-heavy work in the Warp filter does
+/// not stall the threads driving other requests.
+///
+/// The whole request future, including conversion and the filter, is driven with
+/// `Handle::block_on` inside `spawn_blocking`. Panics are resumed on the calling task, so they
+/// are handled as if the filter ran inline.
+#[derive(Clone)]
+pub(crate) struct Blocking {
+    inner: BoxCloneSyncService<Request, Response, Error>,
+}
+
+impl Blocking {
+    pub(crate) fn new(inner: BoxCloneSyncService<Request, Response, Error>) -> Self {
+        Blocking { inner }
+    }
+}
+
+impl Service<Request> for Blocking {
+    type Response = Response;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Response, Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let inner = self.inner.clone();
+        let handle = Handle::current();
+
+        Box::pin(async move {
+            let task = tokio::task::spawn_blocking(move || handle.block_on(inner.oneshot(req)));
+            match task.await {
+                Ok(result) => result,
+                Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+                // Blocking tasks are only cancelled when the runtime shuts down.
+                Err(_) => Err(Error::ShuttingDown),
+            }
+        })
+    }
+}
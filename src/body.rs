@@ -0,0 +1,256 @@
+//! Conversions between Axum and Warp bodies.
+//!
+//! These are the body conversions used by [`WarpService`](crate::WarpService), exposed for
+//! custom adapters and middleware. The streaming conversions do not buffer the body, and
+//! the buffered conversions read the whole body into memory first, up to a limit. [`tee`]
+//! duplicates a body for two consumers, such as a primary and a shadow target.
+//!
+//! # Example
+//!
+//! ```rust
+//! use axum::body::Body;
+//! use warpdrive::body::{to_axum_body, to_warp_body};
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let warp_body = to_warp_body(Body::from("Hello"));
+//! let axum_body = to_axum_body(warp_body);
+//!
+//! let bytes = axum::body::to_bytes(axum_body, usize::MAX).await.unwrap();
+//! assert_eq!(bytes, "Hello");
+//! # }
+//! ```
+
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use axum::body::{Body as AxumBody, Bytes};
+use http_body::{Body as HttpBody, Frame, SizeHint};
+use tower::BoxError;
+use warp::hyper::body::Body as WarpBody;
+
+use crate::{compat::CompatBody, error::Error};
+
+/// Converts an Axum body into a Warp body, streaming the data.
+///
+/// Warp bodies are built from a stream of data, so trailers are not carried over.
+pub fn to_warp_body(body: AxumBody) -> WarpBody {
+    WarpBody::wrap_stream(body.into_data_stream())
+}
+
+/// Converts a Warp body into an Axum body, streaming the data and any trailers.
+pub fn to_axum_body(body: WarpBody) -> AxumBody {
+    AxumBody::new(CompatBody::from(body))
+}
+
+/// Reads an Axum body into memory and converts it into a Warp body.
+///
+/// The resulting body has a known length. Returns [`Error::Conversion`] if the body cannot
+/// be read or is longer than `limit` bytes.
+pub async fn to_warp_body_buffered(body: AxumBody, limit: usize) -> Result<WarpBody, Error> {
+    let bytes = axum::body::to_bytes(body, limit)
+        .await
+        .map_err(|e| Error::Conversion(format!("Failed to read body: {}", e)))?;
+
+    Ok(WarpBody::from(bytes))
+}
+
+/// Reads a Warp body into memory and converts it into an Axum body.
+///
+/// The resulting body has a known length. Returns [`Error::Conversion`] if the body cannot
+/// be read or is longer than `limit` bytes.
+pub async fn to_axum_body_buffered(body: WarpBody, limit: usize) -> Result<AxumBody, Error> {
+    let bytes = axum::body::to_bytes(to_axum_body(body), limit)
+        .await
+        .map_err(|e| Error::Conversion(format!("Failed to read body: {}", e)))?;
+
+    Ok(AxumBody::from(bytes))
+}
+
+/// Duplicates a body into a primary and a shadow body, without reading it into memory.
+///
+/// The primary body streams the original body. Each frame the primary reads is also queued
+/// for the shadow body, so the shadow receives data as fast as the primary consumes it, and
+/// never slows the primary down. If the shadow falls more than `max_buffered` bytes behind,
+/// its queue is dropped and it fails, while the primary continues unaffected. The shadow also
+/// fails if the original body fails, or if the primary is dropped before the end of the body.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::body::Body;
+/// use warpdrive::body::tee;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let (primary, shadow) = tee(Body::from("Hello"), 64 * 1024);
+///
+/// let primary = axum::body::to_bytes(primary, usize::MAX).await.unwrap();
+/// let shadow = axum::body::to_bytes(shadow, usize::MAX).await.unwrap();
+/// assert_eq!(primary, shadow);
+/// # }
+/// ```
+pub fn tee(body: AxumBody, max_buffered: usize) -> (AxumBody, AxumBody) {
+    let size_hint = body.size_hint();
+    let shared = Arc::new(Mutex::new(TeeState {
+        queue: VecDeque::new(),
+        buffered: 0,
+        end: None,
+        shadow_dropped: false,
+        waker: None,
+    }));
+
+    let primary = TeeBody {
+        inner: body,
+        shared: Arc::clone(&shared),
+        max_buffered,
+        finished: false,
+    };
+    let shadow = ShadowBody { shared, size_hint };
+
+    (AxumBody::new(primary), AxumBody::new(shadow))
+}
+
+struct TeeState {
+    queue: VecDeque<Frame<Bytes>>,
+    buffered: usize,
+    end: Option<Result<(), String>>,
+    shadow_dropped: bool,
+    waker: Option<Waker>,
+}
+
+impl TeeState {
+    fn finish(&mut self, end: Result<(), String>) {
+        if self.end.is_none() {
+            self.end = Some(end);
+        }
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+struct TeeBody {
+    inner: AxumBody,
+    shared: Arc<Mutex<TeeState>>,
+    max_buffered: usize,
+    finished: bool,
+}
+
+impl HttpBody for TeeBody {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+        let frame = std::task::ready!(Pin::new(&mut this.inner).poll_frame(cx));
+
+        let mut shared = this.shared.lock().unwrap();
+        match &frame {
+            Some(Ok(original)) if !shared.shadow_dropped && shared.end.is_none() => {
+                let copy = match (original.data_ref(), original.trailers_ref()) {
+                    (Some(data), _) => Frame::data(data.clone()),
+                    (None, Some(trailers)) => Frame::trailers(trailers.clone()),
+                    (None, None) => Frame::data(Bytes::new()),
+                };
+
+                shared.buffered += copy.data_ref().map_or(0, Bytes::len);
+                if shared.buffered > this.max_buffered {
+                    shared.queue.clear();
+                    shared.finish(Err(format!(
+                        "shadow body fell more than {} bytes behind",
+                        this.max_buffered
+                    )));
+                } else {
+                    shared.queue.push_back(copy);
+                    if let Some(waker) = shared.waker.take() {
+                        waker.wake();
+                    }
+                }
+            }
+            Some(Ok(_)) => {}
+            Some(Err(err)) => {
+                this.finished = true;
+                shared.finish(Err(err.to_string()));
+            }
+            None => {
+                this.finished = true;
+                shared.finish(Ok(()));
+            }
+        }
+        drop(shared);
+
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl Drop for TeeBody {
+    fn drop(&mut self) {
+        if !self.finished {
+            let mut shared = self.shared.lock().unwrap();
+            if self.inner.is_end_stream() {
+                shared.finish(Ok(()));
+            } else {
+                shared.finish(Err("primary body was dropped before the end".to_string()));
+            }
+        }
+    }
+}
+
+struct ShadowBody {
+    shared: Arc<Mutex<TeeState>>,
+    size_hint: SizeHint,
+}
+
+impl HttpBody for ShadowBody {
+    type Data = Bytes;
+    type Error = BoxError;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let mut shared = self.shared.lock().unwrap();
+
+        if let Some(frame) = shared.queue.pop_front() {
+            shared.buffered -= frame.data_ref().map_or(0, Bytes::len);
+            return Poll::Ready(Some(Ok(frame)));
+        }
+
+        match &shared.end {
+            Some(Ok(())) => Poll::Ready(None),
+            Some(Err(err)) => Poll::Ready(Some(Err(err.clone().into()))),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.size_hint.clone()
+    }
+}
+
+impl Drop for ShadowBody {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.shadow_dropped = true;
+        shared.queue.clear();
+    }
+}
@@ -0,0 +1,98 @@
+use std::{
+    collections::HashMap,
+    ops::{Deref, DerefMut},
+    time::{Duration, Instant},
+};
+
+/// The key that new keys share once a [`BoundedMap`] is full.
+pub(crate) const OVERFLOW_KEY: &str = "(other)";
+
+/// The number of keys tracked by default before new keys share the overflow entry.
+pub(crate) const DEFAULT_MAX_KEYS: usize = 10_000;
+
+/// A map from keys such as client addresses or routes to per-key state, holding at most a
+/// fixed number of keys.
+///
+/// Keys usually come from requests, so a client can create any number of them. Once the map
+/// is full, idle entries are pruned, scanning the map at most once per prune interval, and
+/// new keys that still do not fit share the entry of [`OVERFLOW_KEY`]. The map never holds
+/// more than its limit plus the overflow entry, and a flood of new keys costs at most one
+/// scan per interval.
+///
+/// Only keys returned by [`admit`](BoundedMap::admit) should be inserted.
+pub(crate) struct BoundedMap<V> {
+    entries: HashMap<String, V>,
+    max_keys: usize,
+    prune_interval: Duration,
+    last_pruned: Option<Instant>,
+}
+
+impl<V> BoundedMap<V> {
+    /// Creates a map holding at most `max_keys` keys, pruned at most once per
+    /// `prune_interval`.
+    pub(crate) fn new(max_keys: usize, prune_interval: Duration) -> Self {
+        BoundedMap {
+            entries: HashMap::new(),
+            max_keys,
+            prune_interval,
+            last_pruned: None,
+        }
+    }
+
+    /// Sets the largest number of keys held.
+    pub(crate) fn set_max_keys(&mut self, max_keys: usize) {
+        self.max_keys = max_keys;
+    }
+
+    /// Sets the shortest time between two scans for idle entries.
+    pub(crate) fn set_prune_interval(&mut self, prune_interval: Duration) {
+        self.prune_interval = prune_interval;
+    }
+
+    /// Returns the largest number of keys held.
+    pub(crate) fn max_keys(&self) -> usize {
+        self.max_keys
+    }
+
+    /// Returns the key to record `key` under: `key` itself if it is tracked or fits, and
+    /// otherwise [`OVERFLOW_KEY`].
+    ///
+    /// If the map is full and the prune interval has passed, entries for which `idle` returns
+    /// `true` are removed first.
+    pub(crate) fn admit<F>(&mut self, key: String, now: Instant, mut idle: F) -> String
+    where
+        F: FnMut(&str, &V) -> bool,
+    {
+        if self.entries.len() < self.max_keys || self.entries.contains_key(&key) {
+            return key;
+        }
+
+        let due = self
+            .last_pruned
+            .is_none_or(|last| now.duration_since(last) >= self.prune_interval);
+        if due {
+            self.last_pruned = Some(now);
+            self.entries.retain(|key, value| !idle(key, value));
+        }
+
+        if self.entries.len() < self.max_keys {
+            key
+        } else {
+            OVERFLOW_KEY.to_string()
+        }
+    }
+}
+
+impl<V> Deref for BoundedMap<V> {
+    type Target = HashMap<String, V>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.entries
+    }
+}
+
+impl<V> DerefMut for BoundedMap<V> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.entries
+    }
+}
@@ -0,0 +1,115 @@
+use std::{
+    sync::{Arc, OnceLock},
+    task::{Context, Poll},
+};
+
+use axum::{extract::Request, response::Response};
+use futures::future::BoxFuture;
+use tower::{
+    BoxError, Service, ServiceBuilder, buffer::Buffer, limit::concurrency::future::ResponseFuture,
+    util::BoxCloneSyncService,
+};
+
+use crate::error::Error;
+
+type Pipeline = BoxCloneSyncService<Request, Response, Error>;
+type Buffered = Buffer<Request, ResponseFuture<BoxFuture<'static, Result<Response, Error>>>>;
+
+/// A bounded queue in front of the request pipeline, shared by every clone of a
+/// `WarpService`.
+///
+/// The queue is created on first use, once the service has been fully configured, so it
+/// runs the final pipeline and is spawned on the runtime serving requests.
+pub(crate) struct BufferSlot {
+    bound: usize,
+    max_concurrency: usize,
+    shared: Arc<OnceLock<Buffered>>,
+    /// This clone's handle, which holds its own reserved slot in the queue.
+    handle: Option<Buffered>,
+    /// The error from the last readiness check, returned by the next call.
+    failed: Option<BoxError>,
+}
+
+impl Clone for BufferSlot {
+    fn clone(&self) -> Self {
+        BufferSlot {
+            bound: self.bound,
+            max_concurrency: self.max_concurrency,
+            shared: Arc::clone(&self.shared),
+            handle: None,
+            failed: None,
+        }
+    }
+}
+
+impl BufferSlot {
+    pub(crate) fn new(bound: usize, max_concurrency: usize) -> Self {
+        assert!(bound > 0, "buffer bound must be at least 1");
+        assert!(max_concurrency > 0, "buffer concurrency must be at least 1");
+        BufferSlot {
+            bound,
+            max_concurrency,
+            shared: Arc::default(),
+            handle: None,
+            failed: None,
+        }
+    }
+
+    /// Returns the queue bound and concurrency limit.
+    pub(crate) fn config(&self) -> (usize, usize) {
+        (self.bound, self.max_concurrency)
+    }
+
+    /// Waits for a slot in the queue, creating the queue with `pipeline` on first use.
+    pub(crate) fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+        pipeline: impl FnOnce() -> Pipeline,
+    ) -> Poll<()> {
+        let handle = self.handle.get_or_insert_with(|| {
+            self.shared
+                .get_or_init(|| {
+                    let service = ServiceBuilder::new()
+                        .concurrency_limit(self.max_concurrency)
+                        .service(pipeline());
+                    Buffer::new(service, self.bound)
+                })
+                .clone()
+        });
+
+        match handle.poll_ready(cx) {
+            Poll::Ready(Ok(())) => Poll::Ready(()),
+            Poll::Ready(Err(err)) => {
+                self.failed = Some(err);
+                Poll::Ready(())
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    /// Sends a request through the queue.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `poll_ready` has not returned `Ready` since the last call.
+    pub(crate) fn call(&mut self, req: Request) -> BoxFuture<'static, Result<Response, Error>> {
+        if let Some(err) = self.failed.take() {
+            return Box::pin(async move { Err(into_error(err)) });
+        }
+
+        let handle = self
+            .handle
+            .as_mut()
+            .expect("poll_ready must be called before call");
+        let response = handle.call(req);
+        Box::pin(async move { response.await.map_err(into_error) })
+    }
+}
+
+/// Recovers boundary errors that passed through the queue.
+fn into_error(err: BoxError) -> Error {
+    match err.downcast::<Error>() {
+        Ok(err) => *err,
+        Err(err) => Error::Layer(err),
+    }
+}
@@ -0,0 +1,334 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::{Body, Bytes, HttpBody},
+    extract::Request,
+    http::{
+        HeaderMap, HeaderName, HeaderValue, Method, StatusCode,
+        header::{AGE, AUTHORIZATION, CACHE_CONTROL, COOKIE, SET_COOKIE, VARY},
+    },
+    response::Response,
+};
+use futures::stream;
+
+use crate::prefix::{matches_prefix, trim_prefix};
+
+/// An in-memory cache for `GET` responses from a [`WarpService`](crate::WarpService).
+///
+/// Responses are keyed by path and query, and stored for a fixed time to live. Only
+/// `200 OK` responses with a known body length are cached, and responses with
+/// `Cache-Control: no-store`, `no-cache`, or `private`, a `Set-Cookie` header, or
+/// `Vary: *` are never cached. Responses with a `Vary` header are cached separately for each
+/// combination of the named request headers. As the cache is shared between users, responses
+/// to requests with an `Authorization` or `Cookie` header are only cached, and only served
+/// from the cache, if they are marked `Cache-Control: public` or have an `s-maxage`, as in
+/// [RFC 9111, section 3.5](https://www.rfc-editor.org/rfc/rfc9111#section-3.5). When the
+/// total size of cached bodies would exceed the configured limit, the entries closest to
+/// expiry are evicted first.
+///
+/// Cached responses have an `Age` header and an `x-warpdrive-cache: hit` header, and
+/// responses that were looked up but not found have `x-warpdrive-cache: miss`.
+///
+/// The cache is applied with [`WarpService::with_cache`](crate::WarpService::with_cache).
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use warpdrive::{ResponseCache, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("reports").map(|| "Expensive report").boxed();
+///
+/// // Cache up to 16 MiB of report responses for 30 seconds.
+/// let cache = ResponseCache::new(Duration::from_secs(30), 16 * 1024 * 1024).route("/reports");
+///
+/// let service = WarpService::new(filter).with_cache(cache);
+/// ```
+pub struct ResponseCache {
+    ttl: Duration,
+    max_bytes: usize,
+    routes: Vec<String>,
+    entries: Mutex<Entries>,
+}
+
+#[derive(Default)]
+struct Entries {
+    by_key: HashMap<String, Vec<Entry>>,
+    bytes: usize,
+}
+
+struct Entry {
+    vary: Vec<(HeaderName, Option<HeaderValue>)>,
+    headers: HeaderMap,
+    body: Bytes,
+    /// Whether the response was explicitly marked as shareable, so it may be served to
+    /// requests with credentials.
+    shared: bool,
+    stored: Instant,
+    expires: Instant,
+}
+
+/// The request details needed to store a response after a cache miss.
+pub(crate) struct CacheKey {
+    key: String,
+    headers: HeaderMap,
+}
+
+impl ResponseCache {
+    /// Creates a cache that stores responses for `ttl`, with at most `max_bytes` of bodies
+    /// in total.
+    ///
+    /// All `GET` requests are cached unless routes are selected with
+    /// [`route`](ResponseCache::route).
+    pub fn new(ttl: Duration, max_bytes: usize) -> Self {
+        ResponseCache {
+            ttl,
+            max_bytes,
+            routes: Vec::new(),
+            entries: Mutex::new(Entries::default()),
+        }
+    }
+
+    /// Caches requests whose path is equal to the given prefix or below it, so `/reports`
+    /// selects `/reports` and `/reports/42`, but not `/reports-old`. Can be called more than
+    /// once to select several routes.
+    pub fn route(mut self, path_prefix: impl Into<String>) -> Self {
+        self.routes.push(trim_prefix(&path_prefix.into()));
+        self
+    }
+
+    /// Removes every cached response.
+    pub fn clear(&self) {
+        *self.entries.lock().unwrap() = Entries::default();
+    }
+
+    /// Looks up a response for the request.
+    ///
+    /// Returns `Ok(None)` if the request is not cacheable, and `Err` with the key to store
+    /// the response under on a miss.
+    pub(crate) fn lookup(&self, req: &Request) -> Result<Option<Response>, CacheKey> {
+        let path = req.uri().path();
+        let selected =
+            self.routes.is_empty() || self.routes.iter().any(|route| matches_prefix(route, path));
+
+        if req.method() != Method::GET || !selected {
+            return Ok(None);
+        }
+
+        let key = req
+            .uri()
+            .path_and_query()
+            .map_or(path, |path_and_query| path_and_query.as_str())
+            .to_string();
+        let authenticated = has_credentials(req.headers());
+
+        let now = Instant::now();
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.by_key.get(&key).and_then(|variants| {
+            variants.iter().find(|entry| {
+                entry.expires > now
+                    && (entry.shared || !authenticated)
+                    && entry
+                        .vary
+                        .iter()
+                        .all(|(name, value)| req.headers().get(name) == value.as_ref())
+            })
+        });
+
+        match entry {
+            Some(entry) => {
+                let mut response = Response::new(Body::from(entry.body.clone()));
+                *response.headers_mut() = entry.headers.clone();
+                response.headers_mut().insert(
+                    AGE,
+                    HeaderValue::from(now.duration_since(entry.stored).as_secs()),
+                );
+                response
+                    .headers_mut()
+                    .insert("x-warpdrive-cache", HeaderValue::from_static("hit"));
+                Ok(Some(response))
+            }
+            None => Err(CacheKey {
+                key,
+                headers: req.headers().clone(),
+            }),
+        }
+    }
+
+    /// Stores a response after a miss, if it is cacheable.
+    pub(crate) async fn store(&self, key: CacheKey, response: Response) -> Response {
+        let (mut parts, body) = response.into_parts();
+        parts
+            .headers
+            .insert("x-warpdrive-cache", HeaderValue::from_static("miss"));
+
+        let shared = is_shared(&parts.headers);
+        let vary = match self.vary(&parts.status, &parts.headers, &body) {
+            Some(vary) if shared || !has_credentials(&key.headers) => vary,
+            _ => return Response::from_parts(parts, body),
+        };
+
+        let body = match axum::body::to_bytes(body, self.max_bytes).await {
+            Ok(body) => body,
+            // The body was already partially read, so the error is passed on to the client.
+            Err(err) => {
+                let body = Body::from_stream(stream::once(async { Err::<Bytes, _>(err) }));
+                return Response::from_parts(parts, body);
+            }
+        };
+
+        let now = Instant::now();
+        let mut headers = parts.headers.clone();
+        headers.remove("x-warpdrive-cache");
+
+        let entry = Entry {
+            vary: vary
+                .into_iter()
+                .map(|name| {
+                    let value = key.headers.get(&name).cloned();
+                    (name, value)
+                })
+                .collect(),
+            headers,
+            body: body.clone(),
+            shared,
+            stored: now,
+            expires: now + self.ttl,
+        };
+
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key.key, entry, now, self.max_bytes);
+
+        Response::from_parts(parts, Body::from(body))
+    }
+
+    /// Returns the request headers the response varies on, or `None` if it is not cacheable.
+    fn vary(
+        &self,
+        status: &StatusCode,
+        headers: &HeaderMap,
+        body: &Body,
+    ) -> Option<Vec<HeaderName>> {
+        let size = body.size_hint().exact()?;
+        if *status != StatusCode::OK
+            || size > self.max_bytes as u64
+            || headers.contains_key(SET_COOKIE)
+        {
+            return None;
+        }
+
+        let uncacheable = cache_directives(headers).any(|directive| {
+            ["no-store", "no-cache", "private"]
+                .iter()
+                .any(|uncacheable| directive.eq_ignore_ascii_case(uncacheable))
+        });
+        if uncacheable || headers.contains_key("x-warpdrive-failover") {
+            return None;
+        }
+
+        let mut vary = Vec::new();
+        for value in headers.get_all(VARY) {
+            for name in value.to_str().ok()?.split(',') {
+                let name = name.trim();
+                if name == "*" {
+                    return None;
+                }
+                vary.push(HeaderName::try_from(name).ok()?);
+            }
+        }
+
+        Some(vary)
+    }
+}
+
+/// Returns `true` if a request carries credentials, so its response may be specific to a user.
+fn has_credentials(headers: &HeaderMap) -> bool {
+    headers.contains_key(AUTHORIZATION) || headers.contains_key(COOKIE)
+}
+
+/// Returns the names of the `Cache-Control` directives of a response, without their values.
+fn cache_directives(headers: &HeaderMap) -> impl Iterator<Item = &str> {
+    headers
+        .get_all(CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|directive| directive.split('=').next().unwrap_or_default().trim())
+}
+
+/// Returns `true` if a response may be stored for requests with credentials, as it is marked
+/// `public` or has an `s-maxage`.
+fn is_shared(headers: &HeaderMap) -> bool {
+    cache_directives(headers).any(|directive| {
+        directive.eq_ignore_ascii_case("public") || directive.eq_ignore_ascii_case("s-maxage")
+    })
+}
+
+impl Entries {
+    fn insert(&mut self, key: String, entry: Entry, now: Instant, max_bytes: usize) {
+        // Replace any existing entry for the same variant.
+        self.remove_where(|entry_key, existing| {
+            entry_key == key && (existing.vary == entry.vary || existing.expires <= now)
+        });
+
+        if self.bytes + entry.body.len() > max_bytes {
+            self.remove_where(|_, existing| existing.expires <= now);
+        }
+
+        while self.bytes + entry.body.len() > max_bytes {
+            let soonest = self
+                .by_key
+                .values()
+                .flatten()
+                .map(|entry| entry.expires)
+                .min();
+
+            match soonest {
+                Some(soonest) => self.remove_where(|_, existing| existing.expires <= soonest),
+                None => return,
+            }
+        }
+
+        self.bytes += entry.body.len();
+        self.by_key.entry(key).or_default().push(entry);
+    }
+
+    fn remove_where(&mut self, mut remove: impl FnMut(&str, &Entry) -> bool) {
+        let mut removed = 0;
+
+        self.by_key.retain(|key, variants| {
+            variants.retain(|entry| {
+                let keep = !remove(key, entry);
+                if !keep {
+                    removed += entry.body.len();
+                }
+                keep
+            });
+            !variants.is_empty()
+        });
+
+        self.bytes -= removed;
+    }
+}
+
+impl fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self.entries.lock().unwrap();
+
+        f.debug_struct("ResponseCache")
+            .field("ttl", &self.ttl)
+            .field("max_bytes", &self.max_bytes)
+            .field("routes", &self.routes)
+            .field("bytes", &entries.bytes)
+            .finish()
+    }
+}
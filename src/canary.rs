@@ -0,0 +1,197 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{http::StatusCode, response::Response};
+
+use crate::{error::Error, route::served_by_warp};
+
+/// The upper bounds of the latency histogram buckets, in milliseconds.
+const BUCKETS_MS: [u64; 11] = [5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000];
+
+/// Paired metrics for the Warp and Axum implementations of a canary route, as returned by
+/// [`Migration::canary_metrics`](crate::Migration::canary_metrics).
+///
+/// Both implementations are measured the same way, from when the request is dispatched until
+/// the response head is ready, so their latencies and success ratios can be compared directly
+/// when deciding whether to move the route further. Requests steered to Warp are only counted
+/// if the Warp filter produced their response, so those answered by the cache or the kill
+/// switch, rejected by the rate limit or circuit breaker, or served by failover or hedging to
+/// Axum are left out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanaryMetrics {
+    /// The path prefix of the route, as set with [`Migration::phase`](crate::Migration::phase).
+    pub route: String,
+    /// The requests served by Warp.
+    pub warp: CanaryStats,
+    /// The requests served by Axum.
+    pub axum: CanaryStats,
+}
+
+/// Latency and status distributions for one implementation of a canary route.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CanaryStats {
+    /// The number of requests served.
+    pub requests: u64,
+    /// The number of requests answered with a status below `500`.
+    pub successes: u64,
+    /// The number of requests answered with each status. Requests that failed at the boundary
+    /// are counted under the status they were answered with, such as `504` for timeouts.
+    pub statuses: BTreeMap<u16, u64>,
+    /// The time taken to produce the response head.
+    pub latency: LatencyHistogram,
+}
+
+/// A histogram of request latencies, with fixed buckets from 5 ms to 10 s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencyHistogram {
+    /// The count of each bucket in `BUCKETS_MS`, followed by the overflow bucket.
+    counts: [u64; BUCKETS_MS.len() + 1],
+    sum: Duration,
+}
+
+impl CanaryStats {
+    /// Returns the fraction of requests that succeeded, or `None` if there were none.
+    pub fn success_ratio(&self) -> Option<f64> {
+        (self.requests > 0).then(|| self.successes as f64 / self.requests as f64)
+    }
+
+    fn record(&mut self, status: StatusCode, latency: Duration) {
+        self.requests += 1;
+        if !status.is_server_error() {
+            self.successes += 1;
+        }
+        *self.statuses.entry(status.as_u16()).or_default() += 1;
+        self.latency.record(latency);
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        LatencyHistogram {
+            counts: [0; BUCKETS_MS.len() + 1],
+            sum: Duration::ZERO,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    /// Returns the cumulative count of each bucket, by upper bound, in the style of a
+    /// Prometheus histogram. The last bucket has no upper bound.
+    pub fn buckets(&self) -> Vec<(Option<Duration>, u64)> {
+        let bounds = BUCKETS_MS
+            .iter()
+            .map(|ms| Some(Duration::from_millis(*ms)))
+            .chain([None]);
+        let mut cumulative = 0;
+
+        bounds
+            .zip(self.counts)
+            .map(|(bound, count)| {
+                cumulative += count;
+                (bound, cumulative)
+            })
+            .collect()
+    }
+
+    /// Returns the number of latencies recorded.
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Returns the sum of the latencies recorded.
+    pub fn sum(&self) -> Duration {
+        self.sum
+    }
+
+    /// Returns the upper bound of the bucket containing the given quantile, from 0.0 to 1.0,
+    /// or `None` if no latencies were recorded or the quantile is in the overflow bucket.
+    pub fn quantile(&self, quantile: f64) -> Option<Duration> {
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+        let rank = ((quantile.clamp(0.0, 1.0) * count as f64).ceil() as u64).max(1);
+
+        self.buckets()
+            .into_iter()
+            .find(|(_, cumulative)| *cumulative >= rank)
+            .and_then(|(bound, _)| bound)
+    }
+
+    fn record(&mut self, latency: Duration) {
+        let bucket = BUCKETS_MS
+            .iter()
+            .position(|ms| latency <= Duration::from_millis(*ms))
+            .unwrap_or(BUCKETS_MS.len());
+        self.counts[bucket] += 1;
+        self.sum += latency;
+    }
+}
+
+/// Records canary metrics for every clone of a `Migration`.
+#[derive(Clone, Default)]
+pub(crate) struct CanaryRecorder {
+    routes: Arc<Mutex<BTreeMap<String, CanaryMetrics>>>,
+}
+
+impl CanaryRecorder {
+    /// Returns the metrics of each route, sorted by route.
+    pub(crate) fn metrics(&self) -> Vec<CanaryMetrics> {
+        self.routes.lock().unwrap().values().cloned().collect()
+    }
+
+    /// Records a response from Axum.
+    pub(crate) fn axum(&self, route: &str, response: &Response, started: Instant) {
+        self.record(route, |metrics| {
+            metrics.axum.record(response.status(), started.elapsed())
+        });
+    }
+
+    /// Continues timing a request that is to be served by Warp.
+    pub(crate) fn warp(&self, route: String, started: Instant) -> CanarySample {
+        CanarySample {
+            recorder: self.clone(),
+            route,
+            started,
+        }
+    }
+
+    fn record(&self, route: &str, record: impl FnOnce(&mut CanaryMetrics)) {
+        let mut routes = self.routes.lock().unwrap();
+        let metrics = routes
+            .entry(route.to_string())
+            .or_insert_with(|| CanaryMetrics {
+                route: route.to_string(),
+                warp: CanaryStats::default(),
+                axum: CanaryStats::default(),
+            });
+        record(metrics);
+    }
+}
+
+/// A canary request served by Warp, whose result is yet to be recorded.
+pub(crate) struct CanarySample {
+    recorder: CanaryRecorder,
+    route: String,
+    started: Instant,
+}
+
+impl CanarySample {
+    /// Records the Warp result, unless it was not produced by the Warp filter.
+    pub(crate) fn finish(self, result: &Result<Response, Error>) {
+        if !served_by_warp(result) {
+            return;
+        }
+
+        let status = match result {
+            Ok(response) => response.status(),
+            Err(err) => err.status(),
+        };
+        let latency = self.started.elapsed();
+        self.recorder
+            .record(&self.route, |metrics| metrics.warp.record(status, latency));
+    }
+}
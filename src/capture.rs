@@ -0,0 +1,352 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::Request,
+    http::{
+        HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Uri,
+        header::{AUTHORIZATION, COOKIE, Entry, PROXY_AUTHORIZATION, SET_COOKIE},
+    },
+    response::Response,
+};
+use serde_json::{Value, json};
+
+use crate::{
+    excerpt::{Excerpt, ExcerptBody},
+    prefix::{matches_prefix, trim_prefix},
+};
+
+/// The number of exchanges kept by default.
+const DEFAULT_CAPACITY: usize = 50;
+
+/// The largest body excerpt kept by default, in bytes.
+const DEFAULT_MAX_BODY: usize = 4 * 1024;
+
+/// A debug capture of live requests to a path prefix served by the Warp filter.
+///
+/// A sample of the requests under the prefix, one in every
+/// [`sample_every`](Capture::sample_every), is captured with its response, and the most
+/// recent [`capacity`](Capture::capacity) exchanges are kept in a ring buffer. They can be
+/// read with [`exchanges`](Capture::exchanges), or through the admin API by registering the
+/// capture with [`AdminRouter::capture`](crate::admin::AdminRouter::capture), to inspect a
+/// misbehaving route without redeploying.
+///
+/// Captures are sanitized and size-capped: the values of `Authorization`,
+/// `Proxy-Authorization`, `Cookie`, and `Set-Cookie`, and of any header added with
+/// [`redact_header`](Capture::redact_header), are replaced with `[redacted]`, and only the
+/// first [`max_body`](Capture::max_body) bytes of each body are kept. Bodies are not
+/// buffered: the request excerpt only covers what the Warp filter read, and an exchange is
+/// kept once its response body has been sent or dropped.
+///
+/// The capture is a cheap handle that can be cloned, and is applied with
+/// [`WarpService::with_capture`](crate::WarpService::with_capture).
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::{Capture, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("orders").map(|| "Orders").boxed();
+///
+/// let capture = Capture::new("/orders")
+///     .sample_every(10)
+///     .capacity(20)
+///     .redact_header("x-api-key");
+///
+/// let service = WarpService::new(filter).with_capture(capture.clone());
+///
+/// for exchange in capture.exchanges() {
+///     println!("{} {} -> {}", exchange.method, exchange.uri, exchange.status);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Capture {
+    prefix: String,
+    sample_every: u64,
+    capacity: usize,
+    max_body: usize,
+    redacted: Vec<HeaderName>,
+    state: Arc<State>,
+}
+
+#[derive(Default)]
+struct State {
+    seen: AtomicU64,
+    exchanges: Mutex<VecDeque<CapturedExchange>>,
+}
+
+/// A request and response kept by a [`Capture`].
+#[derive(Debug, Clone)]
+pub struct CapturedExchange {
+    /// When the request was received.
+    pub time: SystemTime,
+    /// The request method.
+    pub method: Method,
+    /// The request URI.
+    pub uri: Uri,
+    /// The request headers, with sensitive values redacted.
+    pub request_headers: HeaderMap,
+    /// The start of the request body, as read by the Warp filter.
+    pub request_body: Bytes,
+    /// Whether the request body was longer than the excerpt.
+    pub request_body_truncated: bool,
+    /// The response status.
+    pub status: StatusCode,
+    /// The response headers, with sensitive values redacted.
+    pub response_headers: HeaderMap,
+    /// The start of the response body.
+    pub response_body: Bytes,
+    /// Whether the response body was longer than the excerpt.
+    pub response_body_truncated: bool,
+    /// The time until the response head was ready.
+    pub duration: Duration,
+}
+
+impl CapturedExchange {
+    /// Describes the exchange as JSON, with bodies decoded as lossy UTF-8.
+    pub fn to_json(&self) -> Value {
+        let headers = |headers: &HeaderMap| {
+            headers
+                .iter()
+                .map(|(name, value)| {
+                    json!([name.as_str(), String::from_utf8_lossy(value.as_bytes())])
+                })
+                .collect::<Vec<_>>()
+        };
+        let time = self
+            .time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+
+        json!({
+            "time_ms": time.as_millis() as u64,
+            "method": self.method.as_str(),
+            "uri": self.uri.to_string(),
+            "request_headers": headers(&self.request_headers),
+            "request_body": String::from_utf8_lossy(&self.request_body),
+            "request_body_truncated": self.request_body_truncated,
+            "status": self.status.as_u16(),
+            "response_headers": headers(&self.response_headers),
+            "response_body": String::from_utf8_lossy(&self.response_body),
+            "response_body_truncated": self.response_body_truncated,
+            "duration_ms": self.duration.as_secs_f64() * 1000.0,
+        })
+    }
+}
+
+impl Capture {
+    /// Creates a capture of every request under a path prefix, keeping the last 50
+    /// exchanges with bodies of up to 4 KiB.
+    pub fn new(prefix: &str) -> Self {
+        Capture {
+            prefix: trim_prefix(prefix),
+            sample_every: 1,
+            capacity: DEFAULT_CAPACITY,
+            max_body: DEFAULT_MAX_BODY,
+            redacted: vec![AUTHORIZATION, PROXY_AUTHORIZATION, COOKIE, SET_COOKIE],
+            state: Arc::default(),
+        }
+    }
+
+    /// Captures one in every `n` requests under the prefix. Defaults to every request.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn sample_every(mut self, n: u64) -> Self {
+        assert!(n > 0, "sample interval must be at least 1");
+        self.sample_every = n;
+        self
+    }
+
+    /// Sets the number of exchanges kept, dropping the oldest first. Defaults to 50.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets the largest excerpt kept from each body, in bytes. Defaults to 4 KiB.
+    pub fn max_body(mut self, max_body: usize) -> Self {
+        self.max_body = max_body;
+        self
+    }
+
+    /// Redacts the value of a request and response header, in addition to the defaults.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid header name.
+    pub fn redact_header(mut self, name: &str) -> Self {
+        self.redacted
+            .push(HeaderName::try_from(name).expect("invalid header name"));
+        self
+    }
+
+    /// Returns the captured exchanges, oldest first.
+    pub fn exchanges(&self) -> Vec<CapturedExchange> {
+        self.state
+            .exchanges
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Discards the captured exchanges.
+    pub fn clear(&self) {
+        self.state.exchanges.lock().unwrap().clear();
+    }
+
+    /// Starts capturing a request if it is under the prefix and sampled.
+    pub(crate) fn start(&self, req: Request) -> (Request, Option<CapturedRequest>) {
+        if !matches_prefix(&self.prefix, req.uri().path()) {
+            return (req, None);
+        }
+        let seen = self.state.seen.fetch_add(1, Ordering::Relaxed);
+        if !seen.is_multiple_of(self.sample_every) {
+            return (req, None);
+        }
+
+        let request_excerpt = Arc::new(Mutex::new(Excerpt::default()));
+        let captured = CapturedRequest {
+            capture: self.clone(),
+            started: Instant::now(),
+            time: SystemTime::now(),
+            method: req.method().clone(),
+            uri: req.uri().clone(),
+            headers: self.sanitize(req.headers()),
+            request_excerpt: Arc::clone(&request_excerpt),
+        };
+
+        let req = req.map(|body| {
+            Body::new(ExcerptBody::<Done>::new(
+                body,
+                request_excerpt,
+                self.max_body,
+                None,
+            ))
+        });
+        (req, Some(captured))
+    }
+
+    fn sanitize(&self, headers: &HeaderMap) -> HeaderMap {
+        let mut sanitized = headers.clone();
+        for name in &self.redacted {
+            if let Entry::Occupied(mut entry) = sanitized.entry(name) {
+                for value in entry.iter_mut() {
+                    *value = HeaderValue::from_static("[redacted]");
+                }
+            }
+        }
+        sanitized
+    }
+
+    fn push(&self, exchange: CapturedExchange) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut exchanges = self.state.exchanges.lock().unwrap();
+        while exchanges.len() >= self.capacity {
+            exchanges.pop_front();
+        }
+        exchanges.push_back(exchange);
+    }
+}
+
+impl fmt::Debug for Capture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Capture")
+            .field("prefix", &self.prefix)
+            .field("sample_every", &self.sample_every)
+            .field("capacity", &self.capacity)
+            .field("max_body", &self.max_body)
+            .field("redacted", &self.redacted)
+            .finish()
+    }
+}
+
+/// A captured request, whose response is yet to be recorded.
+pub(crate) struct CapturedRequest {
+    capture: Capture,
+    started: Instant,
+    time: SystemTime,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    request_excerpt: Arc<Mutex<Excerpt>>,
+}
+
+impl CapturedRequest {
+    /// Records the response, keeping the exchange once its body has been sent or dropped.
+    pub(crate) fn finish(self, response: Response) -> Response {
+        let (parts, body) = response.into_parts();
+        let limit = self.capture.max_body;
+        let response_headers = self.capture.sanitize(&parts.headers);
+        let done = self.into_done(parts.status, response_headers);
+
+        let body = Body::new(ExcerptBody::new(
+            body,
+            Arc::clone(&done.response_excerpt),
+            limit,
+            Some(done),
+        ));
+        Response::from_parts(parts, body)
+    }
+
+    /// Records a request that failed at the boundary, keeping the exchange immediately.
+    pub(crate) fn fail(self, status: StatusCode) {
+        drop(self.into_done(status, HeaderMap::new()));
+    }
+
+    fn into_done(self, status: StatusCode, response_headers: HeaderMap) -> Done {
+        Done {
+            exchange: Some(CapturedExchange {
+                time: self.time,
+                method: self.method,
+                uri: self.uri,
+                request_headers: self.headers,
+                request_body: Bytes::new(),
+                request_body_truncated: false,
+                status,
+                response_headers,
+                response_body: Bytes::new(),
+                response_body_truncated: false,
+                duration: self.started.elapsed(),
+            }),
+            capture: self.capture,
+            request_excerpt: self.request_excerpt,
+            response_excerpt: Arc::default(),
+        }
+    }
+}
+
+/// Keeps the exchange when dropped.
+struct Done {
+    capture: Capture,
+    exchange: Option<CapturedExchange>,
+    request_excerpt: Arc<Mutex<Excerpt>>,
+    response_excerpt: Arc<Mutex<Excerpt>>,
+}
+
+impl Drop for Done {
+    fn drop(&mut self) {
+        let Some(mut exchange) = self.exchange.take() else {
+            return;
+        };
+        (exchange.request_body, exchange.request_body_truncated) =
+            self.request_excerpt.lock().unwrap().take();
+        (exchange.response_body, exchange.response_body_truncated) =
+            self.response_excerpt.lock().unwrap().take();
+        self.capture.push(exchange);
+    }
+}
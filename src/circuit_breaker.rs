@@ -0,0 +1,471 @@
+use std::{
+    convert::Infallible,
+    fmt,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{extract::Request, http::HeaderValue, response::Response};
+use tower::{Service, ServiceExt, util::BoxCloneSyncService};
+
+use crate::{
+    bounded::{BoundedMap, DEFAULT_MAX_KEYS},
+    error::Error,
+    route::{RouteKeyFn, route_failed, route_of},
+};
+
+type StateHook = Arc<dyn Fn(&str, CircuitState) + Send + Sync>;
+
+/// A per-route circuit breaker for a [`WarpService`](crate::WarpService).
+///
+/// Each route has a circuit that counts requests and failures over a sliding window. By
+/// default, the whole service shares one circuit, reported as the route `*`. Use
+/// [`route_key`](CircuitBreaker::route_key) to give each route its own circuit, keyed by
+/// the route rather than the raw path, so that `/users/1` and `/users/2` share a circuit
+/// and a client cannot trip a circuit for a single id. A request fails if it is answered with
+/// a `5xx` status, fails at the boundary, or takes longer than the
+/// [slow threshold](CircuitBreaker::slow). Requests rejected by the
+/// [rate limit](crate::WarpService::with_rate_limit) or dropped before completing, such as
+/// when the client disconnects, are not counted. Once at least
+/// [`min_requests`](CircuitBreaker::min_requests) have been seen in a window and the share of
+/// failures reaches the [error rate](CircuitBreaker::error_rate), the circuit opens.
+///
+/// While a circuit is open, requests for its route do not reach the Warp filter. They are
+/// served by the [fallback](CircuitBreaker::fallback) Axum service, with an
+/// `x-warpdrive-circuit: open` header, or fail with
+/// [`Error::CircuitOpen`](crate::Error::CircuitOpen), a `503 Service Unavailable`. After the
+/// [open duration](CircuitBreaker::open_for), the circuit is half-open: a single probe
+/// request is sent to the Warp filter, closing the circuit if it succeeds and opening it again
+/// if it fails. If the probe is rate limited, the next request is sent as the probe instead.
+///
+/// At most [`max_routes`](CircuitBreaker::max_routes) circuits are tracked. Once that many
+/// are tracked, circuits that have been idle for a whole window are forgotten, at most once
+/// per window, and new routes that still do not fit share a circuit reported as `(other)`.
+/// Open circuits count as idle once they have been open for a window past the open
+/// duration without a probe, and are reported as closed when forgotten.
+///
+/// The circuit breaker applies inside [failover](crate::WarpService::with_failover), so
+/// failed requests still fail over while the circuit is closed. It is applied with
+/// [`WarpService::with_circuit_breaker`](crate::WarpService::with_circuit_breaker).
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use axum::routing::get;
+/// use warpdrive::{CircuitBreaker, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("users").map(|| "Users from Warp").boxed();
+///
+/// // Serve from Axum for 30 seconds once half of the last 10 seconds of requests failed or
+/// // took longer than 2 seconds.
+/// let breaker = CircuitBreaker::new()
+///     .window(Duration::from_secs(10))
+///     .error_rate(0.5)
+///     .slow(Duration::from_secs(2))
+///     .open_for(Duration::from_secs(30))
+///     .fallback(get(|| async { "Users from Axum" }))
+///     .on_state_change(|route, state| eprintln!("Circuit for {} is now {:?}", route, state));
+///
+/// let service = WarpService::new(filter).with_circuit_breaker(breaker);
+/// ```
+pub struct CircuitBreaker {
+    route_key: Option<RouteKeyFn>,
+    window: Duration,
+    min_requests: u32,
+    error_rate: f64,
+    slow: Option<Duration>,
+    open_for: Duration,
+    fallback: Option<BoxCloneSyncService<Request, Response, Infallible>>,
+    on_state_change: Option<StateHook>,
+    circuits: Mutex<BoundedMap<Circuit>>,
+}
+
+/// The state of a route's circuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests reach the Warp filter.
+    Closed,
+    /// Requests are served by the fallback, or fail.
+    Open,
+    /// A probe request is testing whether the Warp filter has recovered.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct Circuit {
+    state: CircuitState,
+    window_start: Instant,
+    requests: u32,
+    failures: u32,
+    opened_at: Instant,
+    probing: bool,
+}
+
+impl Circuit {
+    fn new(now: Instant) -> Self {
+        Circuit {
+            state: CircuitState::Closed,
+            window_start: now,
+            requests: 0,
+            failures: 0,
+            opened_at: now,
+            probing: false,
+        }
+    }
+}
+
+/// A request allowed through a circuit, whose outcome is recorded when it completes.
+///
+/// Requests dropped before completing, such as when the client disconnects, are not recorded.
+/// A dropped probe is recorded as a failure instead, so a half-open circuit is never left
+/// waiting for a probe.
+struct Permit<'a> {
+    breaker: &'a CircuitBreaker,
+    route: String,
+    probe: bool,
+    started: Instant,
+    recorded: bool,
+}
+
+impl Permit<'_> {
+    /// Records whether the request failed, or releases the permit without recording it if
+    /// the outcome says nothing about the route, as for rate limited requests.
+    fn record(mut self, failed: Option<bool>) {
+        self.recorded = true;
+        let Some(failed) = failed else {
+            self.breaker.release(&self.route, self.probe);
+            return;
+        };
+
+        let slow = self
+            .breaker
+            .slow
+            .is_some_and(|slow| self.started.elapsed() > slow);
+        self.breaker.record(&self.route, self.probe, failed || slow);
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        if !self.recorded && self.probe {
+            self.breaker.record(&self.route, true, true);
+        }
+    }
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        CircuitBreaker::new()
+    }
+}
+
+impl CircuitBreaker {
+    /// Creates a circuit breaker that opens a route's circuit for 30 seconds once half of at
+    /// least 20 requests in a 10 second window fail.
+    pub fn new() -> Self {
+        CircuitBreaker {
+            route_key: None,
+            window: Duration::from_secs(10),
+            min_requests: 20,
+            error_rate: 0.5,
+            slow: None,
+            open_for: Duration::from_secs(30),
+            fallback: None,
+            on_state_change: None,
+            circuits: Mutex::new(BoundedMap::new(DEFAULT_MAX_KEYS, Duration::from_secs(10))),
+        }
+    }
+
+    /// Gives each route its own circuit, identified by the given function, such as the method
+    /// and the path with ids replaced by placeholders.
+    ///
+    /// Keys should come from a bounded set of routes. Keying by the raw path lets requests for
+    /// unknown paths, such as scans answered with `404 Not Found`, create circuits.
+    pub fn route_key<F>(mut self, route_key: F) -> Self
+    where
+        F: Fn(&Request) -> String + Send + Sync + 'static,
+    {
+        self.route_key = Some(Arc::new(route_key));
+        self
+    }
+
+    /// Sets the largest number of circuits tracked, after which new routes share one
+    /// circuit. Defaults to 10,000.
+    pub fn max_routes(mut self, max_routes: usize) -> Self {
+        self.circuits.get_mut().unwrap().set_max_keys(max_routes);
+        self
+    }
+
+    /// Sets the window over which requests and failures are counted. Defaults to 10 seconds.
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self.circuits.get_mut().unwrap().set_prune_interval(window);
+        self
+    }
+
+    /// Sets the number of requests in a window below which the circuit never opens. Defaults
+    /// to 20.
+    pub fn min_requests(mut self, min_requests: u32) -> Self {
+        self.min_requests = min_requests;
+        self
+    }
+
+    /// Sets the share of failed requests, between `0.0` and `1.0`, at which the circuit
+    /// opens. Defaults to `0.5`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `error_rate` is not between `0.0` and `1.0`.
+    pub fn error_rate(mut self, error_rate: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&error_rate),
+            "error rate must be between 0.0 and 1.0"
+        );
+        self.error_rate = error_rate;
+        self
+    }
+
+    /// Counts requests that take longer than `threshold` as failures.
+    pub fn slow(mut self, threshold: Duration) -> Self {
+        self.slow = Some(threshold);
+        self
+    }
+
+    /// Sets how long a circuit stays open before a probe request is sent. Defaults to
+    /// 30 seconds.
+    pub fn open_for(mut self, open_for: Duration) -> Self {
+        self.open_for = open_for;
+        self
+    }
+
+    /// Serves requests with the given Axum service while their circuit is open, instead of
+    /// failing with `503 Service Unavailable`.
+    pub fn fallback<S>(mut self, fallback: S) -> Self
+    where
+        S: Service<Request, Response = Response, Error = Infallible>
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        S::Future: Send + 'static,
+    {
+        self.fallback = Some(BoxCloneSyncService::new(fallback));
+        self
+    }
+
+    /// Calls a function whenever a route's circuit changes state, for logging or alerting.
+    pub fn on_state_change<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&str, CircuitState) + Send + Sync + 'static,
+    {
+        self.on_state_change = Some(Arc::new(hook));
+        self
+    }
+
+    /// Returns the state of a route's circuit. Routes that have not been requested are
+    /// closed.
+    pub fn state(&self, route: &str) -> CircuitState {
+        self.circuits
+            .lock()
+            .unwrap()
+            .get(route)
+            .map_or(CircuitState::Closed, |circuit| circuit.state)
+    }
+
+    /// Returns the routes whose circuit is open or half-open, sorted by route.
+    pub fn open_routes(&self) -> Vec<String> {
+        let mut routes: Vec<_> = self
+            .circuits
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, circuit)| circuit.state != CircuitState::Closed)
+            .map(|(route, _)| route.clone())
+            .collect();
+        routes.sort();
+        routes
+    }
+
+    /// Returns `true` if requests are served by a fallback while their circuit is open.
+    pub(crate) fn has_fallback(&self) -> bool {
+        self.fallback.is_some()
+    }
+
+    /// Runs a request through `attempt` if its circuit allows it, recording the outcome.
+    pub(crate) async fn run<F, Fut>(&self, req: Request, attempt: F) -> Result<Response, Error>
+    where
+        F: FnOnce(Request) -> Fut,
+        Fut: Future<Output = Result<Response, Error>>,
+    {
+        let route = route_of(self.route_key.as_ref(), &req);
+
+        let Some(permit) = self.acquire(route) else {
+            return self.reject(req).await;
+        };
+
+        let result = attempt(req).await;
+        permit.record(route_failed(&result));
+
+        result
+    }
+
+    /// Circuits forgotten to make room for the route are reported as closed once the lock is
+    /// released.
+    fn acquire(&self, route: String) -> Option<Permit<'_>> {
+        let mut forgotten = Vec::new();
+        let permit = self.try_acquire(route, &mut forgotten);
+        for route in &forgotten {
+            self.notify(route, CircuitState::Closed);
+        }
+        permit
+    }
+
+    fn try_acquire(&self, route: String, forgotten: &mut Vec<String>) -> Option<Permit<'_>> {
+        let now = Instant::now();
+        let mut circuits = self.circuits.lock().unwrap();
+
+        let route = circuits.admit(route, now, |route, circuit| {
+            let idle = match circuit.state {
+                // Closed circuits whose window has passed are equivalent to new ones.
+                CircuitState::Closed => now.duration_since(circuit.window_start) > self.window,
+                CircuitState::Open | CircuitState::HalfOpen => {
+                    !circuit.probing
+                        && now.duration_since(circuit.opened_at) > self.open_for + self.window
+                }
+            };
+            if idle && circuit.state != CircuitState::Closed {
+                forgotten.push(route.to_string());
+            }
+            idle
+        });
+
+        let circuit = circuits
+            .entry(route.clone())
+            .or_insert_with(|| Circuit::new(now));
+
+        let probe = match circuit.state {
+            CircuitState::Closed => {
+                if now.duration_since(circuit.window_start) > self.window {
+                    circuit.window_start = now;
+                    circuit.requests = 0;
+                    circuit.failures = 0;
+                }
+                false
+            }
+            CircuitState::Open if now.duration_since(circuit.opened_at) < self.open_for => {
+                return None;
+            }
+            CircuitState::Open | CircuitState::HalfOpen => {
+                if circuit.probing {
+                    return None;
+                }
+                circuit.probing = true;
+                if circuit.state == CircuitState::Open {
+                    circuit.state = CircuitState::HalfOpen;
+                    drop(circuits);
+                    self.notify(&route, CircuitState::HalfOpen);
+                }
+                true
+            }
+        };
+
+        Some(Permit {
+            breaker: self,
+            route,
+            probe,
+            started: now,
+            recorded: false,
+        })
+    }
+
+    /// Lets another request probe the route, leaving the circuit's counts unchanged.
+    fn release(&self, route: &str, probe: bool) {
+        if !probe {
+            return;
+        }
+        if let Some(circuit) = self.circuits.lock().unwrap().get_mut(route) {
+            circuit.probing = false;
+        }
+    }
+
+    fn record(&self, route: &str, probe: bool, failed: bool) {
+        let now = Instant::now();
+        let mut circuits = self.circuits.lock().unwrap();
+        let Some(circuit) = circuits.get_mut(route) else {
+            return;
+        };
+
+        let changed = if probe {
+            circuit.probing = false;
+            if failed {
+                circuit.state = CircuitState::Open;
+                circuit.opened_at = now;
+            } else {
+                *circuit = Circuit::new(now);
+            }
+            Some(circuit.state)
+        } else if circuit.state == CircuitState::Closed {
+            circuit.requests += 1;
+            circuit.failures += u32::from(failed);
+
+            let tripped = circuit.requests >= self.min_requests
+                && circuit.failures as f64 >= self.error_rate * circuit.requests as f64;
+            if failed && tripped {
+                circuit.state = CircuitState::Open;
+                circuit.opened_at = now;
+                Some(CircuitState::Open)
+            } else {
+                None
+            }
+        } else {
+            // Requests that started before the circuit opened.
+            None
+        };
+
+        drop(circuits);
+        if let Some(state) = changed {
+            self.notify(route, state);
+        }
+    }
+
+    async fn reject(&self, req: Request) -> Result<Response, Error> {
+        let Some(fallback) = &self.fallback else {
+            return Err(Error::CircuitOpen);
+        };
+
+        let mut response = match fallback.clone().oneshot(req).await {
+            Ok(response) => response,
+            Err(never) => match never {},
+        };
+        response
+            .headers_mut()
+            .insert("x-warpdrive-circuit", HeaderValue::from_static("open"));
+
+        Ok(response)
+    }
+
+    fn notify(&self, route: &str, state: CircuitState) {
+        if let Some(hook) = &self.on_state_change {
+            hook(route, state);
+        }
+    }
+}
+
+impl fmt::Debug for CircuitBreaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("window", &self.window)
+            .field("min_requests", &self.min_requests)
+            .field("error_rate", &self.error_rate)
+            .field("slow", &self.slow)
+            .field("open_for", &self.open_for)
+            .field("fallback", &self.fallback.is_some())
+            .field("on_state_change", &self.on_state_change.is_some())
+            .field("circuits", &self.circuits.lock().unwrap().len())
+            .finish()
+    }
+}
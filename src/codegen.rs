@@ -0,0 +1,282 @@
+//! Scaffolding of Axum handlers for legacy routes.
+//!
+//! [`Scaffold`] takes the routes recorded by a [`UsageReport`](crate::UsageReport), or listed
+//! by hand from a route registry, and emits Rust source with a skeleton handler for each route
+//! and a `router` function that registers them. The handlers extract their path parameters and
+//! end in `todo!()`, so the generated module compiles as soon as it is added, and the
+//! mechanical part of a migration is done before the first handler is written.
+//!
+//! This module is available with the `codegen` feature.
+//!
+//! # Example
+//!
+//! ```rust
+//! use axum::http::Method;
+//! use warpdrive::codegen::Scaffold;
+//!
+//! let source = Scaffold::new()
+//!     .route(Method::GET, "/users/{id}")
+//!     .route(Method::DELETE, "/users/{id}")
+//!     .render();
+//!
+//! assert!(source.contains("pub async fn get_users_id(Path(id): Path<String>) -> Response {"));
+//! assert!(source.contains(r#".route("/users/{id}", delete(delete_users_id).get(get_users_id))"#));
+//! ```
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use axum::http::Method;
+
+use crate::usage::RouteUsage;
+
+/// The methods with a routing function in `axum::routing`.
+const ROUTING_METHODS: [&str; 8] = [
+    "delete", "get", "head", "options", "patch", "post", "put", "trace",
+];
+
+/// Rust keywords that cannot be used as identifiers without escaping.
+const KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+    "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe",
+    "use", "where", "while",
+];
+
+/// A generator of Axum handler skeletons and router registrations for legacy routes.
+///
+/// Routes are given as a method and an Axum path, with parameters written as `{name}`, such
+/// as `/users/{id}`. Each handler is named after its method and path, and its doc comment
+/// records what is known about the route, such as its traffic and owner when built from
+/// usage. Methods without a routing function in `axum::routing`, such as `CONNECT`, are
+/// listed in a comment rather than registered.
+#[derive(Debug, Clone, Default)]
+pub struct Scaffold {
+    /// Notes on each route, keyed by path and method.
+    routes: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+}
+
+impl Scaffold {
+    /// Creates a scaffold with no routes.
+    pub fn new() -> Self {
+        Scaffold::default()
+    }
+
+    /// Adds a route.
+    pub fn route(mut self, method: Method, path: &str) -> Self {
+        self.insert(method, path, Vec::new());
+        self
+    }
+
+    /// Adds the routes recorded by a [`UsageReport`](crate::UsageReport), from its
+    /// [`snapshot`](crate::UsageReport::snapshot).
+    ///
+    /// Routes are parsed as `METHOD /path`, as they are keyed by default, and routes that
+    /// cannot be parsed, such as the `(other)` route, are skipped. Use a
+    /// [`route_key`](crate::UsageReport::route_key) that writes path parameters as `{name}`
+    /// so that requests to the same route are scaffolded as one handler.
+    pub fn from_usage(routes: &[RouteUsage]) -> Self {
+        let mut scaffold = Scaffold::new();
+
+        for usage in routes {
+            let Some((method, path)) = usage.route.split_once(' ') else {
+                continue;
+            };
+            let Ok(method) = Method::from_bytes(method.as_bytes()) else {
+                continue;
+            };
+            if !path.starts_with('/') {
+                continue;
+            }
+
+            let mut notes = vec![format!(
+                "Served {} requests, {} with server errors.",
+                usage.hits, usage.errors
+            )];
+            if !usage.statuses.is_empty() {
+                let statuses: Vec<_> = usage.statuses.keys().map(u16::to_string).collect();
+                notes.push(format!("Responded with {}.", statuses.join(", ")));
+            }
+            if !usage.content_types.is_empty() {
+                let content_types: Vec<_> = usage
+                    .content_types
+                    .iter()
+                    .map(|content_type| format!("`{}`", content_type))
+                    .collect();
+                notes.push(format!("Content types: {}.", content_types.join(", ")));
+            }
+            if let Some(owner) = &usage.owner {
+                match &owner.target_date {
+                    Some(date) => notes.push(format!("Owned by {}, due {}.", owner.team, date)),
+                    None => notes.push(format!("Owned by {}.", owner.team)),
+                }
+            }
+
+            scaffold.insert(method, path, notes);
+        }
+
+        scaffold
+    }
+
+    /// Returns the number of routes.
+    pub fn len(&self) -> usize {
+        self.routes.values().map(BTreeMap::len).sum()
+    }
+
+    /// Returns `true` if no routes have been added.
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Renders the handlers and the `router` function as the source of a Rust module.
+    pub fn render(&self) -> String {
+        let mut names: BTreeMap<String, usize> = BTreeMap::new();
+        let mut handlers = String::new();
+        let mut registrations = String::new();
+        let mut unroutable = Vec::new();
+        let mut routing: Vec<&str> = Vec::new();
+        let mut uses_path = false;
+
+        for (path, methods) in &self.routes {
+            let params = path_params(path);
+            let mut chain = Vec::new();
+
+            for (method, notes) in methods {
+                let route = format!("{} {}", method.to_ascii_uppercase(), path);
+                let Some(routing_fn) = ROUTING_METHODS.iter().find(|name| **name == method) else {
+                    unroutable.push(route);
+                    continue;
+                };
+
+                let mut name = identifier(&format!("{}_{}", method, path));
+                if name == *method {
+                    name.push_str("_root");
+                }
+                let count = names.entry(name.clone()).or_default();
+                *count += 1;
+                if *count > 1 {
+                    name = format!("{}_{}", name, count);
+                }
+
+                writeln!(handlers, "/// `{}`", route).unwrap();
+                if !notes.is_empty() {
+                    writeln!(handlers, "///").unwrap();
+                    for note in notes {
+                        writeln!(handlers, "/// {}", note).unwrap();
+                    }
+                }
+                writeln!(
+                    handlers,
+                    "pub async fn {}({}) -> Response {{",
+                    name,
+                    extractor(&params)
+                )
+                .unwrap();
+                // Braces are escaped, as the message is a format string.
+                let message = route.replace('{', "{{").replace('}', "}}");
+                writeln!(handlers, "    todo!(\"migrate {}\")", message).unwrap();
+                writeln!(handlers, "}}\n").unwrap();
+
+                if !routing.contains(routing_fn) {
+                    routing.push(routing_fn);
+                }
+                uses_path |= !params.is_empty();
+                chain.push(if chain.is_empty() {
+                    format!("{}({})", routing_fn, name)
+                } else {
+                    format!(".{}({})", routing_fn, name)
+                });
+            }
+
+            if !chain.is_empty() {
+                writeln!(
+                    registrations,
+                    "        .route({:?}, {})",
+                    path,
+                    chain.concat()
+                )
+                .unwrap();
+            }
+        }
+
+        routing.sort_unstable();
+        let mut imports = vec!["Router".to_string()];
+        if uses_path {
+            imports.push("extract::Path".to_string());
+        }
+        imports.push("response::Response".to_string());
+        if !routing.is_empty() {
+            imports.push(format!("routing::{{{}}}", routing.join(", ")));
+        }
+
+        let mut source = String::new();
+        writeln!(
+            source,
+            "//! Axum handlers scaffolded from legacy Warp routes.\n\n#![allow(unused_variables)]\n"
+        )
+        .unwrap();
+        writeln!(source, "use axum::{{{}}};\n", imports.join(", ")).unwrap();
+        source.push_str(&handlers);
+        if !unroutable.is_empty() {
+            writeln!(source, "// Routes without an `axum::routing` function:").unwrap();
+            for route in &unroutable {
+                writeln!(source, "// - `{}`", route).unwrap();
+            }
+            writeln!(source).unwrap();
+        }
+        writeln!(source, "/// Registers the scaffolded handlers.").unwrap();
+        writeln!(source, "pub fn router() -> Router {{").unwrap();
+        write!(source, "    Router::new()\n{}", registrations).unwrap();
+        writeln!(source, "}}").unwrap();
+
+        source
+    }
+
+    fn insert(&mut self, method: Method, path: &str, notes: Vec<String>) {
+        self.routes
+            .entry(path.to_string())
+            .or_default()
+            .insert(method.as_str().to_ascii_lowercase(), notes);
+    }
+}
+
+/// Returns the names of the `{name}` parameters of a path.
+fn path_params(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .map(|name| identifier(name.trim_start_matches('*')))
+        .collect()
+}
+
+/// Returns the extractor arguments of a handler with the given path parameters.
+fn extractor(params: &[String]) -> String {
+    match params {
+        [] => String::new(),
+        [param] => format!("Path({}): Path<String>", param),
+        params => format!(
+            "Path(({})): Path<({})>",
+            params.join(", "),
+            vec!["String"; params.len()].join(", ")
+        ),
+    }
+}
+
+/// Converts text into a snake case Rust identifier.
+fn identifier(text: &str) -> String {
+    let mut ident = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            ident.push(c.to_ascii_lowercase());
+        } else if !ident.is_empty() && !ident.ends_with('_') {
+            ident.push('_');
+        }
+    }
+    let ident = ident.trim_end_matches('_');
+
+    if ident.is_empty() {
+        "root".to_string()
+    } else if ident.starts_with(|c: char| c.is_ascii_digit()) || KEYWORDS.contains(&ident) {
+        format!("r_{}", ident)
+    } else {
+        ident.to_string()
+    }
+}
@@ -0,0 +1,335 @@
+//! Tower adapters between `http` 0.2 and `http` 1.0 services.
+//!
+//! [`HttpCompatService`] wraps a service written against one version of the `http` crate so it
+//! can be called with the other, independently of Warp. The direction is chosen by the inner
+//! service's request type:
+//!
+//! - A service accepting `http` 0.2 requests with a hyper 0.14 body can be called with `http`
+//!   1.0 requests, such as from an Axum router.
+//! - A service accepting `http` 1.0 requests with an Axum body can be called with `http` 0.2
+//!   requests, such as from a hyper 0.14 server.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::convert::Infallible;
+//!
+//! use axum::{Router, routing::get};
+//! use tower::{Layer, ServiceBuilder};
+//! use warp::http::{Request, Response};
+//! use warp::hyper::Body;
+//! use warpdrive::compat::HttpCompatLayer;
+//!
+//! // A hyper 0.14 service mounted in Axum.
+//! let legacy = ServiceBuilder::new()
+//!     .layer(HttpCompatLayer::new())
+//!     .service_fn(|_req: Request<Body>| async {
+//!         Ok::<_, Infallible>(Response::new(Body::from("Hello from hyper!")))
+//!     });
+//! let app: Router = Router::new().fallback_service(legacy);
+//!
+//! // An Axum router served by hyper 0.14.
+//! let router: Router = Router::new().route("/", get(|| async { "Hello from Axum!" }));
+//! let make_service = HttpCompatLayer::new().layer(router).into_hyper_make_service();
+//! ```
+//!
+//! [`CompatBody`] implements the body traits of both versions, so bodies can be passed across
+//! the boundary without being re-wrapped.
+
+use std::{
+    convert::Infallible,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::{Body, Bytes, HttpBody},
+    http,
+    response::{IntoResponse, Response},
+};
+use futures::Future;
+use tower::{BoxError, Layer, Service, ServiceExt};
+use warp::http::{Request as WarpRequest, Response as WarpResponse};
+use warp::hyper::body::Body as WarpBody;
+
+pub use crate::compat_body::CompatBody;
+use crate::{
+    convert_request::{into_axum_request, into_warp_request},
+    convert_response::{convert_response_head, into_axum_response},
+    error::Error,
+};
+
+/// A Tower layer that wraps services in an [`HttpCompatService`].
+#[derive(Debug, Clone, Default)]
+pub struct HttpCompatLayer {
+    _private: (),
+}
+
+impl HttpCompatLayer {
+    /// Creates a new `HttpCompatLayer`.
+    pub fn new() -> Self {
+        HttpCompatLayer { _private: () }
+    }
+}
+
+impl<S> Layer<S> for HttpCompatLayer {
+    type Service = HttpCompatService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HttpCompatService::new(inner)
+    }
+}
+
+/// A Tower service that adapts an inner service between `http` 0.2 and `http` 1.0 types.
+///
+/// See the [module documentation](self) for the supported directions.
+///
+/// Errors returned by the inner service are reported as [`Error::Service`], which is converted
+/// into a `500 Internal Server Error` response. Conversion failures are converted into the same
+/// responses as [`WarpService`](crate::WarpService).
+///
+/// # gRPC
+///
+/// gRPC services, such as older tonic services, can be bridged in either direction. Headers
+/// such as `te: trailers` and the HTTP version are preserved, and response trailers such as
+/// `grpc-status` are passed through using [`CompatBody`]. Errors for gRPC requests are
+/// returned as trailers-only responses with a `grpc-status` of `INTERNAL`, or
+/// `DEADLINE_EXCEEDED` for timeouts.
+#[derive(Debug, Clone)]
+pub struct HttpCompatService<S> {
+    inner: S,
+}
+
+impl<S> HttpCompatService<S> {
+    /// Creates a new `HttpCompatService` wrapping the given service.
+    pub fn new(inner: S) -> Self {
+        HttpCompatService { inner }
+    }
+
+    /// Consumes the adapter, returning the wrapped service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Converts this service into a hyper 0.14 make service, which creates a copy of the
+    /// service for each connection.
+    ///
+    /// This allows an Axum `Router` to be served by an existing hyper 0.14 server.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use axum::{Router, routing::get};
+    /// use warpdrive::compat::HttpCompatService;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let router: Router = Router::new().route("/", get(|| async { "Hello from Axum!" }));
+    ///
+    /// warp::hyper::Server::bind(&([127, 0, 0, 1], 3000).into())
+    ///     .serve(HttpCompatService::new(router).into_hyper_make_service())
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn into_hyper_make_service(self) -> HyperMakeService<S> {
+        HyperMakeService { service: self }
+    }
+}
+
+/// A hyper 0.14 make service that creates an [`HttpCompatService`] for each connection.
+///
+/// Created with [`HttpCompatService::into_hyper_make_service`].
+#[derive(Debug, Clone)]
+pub struct HyperMakeService<S> {
+    service: HttpCompatService<S>,
+}
+
+impl<S, T> Service<T> for HyperMakeService<S>
+where
+    S: Clone,
+{
+    type Response = HttpCompatService<S>;
+    type Error = Infallible;
+    type Future = futures::future::Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _target: T) -> Self::Future {
+        futures::future::ready(Ok(self.service.clone()))
+    }
+}
+
+/// Calls an `http` 0.2 service with an `http` 1.0 request.
+impl<S, B> Service<http::Request<B>> for HttpCompatService<S>
+where
+    S: Service<WarpRequest<WarpBody>, Response = WarpResponse<WarpBody>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Into<BoxError>,
+    B: HttpBody<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let inner = self.inner.clone();
+        let content_type = req.headers().get(http::header::CONTENT_TYPE);
+        let grpc = is_grpc_content_type(content_type.map(|value| value.as_bytes()));
+
+        Box::pin(async move {
+            Ok(call_http02_service(req.map(Body::new), inner)
+                .await
+                .unwrap_or_else(|err| error_response(err, grpc)))
+        })
+    }
+}
+
+/// Calls an `http` 1.0 service with an `http` 0.2 request.
+impl<S, ResBody> Service<WarpRequest<WarpBody>> for HttpCompatService<S>
+where
+    S: Service<http::Request<Body>, Response = http::Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Into<BoxError>,
+    ResBody: HttpBody<Data = Bytes> + Send + 'static,
+    ResBody::Error: Into<BoxError>,
+{
+    type Response = WarpResponse<CompatBody>;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: WarpRequest<WarpBody>) -> Self::Future {
+        let inner = self.inner.clone();
+        let content_type = req.headers().get(warp::http::header::CONTENT_TYPE);
+        let grpc = is_grpc_content_type(content_type.map(|value| value.as_bytes()));
+
+        Box::pin(async move {
+            let response = call_http1_service(req, inner)
+                .await
+                .unwrap_or_else(|err| error_response(err, grpc));
+
+            let response = response.map(CompatBody::from);
+
+            // Converting the status and headers only fails for invalid values, which the
+            // response builder has already rejected.
+            Ok(convert_response_head(response).unwrap_or_else(|err| {
+                let mut response = WarpResponse::new(CompatBody::from(WarpBody::from(err)));
+                *response.status_mut() = warp::http::StatusCode::INTERNAL_SERVER_ERROR;
+                response
+            }))
+        })
+    }
+}
+
+const GRPC_CONTENT_TYPE: &str = "application/grpc";
+
+fn is_grpc_content_type(content_type: Option<&[u8]>) -> bool {
+    content_type.is_some_and(|value| value.starts_with(GRPC_CONTENT_TYPE.as_bytes()))
+}
+
+/// Converts a boundary error into a response, using a gRPC trailers-only response for gRPC
+/// requests so clients see a gRPC status instead of a transport error.
+fn error_response(err: Error, grpc: bool) -> Response {
+    if !grpc {
+        return err.into_response();
+    }
+
+    let code = match err {
+        // DEADLINE_EXCEEDED
+        Error::Timeout(_) => "4",
+        // INTERNAL
+        _ => "13",
+    };
+
+    Response::builder()
+        .header(http::header::CONTENT_TYPE, GRPC_CONTENT_TYPE)
+        .header("grpc-status", code)
+        .header("grpc-message", encode_grpc_message(&err.to_string()))
+        .body(Body::empty())
+        .unwrap_or_else(|_| err.into_response())
+}
+
+/// Percent-encodes a gRPC status message, as required by the gRPC HTTP/2 protocol.
+fn encode_grpc_message(message: &str) -> String {
+    let mut encoded = String::with_capacity(message.len());
+
+    for byte in message.bytes() {
+        if (0x20..=0x7e).contains(&byte) && byte != b'%' {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    encoded
+}
+
+async fn call_http02_service<S>(req: http::Request<Body>, service: S) -> Result<Response, Error>
+where
+    S: Service<WarpRequest<WarpBody>, Response = WarpResponse<WarpBody>>,
+    S::Error: Into<BoxError>,
+{
+    let warp_req = into_warp_request(req).await?;
+
+    let warp_response = service
+        .oneshot(warp_req)
+        .await
+        .map_err(|err| Error::Service(err.into()))?;
+
+    Ok(into_axum_response(warp_response)?)
+}
+
+async fn call_http1_service<S, ResBody>(
+    req: WarpRequest<WarpBody>,
+    service: S,
+) -> Result<Response, Error>
+where
+    S: Service<http::Request<Body>, Response = http::Response<ResBody>>,
+    S::Error: Into<BoxError>,
+    ResBody: HttpBody<Data = Bytes> + Send + 'static,
+    ResBody::Error: Into<BoxError>,
+{
+    let axum_req = into_axum_request(req)?;
+
+    let response = service
+        .oneshot(axum_req)
+        .await
+        .map_err(|err| Error::Service(err.into()))?;
+
+    Ok(response.map(Body::new))
+}
+
+/// A Tower service that wraps a hyper 0.14 service to run within Axum servers.
+///
+/// This is the counterpart of [`WarpService`](crate::WarpService) for services built directly
+/// on hyper 0.14 and `http` 0.2, such as hand-rolled services or routers from that ecosystem.
+/// It is an [`HttpCompatService`] used in the `http` 0.2 to 1.0 direction.
+///
+/// # Example
+///
+/// ```rust
+/// use std::convert::Infallible;
+///
+/// use axum::Router;
+/// use warp::http::{Request, Response};
+/// use warp::hyper::Body;
+/// use warpdrive::HyperCompatService;
+///
+/// let legacy = tower::service_fn(|_req: Request<Body>| async {
+///     Ok::<_, Infallible>(Response::new(Body::from("Hello from hyper!")))
+/// });
+///
+/// let app: Router = Router::new().fallback_service(HyperCompatService::new(legacy));
+/// ```
+pub type HyperCompatService<S> = HttpCompatService<S>;
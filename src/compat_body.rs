@@ -0,0 +1,338 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::ready;
+use http_body::{Body as HttpBody, Frame, SizeHint};
+use http_body_util::{BodyExt, combinators::UnsyncBoxBody};
+use tower::BoxError;
+use warp::http::HeaderMap as WarpHeaderMap;
+use warp::hyper::body::{
+    Body as WarpBody, Bytes, HttpBody as WarpHttpBody, SizeHint as WarpSizeHint,
+};
+
+/// A body that implements both the http-body 1.0 `Body` trait and the hyper 0.14 `HttpBody`
+/// trait.
+///
+/// Wrapping an Axum or hyper 0.14 body in a `CompatBody` allows it to be used on either side of
+/// the boundary without copying it into a new stream. Trailers are passed through in both
+/// directions.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "axum", doc = "```rust")]
+#[cfg_attr(not(feature = "axum"), doc = "```rust,ignore")]
+/// use warpdrive::compat::CompatBody;
+///
+/// // An Axum body that can be used in a hyper 0.14 response.
+/// let body = CompatBody::from(axum::body::Body::from("Hello"));
+/// let response = warp::http::Response::new(body);
+/// ```
+///
+/// Without the `axum` feature, it is available from the [`http1`](crate::http1) module:
+///
+/// ```rust
+/// use http_body_util::BodyExt;
+/// use warpdrive::http1::CompatBody;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// // A hyper 0.14 body read as an http-body 1.0 body.
+/// let body = CompatBody::from(warp::hyper::Body::from("Hello"));
+/// assert_eq!(body.collect().await.unwrap().to_bytes(), "Hello");
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CompatBody {
+    inner: CompatBodyInner,
+    trailers: Option<WarpHeaderMap>,
+    data_done: bool,
+    /// The bytes left of a declared length, for Warp bodies built from a stream.
+    remaining: Option<u64>,
+}
+
+#[derive(Debug)]
+enum CompatBodyInner {
+    Http(UnsyncBoxBody<Bytes, BoxError>),
+    Warp(WarpBody),
+}
+
+impl CompatBody {
+    fn new(inner: CompatBodyInner) -> Self {
+        CompatBody {
+            inner,
+            trailers: None,
+            data_done: false,
+            remaining: None,
+        }
+    }
+
+    /// Declares the length of a Warp body, such as from the `Content-Length` of its response.
+    ///
+    /// Warp bodies built from a stream, such as the files served by `warp::fs`, have no size
+    /// hint, even when their response declares a length. Without one, the length is lost to
+    /// layers that rely on the hint, such as those that buffer bounded bodies. The declared
+    /// length is only used if the body does not already report an exact size.
+    pub(crate) fn with_declared_length(mut self, length: Option<u64>) -> Self {
+        if let CompatBodyInner::Warp(body) = &self.inner
+            && WarpHttpBody::size_hint(body).exact().is_none()
+        {
+            self.remaining = length;
+        }
+        self
+    }
+
+    /// Wraps an http-body 1.0 body, such as a hyper 1.x body, for use as a hyper 0.14 body.
+    pub fn from_http<B>(body: B) -> Self
+    where
+        B: HttpBody<Data = Bytes> + Send + 'static,
+        B::Error: Into<BoxError>,
+    {
+        CompatBody::new(CompatBodyInner::Http(
+            body.map_err(Into::into).boxed_unsync(),
+        ))
+    }
+}
+
+#[cfg(feature = "axum")]
+impl From<axum::body::Body> for CompatBody {
+    fn from(body: axum::body::Body) -> Self {
+        CompatBody::from_http(body)
+    }
+}
+
+impl From<WarpBody> for CompatBody {
+    fn from(body: WarpBody) -> Self {
+        CompatBody::new(CompatBodyInner::Warp(body))
+    }
+}
+
+impl HttpBody for CompatBody {
+    type Data = Bytes;
+    type Error = BoxError;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+
+        match &mut this.inner {
+            CompatBodyInner::Http(body) => Pin::new(body).poll_frame(cx),
+            CompatBodyInner::Warp(body) => {
+                if !this.data_done {
+                    match ready!(Pin::new(&mut *body).poll_data(cx)) {
+                        Some(Ok(data)) => {
+                            if let Some(remaining) = &mut this.remaining {
+                                *remaining = remaining.saturating_sub(data.len() as u64);
+                            }
+                            return Poll::Ready(Some(Ok(Frame::data(data))));
+                        }
+                        Some(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
+                        None => this.data_done = true,
+                    }
+                }
+
+                match ready!(Pin::new(body).poll_trailers(cx)) {
+                    Ok(Some(trailers)) => {
+                        Poll::Ready(Some(Ok(Frame::trailers(to_http_headers(&trailers)))))
+                    }
+                    Ok(None) => Poll::Ready(None),
+                    Err(err) => Poll::Ready(Some(Err(err.into()))),
+                }
+            }
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        match &self.inner {
+            CompatBodyInner::Http(body) => HttpBody::is_end_stream(body),
+            CompatBodyInner::Warp(body) => WarpHttpBody::is_end_stream(body),
+        }
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        match (&self.inner, self.remaining) {
+            (CompatBodyInner::Http(body), _) => HttpBody::size_hint(body),
+            (CompatBodyInner::Warp(_), Some(remaining)) => SizeHint::with_exact(remaining),
+            (CompatBodyInner::Warp(body), None) => {
+                let hint = WarpHttpBody::size_hint(body);
+                let mut size_hint = SizeHint::new();
+                size_hint.set_lower(hint.lower());
+                if let Some(upper) = hint.upper() {
+                    size_hint.set_upper(upper);
+                }
+                size_hint
+            }
+        }
+    }
+}
+
+impl WarpHttpBody for CompatBody {
+    type Data = Bytes;
+    type Error = BoxError;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let this = self.get_mut();
+
+        match &mut this.inner {
+            CompatBodyInner::Http(body) => {
+                if this.data_done {
+                    return Poll::Ready(None);
+                }
+
+                loop {
+                    match ready!(Pin::new(&mut *body).poll_frame(cx)) {
+                        Some(Ok(frame)) => match frame.into_data() {
+                            Ok(data) => return Poll::Ready(Some(Ok(data))),
+                            Err(frame) => {
+                                // Trailers end the data, so keep them for `poll_trailers`.
+                                if let Ok(trailers) = frame.into_trailers() {
+                                    this.trailers = Some(to_warp_headers(&trailers));
+                                    this.data_done = true;
+                                    return Poll::Ready(None);
+                                }
+                            }
+                        },
+                        Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                        None => {
+                            this.data_done = true;
+                            return Poll::Ready(None);
+                        }
+                    }
+                }
+            }
+            CompatBodyInner::Warp(body) => Pin::new(body).poll_data(cx).map_err(Into::into),
+        }
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<WarpHeaderMap>, Self::Error>> {
+        let this = self.get_mut();
+
+        match &mut this.inner {
+            CompatBodyInner::Http(body) => {
+                // Skip any remaining data if the trailers are requested early.
+                while !this.data_done {
+                    match ready!(Pin::new(&mut *body).poll_frame(cx)) {
+                        Some(Ok(frame)) => {
+                            if let Ok(trailers) = frame.into_trailers() {
+                                this.trailers = Some(to_warp_headers(&trailers));
+                                this.data_done = true;
+                            }
+                        }
+                        Some(Err(err)) => return Poll::Ready(Err(err)),
+                        None => this.data_done = true,
+                    }
+                }
+
+                Poll::Ready(Ok(this.trailers.take()))
+            }
+            CompatBodyInner::Warp(body) => Pin::new(body).poll_trailers(cx).map_err(Into::into),
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        match &self.inner {
+            CompatBodyInner::Http(body) => {
+                (self.data_done || HttpBody::is_end_stream(body)) && self.trailers.is_none()
+            }
+            CompatBodyInner::Warp(body) => WarpHttpBody::is_end_stream(body),
+        }
+    }
+
+    fn size_hint(&self) -> WarpSizeHint {
+        match &self.inner {
+            CompatBodyInner::Http(body) => {
+                let hint = HttpBody::size_hint(body);
+                let mut size_hint = WarpSizeHint::new();
+                size_hint.set_lower(hint.lower());
+                if let Some(upper) = hint.upper() {
+                    size_hint.set_upper(upper);
+                }
+                size_hint
+            }
+            CompatBodyInner::Warp(body) => WarpHttpBody::size_hint(body),
+        }
+    }
+}
+
+/// Returns the length declared by the `Content-Length` of a response, if its status allows a
+/// body.
+pub(crate) fn declared_length(status: u16, headers: &WarpHeaderMap) -> Option<u64> {
+    if forbids_body(status) {
+        return None;
+    }
+
+    let mut values = headers.get_all(warp::http::header::CONTENT_LENGTH).iter();
+    let length = values.next()?.to_str().ok()?.trim().parse().ok()?;
+    values.next().is_none().then_some(length)
+}
+
+/// Returns `true` for the statuses whose responses never have a body: `1xx`, `204 No Content`,
+/// and `304 Not Modified`.
+pub(crate) fn forbids_body(status: u16) -> bool {
+    (100..200).contains(&status) || status == 204 || status == 304
+}
+
+/// Removes the framing headers that a response without a body must not have, returning `true`
+/// if its body must be dropped.
+///
+/// Legacy replies sometimes attach an empty body to a `204` or `304`, which would otherwise be
+/// re-framed as a chunked body. `Transfer-Encoding` is always removed, as is `Content-Length`,
+/// except on a `304`, where it describes the representation that was not sent.
+pub(crate) fn strip_body_framing(status: u16, headers: &mut WarpHeaderMap) -> bool {
+    if !forbids_body(status) {
+        return false;
+    }
+
+    headers.remove(warp::http::header::TRANSFER_ENCODING);
+    if status != 304 {
+        headers.remove(warp::http::header::CONTENT_LENGTH);
+    }
+    true
+}
+
+/// Copies a Warp header map into an `http` 1.0 header map.
+///
+/// Values are copied as opaque bytes, so values that are not valid UTF-8, such as Latin-1 text
+/// sent by legacy clients, arrive unchanged, and values marked as sensitive stay sensitive.
+/// Repeated headers are appended one by one, so they keep their count and their order.
+pub(crate) fn to_http_headers(headers: &WarpHeaderMap) -> http::HeaderMap {
+    let mut converted = http::HeaderMap::with_capacity(headers.len());
+
+    for (name, value) in headers.iter() {
+        if let (Ok(name), Ok(mut converted_value)) = (
+            http::HeaderName::from_bytes(name.as_str().as_bytes()),
+            http::HeaderValue::from_bytes(value.as_bytes()),
+        ) {
+            converted_value.set_sensitive(value.is_sensitive());
+            converted.append(name, converted_value);
+        }
+    }
+
+    converted
+}
+
+/// Copies an `http` 1.0 header map into a Warp header map, the inverse of [`to_http_headers`].
+pub(crate) fn to_warp_headers(headers: &http::HeaderMap) -> WarpHeaderMap {
+    let mut converted = WarpHeaderMap::with_capacity(headers.len());
+
+    for (name, value) in headers.iter() {
+        if let (Ok(name), Ok(mut converted_value)) = (
+            warp::http::HeaderName::from_bytes(name.as_str().as_bytes()),
+            warp::http::HeaderValue::from_bytes(value.as_bytes()),
+        ) {
+            converted_value.set_sensitive(value.is_sensitive());
+            converted.append(name, converted_value);
+        }
+    }
+
+    converted
+}
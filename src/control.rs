@@ -0,0 +1,79 @@
+use std::{
+    fmt,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU8, Ordering},
+    },
+};
+
+/// A runtime on/off control, such as a kill switch.
+#[derive(Clone)]
+pub struct Switch {
+    on: Arc<AtomicBool>,
+}
+
+impl Switch {
+    /// Creates a switch in the given state.
+    pub fn new(on: bool) -> Self {
+        Switch {
+            on: Arc::new(AtomicBool::new(on)),
+        }
+    }
+
+    /// Returns `true` if the switch is on.
+    pub fn is_on(&self) -> bool {
+        self.on.load(Ordering::Relaxed)
+    }
+
+    /// Turns the switch on or off.
+    pub fn set(&self, on: bool) {
+        self.on.store(on, Ordering::Relaxed);
+    }
+}
+
+impl fmt::Debug for Switch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Switch").field(&self.is_on()).finish()
+    }
+}
+
+/// A runtime percentage control, such as the share of traffic sent to a canary.
+#[derive(Clone)]
+pub struct Percentage {
+    value: Arc<AtomicU8>,
+}
+
+impl Percentage {
+    /// Creates a percentage with the given value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is greater than 100.
+    pub fn new(value: u8) -> Self {
+        assert!(value <= 100, "percentage must be at most 100");
+
+        Percentage {
+            value: Arc::new(AtomicU8::new(value)),
+        }
+    }
+
+    /// Returns the current value, from 0 to 100.
+    pub fn get(&self) -> u8 {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    /// Sets the value, returning `false` without changing it if `value` is greater than 100.
+    pub fn set(&self, value: u8) -> bool {
+        if value > 100 {
+            return false;
+        }
+        self.value.store(value, Ordering::Relaxed);
+        true
+    }
+}
+
+impl fmt::Debug for Percentage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Percentage").field(&self.get()).finish()
+    }
+}
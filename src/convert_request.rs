@@ -1,45 +1,216 @@
-use std::str::FromStr;
+use std::{convert::Infallible, sync::Arc};
 
-use axum::body::Body as AxumBody;
+use axum::body::{Body as AxumBody, Bytes};
 use axum::extract::Request as AxumRequest;
+use axum::http::{Extensions, request::Parts as AxumParts};
+use futures::TryStreamExt;
+use warp::filters::path::FullPath;
 use warp::http::{
-    Request as WarpRequest, method::Method, uri::Uri, version::Version as WarpVersion,
+    HeaderMap as WarpHeaderMap, Request as WarpRequest, method::Method,
+    version::Version as WarpVersion,
 };
 use warp::hyper::body::Body as WarpBody;
+use warp::{Buf, Filter, Rejection};
+
+use crate::{
+    body::{to_axum_body, to_warp_body},
+    compat_body::to_http_headers,
+    http1::{is_asterisk_form, to_warp_request_head},
+};
 
 pub async fn into_warp_request(
     axum_request: AxumRequest<AxumBody>,
 ) -> Result<WarpRequest<WarpBody>, String> {
-    let (parts, body) = axum_request.into_parts();
+    let (mut parts, body) = axum_request.into_parts();
+    let extensions = std::mem::take(&mut parts.extensions);
+
+    let mut request = to_warp_request_head(AxumRequest::from_parts(parts, body))?;
+    request
+        .extensions_mut()
+        .insert(CarriedExtensions(Arc::new(extensions)));
+
+    Ok(request.map(to_warp_body))
+}
 
-    let method = Method::from_str(parts.method.as_ref())
-        .map_err(|e| format!("Invalid method '{}': {}", parts.method, e))?;
+pub fn into_axum_request(
+    warp_request: WarpRequest<WarpBody>,
+) -> Result<AxumRequest<AxumBody>, String> {
+    let (parts, body) = warp_request.into_parts();
 
-    let uri = Uri::try_from(&parts.uri.to_string())
-        .map_err(|e| format!("Invalid URI '{}': {}", parts.uri, e))?;
+    let extensions = parts.extensions.get::<CarriedExtensions>().cloned();
 
-    let mut builder = WarpRequest::builder()
-        .method(method)
+    let uri = if is_asterisk_form(parts.uri.path(), parts.uri.query()) {
+        axum::http::Uri::from_static("*")
+    } else {
+        axum::http::Uri::try_from(parts.uri.to_string())
+            .map_err(|e| format!("Invalid URI '{}': {}", parts.uri, e))?
+    };
+
+    let mut request = AxumRequest::builder()
+        .method(parts.method.as_str())
         .uri(uri)
-        .version(convert_version(parts.version));
+        .version(convert_version_to_axum(parts.version))
+        .body(to_axum_body(body))
+        .map_err(|e| format!("Failed to build Axum request: {}", e))?;
+    *request.headers_mut() = to_http_headers(&parts.headers);
+
+    if let Some(extensions) = extensions {
+        *request.extensions_mut() = extensions.restore();
+    }
+
+    Ok(request)
+}
+
+/// The extensions of an Axum request, carried through the Warp request it was converted into.
+///
+/// Warp filters cannot hold `http` 1.0 extensions such as hyper's `OnUpgrade`, so they are
+/// stored as a Warp request extension and restored when the request is converted back, for
+/// example before it is forwarded to an Axum service.
+#[derive(Clone)]
+pub(crate) struct CarriedExtensions(Arc<Extensions>);
+
+impl CarriedExtensions {
+    fn restore(&self) -> Extensions {
+        self.0.as_ref().clone()
+    }
+
+    pub(crate) fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.0.get::<T>()
+    }
+}
+
+/// Rewrites an empty request path to `/`, so `warp::path::end()` and `warp::path::full()`
+/// see the root of the mount rather than an empty path.
+///
+/// Paths are empty when the request target is in authority-form, or when a layer that strips
+/// a mount prefix leaves nothing behind. The authority is moved into the `Host` header if it
+/// is not already set. `CONNECT` requests, whose target is always in authority-form, are left
+/// unchanged.
+pub(crate) fn normalize_empty_path(req: &mut AxumRequest<AxumBody>) {
+    if !req.uri().path().is_empty() || req.method() == axum::http::Method::CONNECT {
+        return;
+    }
+
+    let path_and_query = match req.uri().query() {
+        Some(query) => format!("/?{}", query),
+        None => "/".to_string(),
+    };
+    let Ok(uri) = axum::http::Uri::try_from(path_and_query) else {
+        return;
+    };
+
+    if let Some(authority) = req.uri().authority()
+        && let Ok(host) = axum::http::HeaderValue::try_from(authority.as_str())
+    {
+        req.headers_mut()
+            .entry(axum::http::header::HOST)
+            .or_insert(host);
+    }
+    *req.uri_mut() = uri;
+}
+
+/// Rewrites an HTTP/1 request target in absolute-form, such as `http://example.com/users`
+/// sent by a forward proxy, to origin-form, moving the authority into the `Host` header.
+///
+/// As the authority of an absolute-form target takes precedence over the `Host` header, any
+/// existing `Host` is replaced. `CONNECT` requests, and HTTP/2 requests, whose URIs always
+/// carry the authority, are left unchanged.
+pub(crate) fn normalize_absolute_form(req: &mut AxumRequest<AxumBody>) {
+    if req.uri().scheme().is_none()
+        || req.version() > axum::http::Version::HTTP_11
+        || req.method() == axum::http::Method::CONNECT
+    {
+        return;
+    }
+    let Some(authority) = req.uri().authority() else {
+        return;
+    };
+    let Ok(host) = axum::http::HeaderValue::try_from(authority.as_str()) else {
+        return;
+    };
+    let path_and_query = req
+        .uri()
+        .path_and_query()
+        .map_or("/", |path_and_query| path_and_query.as_str());
+    let Ok(uri) = axum::http::Uri::try_from(path_and_query) else {
+        return;
+    };
+
+    req.headers_mut().insert(axum::http::header::HOST, host);
+    *req.uri_mut() = uri;
+}
+
+/// A Warp filter that rebuilds the Axum request parts from the request being filtered.
+///
+/// Warp does not expose the request version, so it is left at its default. Extensions are
+/// restored from the original Axum request, if the request was converted from one.
+pub fn axum_parts_filter()
+-> impl Filter<Extract = (Result<AxumParts, String>,), Error = Infallible> + Clone {
+    warp::method()
+        .and(warp::path::full())
+        .and(raw_query())
+        .and(warp::header::headers_cloned())
+        .and(warp::ext::optional::<CarriedExtensions>())
+        .map(into_axum_parts)
+}
+
+/// A Warp filter that rebuilds the full Axum request, streaming the body.
+pub fn axum_request_filter()
+-> impl Filter<Extract = (Result<AxumRequest<AxumBody>, String>,), Error = Rejection> + Clone {
+    axum_parts_filter()
+        .and(warp::body::stream())
+        .map(|parts: Result<AxumParts, String>, body| {
+            let body = AxumBody::from_stream(TryStreamExt::map_ok(body, buf_into_bytes));
+            parts.map(|parts| AxumRequest::from_parts(parts, body))
+        })
+}
+
+fn buf_into_bytes(mut buf: impl Buf) -> Bytes {
+    buf.copy_to_bytes(buf.remaining())
+}
+
+pub(crate) fn raw_query() -> impl Filter<Extract = (Option<String>,), Error = Infallible> + Clone {
+    warp::query::raw()
+        .map(Some)
+        .or(warp::any().map(|| None))
+        .unify()
+}
+
+fn into_axum_parts(
+    method: Method,
+    path: FullPath,
+    query: Option<String>,
+    headers: WarpHeaderMap,
+    extensions: Option<CarriedExtensions>,
+) -> Result<AxumParts, String> {
+    let uri = match query {
+        Some(query) => format!("{}?{}", path.as_str(), query),
+        None => path.as_str().to_string(),
+    };
+
+    let mut parts = AxumRequest::builder()
+        .method(method.as_str())
+        .uri(&uri)
+        .body(())
+        .map(|req| req.into_parts().0)
+        .map_err(|e| format!("Failed to build Axum request parts for '{}': {}", uri, e))?;
+    parts.headers = to_http_headers(&headers);
 
-    for (name, value) in parts.headers.iter() {
-        builder = builder.header(name.as_str(), value.as_bytes())
+    if let Some(extensions) = extensions {
+        parts.extensions = extensions.restore();
     }
 
-    builder
-        .body(WarpBody::wrap_stream(body.into_data_stream()))
-        .map_err(|e| format!("Failed to build Warp request: {}", e))
+    Ok(parts)
 }
 
-fn convert_version(version: axum::http::Version) -> WarpVersion {
+fn convert_version_to_axum(version: WarpVersion) -> axum::http::Version {
     match version {
-        axum::http::Version::HTTP_09 => WarpVersion::HTTP_09,
-        axum::http::Version::HTTP_10 => WarpVersion::HTTP_10,
-        axum::http::Version::HTTP_11 => WarpVersion::HTTP_11,
-        axum::http::Version::HTTP_2 => WarpVersion::HTTP_2,
-        axum::http::Version::HTTP_3 => WarpVersion::HTTP_3,
+        WarpVersion::HTTP_09 => axum::http::Version::HTTP_09,
+        WarpVersion::HTTP_10 => axum::http::Version::HTTP_10,
+        WarpVersion::HTTP_11 => axum::http::Version::HTTP_11,
+        WarpVersion::HTTP_2 => axum::http::Version::HTTP_2,
+        WarpVersion::HTTP_3 => axum::http::Version::HTTP_3,
         // Default to 1.1 for compatibility.
-        _ => WarpVersion::HTTP_11,
+        _ => axum::http::Version::HTTP_11,
     }
 }
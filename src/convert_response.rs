@@ -1,38 +1,80 @@
 use axum::body::Body as AxumBody;
 use axum::http::{Response as AxumResponse, version::Version};
-use futures::TryStreamExt;
 use warp::http::Response as WarpResponse;
 use warp::hyper::body::Body as WarpBody;
 
-pub async fn into_axum_response(
-    warp_response: WarpResponse<WarpBody>,
+use crate::{
+    body::to_warp_body,
+    compat_body::{CompatBody, declared_length, strip_body_framing, to_warp_headers},
+    error_bridge::ErrorChain,
+    http1::from_warp_response_head,
+};
+
+// Extensions are not carried across, except for an `ErrorChain`, so that errors mapped by an
+// `ErrorBridge` on the Warp side can be logged on the Axum side.
+
+pub fn into_axum_response(
+    mut warp_response: WarpResponse<WarpBody>,
 ) -> Result<AxumResponse<AxumBody>, String> {
-    let (parts, body) = warp_response.into_parts();
+    let chain = warp_response.extensions_mut().remove::<ErrorChain>();
+    let status = warp_response.status().as_u16();
+    let length = declared_length(status, warp_response.headers());
+    let empty = strip_body_framing(status, warp_response.headers_mut());
 
-    let status_code = axum::http::StatusCode::from_u16(parts.status.as_u16())
-        .map_err(|e| format!("Invalid status code {}: {}", parts.status.as_u16(), e))?;
+    let mut response = from_warp_response_head(warp_response)?;
+    if let Some(chain) = chain {
+        response.extensions_mut().insert(chain);
+    }
 
-    let mut builder = AxumResponse::builder()
-        .status(status_code)
-        .version(convert_version(parts.version));
+    Ok(response.map(|body| {
+        if empty {
+            AxumBody::empty()
+        } else {
+            AxumBody::new(CompatBody::from(body).with_declared_length(length))
+        }
+    }))
+}
+
+pub fn into_warp_response(
+    mut axum_response: AxumResponse<AxumBody>,
+) -> Result<WarpResponse<WarpBody>, String> {
+    let chain = axum_response.extensions_mut().remove::<ErrorChain>();
 
-    for (name, value) in parts.headers.iter() {
-        builder = builder.header(name.as_str(), value.as_bytes());
+    let mut response = convert_response_head(axum_response)?;
+    if let Some(chain) = chain {
+        response.extensions_mut().insert(chain);
     }
 
-    builder
-        .body(AxumBody::from_stream(body.into_stream()))
-        .map_err(|e| format!("Failed to build Axum response: {}", e))
+    Ok(response.map(to_warp_body))
+}
+
+/// Converts the status, version, and headers of a response, keeping the body as is.
+pub(crate) fn convert_response_head<B>(
+    axum_response: AxumResponse<B>,
+) -> Result<WarpResponse<B>, String> {
+    let (parts, body) = axum_response.into_parts();
+
+    let status_code = warp::http::StatusCode::from_u16(parts.status.as_u16())
+        .map_err(|e| format!("Invalid status code {}: {}", parts.status.as_u16(), e))?;
+
+    let mut response = WarpResponse::builder()
+        .status(status_code)
+        .version(convert_version_to_warp(parts.version))
+        .body(body)
+        .map_err(|e| format!("Failed to build Warp response: {}", e))?;
+    *response.headers_mut() = to_warp_headers(&parts.headers);
+
+    Ok(response)
 }
 
-fn convert_version(version: warp::http::Version) -> Version {
+fn convert_version_to_warp(version: Version) -> warp::http::Version {
     match version {
-        warp::http::Version::HTTP_09 => Version::HTTP_09,
-        warp::http::Version::HTTP_10 => Version::HTTP_10,
-        warp::http::Version::HTTP_11 => Version::HTTP_11,
-        warp::http::Version::HTTP_2 => Version::HTTP_2,
-        warp::http::Version::HTTP_3 => Version::HTTP_3,
+        Version::HTTP_09 => warp::http::Version::HTTP_09,
+        Version::HTTP_10 => warp::http::Version::HTTP_10,
+        Version::HTTP_11 => warp::http::Version::HTTP_11,
+        Version::HTTP_2 => warp::http::Version::HTTP_2,
+        Version::HTTP_3 => warp::http::Version::HTTP_3,
         // Default to 1.1 for compatibility.
-        _ => Version::HTTP_11,
+        _ => warp::http::Version::HTTP_11,
     }
 }
@@ -0,0 +1,182 @@
+//! A single CORS configuration for Axum and Warp routes.
+//!
+//! During a migration the same origins, methods, and headers must be allowed by the Axum router
+//! and by the Warp filters it falls back to. [`CorsConfig`] holds these values once and builds
+//! both a tower-http [`CorsLayer`] and a [`warp::cors`] builder from them, so the two stacks
+//! cannot drift apart.
+//!
+//! This module is available with the `cors` feature.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use axum::{Router, http::{Method, header}, routing::post};
+//! use warpdrive::{WarpService, cors::CorsConfig};
+//! use warp::Filter;
+//!
+//! let cors = CorsConfig::new()
+//!     .allow_origin("http://example.com")
+//!     .allow_methods([Method::GET, Method::POST])
+//!     .allow_headers([header::CONTENT_TYPE])
+//!     .max_age(Duration::from_secs(600));
+//!
+//! let warp_routes = warp::path("legacy")
+//!     .map(|| "Hello from Warp!")
+//!     .with(cors.warp())
+//!     .boxed();
+//!
+//! let app: Router = Router::new()
+//!     .route("/data", post(|| async { "Hello from Axum!" }))
+//!     .layer(cors.layer())
+//!     .fallback_service(WarpService::new(warp_routes));
+//! ```
+
+use std::time::Duration;
+
+use axum::http::{HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// CORS settings that generate matching configurations for Axum and Warp.
+///
+/// Nothing is allowed by default, as with both [`CorsLayer::new`] and [`warp::cors`].
+///
+/// The two stacks differ in how they treat requests from origins that are not allowed: the
+/// Axum layer answers them without CORS headers, leaving the browser to block the response,
+/// while Warp rejects them with `403 Forbidden`. When any origin is allowed, both echo the
+/// request's origin rather than answering with `*`, as Warp does, so credentials can be
+/// allowed as well.
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    /// The allowed origins, or `None` if any origin is allowed.
+    origins: Option<Vec<HeaderValue>>,
+    methods: Vec<Method>,
+    allow_headers: Vec<HeaderName>,
+    expose_headers: Vec<HeaderName>,
+    credentials: bool,
+    max_age: Option<Duration>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        CorsConfig {
+            origins: Some(Vec::new()),
+            methods: Vec::new(),
+            allow_headers: Vec::new(),
+            expose_headers: Vec::new(),
+            credentials: false,
+            max_age: None,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Creates a configuration that allows nothing.
+    pub fn new() -> Self {
+        CorsConfig::default()
+    }
+
+    /// Allows requests from an origin, such as `https://example.com`, in place of any origin
+    /// if [`allow_any_origin`](CorsConfig::allow_any_origin) was set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the origin is not a valid header value.
+    pub fn allow_origin(mut self, origin: &str) -> Self {
+        let origin = HeaderValue::from_str(origin).expect("invalid CORS origin");
+        self.origins.get_or_insert_with(Vec::new).push(origin);
+        self
+    }
+
+    /// Allows requests from each of the given origins.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an origin is not a valid header value.
+    pub fn allow_origins<'a>(self, origins: impl IntoIterator<Item = &'a str>) -> Self {
+        origins.into_iter().fold(self, Self::allow_origin)
+    }
+
+    /// Allows requests from any origin, replacing any origins allowed so far.
+    pub fn allow_any_origin(mut self) -> Self {
+        self.origins = None;
+        self
+    }
+
+    /// Allows the given methods.
+    pub fn allow_methods(mut self, methods: impl IntoIterator<Item = Method>) -> Self {
+        self.methods.extend(methods);
+        self
+    }
+
+    /// Allows the given request headers.
+    pub fn allow_headers(mut self, headers: impl IntoIterator<Item = HeaderName>) -> Self {
+        self.allow_headers.extend(headers);
+        self
+    }
+
+    /// Exposes the given response headers to scripts.
+    pub fn expose_headers(mut self, headers: impl IntoIterator<Item = HeaderName>) -> Self {
+        self.expose_headers.extend(headers);
+        self
+    }
+
+    /// Sets whether credentials such as cookies are allowed. Defaults to `false`.
+    pub fn allow_credentials(mut self, allow: bool) -> Self {
+        self.credentials = allow;
+        self
+    }
+
+    /// Sets how long browsers may cache preflight responses.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Builds a tower-http layer for Axum routes.
+    pub fn layer(&self) -> CorsLayer {
+        let origins = match &self.origins {
+            Some(origins) => AllowOrigin::list(origins.iter().cloned()),
+            None => AllowOrigin::mirror_request(),
+        };
+
+        let mut layer = CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(self.methods.clone())
+            .allow_headers(self.allow_headers.clone())
+            .expose_headers(self.expose_headers.clone())
+            .allow_credentials(self.credentials);
+        if let Some(max_age) = self.max_age {
+            layer = layer.max_age(max_age);
+        }
+        layer
+    }
+
+    /// Builds a Warp CORS builder, which can be passed to `Filter::with` or further configured.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an allowed origin is not a valid origin URL, as with
+    /// [`warp::cors::Builder::allow_origin`].
+    pub fn warp(&self) -> warp::cors::Builder {
+        let mut builder = warp::cors()
+            .allow_methods(self.methods.iter().map(Method::as_str))
+            .allow_headers(self.allow_headers.iter().map(HeaderName::as_str))
+            .expose_headers(self.expose_headers.iter().map(HeaderName::as_str))
+            .allow_credentials(self.credentials);
+
+        builder = match &self.origins {
+            Some(origins) => builder.allow_origins(
+                origins
+                    .iter()
+                    .map(|origin| origin.to_str().expect("invalid CORS origin")),
+            ),
+            None => builder.allow_any_origin(),
+        };
+        if let Some(max_age) = self.max_age {
+            builder = builder.max_age(max_age);
+        }
+        builder
+    }
+}
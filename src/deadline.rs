@@ -0,0 +1,57 @@
+use std::time::{Duration, Instant};
+
+/// A deadline for handling a request, attached as a request extension by an upstream layer.
+///
+/// [`WarpService`](crate::WarpService) honors a `Deadline` in the request extensions by
+/// cancelling the Warp filter once it passes, answering with `504 Gateway Timeout`, or
+/// failing with [`Error::Timeout`](crate::Error::Timeout) when using
+/// [`FallibleWarpService`](crate::FallibleWarpService). When a timeout is also set with
+/// [`WarpService::with_timeout`](crate::WarpService::with_timeout), whichever is sooner
+/// applies. The remaining budget can be forwarded to the Warp filter as a header with
+/// [`WarpService::with_deadline_header`](crate::WarpService::with_deadline_header).
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use axum::{Router, extract::Request, middleware::{self, Next}};
+/// use warpdrive::{Deadline, WarpService};
+/// use warp::Filter;
+///
+/// async fn budget(mut req: Request, next: Next) -> axum::response::Response {
+///     req.extensions_mut().insert(Deadline::after(Duration::from_secs(2)));
+///     next.run(req).await
+/// }
+///
+/// let filter = warp::path("legacy").map(|| "Hello").boxed();
+/// let service = WarpService::new(filter).with_deadline_header("x-request-budget-ms");
+///
+/// let app: Router = Router::new()
+///     .fallback_service(service)
+///     .layer(middleware::from_fn(budget));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline(Instant);
+
+impl Deadline {
+    /// Creates a deadline at the given instant.
+    pub fn at(instant: Instant) -> Self {
+        Deadline(instant)
+    }
+
+    /// Creates a deadline the given duration from now.
+    pub fn after(budget: Duration) -> Self {
+        Deadline(Instant::now() + budget)
+    }
+
+    /// Returns the instant of the deadline.
+    pub fn instant(&self) -> Instant {
+        self.0
+    }
+
+    /// Returns the time left before the deadline, or zero if it has passed.
+    pub fn remaining(&self) -> Duration {
+        self.0.saturating_duration_since(Instant::now())
+    }
+}
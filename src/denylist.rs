@@ -0,0 +1,110 @@
+use axum::{
+    body::{Body, Bytes},
+    http::{HeaderValue, StatusCode, header::CONTENT_TYPE},
+    response::Response,
+};
+
+use crate::prefix::{matches_prefix, trim_prefix};
+
+/// Paths that are answered at the boundary without reaching the Warp filter.
+///
+/// Once a route has been migrated to Axum, requests that still reach the Warp filter come
+/// from stale clients or misrouted traffic. Adding the route to a denylist answers them with
+/// `410 Gone` or `404 Not Found` and a custom body instead, so they can be noticed and do not
+/// run legacy code.
+///
+/// Paths match a rule if they are equal to its path, or are below it: `/v1/users` matches
+/// `/v1/users` and `/v1/users/42`, but not `/v1/users-old`. The first matching rule applies.
+/// The denylist is applied with
+/// [`WarpService::with_denylist`](crate::WarpService::with_denylist).
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::{Denylist, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("v1").map(|| "Hello").boxed();
+///
+/// let denylist = Denylist::new()
+///     .gone("/v1/users", "Moved to /v2/users")
+///     .not_found("/v1/internal", "");
+///
+/// let service = WarpService::new(filter).with_denylist(denylist);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Denylist {
+    rules: Vec<DenyRule>,
+    content_type: HeaderValue,
+}
+
+#[derive(Debug, Clone)]
+struct DenyRule {
+    path: String,
+    status: StatusCode,
+    body: Bytes,
+}
+
+impl Default for Denylist {
+    fn default() -> Self {
+        Denylist::new()
+    }
+}
+
+impl Denylist {
+    /// Creates an empty denylist.
+    pub fn new() -> Self {
+        Denylist {
+            rules: Vec::new(),
+            content_type: HeaderValue::from_static("text/plain; charset=utf-8"),
+        }
+    }
+
+    /// Answers requests for the path with `410 Gone` and the given body.
+    pub fn gone(self, path: impl Into<String>, body: impl Into<Bytes>) -> Self {
+        self.rule(path, StatusCode::GONE, body)
+    }
+
+    /// Answers requests for the path with `404 Not Found` and the given body.
+    pub fn not_found(self, path: impl Into<String>, body: impl Into<Bytes>) -> Self {
+        self.rule(path, StatusCode::NOT_FOUND, body)
+    }
+
+    /// Sets the content type of the response bodies. Defaults to
+    /// `text/plain; charset=utf-8`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `content_type` is not a valid header value.
+    pub fn content_type(mut self, content_type: &str) -> Self {
+        self.content_type = HeaderValue::try_from(content_type).expect("invalid content type");
+        self
+    }
+
+    fn rule(mut self, path: impl Into<String>, status: StatusCode, body: impl Into<Bytes>) -> Self {
+        self.rules.push(DenyRule {
+            path: trim_prefix(&path.into()),
+            status,
+            body: body.into(),
+        });
+        self
+    }
+
+    /// Returns the response for a denied path, or `None` if the path is allowed.
+    pub(crate) fn check(&self, path: &str) -> Option<Response> {
+        let rule = self
+            .rules
+            .iter()
+            .find(|rule| matches_prefix(&rule.path, path))?;
+
+        let mut response = Response::new(Body::from(rule.body.clone()));
+        *response.status_mut() = rule.status;
+        if !rule.body.is_empty() {
+            response
+                .headers_mut()
+                .insert(CONTENT_TYPE, self.content_type.clone());
+        }
+
+        Some(response)
+    }
+}
@@ -0,0 +1,145 @@
+use std::{fmt, time::Duration};
+
+use axum::{
+    body::Body,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use tower::BoxError;
+
+use crate::warp_service::create_conversion_error_response;
+
+/// Errors produced at the boundary between Axum and Warp.
+///
+/// `WarpService` converts these into responses. [`FallibleWarpService`] returns them as the
+/// service error instead, so they can be handled with `HandleErrorLayer` or other Tower error
+/// handling.
+///
+/// [`FallibleWarpService`]: crate::FallibleWarpService
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The request or response could not be converted between HTTP versions.
+    Conversion(String),
+    /// The request did not complete within the configured timeout.
+    Timeout(Duration),
+    /// A layer applied with `WarpService::layer` returned an error.
+    Layer(BoxError),
+    /// A service wrapped by `HttpCompatService` returned an error.
+    Service(BoxError),
+    /// The request was rejected by a `RateLimit`, and may be retried after the given delay.
+    RateLimited(Duration),
+    /// The service is shutting down and no longer accepts requests.
+    ShuttingDown,
+    /// The request body is longer than the configured limit, in bytes.
+    PayloadTooLarge(usize),
+    /// The request could not be forwarded to an upstream server, such as by a
+    /// `RemoteWarpService`.
+    Upstream(BoxError),
+    /// The route's `CircuitBreaker` is open and no fallback is configured.
+    CircuitOpen,
+    /// The request has more header fields than the configured limit.
+    TooManyHeaders(usize),
+    /// The request headers are larger than the configured limit, in bytes.
+    HeadersTooLarge(usize),
+    /// The request path and query are longer than the configured limit, in bytes.
+    UriTooLong(usize),
+    /// The request body framing is ambiguous, such as when both `Content-Length` and
+    /// `Transfer-Encoding` are set.
+    InvalidFraming(&'static str),
+}
+
+impl Error {
+    /// Returns the status code used when this error is converted into a response.
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Error::Conversion(_) | Error::Layer(_) | Error::Service(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            Error::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            Error::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            Error::ShuttingDown | Error::CircuitOpen => StatusCode::SERVICE_UNAVAILABLE,
+            Error::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            Error::Upstream(_) => StatusCode::BAD_GATEWAY,
+            Error::TooManyHeaders(_) | Error::HeadersTooLarge(_) => {
+                StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+            }
+            Error::UriTooLong(_) => StatusCode::URI_TOO_LONG,
+            Error::InvalidFraming(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Conversion(err) => write!(f, "Conversion error: {}", err),
+            Error::Timeout(timeout) => write!(f, "Request timed out after {:?}", timeout),
+            Error::Layer(err) => write!(f, "Unhandled internal error: {}", err),
+            Error::Service(err) => write!(f, "Service error: {}", err),
+            Error::RateLimited(retry_after) => {
+                write!(f, "Rate limit exceeded, retry after {:?}", retry_after)
+            }
+            Error::ShuttingDown => write!(f, "Service is shutting down"),
+            Error::PayloadTooLarge(limit) => {
+                write!(f, "Request body exceeds the limit of {} bytes", limit)
+            }
+            Error::Upstream(err) => write!(f, "Upstream error: {}", err),
+            Error::CircuitOpen => write!(f, "Circuit breaker is open"),
+            Error::TooManyHeaders(limit) => {
+                write!(f, "Request has more than {} header fields", limit)
+            }
+            Error::HeadersTooLarge(limit) => {
+                write!(f, "Request headers exceed the limit of {} bytes", limit)
+            }
+            Error::UriTooLong(limit) => {
+                write!(f, "Request URI exceeds the limit of {} bytes", limit)
+            }
+            Error::InvalidFraming(reason) => write!(f, "Invalid request framing: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Layer(err) | Error::Service(err) | Error::Upstream(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<String> for Error {
+    fn from(err: String) -> Self {
+        Error::Conversion(err)
+    }
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        if let Error::Conversion(err) = self {
+            return create_conversion_error_response(err);
+        }
+
+        let status = self.status();
+
+        let mut builder = Response::builder()
+            .status(status)
+            .header("content-type", "text/plain");
+
+        if let Error::RateLimited(retry_after) = &self {
+            // Retry-After is in whole seconds, so round up to avoid retrying too early.
+            let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+            builder = builder.header("retry-after", seconds);
+        }
+
+        builder
+            .body(Body::from(self.to_string()))
+            .unwrap_or_else(|_| {
+                Response::builder()
+                    .status(status)
+                    .body(Body::from("Critical error"))
+                    .unwrap()
+            })
+    }
+}
@@ -0,0 +1,240 @@
+use std::{error::Error as StdError, fmt, sync::Arc};
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use futures::future::{self, BoxFuture};
+use warp::{Rejection, reject::Reject};
+
+use crate::reply::AxumReply;
+
+type Extract = dyn for<'a> Fn(&'a Rejection) -> Option<&'a (dyn StdError + 'static)> + Send + Sync;
+type Respond = dyn Fn(&ErrorChain) -> Response + Send + Sync;
+
+/// Maps Warp rejections that wrap an error, such as an `anyhow::Error`, into Axum responses.
+///
+/// Warp answers custom rejections it does not recognize with a generic `500 Internal Server
+/// Error`, losing the error. The bridge finds the error in a rejection with the given
+/// extractor, captures its chain of sources as an [`ErrorChain`], and builds the response
+/// from it. The chain is attached to the response as an extension, and carried through the
+/// conversion to Axum, so that middleware on the Axum side can log it.
+///
+/// By default, the response is a plain `500 Internal Server Error` that does not reveal the
+/// error. Rejections without an error are passed through unchanged. The bridge is applied
+/// to a filter with [`recover`](warp::Filter::recover), using [`ErrorBridge::handler`].
+///
+/// # Example
+///
+/// ```rust
+/// use axum::{Router, http::StatusCode, middleware::map_response, response::Response};
+/// use warpdrive::{ErrorBridge, ErrorChain, WarpService};
+/// use warp::Filter;
+///
+/// // The rejection used by the legacy handlers, wrapping an `anyhow::Error` or any other
+/// // error type.
+/// #[derive(Debug)]
+/// struct AppError(Box<dyn std::error::Error + Send + Sync>);
+///
+/// impl warp::reject::Reject for AppError {}
+///
+/// let bridge = ErrorBridge::new(|rejection| {
+///     rejection
+///         .find::<AppError>()
+///         .map(|err| &*err.0 as &(dyn std::error::Error + 'static))
+/// })
+/// .respond(|chain| (StatusCode::BAD_GATEWAY, chain.root_cause().to_string()));
+///
+/// let filter = warp::path("legacy")
+///     .and_then(|| async {
+///         Err::<String, _>(warp::reject::custom(AppError("upstream failed".into())))
+///     })
+///     .recover(bridge.handler())
+///     .boxed();
+///
+/// let app: Router = Router::new()
+///     .fallback_service(WarpService::new(filter))
+///     .layer(map_response(|response: Response| async move {
+///         if let Some(chain) = response.extensions().get::<ErrorChain>() {
+///             eprintln!("legacy route failed: {}", chain);
+///         }
+///         response
+///     }));
+/// ```
+#[derive(Clone)]
+pub struct ErrorBridge {
+    extract: Arc<Extract>,
+    respond: Arc<Respond>,
+}
+
+impl ErrorBridge {
+    /// Creates a bridge that finds errors in rejections with the given extractor.
+    pub fn new<F>(extract: F) -> Self
+    where
+        F: for<'a> Fn(&'a Rejection) -> Option<&'a (dyn StdError + 'static)>
+            + Send
+            + Sync
+            + 'static,
+    {
+        ErrorBridge {
+            extract: Arc::new(extract),
+            respond: Arc::new(|_: &ErrorChain| StatusCode::INTERNAL_SERVER_ERROR.into_response()),
+        }
+    }
+
+    /// Sets how the response is built from the error chain.
+    pub fn respond<F, R>(mut self, respond: F) -> Self
+    where
+        F: Fn(&ErrorChain) -> R + Send + Sync + 'static,
+        R: IntoResponse,
+    {
+        self.respond = Arc::new(move |chain| respond(chain).into_response());
+        self
+    }
+
+    /// Maps a rejection into a response with the error chain attached, if the extractor
+    /// finds an error in it.
+    pub fn map_rejection(&self, rejection: &Rejection) -> Option<Response> {
+        let chain = ErrorChain::new((self.extract)(rejection)?);
+
+        let mut response = (self.respond)(&chain);
+        response.extensions_mut().insert(chain);
+        Some(response)
+    }
+
+    /// Returns a Warp recovery handler that applies the bridge, for use with
+    /// [`recover`](warp::Filter::recover).
+    pub fn handler(
+        &self,
+    ) -> impl Fn(Rejection) -> BoxFuture<'static, Result<warp::reply::Response, Rejection>>
+    + Clone
+    + Send
+    + Sync
+    + 'static {
+        let bridge = self.clone();
+
+        move |rejection: Rejection| {
+            let result = match bridge.map_rejection(&rejection) {
+                Some(response) => Ok(warp::Reply::into_response(AxumReply(response))),
+                None => Err(rejection),
+            };
+            Box::pin(future::ready(result))
+        }
+    }
+}
+
+impl fmt::Debug for ErrorBridge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErrorBridge").finish_non_exhaustive()
+    }
+}
+
+/// The messages of an error and its sources, outermost first.
+///
+/// An `ErrorChain` is captured by an [`ErrorBridge`] and attached to the response it builds,
+/// where it can be read with `response.extensions().get::<ErrorChain>()`. It displays as the
+/// messages separated by `: `, like `anyhow`'s alternate format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorChain {
+    messages: Arc<[String]>,
+}
+
+impl ErrorChain {
+    /// Captures the messages of an error and its sources.
+    pub fn new(error: &(dyn StdError + 'static)) -> Self {
+        let mut messages = Vec::new();
+        let mut source = Some(error);
+        while let Some(error) = source {
+            messages.push(error.to_string());
+            source = error.source();
+        }
+
+        ErrorChain {
+            messages: messages.into(),
+        }
+    }
+
+    /// Returns the messages, outermost first.
+    pub fn messages(&self) -> &[String] {
+        &self.messages
+    }
+
+    /// Returns the message of the outermost error.
+    pub fn message(&self) -> &str {
+        &self.messages[0]
+    }
+
+    /// Returns the message of the innermost source.
+    pub fn root_cause(&self) -> &str {
+        &self.messages[self.messages.len() - 1]
+    }
+}
+
+impl fmt::Display for ErrorChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.messages.join(": "))
+    }
+}
+
+/// An error type whose rejections map to a response with a status code and a message.
+///
+/// This declares the mapping of a legacy error type, usually an enum, in one place. The
+/// mapping is applied to a filter with [`recover`](warp::Filter::recover), using
+/// [`recover_mapped`], and rejections with the error are answered with its status and a
+/// plain text message. With the `macros` feature, it can be derived from attributes on each
+/// variant, as shown below.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "macros")]
+/// # {
+/// use warpdrive::RejectionMapper;
+/// use warp::Filter;
+///
+/// #[derive(Debug, thiserror::Error, RejectionMapper)]
+/// #[rejection(status = 500)]
+/// enum ApiError {
+///     #[error("user {0} not found")]
+///     #[rejection(status = 404)]
+///     NotFound(u32),
+///     #[error("query failed: {0}")]
+///     #[rejection(message = "Internal Server Error")]
+///     Database(String),
+/// }
+///
+/// impl warp::reject::Reject for ApiError {}
+///
+/// let filter = warp::path!("users" / u32)
+///     .and_then(|id| async move {
+///         Err::<String, _>(warp::reject::custom(ApiError::NotFound(id)))
+///     })
+///     .recover(warpdrive::recover_mapped::<ApiError>);
+/// # }
+/// ```
+///
+/// The derive accepts enums with a `#[rejection(...)]` attribute on each variant, or on the
+/// enum as a default. Each variant needs a `status`, and its `message` defaults to the
+/// variant's `Display` output, as generated by `thiserror`.
+pub trait RejectionMapper: Reject {
+    /// Returns the status code of the response.
+    fn status(&self) -> StatusCode;
+
+    /// Returns the message in the body of the response.
+    fn message(&self) -> String;
+}
+
+/// A Warp recovery handler that answers rejections with an error of type `E` with its status
+/// and message, as declared by its [`RejectionMapper`] implementation.
+///
+/// Any other rejection is passed through unchanged.
+pub async fn recover_mapped<E: RejectionMapper>(
+    rejection: Rejection,
+) -> Result<warp::reply::Response, Rejection> {
+    let Some(err) = rejection.find::<E>() else {
+        return Err(rejection);
+    };
+
+    let response = (err.status(), err.message()).into_response();
+    Ok(warp::Reply::into_response(AxumReply(response)))
+}
@@ -0,0 +1,92 @@
+use std::{
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use axum::body::{Body, Bytes};
+use http_body::{Body as HttpBody, Frame, SizeHint};
+
+/// The first bytes of a body, up to a limit, and whether the body was longer.
+#[derive(Default)]
+pub(crate) struct Excerpt {
+    bytes: Vec<u8>,
+    truncated: bool,
+}
+
+impl Excerpt {
+    fn push(&mut self, data: &[u8], limit: usize) {
+        let remaining = limit.saturating_sub(self.bytes.len());
+        self.bytes
+            .extend_from_slice(&data[..data.len().min(remaining)]);
+        self.truncated |= data.len() > remaining;
+    }
+
+    /// Takes the bytes of the excerpt, and whether the body was longer.
+    pub(crate) fn take(&mut self) -> (Bytes, bool) {
+        (Bytes::from(std::mem::take(&mut self.bytes)), self.truncated)
+    }
+}
+
+/// A body that copies the start of its data into an excerpt as it is read, without buffering
+/// it.
+///
+/// A value can be attached that is dropped when the body ends, fails, or is dropped, for
+/// recording the excerpts once the exchange is over.
+pub(crate) struct ExcerptBody<D> {
+    inner: Body,
+    excerpt: Arc<Mutex<Excerpt>>,
+    limit: usize,
+    on_end: Option<D>,
+}
+
+impl<D> ExcerptBody<D> {
+    /// Wraps a body, copying up to `limit` bytes of it into `excerpt`.
+    pub(crate) fn new(
+        inner: Body,
+        excerpt: Arc<Mutex<Excerpt>>,
+        limit: usize,
+        on_end: Option<D>,
+    ) -> Self {
+        ExcerptBody {
+            inner,
+            excerpt,
+            limit,
+            on_end,
+        }
+    }
+}
+
+impl<D: Unpin> HttpBody for ExcerptBody<D> {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+        let frame = std::task::ready!(Pin::new(&mut this.inner).poll_frame(cx));
+
+        match &frame {
+            Some(Ok(frame)) => {
+                if let Some(data) = frame.data_ref() {
+                    this.excerpt.lock().unwrap().push(data, this.limit);
+                }
+            }
+            Some(Err(_)) | None => {
+                this.on_end.take();
+            }
+        }
+
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
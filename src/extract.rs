@@ -0,0 +1,260 @@
+use std::{
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::{FromRequest, FromRequestParts, Request},
+    http::request::Parts,
+    response::{IntoResponse, Response},
+};
+use tower::Service;
+use warp::{Filter, Rejection, filters::BoxedFilter, reject::Reject};
+
+use crate::{
+    convert_request::{axum_parts_filter, into_warp_request},
+    convert_response::into_axum_response,
+    reply::AxumReply,
+    warp_service::create_conversion_error_response,
+};
+
+/// A source of a Warp filter that can be used with [`WarpExtract`].
+///
+/// The filter is requested once per extraction, so constructing it should be cheap.
+/// Large filter trees can be built once and cached, since cloning a `BoxedFilter`
+/// only clones an `Arc`.
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::ExtractFilter;
+/// use warp::{Filter, filters::BoxedFilter};
+///
+/// struct ApiKey;
+///
+/// impl ExtractFilter for ApiKey {
+///     type Extract = String;
+///
+///     fn filter() -> BoxedFilter<(String,)> {
+///         warp::header::<String>("x-api-key").boxed()
+///     }
+/// }
+/// ```
+pub trait ExtractFilter: Send + Sync + 'static {
+    /// The value extracted by the filter.
+    type Extract: Send + 'static;
+
+    /// Returns the filter to run against the request.
+    fn filter() -> BoxedFilter<(Self::Extract,)>;
+}
+
+/// An Axum extractor that runs a Warp filter against the request.
+///
+/// On success the extracted value is available as the first field. If the filter
+/// rejects, the rejection is converted using Warp's default rejection handling and
+/// returned as the Axum response.
+///
+/// Since the filter may consume the request body, `WarpExtract` must be the last
+/// extractor in the handler signature.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::{Router, routing::get};
+/// use warpdrive::{ExtractFilter, WarpExtract};
+/// use warp::{Filter, filters::BoxedFilter};
+///
+/// struct ApiKey;
+///
+/// impl ExtractFilter for ApiKey {
+///     type Extract = String;
+///
+///     fn filter() -> BoxedFilter<(String,)> {
+///         warp::header::<String>("x-api-key").boxed()
+///     }
+/// }
+///
+/// async fn handler(WarpExtract(key, _): WarpExtract<String, ApiKey>) -> String {
+///     format!("Key: {}", key)
+/// }
+///
+/// let app: Router = Router::new().route("/", get(handler));
+/// ```
+pub struct WarpExtract<T, F>(pub T, pub PhantomData<F>);
+
+impl<T, F> WarpExtract<T, F> {
+    /// Consumes the extractor, returning the extracted value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T, F> Deref for WarpExtract<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, F> DerefMut for WarpExtract<T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<S, T, F> FromRequest<S> for WarpExtract<T, F>
+where
+    S: Send + Sync,
+    T: Send + 'static,
+    F: ExtractFilter<Extract = T>,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, _state: &S) -> Result<Self, Self::Rejection> {
+        run_filter(req, F::filter())
+            .await
+            .map(|value| WarpExtract(value, PhantomData))
+    }
+}
+
+/// Runs `filter` against the request, returning the extracted value or the converted
+/// rejection response.
+pub(crate) async fn run_filter<T>(req: Request, filter: BoxedFilter<(T,)>) -> Result<T, Response>
+where
+    T: Send + 'static,
+{
+    let warp_req = into_warp_request(req)
+        .await
+        .map_err(create_conversion_error_response)?;
+
+    let slot = Arc::new(Mutex::new(None));
+    let filter_slot = Arc::clone(&slot);
+    let capture = filter.map(move |value: T| {
+        *filter_slot.lock().unwrap() = Some(value);
+        warp::reply()
+    });
+
+    let warp_response = match warp::service(capture).call(warp_req).await {
+        Ok(reply) => reply,
+        Err(never) => match never {},
+    };
+
+    if let Some(value) = slot.lock().unwrap().take() {
+        return Ok(value);
+    }
+
+    Err(match into_axum_response(warp_response) {
+        Ok(resp) => resp,
+        Err(err) => create_conversion_error_response(err),
+    })
+}
+
+/// Creates a Warp filter that runs an Axum [`FromRequestParts`] extractor.
+///
+/// This allows extraction logic written for Axum (typed headers, custom extractors) to be
+/// shared with Warp routes that have not been migrated yet. Rejections are reported as an
+/// [`AxumRejection`], which can be turned back into the extractor's response with
+/// [`handle_axum_rejection`].
+///
+/// Warp does not expose request extensions, so extractors that depend on them, such as
+/// `Extension<T>`, only work for requests that reached the filter through a
+/// [`WarpService`](crate::WarpService), which carries the extensions of the Axum request.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::http::Method;
+/// use warpdrive::axum_extract;
+/// use warp::Filter;
+///
+/// let route = warp::path("method")
+///     .and(axum_extract::<Method>())
+///     .map(|method: Method| format!("Method: {}", method))
+///     .recover(warpdrive::handle_axum_rejection);
+/// ```
+pub fn axum_extract<E>() -> BoxedFilter<(E,)>
+where
+    E: FromRequestParts<()> + Send + 'static,
+{
+    axum_extract_with_state(())
+}
+
+/// Creates a Warp filter that runs an Axum [`FromRequestParts`] extractor with the given state.
+///
+/// See [`axum_extract`] for details.
+pub fn axum_extract_with_state<E, S>(state: S) -> BoxedFilter<(E,)>
+where
+    E: FromRequestParts<S> + Send + 'static,
+    S: Clone + Send + Sync + 'static,
+{
+    axum_parts_filter()
+        .and_then(move |parts: Result<Parts, String>| {
+            let state = state.clone();
+            async move {
+                let mut parts = parts.map_err(|e| {
+                    warp::reject::custom(AxumRejection::new(create_conversion_error_response(e)))
+                })?;
+
+                E::from_request_parts(&mut parts, &state)
+                    .await
+                    .map_err(|rejection| {
+                        warp::reject::custom(AxumRejection::new(rejection.into_response()))
+                    })
+            }
+        })
+        .boxed()
+}
+
+/// A Warp rejection produced when an Axum extractor run by [`axum_extract`] fails.
+///
+/// The rejection holds the extractor's response, which can be taken exactly once.
+pub struct AxumRejection {
+    status: axum::http::StatusCode,
+    response: Mutex<Option<Response>>,
+}
+
+impl AxumRejection {
+    fn new(response: Response) -> Self {
+        AxumRejection {
+            status: response.status(),
+            response: Mutex::new(Some(response)),
+        }
+    }
+
+    /// Returns the status code of the extractor's rejection response.
+    pub fn status(&self) -> axum::http::StatusCode {
+        self.status
+    }
+
+    /// Takes the extractor's rejection response, if it has not already been taken.
+    pub fn take_response(&self) -> Option<Response> {
+        self.response.lock().unwrap().take()
+    }
+}
+
+impl fmt::Debug for AxumRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AxumRejection")
+            .field("status", &self.status)
+            .finish()
+    }
+}
+
+impl Reject for AxumRejection {}
+
+/// A Warp recovery handler that converts an [`AxumRejection`] into the extractor's response.
+///
+/// Any other rejection is passed through unchanged.
+pub async fn handle_axum_rejection(err: Rejection) -> Result<warp::reply::Response, Rejection> {
+    let Some(response) = err
+        .find::<AxumRejection>()
+        .and_then(AxumRejection::take_response)
+    else {
+        return Err(err);
+    };
+
+    Ok(warp::Reply::into_response(AxumReply(response)))
+}
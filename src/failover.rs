@@ -0,0 +1,189 @@
+use std::{
+    convert::Infallible, fmt, future::Future, panic::AssertUnwindSafe, sync::Arc, time::Duration,
+};
+
+use axum::{
+    body::{Body, HttpBody},
+    extract::Request,
+    http::{HeaderValue, StatusCode, request::Parts},
+    response::Response,
+};
+use futures::FutureExt;
+use tower::{Service, ServiceExt, util::BoxCloneSyncService};
+
+use crate::error::Error;
+
+/// The largest request body buffered for replay by default.
+const DEFAULT_MAX_BODY_SIZE: usize = 1024 * 1024;
+
+type FailoverHook = Arc<dyn Fn(&Parts, &FailoverReason) + Send + Sync>;
+
+/// Failover settings for a [`WarpService`](crate::WarpService).
+///
+/// When the Warp filter responds with a `5xx` status, times out, fails at the boundary, or
+/// panics, the request is replayed against a designated Axum service and its response is
+/// served instead. Responses served this way have an `x-warpdrive-failover` header naming
+/// the reason. This is a safety net while migrating a route: keep the Warp implementation
+/// serving traffic, with the Axum implementation standing by.
+///
+/// The request body is buffered so it can be replayed. Requests whose body may exceed
+/// [`max_body_size`](Failover::max_body_size), including streaming bodies of unknown length,
+/// are sent to the Warp filter without failover. Rate limited requests never fail over.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::routing::get;
+/// use warpdrive::{Failover, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("users").map(|| "Users from Warp").boxed();
+///
+/// let failover = Failover::new(get(|| async { "Users from Axum" })).on_failover(|req, reason| {
+///     eprintln!("{} {} failed over: {}", req.method, req.uri, reason);
+/// });
+///
+/// let service = WarpService::new(filter).with_failover(failover);
+/// ```
+#[derive(Clone)]
+pub struct Failover {
+    fallback: BoxCloneSyncService<Request, Response, Infallible>,
+    max_body_size: usize,
+    on_failover: Option<FailoverHook>,
+}
+
+/// Why a request failed over to the Axum service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FailoverReason {
+    /// The Warp filter responded with a server error status.
+    Status(StatusCode),
+    /// The request timed out.
+    Timeout(Duration),
+    /// The request failed at the boundary, for example during conversion.
+    Error(String),
+    /// The Warp filter panicked.
+    Panic,
+}
+
+impl FailoverReason {
+    fn header_value(&self) -> HeaderValue {
+        HeaderValue::from_static(match self {
+            FailoverReason::Status(_) => "status",
+            FailoverReason::Timeout(_) => "timeout",
+            FailoverReason::Error(_) => "error",
+            FailoverReason::Panic => "panic",
+        })
+    }
+}
+
+impl fmt::Display for FailoverReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailoverReason::Status(status) => write!(f, "Warp responded with {}", status),
+            FailoverReason::Timeout(timeout) => write!(f, "Request timed out after {:?}", timeout),
+            FailoverReason::Error(err) => write!(f, "{}", err),
+            FailoverReason::Panic => write!(f, "Warp filter panicked"),
+        }
+    }
+}
+
+impl Failover {
+    /// Creates failover settings that replay failed requests against the given Axum service,
+    /// such as a `MethodRouter` or a handler converted with `into_service`.
+    pub fn new<S>(fallback: S) -> Self
+    where
+        S: Service<Request, Response = Response, Error = Infallible>
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        S::Future: Send + 'static,
+    {
+        Failover {
+            fallback: BoxCloneSyncService::new(fallback),
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            on_failover: None,
+        }
+    }
+
+    /// Sets the largest request body, in bytes, that is buffered for replay. Defaults to
+    /// 1 MiB.
+    pub fn max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// Calls a function whenever a request fails over, for logging, metrics, or alerting.
+    pub fn on_failover<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Parts, &FailoverReason) + Send + Sync + 'static,
+    {
+        self.on_failover = Some(Arc::new(hook));
+        self
+    }
+
+    /// Runs a request through `attempt`, replaying it against the fallback service if it
+    /// fails.
+    pub(crate) async fn run<F, Fut>(&self, req: Request, attempt: F) -> Result<Response, Error>
+    where
+        F: FnOnce(Request) -> Fut,
+        Fut: Future<Output = Result<Response, Error>>,
+    {
+        let replayable = req
+            .body()
+            .size_hint()
+            .upper()
+            .is_some_and(|upper| upper <= self.max_body_size as u64);
+
+        if !replayable {
+            return attempt(req).await;
+        }
+
+        let (parts, body) = req.into_parts();
+        let body = axum::body::to_bytes(body, self.max_body_size)
+            .await
+            .map_err(|e| Error::Conversion(format!("Failed to read request body: {}", e)))?;
+
+        let warp_req = Request::from_parts(parts.clone(), Body::from(body.clone()));
+        let reason = match AssertUnwindSafe(attempt(warp_req)).catch_unwind().await {
+            Ok(Ok(response)) if response.status().is_server_error() => {
+                FailoverReason::Status(response.status())
+            }
+            Ok(Ok(response)) => return Ok(response),
+            Ok(Err(err @ Error::RateLimited(_))) => return Err(err),
+            Ok(Err(Error::Timeout(timeout))) => FailoverReason::Timeout(timeout),
+            Ok(Err(err)) => FailoverReason::Error(err.to_string()),
+            Err(_) => FailoverReason::Panic,
+        };
+
+        if let Some(hook) = &self.on_failover {
+            hook(&parts, &reason);
+        }
+
+        let mut response = match self
+            .fallback
+            .clone()
+            .oneshot(Request::from_parts(parts, Body::from(body)))
+            .await
+        {
+            Ok(response) => response,
+            Err(never) => match never {},
+        };
+
+        response
+            .headers_mut()
+            .insert("x-warpdrive-failover", reason.header_value());
+
+        Ok(response)
+    }
+}
+
+impl fmt::Debug for Failover {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Failover")
+            .field("max_body_size", &self.max_body_size)
+            .field("on_failover", &self.on_failover.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,213 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::BuildHasher,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use axum::{body::Body, http::StatusCode, response::Response};
+use futures::{StreamExt, stream};
+use tower::BoxError;
+
+use crate::prefix::{matches_prefix, trim_prefix};
+
+/// Fault injection settings for a [`WarpService`](crate::WarpService).
+///
+/// Faults are configured per path prefix with a probability between `0.0` and `1.0`, and are
+/// applied with [`WarpService::with_fault_injection`](crate::WarpService::with_fault_injection).
+/// Prefixes match whole segments: `/legacy` matches `/legacy` and `/legacy/users`, but not
+/// `/legacy-v2`.
+/// Each matching rule is rolled independently for every request, so a request can be both
+/// delayed and failed. This is intended for verifying Axum-side fallbacks, retries, and
+/// alerting in test and staging environments.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use axum::http::StatusCode;
+/// use warpdrive::{FaultInjection, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("legacy").map(|| "Hello").boxed();
+///
+/// let faults = FaultInjection::new()
+///     .latency("/legacy", Duration::from_millis(250), 0.1)
+///     .error("/legacy", StatusCode::SERVICE_UNAVAILABLE, 0.01);
+///
+/// let service = WarpService::new(filter).with_fault_injection(faults);
+/// ```
+#[derive(Debug)]
+pub struct FaultInjection {
+    rules: Vec<FaultRule>,
+    state: AtomicU64,
+}
+
+#[derive(Debug, Clone)]
+struct FaultRule {
+    path_prefix: String,
+    probability: f64,
+    fault: Fault,
+}
+
+#[derive(Debug, Clone)]
+enum Fault {
+    Latency(Duration),
+    Error(StatusCode),
+    TruncateBody(usize),
+}
+
+/// The faults chosen for a single request.
+#[derive(Debug, Default)]
+pub(crate) struct FaultPlan {
+    pub(crate) latency: Option<Duration>,
+    pub(crate) error: Option<StatusCode>,
+    pub(crate) truncate_body: Option<usize>,
+}
+
+impl Default for FaultInjection {
+    fn default() -> Self {
+        FaultInjection::new()
+    }
+}
+
+impl FaultInjection {
+    /// Creates fault injection settings with no faults, seeded randomly.
+    pub fn new() -> Self {
+        FaultInjection {
+            rules: Vec::new(),
+            state: AtomicU64::new(RandomState::new().hash_one(0u64)),
+        }
+    }
+
+    /// Seeds the random number generator, so the same faults are injected on every run.
+    pub fn seed(self, seed: u64) -> Self {
+        self.state.store(seed, Ordering::Relaxed);
+        self
+    }
+
+    /// Delays matching requests before they reach the Warp filter.
+    ///
+    /// The delay counts towards any timeout set with
+    /// [`WarpService::with_timeout`](crate::WarpService::with_timeout).
+    pub fn latency(
+        self,
+        path_prefix: impl Into<String>,
+        delay: Duration,
+        probability: f64,
+    ) -> Self {
+        self.rule(path_prefix, probability, Fault::Latency(delay))
+    }
+
+    /// Responds to matching requests with an empty response with the given status, without
+    /// calling the Warp filter.
+    pub fn error(
+        self,
+        path_prefix: impl Into<String>,
+        status: StatusCode,
+        probability: f64,
+    ) -> Self {
+        self.rule(path_prefix, probability, Fault::Error(status))
+    }
+
+    /// Truncates matching response bodies after the given number of bytes, then fails the
+    /// body stream as if the connection was lost.
+    pub fn truncate_body(
+        self,
+        path_prefix: impl Into<String>,
+        after_bytes: usize,
+        probability: f64,
+    ) -> Self {
+        self.rule(path_prefix, probability, Fault::TruncateBody(after_bytes))
+    }
+
+    fn rule(mut self, path_prefix: impl Into<String>, probability: f64, fault: Fault) -> Self {
+        self.rules.push(FaultRule {
+            path_prefix: trim_prefix(&path_prefix.into()),
+            probability: probability.clamp(0.0, 1.0),
+            fault,
+        });
+        self
+    }
+
+    /// Rolls each rule matching the path, returning the faults to inject.
+    pub(crate) fn plan(&self, path: &str) -> FaultPlan {
+        let mut plan = FaultPlan::default();
+
+        for rule in &self.rules {
+            if !matches_prefix(&rule.path_prefix, path) || self.next_f64() >= rule.probability {
+                continue;
+            }
+
+            match rule.fault {
+                Fault::Latency(delay) => plan.latency = Some(delay),
+                Fault::Error(status) => plan.error = Some(status),
+                Fault::TruncateBody(after_bytes) => plan.truncate_body = Some(after_bytes),
+            }
+        }
+
+        plan
+    }
+
+    /// Returns a number in `[0, 1)` using SplitMix64.
+    fn next_f64(&self) -> f64 {
+        let mut z = self
+            .state
+            .fetch_add(0x9e37_79b9_7f4a_7c15, Ordering::Relaxed)
+            .wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+pub(crate) fn injected_error_response(status: StatusCode) -> Response {
+    Response::builder()
+        .status(status)
+        .header("x-warpdrive-fault", "error")
+        .body(Body::empty())
+        .unwrap_or_else(|_| Response::new(Body::empty()))
+}
+
+pub(crate) fn truncate_response(response: Response, after_bytes: usize) -> Response {
+    let (mut parts, body) = response.into_parts();
+    parts.headers.remove(axum::http::header::CONTENT_LENGTH);
+
+    let initial = (
+        body.into_data_stream(),
+        after_bytes,
+        TruncateStep::Streaming,
+    );
+    let truncated = stream::unfold(initial, |(mut data, remaining, step)| async move {
+        match step {
+            TruncateStep::Streaming => match data.next().await {
+                Some(Ok(chunk)) if chunk.len() >= remaining => {
+                    let partial = chunk.slice(..remaining);
+                    Some((Ok(partial), (data, 0, TruncateStep::Truncated)))
+                }
+                Some(Ok(chunk)) => {
+                    let remaining = remaining - chunk.len();
+                    Some((Ok(chunk), (data, remaining, TruncateStep::Streaming)))
+                }
+                Some(Err(err)) => Some((Err(BoxError::from(err)), (data, 0, TruncateStep::Done))),
+                None => None,
+            },
+            TruncateStep::Truncated => {
+                let err = BoxError::from("response body truncated by fault injection");
+                Some((Err(err), (data, 0, TruncateStep::Done)))
+            }
+            TruncateStep::Done => None,
+        }
+    });
+
+    Response::from_parts(parts, Body::from_stream(truncated))
+}
+
+enum TruncateStep {
+    Streaming,
+    Truncated,
+    Done,
+}
@@ -0,0 +1,58 @@
+use axum::Router;
+use warp::{Filter, Rejection, Reply};
+
+use crate::warp_service::WarpService;
+
+/// Extension methods for converting Warp filters into values that can be mounted in Axum.
+///
+/// This keeps the bridging code next to the filter definition, so a module can export an
+/// Axum service or router directly.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::{Router, routing::get};
+/// use warpdrive::FilterExt;
+/// use warp::Filter;
+///
+/// fn users() -> Router {
+///     warp::path!("users" / u32)
+///         .map(|id| format!("User {}", id))
+///         .into_axum_router("/users/{id}")
+/// }
+///
+/// let app: Router = Router::new()
+///     .route("/", get(|| async { "Hello from Axum!" }))
+///     .merge(users())
+///     .fallback_service(warp::path("legacy").map(|| "Legacy").into_axum_service());
+/// ```
+pub trait FilterExt: Filter + Sized {
+    /// The type of reply produced by the filter.
+    type Reply: Reply + Send + Sync + 'static;
+
+    /// Converts the filter into a [`WarpService`], such as for `Router::fallback_service`.
+    fn into_axum_service(self) -> WarpService<Self::Reply>;
+
+    /// Converts the filter into an Axum router that sends requests for `path` to the filter.
+    ///
+    /// The path uses Axum's syntax, such as `/users/{id}` or `/legacy/{*rest}`. The Warp
+    /// filter sees the full request path, so it must match the same paths.
+    fn into_axum_router<S>(self, path: &str) -> Router<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        Router::new().route_service(path, self.into_axum_service())
+    }
+}
+
+impl<F, R> FilterExt for F
+where
+    F: Filter<Extract = (R,), Error = Rejection> + Clone + Send + Sync + 'static,
+    R: Reply + Send + Sync + 'static,
+{
+    type Reply = R;
+
+    fn into_axum_service(self) -> WarpService<R> {
+        WarpService::new(self.boxed())
+    }
+}
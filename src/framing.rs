@@ -0,0 +1,72 @@
+use axum::http::{
+    HeaderMap,
+    header::{CONTENT_LENGTH, TRANSFER_ENCODING},
+};
+
+use crate::error::Error;
+
+/// Rejects requests whose body framing could be interpreted differently by different layers.
+///
+/// The conversion re-frames request bodies, so a request that a proxy in front of the server
+/// reads one way could reach the Warp filter framed another way. Requests are rejected with
+/// [`Error::InvalidFraming`] if they:
+///
+/// - have both `Content-Length` and `Transfer-Encoding`,
+/// - have a `Content-Length` that is not a number, or several that differ, or
+/// - have a `Transfer-Encoding` whose last coding is not `chunked`, or that applies `chunked`
+///   more than once.
+pub(crate) fn check_framing(headers: &HeaderMap) -> Result<(), Error> {
+    let mut lengths = headers
+        .get_all(CONTENT_LENGTH)
+        .iter()
+        .flat_map(|value| split_list(value.as_bytes()));
+    if let Some(first) = lengths.next() {
+        let first = parse_length(first)?;
+        for length in lengths {
+            if parse_length(length)? != first {
+                return Err(Error::InvalidFraming("conflicting Content-Length values"));
+            }
+        }
+    }
+
+    if !headers.contains_key(TRANSFER_ENCODING) {
+        return Ok(());
+    }
+    if headers.contains_key(CONTENT_LENGTH) {
+        return Err(Error::InvalidFraming(
+            "both Content-Length and Transfer-Encoding are set",
+        ));
+    }
+
+    let codings: Vec<_> = headers
+        .get_all(TRANSFER_ENCODING)
+        .iter()
+        .flat_map(|value| split_list(value.as_bytes()))
+        .collect();
+    let chunked = |coding: &[u8]| coding.eq_ignore_ascii_case(b"chunked");
+    if codings.last().is_none_or(|last| !chunked(last))
+        || codings.iter().filter(|coding| chunked(coding)).count() > 1
+    {
+        return Err(Error::InvalidFraming(
+            "Transfer-Encoding must end with a single chunked coding",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Splits a comma-separated header value into its trimmed, non-empty items.
+fn split_list(value: &[u8]) -> impl Iterator<Item = &[u8]> {
+    value
+        .split(|byte| *byte == b',')
+        .map(|item| item.trim_ascii())
+        .filter(|item| !item.is_empty())
+}
+
+fn parse_length(value: &[u8]) -> Result<u64, Error> {
+    std::str::from_utf8(value)
+        .ok()
+        .filter(|value| value.bytes().all(|byte| byte.is_ascii_digit()))
+        .and_then(|value| value.parse().ok())
+        .ok_or(Error::InvalidFraming("invalid Content-Length"))
+}
@@ -0,0 +1,347 @@
+//! Generators and round-trip properties for fuzzing the conversion boundary.
+//!
+//! The generators build requests and responses from raw bytes, so they can be driven by any
+//! fuzzer or property-testing framework. Generated values favour awkward inputs such as
+//! extension methods, percent-encoded and unusual URIs, repeated headers, non-UTF-8 header
+//! values, and binary bodies.
+//!
+//! Two integrations build on the generators:
+//!
+//! - With the `arbitrary` feature, `ArbitraryRequest` and `ArbitraryResponse` implement
+//!   `arbitrary::Arbitrary`, so they can be taken directly as `cargo fuzz` target inputs.
+//! - With the `proptest` feature, `request_strategy` and `response_strategy` return
+//!   proptest strategies. They generate the input bytes, so failing cases shrink towards
+//!   shorter inputs, which produce simpler requests and responses.
+//!
+//! The `check_*` functions run a value through both conversion directions and report any
+//! difference, and can be combined with your own invariants.
+//!
+//! # Example
+//!
+//! ```rust
+//! use warpdrive::fuzz::{arbitrary_request, check_request_round_trip};
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let input = b"fuzzer-provided bytes";
+//! let request = arbitrary_request(input);
+//!
+//! check_request_round_trip(request).await.unwrap();
+//! # }
+//! ```
+
+use axum::{
+    body::{Body, Bytes},
+    http::{HeaderName, HeaderValue, Method, Request, Response, StatusCode, Uri, Version},
+};
+
+use crate::{
+    convert_request::{into_axum_request, into_warp_request},
+    convert_response::{into_axum_response, into_warp_response},
+};
+
+const METHODS: [Method; 9] = [
+    Method::GET,
+    Method::POST,
+    Method::PUT,
+    Method::DELETE,
+    Method::HEAD,
+    Method::OPTIONS,
+    Method::CONNECT,
+    Method::PATCH,
+    Method::TRACE,
+];
+
+const VERSIONS: [Version; 3] = [Version::HTTP_10, Version::HTTP_11, Version::HTTP_2];
+
+const HEADER_NAMES: [&str; 10] = [
+    "content-type",
+    "accept",
+    "cookie",
+    "set-cookie",
+    "authorization",
+    "x-forwarded-for",
+    "cache-control",
+    "vary",
+    "x-custom",
+    "x-empty",
+];
+
+/// Characters allowed unescaped in a URI path or query, with extra weight on delimiters.
+const URI_CHARS: &[u8] = b"abcxyzABC019-._~!$&'()*+,;=:@//??==&&";
+
+/// Characters allowed in a header name token.
+const TOKEN_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789!#$&'*+-.^_`|~";
+
+/// Characters allowed in an extension method. `http` 0.2 rejects methods containing `#`,
+/// `$`, `%`, `&`, or `'`, so these cannot cross the boundary and are not generated.
+const METHOD_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcxyz0123456789!*+-.^_`|~";
+
+/// The largest input used by the `arbitrary` and `proptest` integrations for one value.
+#[cfg(any(test, feature = "arbitrary", feature = "proptest"))]
+const MAX_INPUT: usize = 1024;
+
+/// A request generated by [`arbitrary_request`], as a `cargo fuzz` target input.
+///
+/// Available with the `arbitrary` feature.
+#[cfg(any(test, feature = "arbitrary"))]
+#[derive(Debug)]
+pub struct ArbitraryRequest(pub Request<Bytes>);
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for ArbitraryRequest {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.arbitrary_len::<u8>()?.min(MAX_INPUT);
+        Ok(ArbitraryRequest(arbitrary_request(u.bytes(len)?)))
+    }
+
+    fn arbitrary_take_rest(u: arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(ArbitraryRequest(arbitrary_request(u.take_rest())))
+    }
+}
+
+/// A response generated by [`arbitrary_response`], as a `cargo fuzz` target input.
+///
+/// Available with the `arbitrary` feature.
+#[cfg(any(test, feature = "arbitrary"))]
+#[derive(Debug)]
+pub struct ArbitraryResponse(pub Response<Bytes>);
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for ArbitraryResponse {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.arbitrary_len::<u8>()?.min(MAX_INPUT);
+        Ok(ArbitraryResponse(arbitrary_response(u.bytes(len)?)))
+    }
+
+    fn arbitrary_take_rest(u: arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(ArbitraryResponse(arbitrary_response(u.take_rest())))
+    }
+}
+
+/// Returns a proptest strategy for requests generated by [`arbitrary_request`].
+///
+/// Available with the `proptest` feature.
+#[cfg(any(test, feature = "proptest"))]
+pub fn request_strategy() -> impl proptest::strategy::Strategy<Value = Request<Bytes>> {
+    use proptest::strategy::Strategy;
+
+    input_strategy().prop_map(|input| arbitrary_request(&input))
+}
+
+/// Returns a proptest strategy for responses generated by [`arbitrary_response`].
+///
+/// Available with the `proptest` feature.
+#[cfg(any(test, feature = "proptest"))]
+pub fn response_strategy() -> impl proptest::strategy::Strategy<Value = Response<Bytes>> {
+    use proptest::strategy::Strategy;
+
+    input_strategy().prop_map(|input| arbitrary_response(&input))
+}
+
+#[cfg(any(test, feature = "proptest"))]
+fn input_strategy() -> impl proptest::strategy::Strategy<Value = Vec<u8>> {
+    proptest::collection::vec(proptest::num::u8::ANY, 0..MAX_INPUT)
+}
+
+/// Generates an HTTP request from raw bytes.
+///
+/// The same input always produces the same request, and every input produces a valid
+/// request.
+pub fn arbitrary_request(data: &[u8]) -> Request<Bytes> {
+    let mut input = Input::new(data);
+
+    let method = if input.ratio(1, 8) {
+        let token = input.token(METHOD_CHARS, 1, 12);
+        Method::from_bytes(&token).unwrap_or(Method::GET)
+    } else {
+        input.choose(&METHODS).clone()
+    };
+
+    let mut request = Request::new(Bytes::new());
+    *request.method_mut() = method;
+    *request.uri_mut() = arbitrary_uri(&mut input);
+    *request.version_mut() = *input.choose(&VERSIONS);
+    arbitrary_headers(&mut input, request.headers_mut());
+    *request.body_mut() = input.bytes(256).into();
+
+    request
+}
+
+/// Generates an HTTP response from raw bytes.
+///
+/// The same input always produces the same response, and every input produces a valid
+/// response.
+pub fn arbitrary_response(data: &[u8]) -> Response<Bytes> {
+    let mut input = Input::new(data);
+
+    let status = if input.ratio(1, 8) {
+        // Any status code, including unregistered ones.
+        100 + input.u16() % 900
+    } else {
+        200 + (input.byte() as u16 % 4) * 100 + input.byte() as u16 % 8
+    };
+
+    let mut response = Response::new(Bytes::new());
+    *response.status_mut() = StatusCode::from_u16(status).unwrap_or(StatusCode::OK);
+    *response.version_mut() = *input.choose(&VERSIONS);
+    arbitrary_headers(&mut input, response.headers_mut());
+    let body = input.bytes(256);
+
+    // `1xx`, `204`, and `304` responses never have a body.
+    if !crate::compat_body::forbids_body(status) {
+        *response.body_mut() = body.into();
+    }
+
+    response
+}
+
+/// Converts a request to a Warp request and back, returning an error describing the first
+/// difference from the original.
+pub async fn check_request_round_trip(request: Request<Bytes>) -> Result<(), String> {
+    let (parts, body) = request.into_parts();
+    let original = Request::from_parts(parts.clone(), Body::from(body.clone()));
+
+    let warp_request = into_warp_request(original).await?;
+    let round_trip = into_axum_request(warp_request)?;
+
+    let (round_trip_parts, round_trip_body) = round_trip.into_parts();
+    let round_trip_body = collect(round_trip_body).await?;
+
+    compare("method", &parts.method, &round_trip_parts.method)?;
+    compare("uri", &parts.uri, &round_trip_parts.uri)?;
+    compare("version", &parts.version, &round_trip_parts.version)?;
+    compare("headers", &parts.headers, &round_trip_parts.headers)?;
+    compare("body", &body.to_vec(), &round_trip_body)
+}
+
+/// Converts a response to a Warp response and back, returning an error describing the
+/// first difference from the original.
+pub async fn check_response_round_trip(response: Response<Bytes>) -> Result<(), String> {
+    let (parts, body) = response.into_parts();
+    let original = Response::from_parts(parts.clone(), Body::from(body.clone()));
+
+    let warp_response = into_warp_response(original)?;
+    let round_trip = into_axum_response(warp_response)?;
+
+    let (round_trip_parts, round_trip_body) = round_trip.into_parts();
+    let round_trip_body = collect(round_trip_body).await?;
+
+    compare("status", &parts.status, &round_trip_parts.status)?;
+    compare("version", &parts.version, &round_trip_parts.version)?;
+    compare("headers", &parts.headers, &round_trip_parts.headers)?;
+    compare("body", &body.to_vec(), &round_trip_body)
+}
+
+async fn collect(body: Body) -> Result<Vec<u8>, String> {
+    axum::body::to_bytes(body, usize::MAX)
+        .await
+        .map(|bytes| bytes.to_vec())
+        .map_err(|e| format!("Failed to read body: {}", e))
+}
+
+fn compare<T: PartialEq + std::fmt::Debug>(
+    field: &str,
+    expected: &T,
+    actual: &T,
+) -> Result<(), String> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} changed across the round trip: expected {:?}, got {:?}",
+            field, expected, actual
+        ))
+    }
+}
+
+fn arbitrary_uri(input: &mut Input<'_>) -> Uri {
+    let mut uri = String::from("/");
+
+    for _ in 0..input.byte() % 6 {
+        for _ in 0..input.byte() % 8 {
+            match input.byte() % 8 {
+                // Percent-encode an arbitrary byte, including non-UTF-8 sequences.
+                0 => uri.push_str(&format!("%{:02X}", input.byte())),
+                _ => uri.push(*input.choose(URI_CHARS) as char),
+            }
+        }
+        uri.push('/');
+    }
+
+    // Authority-form and absolute-form URIs are tested less often than origin-form.
+    if input.ratio(1, 16) {
+        uri.insert_str(0, "http://example.com");
+    }
+
+    Uri::try_from(uri).unwrap_or_else(|_| Uri::from_static("/"))
+}
+
+fn arbitrary_headers(input: &mut Input<'_>, headers: &mut axum::http::HeaderMap) {
+    for _ in 0..input.byte() % 8 {
+        let name = if input.ratio(1, 4) {
+            HeaderName::from_bytes(&input.token(TOKEN_CHARS, 1, 16)).ok()
+        } else {
+            let name = *input.choose(&HEADER_NAMES);
+            Some(HeaderName::from_static(name))
+        };
+
+        // Visible ASCII, spaces, tabs, and obs-text bytes that are not valid UTF-8.
+        let value: Vec<u8> = (0..input.byte() % 24)
+            .map(|_| match input.byte() {
+                byte @ (b'\t' | 0x20..=0x7e | 0x80..=0xff) => byte,
+                byte => b'a' + byte % 26,
+            })
+            .collect();
+
+        if let (Some(name), Ok(value)) = (name, HeaderValue::from_bytes(&value)) {
+            headers.append(name, value);
+        }
+    }
+}
+
+/// A reader over fuzzer input that yields zeroes once the input is exhausted.
+struct Input<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Input<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Input { data }
+    }
+
+    fn byte(&mut self) -> u8 {
+        match self.data.split_first() {
+            Some((byte, rest)) => {
+                self.data = rest;
+                *byte
+            }
+            None => 0,
+        }
+    }
+
+    fn u16(&mut self) -> u16 {
+        u16::from_le_bytes([self.byte(), self.byte()])
+    }
+
+    /// Returns `true` with the given probability, and `false` once the input is exhausted.
+    fn ratio(&mut self, numerator: u8, denominator: u8) -> bool {
+        self.byte() % denominator >= denominator - numerator
+    }
+
+    fn choose<'b, T>(&mut self, items: &'b [T]) -> &'b T {
+        &items[self.byte() as usize % items.len()]
+    }
+
+    fn token(&mut self, chars: &[u8], min: u8, max: u8) -> Vec<u8> {
+        let len = min + self.byte() % (max - min + 1);
+        (0..len).map(|_| *self.choose(chars)).collect()
+    }
+
+    fn bytes(&mut self, max: usize) -> Vec<u8> {
+        let len = (self.u16() as usize % (max + 1)).min(self.data.len());
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        bytes.to_vec()
+    }
+}
@@ -0,0 +1,121 @@
+use std::{
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::{Body, Bytes},
+    response::Response,
+};
+use http_body::{Body as HttpBody, Frame, SizeHint};
+
+/// A gauge of the requests currently in flight in [`WarpService`](crate::WarpService)s, for
+/// watching legacy load during drains and cutovers.
+///
+/// A request is in flight from when it reaches the service until its response body has been
+/// sent or dropped, so long-lived streams are counted for as long as they are open. The gauge
+/// is a cheap handle that can be cloned into a metrics exporter and shared by several
+/// services, which are then counted together. It is applied with
+/// [`WarpService::with_in_flight_gauge`](crate::WarpService::with_in_flight_gauge).
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::{InFlightGauge, WarpService};
+/// use warp::Filter;
+///
+/// let gauge = InFlightGauge::new();
+///
+/// let filter = warp::path("users").map(|| "Users").boxed();
+/// let service = WarpService::new(filter).with_in_flight_gauge(gauge.clone());
+///
+/// assert_eq!(gauge.get(), 0);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InFlightGauge {
+    state: Arc<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    in_flight: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl InFlightGauge {
+    /// Creates a gauge with no requests in flight.
+    pub fn new() -> Self {
+        InFlightGauge::default()
+    }
+
+    /// Returns the number of requests in flight.
+    pub fn get(&self) -> usize {
+        self.state.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Returns the largest number of requests that have been in flight at once.
+    pub fn peak(&self) -> usize {
+        self.state.peak.load(Ordering::SeqCst)
+    }
+
+    /// Counts a request as in flight until the returned guard is dropped.
+    pub(crate) fn start(&self) -> Counted {
+        let in_flight = self.state.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.state.peak.fetch_max(in_flight, Ordering::SeqCst);
+        Counted {
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+/// Counts a request as in flight until dropped.
+pub(crate) struct Counted {
+    state: Arc<State>,
+}
+
+impl Counted {
+    /// Keeps the request counted until the response body finishes.
+    pub(crate) fn track(self, response: Response) -> Response {
+        response.map(|body| {
+            Body::new(CountedBody {
+                inner: body,
+                _counted: self,
+            })
+        })
+    }
+}
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.state.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+struct CountedBody {
+    inner: Body,
+    _counted: Counted,
+}
+
+impl HttpBody for CountedBody {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        Pin::new(&mut self.inner).poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
@@ -0,0 +1,170 @@
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    fmt,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use futures::Future;
+use tower::{Service, ServiceExt, util::BoxCloneSyncService};
+
+use crate::warp_service::WarpService;
+
+type RouteService = BoxCloneSyncService<Request, Response, Infallible>;
+
+/// Routes requests between several [`WarpService`]s by path prefix.
+///
+/// Large Warp applications often combine many independent filter trees with one long `or()`
+/// chain, so every request is tried against each tree in turn until one matches. A group
+/// instead looks up the service for the longest registered prefix in a trie of path
+/// segments, and sends the request only to that service. The request path is left
+/// unchanged, so each filter tree keeps matching its full paths.
+///
+/// Prefixes match whole segments: `/billing` matches `/billing` and `/billing/invoices`, but
+/// not `/billing-old`. Requests that match no prefix are answered with `404 Not Found`, or
+/// sent to the [`fallback`](WarpServiceGroup::fallback) service. Once a prefix matches, a
+/// rejection from its filter tree is returned as is, without trying other services.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::Router;
+/// use warpdrive::{WarpService, WarpServiceGroup};
+/// use warp::Filter;
+///
+/// let billing = warp::path!("billing" / "invoices").map(|| "Invoices").boxed();
+/// let accounts = warp::path!("accounts" / u32).map(|id| format!("Account {}", id)).boxed();
+///
+/// let group = WarpServiceGroup::new()
+///     .route("/billing", WarpService::new(billing))
+///     .route("/accounts", WarpService::new(accounts));
+///
+/// let app: Router = Router::new().fallback_service(group);
+/// ```
+#[derive(Clone, Default)]
+pub struct WarpServiceGroup {
+    root: Arc<Node>,
+    fallback: Option<RouteService>,
+}
+
+#[derive(Clone, Default)]
+struct Node {
+    children: HashMap<String, Node>,
+    service: Option<RouteService>,
+}
+
+impl WarpServiceGroup {
+    /// Creates an empty group.
+    pub fn new() -> Self {
+        WarpServiceGroup::default()
+    }
+
+    /// Sends requests whose path is at or below `prefix` to the service, replacing any
+    /// service already registered for the same prefix.
+    ///
+    /// A prefix of `/` matches every request that does not match a longer prefix.
+    pub fn route<T>(mut self, prefix: &str, service: WarpService<T>) -> Self
+    where
+        T: warp::Reply + Send + Sync + 'static,
+    {
+        let mut node = Arc::make_mut(&mut self.root);
+        for segment in segments(prefix) {
+            node = node.children.entry(segment.to_string()).or_default();
+        }
+        node.service = Some(BoxCloneSyncService::new(service));
+        self
+    }
+
+    /// Sends requests that match no prefix to the given service, instead of answering them
+    /// with `404 Not Found`.
+    pub fn fallback<S>(mut self, service: S) -> Self
+    where
+        S: Service<Request, Response = Response, Error = Infallible>
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        S::Future: Send + 'static,
+    {
+        self.fallback = Some(BoxCloneSyncService::new(service));
+        self
+    }
+
+    /// Returns the service for the longest prefix of the path.
+    fn lookup(&self, path: &str) -> Option<&RouteService> {
+        let mut node = &*self.root;
+        let mut service = node.service.as_ref();
+
+        for segment in segments(path) {
+            match node.children.get(segment) {
+                Some(child) => node = child,
+                None => break,
+            }
+            service = node.service.as_ref().or(service);
+        }
+
+        service.or(self.fallback.as_ref())
+    }
+}
+
+fn segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|segment| !segment.is_empty())
+}
+
+impl Service<Request> for WarpServiceGroup {
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        match self.lookup(req.uri().path()) {
+            Some(service) => Box::pin(service.clone().oneshot(req)),
+            None => Box::pin(async { Ok((StatusCode::NOT_FOUND, Body::empty()).into_response()) }),
+        }
+    }
+}
+
+impl fmt::Debug for WarpServiceGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut prefixes = Vec::new();
+        self.root.prefixes(&mut String::new(), &mut prefixes);
+        prefixes.sort();
+
+        f.debug_struct("WarpServiceGroup")
+            .field("prefixes", &prefixes)
+            .field("fallback", &self.fallback.is_some())
+            .finish()
+    }
+}
+
+impl Node {
+    fn prefixes(&self, path: &mut String, prefixes: &mut Vec<String>) {
+        if self.service.is_some() {
+            prefixes.push(if path.is_empty() {
+                "/".to_string()
+            } else {
+                path.clone()
+            });
+        }
+
+        for (segment, child) in &self.children {
+            let len = path.len();
+            path.push('/');
+            path.push_str(segment);
+            child.prefixes(path, prefixes);
+            path.truncate(len);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use axum::http::{HeaderMap, HeaderName};
+
+/// Request and response header filtering for a [`WarpService`](crate::WarpService).
+///
+/// Request headers are filtered before the request reaches the Warp filter, for example to
+/// strip internal headers that legacy code should not see. Response headers are filtered
+/// before the response is returned to Axum, for example to remove `Server` or debug headers
+/// that legacy handlers add.
+///
+/// Patterns are header names, or prefixes ending with `*` such as `x-internal-*`, and match
+/// case-insensitively. When an allowlist is set for a direction, only matching headers are
+/// kept; denied headers are always removed. Filtering is applied with
+/// [`WarpService::with_header_filter`](crate::WarpService::with_header_filter).
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::{HeaderFilter, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("legacy").map(|| "Hello").boxed();
+///
+/// let headers = HeaderFilter::new()
+///     .deny_request("x-internal-*")
+///     .deny_response("server")
+///     .deny_response("x-debug-*");
+///
+/// let service = WarpService::new(filter).with_header_filter(headers);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HeaderFilter {
+    request: Rules,
+    response: Rules,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Rules {
+    allow: Option<Vec<Pattern>>,
+    deny: Vec<Pattern>,
+}
+
+#[derive(Debug, Clone)]
+enum Pattern {
+    Exact(String),
+    Prefix(String),
+}
+
+impl HeaderFilter {
+    /// Creates a header filter that keeps every header.
+    pub fn new() -> Self {
+        HeaderFilter::default()
+    }
+
+    /// Allows request headers matching the pattern. Once any request header is allowed, all
+    /// other request headers are removed.
+    pub fn allow_request(mut self, pattern: &str) -> Self {
+        self.request.allow(pattern);
+        self
+    }
+
+    /// Removes request headers matching the pattern.
+    pub fn deny_request(mut self, pattern: &str) -> Self {
+        self.request.deny.push(Pattern::new(pattern));
+        self
+    }
+
+    /// Allows response headers matching the pattern. Once any response header is allowed, all
+    /// other response headers are removed.
+    pub fn allow_response(mut self, pattern: &str) -> Self {
+        self.response.allow(pattern);
+        self
+    }
+
+    /// Removes response headers matching the pattern.
+    pub fn deny_response(mut self, pattern: &str) -> Self {
+        self.response.deny.push(Pattern::new(pattern));
+        self
+    }
+
+    pub(crate) fn filter_request(&self, headers: &mut HeaderMap) {
+        self.request.apply(headers);
+    }
+
+    pub(crate) fn filter_response(&self, headers: &mut HeaderMap) {
+        self.response.apply(headers);
+    }
+}
+
+impl Rules {
+    fn allow(&mut self, pattern: &str) {
+        self.allow
+            .get_or_insert_with(Vec::new)
+            .push(Pattern::new(pattern));
+    }
+
+    fn keeps(&self, name: &HeaderName) -> bool {
+        let allowed = match &self.allow {
+            Some(allow) => allow.iter().any(|pattern| pattern.matches(name)),
+            None => true,
+        };
+
+        allowed && !self.deny.iter().any(|pattern| pattern.matches(name))
+    }
+
+    fn apply(&self, headers: &mut HeaderMap) {
+        if self.allow.is_none() && self.deny.is_empty() {
+            return;
+        }
+
+        let removed: Vec<HeaderName> = headers
+            .keys()
+            .filter(|name| !self.keeps(name))
+            .cloned()
+            .collect();
+
+        for name in removed {
+            headers.remove(name);
+        }
+    }
+}
+
+impl Pattern {
+    fn new(pattern: &str) -> Self {
+        let pattern = pattern.to_ascii_lowercase();
+
+        match pattern.strip_suffix('*') {
+            Some(prefix) => Pattern::Prefix(prefix.to_string()),
+            None => Pattern::Exact(pattern),
+        }
+    }
+
+    fn matches(&self, name: &HeaderName) -> bool {
+        match self {
+            Pattern::Exact(exact) => name.as_str() == exact,
+            Pattern::Prefix(prefix) => name.as_str().starts_with(prefix.as_str()),
+        }
+    }
+}
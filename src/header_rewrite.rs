@@ -0,0 +1,132 @@
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+
+use crate::prefix::{matches_prefix, trim_prefix};
+
+/// Response header rewrite rules for a [`WarpService`](crate::WarpService).
+///
+/// Rules set, remove, or rename headers on responses from the Warp filter, so legacy
+/// headers can be adapted to a new scheme without editing the filter. Rules apply in the
+/// order they are added, to every response or only to requests under a path prefix added
+/// with [`prefix`](HeaderRewrite::prefix). Renamed headers keep all of their values, and
+/// replace any existing values of the new name.
+///
+/// Rewrites apply as the response is converted, before any
+/// [`HeaderFilter`](crate::HeaderFilter), and are applied with
+/// [`WarpService::with_header_rewrite`](crate::WarpService::with_header_rewrite).
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::{HeaderRewrite, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("v1").map(|| "Hello").boxed();
+///
+/// let rewrite = HeaderRewrite::new()
+///     .rename("x-api-version", "api-version")
+///     .remove("x-powered-by")
+///     .prefix("/v1/legacy", HeaderRewrite::new().set("deprecation", "true"));
+///
+/// let service = WarpService::new(filter).with_header_rewrite(rewrite);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HeaderRewrite {
+    /// Each rule, with the prefixes a request path must be under for it to apply.
+    rules: Vec<(Vec<String>, Rule)>,
+}
+
+#[derive(Debug, Clone)]
+enum Rule {
+    Set(HeaderName, HeaderValue),
+    Remove(HeaderName),
+    Rename(HeaderName, HeaderName),
+}
+
+impl HeaderRewrite {
+    /// Creates an empty set of rules.
+    pub fn new() -> Self {
+        HeaderRewrite::default()
+    }
+
+    /// Sets a header, replacing any existing values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` or `value` is not a valid header name or value.
+    pub fn set(self, name: &str, value: &str) -> Self {
+        self.rule(Rule::Set(
+            header_name(name),
+            HeaderValue::try_from(value).expect("invalid header value"),
+        ))
+    }
+
+    /// Removes a header.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid header name.
+    pub fn remove(self, name: &str) -> Self {
+        self.rule(Rule::Remove(header_name(name)))
+    }
+
+    /// Renames a header, keeping its values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` or `to` is not a valid header name.
+    pub fn rename(self, from: &str, to: &str) -> Self {
+        self.rule(Rule::Rename(header_name(from), header_name(to)))
+    }
+
+    /// Adds rules that only apply to requests under a path prefix, such as `/v1` for `/v1`
+    /// and `/v1/users` but not `/v1-beta`.
+    pub fn prefix(mut self, prefix: &str, rules: HeaderRewrite) -> Self {
+        let prefix = trim_prefix(prefix);
+        self.rules
+            .extend(rules.rules.into_iter().map(|(mut prefixes, rule)| {
+                prefixes.insert(0, prefix.clone());
+                (prefixes, rule)
+            }));
+        self
+    }
+
+    fn rule(mut self, rule: Rule) -> Self {
+        self.rules.push((Vec::new(), rule));
+        self
+    }
+
+    /// Applies the rules for a request path to response headers.
+    pub(crate) fn apply(&self, path: &str, headers: &mut HeaderMap) {
+        let rules = self
+            .rules
+            .iter()
+            .filter(|(prefixes, _)| prefixes.iter().all(|prefix| matches_prefix(prefix, path)));
+
+        for (_, rule) in rules {
+            match rule {
+                Rule::Set(name, value) => {
+                    headers.insert(name, value.clone());
+                }
+                Rule::Remove(name) => {
+                    headers.remove(name);
+                }
+                Rule::Rename(from, to) => {
+                    let values: Vec<_> = headers.get_all(from).iter().cloned().collect();
+                    if values.is_empty() {
+                        continue;
+                    }
+
+                    headers.remove(from);
+                    headers.remove(to);
+                    for value in values {
+                        headers.append(to, value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn header_name(name: &str) -> HeaderName {
+    HeaderName::try_from(name).expect("invalid header name")
+}
@@ -0,0 +1,144 @@
+use std::{collections::BTreeMap, fmt, future::Future, sync::Arc};
+
+use axum::{
+    Json, Router,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use futures::future::{BoxFuture, join_all};
+use serde_json::{Value, json};
+
+use crate::WarpService;
+
+type ServiceCheck = Arc<dyn Fn() -> (bool, Value) + Send + Sync>;
+type Check = Arc<dyn Fn() -> BoxFuture<'static, bool> + Send + Sync>;
+
+/// Liveness and readiness probes for a server that mixes Axum routes and
+/// [`WarpService`]s, such as for Kubernetes.
+///
+/// The readiness probe reflects the state of each registered service and any checks for the
+/// Axum side of the server, such as a database connection. A service is not ready once its
+/// [`Shutdown`](crate::Shutdown) is triggered, so the server is taken out of rotation while it
+/// drains, or while a route's [`CircuitBreaker`](crate::CircuitBreaker) is open without a
+/// fallback. An engaged [`KillSwitch`](crate::KillSwitch) is reported but does not make the
+/// service unready, since every instance would be taken out of rotation at once.
+///
+/// The router built with [`into_router`](Readiness::into_router) has two endpoints:
+///
+/// - `GET /healthz`: always `200 OK`, for liveness probes.
+/// - `GET /readyz`: `200 OK` if everything is ready and `503 Service Unavailable` otherwise,
+///   with a JSON body describing each service and check.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::Router;
+/// use warpdrive::{Readiness, Shutdown, WarpService};
+/// use warp::Filter;
+///
+/// let shutdown = Shutdown::new();
+/// let service = WarpService::new(warp::path("users").map(|| "Users").boxed())
+///     .with_shutdown(shutdown.clone());
+///
+/// let probes = Readiness::new()
+///     .service("legacy", &service)
+///     .check("database", || async {
+///         // Check the connection pool used by the Axum routes.
+///         true
+///     })
+///     .into_router();
+///
+/// let app: Router = Router::new().merge(probes).fallback_service(service);
+/// ```
+#[derive(Clone, Default)]
+pub struct Readiness {
+    services: BTreeMap<String, ServiceCheck>,
+    checks: BTreeMap<String, Check>,
+}
+
+impl Readiness {
+    /// Creates readiness probes with nothing registered, which are always ready.
+    pub fn new() -> Self {
+        Readiness::default()
+    }
+
+    /// Includes the state of a service under the given name.
+    pub fn service<T>(mut self, name: impl Into<String>, service: &WarpService<T>) -> Self {
+        self.services
+            .insert(name.into(), Arc::new(service.readiness()));
+        self
+    }
+
+    /// Includes a check under the given name, which is ready when it returns `true`.
+    pub fn check<F, Fut>(mut self, name: impl Into<String>, check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = bool> + Send + 'static,
+    {
+        self.checks
+            .insert(name.into(), Arc::new(move || Box::pin(check())));
+        self
+    }
+
+    /// Returns whether everything is ready, with a JSON description of each service and
+    /// check.
+    pub async fn status(&self) -> (bool, Value) {
+        let services: BTreeMap<_, _> = self
+            .services
+            .iter()
+            .map(|(name, readiness)| (name, readiness()))
+            .collect();
+
+        let results = join_all(self.checks.values().map(|check| check())).await;
+        let checks: BTreeMap<_, _> = self.checks.keys().zip(results).collect();
+
+        let ready =
+            services.values().all(|(ready, _)| *ready) && checks.values().all(|ready| *ready);
+        let services: BTreeMap<_, _> = services
+            .into_iter()
+            .map(|(name, (_, status))| (name, status))
+            .collect();
+
+        (
+            ready,
+            json!({
+                "ready": ready,
+                "services": services,
+                "checks": checks,
+            }),
+        )
+    }
+
+    /// Builds a router with the `/healthz` and `/readyz` endpoints.
+    pub fn into_router<S>(self) -> Router<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        Router::new()
+            .route("/healthz", get(|| async { "ok" }))
+            .route("/readyz", get(ready))
+            .with_state(Arc::new(self))
+    }
+}
+
+async fn ready(State(readiness): State<Arc<Readiness>>) -> Response {
+    let (ready, status) = readiness.status().await;
+    let code = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (code, Json(status)).into_response()
+}
+
+impl fmt::Debug for Readiness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Readiness")
+            .field("services", &self.services.keys().collect::<Vec<_>>())
+            .field("checks", &self.checks.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
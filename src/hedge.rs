@@ -0,0 +1,208 @@
+use std::{convert::Infallible, fmt, future::Future, sync::Arc};
+
+use axum::{
+    body::{Body, HttpBody},
+    extract::Request,
+    http::{HeaderValue, Method, request::Parts},
+    response::Response,
+};
+use futures::future::{self, Either};
+use tower::{Service, ServiceExt, util::BoxCloneSyncService};
+
+use crate::error::Error;
+
+/// The largest request body buffered to send to both implementations by default.
+const DEFAULT_MAX_BODY_SIZE: usize = 1024 * 1024;
+
+type HedgeHook = Arc<dyn Fn(&Parts, HedgeWinner) + Send + Sync>;
+
+/// Hedged execution settings for a [`WarpService`](crate::WarpService).
+///
+/// Each request is sent to both the Warp filter and an Axum service at the same time, and
+/// the first successful response is served, cancelling the other. A response is successful
+/// if it does not have a `5xx` status and no boundary error occurred; if the first response
+/// fails, the other is awaited instead, and if both fail, the Warp result is returned.
+/// Requests rejected by the [rate limit](crate::WarpService::with_rate_limit) are rejected
+/// without waiting for the Axum service, so hedging cannot be used to bypass it.
+/// Served responses have an `x-warpdrive-hedge` header naming the implementation that won.
+/// This is meant for the final validation phase of a migration, when both implementations
+/// are trusted but their latency differs.
+///
+/// Since both implementations handle every request, only `GET`, `HEAD`, and `OPTIONS`
+/// requests are hedged by default; other requests are sent to the Warp filter alone. The
+/// request body is buffered so it can be sent to both, and requests whose body may exceed
+/// [`max_body_size`](Hedge::max_body_size) are also sent to the Warp filter alone.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::routing::get;
+/// use warpdrive::{Hedge, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("users").map(|| "Users from Warp").boxed();
+///
+/// let hedge = Hedge::new(get(|| async { "Users from Axum" })).on_winner(|req, winner| {
+///     println!("{} {} served by {}", req.method, req.uri, winner);
+/// });
+///
+/// let service = WarpService::new(filter).with_hedge(hedge);
+/// ```
+#[derive(Clone)]
+pub struct Hedge {
+    axum: BoxCloneSyncService<Request, Response, Infallible>,
+    methods: Vec<Method>,
+    max_body_size: usize,
+    on_winner: Option<HedgeHook>,
+}
+
+/// The implementation whose response was served by a [`Hedge`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HedgeWinner {
+    /// The Warp filter.
+    Warp,
+    /// The Axum service.
+    Axum,
+}
+
+impl HedgeWinner {
+    fn header_value(self) -> HeaderValue {
+        HeaderValue::from_static(match self {
+            HedgeWinner::Warp => "warp",
+            HedgeWinner::Axum => "axum",
+        })
+    }
+}
+
+impl fmt::Display for HedgeWinner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HedgeWinner::Warp => write!(f, "Warp"),
+            HedgeWinner::Axum => write!(f, "Axum"),
+        }
+    }
+}
+
+impl Hedge {
+    /// Creates hedging settings that race the Warp filter against the given Axum service,
+    /// such as a `MethodRouter` or a handler converted with `into_service`.
+    pub fn new<S>(axum: S) -> Self
+    where
+        S: Service<Request, Response = Response, Error = Infallible>
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        S::Future: Send + 'static,
+    {
+        Hedge {
+            axum: BoxCloneSyncService::new(axum),
+            methods: vec![Method::GET, Method::HEAD, Method::OPTIONS],
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            on_winner: None,
+        }
+    }
+
+    /// Sets the methods that are hedged, replacing the default of `GET`, `HEAD`, and
+    /// `OPTIONS`. Only methods that are safe to handle twice should be hedged.
+    pub fn methods(mut self, methods: impl IntoIterator<Item = Method>) -> Self {
+        self.methods = methods.into_iter().collect();
+        self
+    }
+
+    /// Sets the largest request body, in bytes, that is buffered to send to both
+    /// implementations. Defaults to 1 MiB.
+    pub fn max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// Calls a function with the winner of each hedged request, for logging or metrics.
+    pub fn on_winner<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Parts, HedgeWinner) + Send + Sync + 'static,
+    {
+        self.on_winner = Some(Arc::new(hook));
+        self
+    }
+
+    /// Runs a request through `attempt` and the Axum service at the same time, returning the
+    /// first successful response.
+    pub(crate) async fn run<F, Fut>(&self, req: Request, attempt: F) -> Result<Response, Error>
+    where
+        F: FnOnce(Request) -> Fut,
+        Fut: Future<Output = Result<Response, Error>>,
+    {
+        let hedged = self.methods.contains(req.method())
+            && req
+                .body()
+                .size_hint()
+                .upper()
+                .is_some_and(|upper| upper <= self.max_body_size as u64);
+
+        if !hedged {
+            return attempt(req).await;
+        }
+
+        let (parts, body) = req.into_parts();
+        let body = axum::body::to_bytes(body, self.max_body_size)
+            .await
+            .map_err(|e| Error::Conversion(format!("Failed to read request body: {}", e)))?;
+
+        let warp = attempt(Request::from_parts(parts.clone(), Body::from(body.clone())));
+        let axum = self
+            .axum
+            .clone()
+            .oneshot(Request::from_parts(parts.clone(), Body::from(body)));
+
+        let (result, winner) = match future::select(Box::pin(warp), axum).await {
+            Either::Left((Err(err @ Error::RateLimited(_)), _)) => return Err(err),
+            Either::Left((warp_result, _)) if succeeded(&warp_result) => {
+                (warp_result, HedgeWinner::Warp)
+            }
+            Either::Left((warp_result, axum)) => match axum.await {
+                Ok(response) if !response.status().is_server_error() => {
+                    (Ok(response), HedgeWinner::Axum)
+                }
+                _ => return warp_result,
+            },
+            Either::Right((Ok(response), _)) if !response.status().is_server_error() => {
+                (Ok(response), HedgeWinner::Axum)
+            }
+            Either::Right((_, warp)) => {
+                let warp_result = warp.await;
+                if !succeeded(&warp_result) {
+                    return warp_result;
+                }
+                (warp_result, HedgeWinner::Warp)
+            }
+        };
+
+        if let Some(hook) = &self.on_winner {
+            hook(&parts, winner);
+        }
+
+        result.map(|mut response| {
+            response
+                .headers_mut()
+                .insert("x-warpdrive-hedge", winner.header_value());
+            response
+        })
+    }
+}
+
+fn succeeded(result: &Result<Response, Error>) -> bool {
+    result
+        .as_ref()
+        .is_ok_and(|response| !response.status().is_server_error())
+}
+
+impl fmt::Debug for Hedge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hedge")
+            .field("methods", &self.methods)
+            .field("max_body_size", &self.max_body_size)
+            .field("on_winner", &self.on_winner.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,112 @@
+use axum::{
+    extract::Request,
+    http::{
+        HeaderValue, Uri,
+        header::HOST,
+        uri::{Authority, Parts},
+    },
+};
+
+/// Overrides or normalizes the host of requests before they reach the Warp filter, for legacy
+/// code that expects to be served under the host it was historically deployed at.
+///
+/// The host is rewritten in both the `Host` header and, for requests that carry one, such as
+/// HTTP/2 requests, the authority of the URI, so filters see the same host either way. It is
+/// applied with [`WarpService::with_host_override`](crate::WarpService::with_host_override).
+///
+/// Normalization lowercases the host, removes a trailing dot, and removes the port if it is
+/// the default port of the scheme. Hosts that cannot be parsed are left as they are.
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::{HostOverride, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::header::<String>("host").map(|host: String| host).boxed();
+///
+/// let service = WarpService::new(filter).with_host_override(HostOverride::set("legacy.internal"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct HostOverride {
+    /// The host to present, or `None` to normalize the request's own host.
+    host: Option<Authority>,
+    strip_port: bool,
+}
+
+impl HostOverride {
+    /// Presents every request with the given host, such as `api.example.com:8080`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the host is not a valid URI authority.
+    pub fn set(host: &str) -> Self {
+        HostOverride {
+            host: Some(Authority::try_from(host).expect("invalid host")),
+            strip_port: false,
+        }
+    }
+
+    /// Normalizes the request's own host.
+    pub fn normalize() -> Self {
+        HostOverride {
+            host: None,
+            strip_port: false,
+        }
+    }
+
+    /// Removes any port from the host, not only the default port of the scheme.
+    pub fn strip_port(mut self, strip_port: bool) -> Self {
+        self.strip_port = strip_port;
+        self
+    }
+
+    /// Rewrites the host of a request.
+    pub(crate) fn apply(&self, req: &mut Request) {
+        let current = match &self.host {
+            Some(host) => host.clone(),
+            None => {
+                let authority = req.uri().authority().cloned();
+                let header = req
+                    .headers()
+                    .get(HOST)
+                    .and_then(|host| Authority::try_from(host.as_bytes()).ok());
+                match authority.or(header) {
+                    Some(authority) => authority,
+                    None => return,
+                }
+            }
+        };
+        let Some(host) = self.normalized(&current, req.uri().scheme_str()) else {
+            return;
+        };
+
+        if let Ok(value) = HeaderValue::try_from(host.as_str()) {
+            req.headers_mut().insert(HOST, value);
+        }
+        if req.uri().authority().is_some() {
+            let mut parts = Parts::from(req.uri().clone());
+            parts.authority = Some(host);
+            if let Ok(uri) = Uri::from_parts(parts) {
+                *req.uri_mut() = uri;
+            }
+        }
+    }
+
+    fn normalized(&self, authority: &Authority, scheme: Option<&str>) -> Option<Authority> {
+        let host = authority.host().to_ascii_lowercase();
+        let host = host.strip_suffix('.').unwrap_or(&host);
+        let default_port = match scheme {
+            Some("https") | Some("wss") => 443,
+            _ => 80,
+        };
+
+        let normalized = match authority.port_u16() {
+            Some(port) if !self.strip_port && port != default_port => {
+                format!("{}:{}", host, port)
+            }
+            _ => host.to_string(),
+        };
+        Authority::try_from(normalized).ok()
+    }
+}
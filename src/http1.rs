@@ -0,0 +1,252 @@
+//! Hosting Warp filters on plain `http` 1.0 types, without Axum.
+//!
+//! [`HttpWarpService`] is a Tower service over `http` 1.0 requests with any http-body 1.0
+//! body, for servers built directly on hyper 1.x or on other Tower-based stacks. It and the
+//! conversion functions in this module are the core of the crate, and remain available when
+//! the default `axum` feature is disabled:
+//!
+//! ```toml
+//! [dependencies]
+//! warpdrive = { version = "0.1", default-features = false }
+//! ```
+//!
+//! Requests are converted, served by the filter and converted back, and conversion errors are
+//! answered with a `500 Internal Server Error`. The boundary settings of
+//! [`WarpService`](crate::WarpService), such as timeouts and rate limits, need Axum and are not
+//! available here. Request trailers are not carried over, as Warp bodies are built from a stream
+//! of data, while response trailers are.
+//!
+//! # Example
+//!
+//! ```rust
+//! use http_body_util::{BodyExt, Empty};
+//! use tower::ServiceExt;
+//! use warp::{Filter, hyper::body::Bytes};
+//! use warpdrive::http1::HttpWarpService;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let filter = warp::path("hello").map(|| "Hello from Warp!").boxed();
+//! let service = HttpWarpService::new(filter);
+//!
+//! let request = http::Request::get("/hello")
+//!     .body(Empty::<Bytes>::new())
+//!     .unwrap();
+//! let response = service.oneshot(request).await.unwrap();
+//!
+//! let body = response.into_body().collect().await.unwrap().to_bytes();
+//! assert_eq!(body, "Hello from Warp!");
+//! # }
+//! ```
+
+use std::{
+    convert::Infallible,
+    fmt,
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::future::BoxFuture;
+use http_body::Body as HttpBody;
+use http_body_util::BodyDataStream;
+use tower::{BoxError, Service};
+use warp::{
+    Reply,
+    filters::BoxedFilter,
+    http::{Request as WarpRequest, Response as WarpResponse},
+    hyper::body::{Body as WarpBody, Bytes},
+};
+
+use crate::compat_body::{declared_length, strip_body_framing, to_http_headers, to_warp_headers};
+
+pub use crate::compat_body::CompatBody;
+
+/// A Tower service that runs a Warp filter on `http` 1.0 requests and responses.
+///
+/// See the [module documentation](self) for details.
+pub struct HttpWarpService<T = Box<dyn Reply + Send + Sync>> {
+    filter: Arc<BoxedFilter<(T,)>>,
+}
+
+impl<T> HttpWarpService<T>
+where
+    T: Reply + Send + Sync + 'static,
+{
+    /// Creates a new `HttpWarpService` from a boxed Warp filter.
+    pub fn new(filter: BoxedFilter<(T,)>) -> Self {
+        HttpWarpService {
+            filter: Arc::new(filter),
+        }
+    }
+}
+
+impl<T> Clone for HttpWarpService<T> {
+    fn clone(&self) -> Self {
+        HttpWarpService {
+            filter: Arc::clone(&self.filter),
+        }
+    }
+}
+
+impl<T> fmt::Debug for HttpWarpService<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpWarpService").finish_non_exhaustive()
+    }
+}
+
+impl<T, B> Service<http::Request<B>> for HttpWarpService<T>
+where
+    T: Reply + Send + Sync + 'static,
+    B: HttpBody<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    type Response = http::Response<CompatBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let filter = Arc::clone(&self.filter);
+
+        Box::pin(async move {
+            let response = match into_warp_request(req) {
+                Ok(req) => {
+                    let mut service = warp::service(BoxedFilter::clone(&filter));
+                    match service.call(req).await {
+                        Ok(reply) => into_http_response(reply.into_response()),
+                        Err(never) => match never {},
+                    }
+                }
+                Err(err) => Err(err),
+            };
+
+            Ok(response.unwrap_or_else(conversion_error_response))
+        })
+    }
+}
+
+/// Converts an `http` 1.0 request into a Warp request, streaming the body.
+///
+/// Extensions are not carried over, as Warp filters cannot read `http` 1.0 extensions.
+pub fn into_warp_request<B>(request: http::Request<B>) -> Result<WarpRequest<WarpBody>, String>
+where
+    B: HttpBody<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    let request = to_warp_request_head(request)?;
+
+    Ok(request.map(|body| WarpBody::wrap_stream(BodyDataStream::new(body))))
+}
+
+/// Converts a Warp response into an `http` 1.0 response, streaming the body and any trailers.
+///
+/// Responses whose status does not allow a body, such as `204 No Content`, are converted with
+/// an empty body and without `Transfer-Encoding`.
+pub fn into_http_response(
+    mut response: WarpResponse<WarpBody>,
+) -> Result<http::Response<CompatBody>, String> {
+    let status = response.status().as_u16();
+    let length = declared_length(status, response.headers());
+    let empty = strip_body_framing(status, response.headers_mut());
+
+    let response = from_warp_response_head(response)?;
+
+    Ok(response.map(|body| {
+        let body = if empty { WarpBody::empty() } else { body };
+        CompatBody::from(body).with_declared_length(length)
+    }))
+}
+
+/// Converts the method, URI, version, and headers of a request, keeping the body as is.
+pub(crate) fn to_warp_request_head<B>(request: http::Request<B>) -> Result<WarpRequest<B>, String> {
+    let (parts, body) = request.into_parts();
+
+    let method = warp::http::Method::from_str(parts.method.as_ref())
+        .map_err(|e| format!("Invalid method '{}': {}", parts.method, e))?;
+
+    let uri = if is_asterisk_form(parts.uri.path(), parts.uri.query()) {
+        warp::http::Uri::from_static("*")
+    } else {
+        warp::http::Uri::try_from(&parts.uri.to_string())
+            .map_err(|e| format!("Invalid URI '{}': {}", parts.uri, e))?
+    };
+
+    let mut request = WarpRequest::builder()
+        .method(method)
+        .uri(uri)
+        .version(to_warp_version(parts.version))
+        .body(body)
+        .map_err(|e| format!("Failed to build Warp request: {}", e))?;
+    *request.headers_mut() = to_warp_headers(&parts.headers);
+
+    Ok(request)
+}
+
+/// Converts the status, version, and headers of a Warp response, keeping the body as is.
+pub(crate) fn from_warp_response_head<B>(
+    response: WarpResponse<B>,
+) -> Result<http::Response<B>, String> {
+    let (parts, body) = response.into_parts();
+
+    let status_code = http::StatusCode::from_u16(parts.status.as_u16())
+        .map_err(|e| format!("Invalid status code {}: {}", parts.status.as_u16(), e))?;
+
+    let mut response = http::Response::builder()
+        .status(status_code)
+        .version(from_warp_version(parts.version))
+        .body(body)
+        .map_err(|e| format!("Failed to build response: {}", e))?;
+    *response.headers_mut() = to_http_headers(&parts.headers);
+
+    Ok(response)
+}
+
+/// Returns `true` for the asterisk-form request target, as used by `OPTIONS *` requests.
+///
+/// The asterisk-form has no leading slash, so it is converted explicitly rather than
+/// relying on both versions of `http` parsing its string form the same way.
+pub(crate) fn is_asterisk_form(path: &str, query: Option<&str>) -> bool {
+    path == "*" && query.is_none()
+}
+
+fn to_warp_version(version: http::Version) -> warp::http::Version {
+    match version {
+        http::Version::HTTP_09 => warp::http::Version::HTTP_09,
+        http::Version::HTTP_10 => warp::http::Version::HTTP_10,
+        http::Version::HTTP_11 => warp::http::Version::HTTP_11,
+        http::Version::HTTP_2 => warp::http::Version::HTTP_2,
+        http::Version::HTTP_3 => warp::http::Version::HTTP_3,
+        // Default to 1.1 for compatibility.
+        _ => warp::http::Version::HTTP_11,
+    }
+}
+
+fn from_warp_version(version: warp::http::Version) -> http::Version {
+    match version {
+        warp::http::Version::HTTP_09 => http::Version::HTTP_09,
+        warp::http::Version::HTTP_10 => http::Version::HTTP_10,
+        warp::http::Version::HTTP_11 => http::Version::HTTP_11,
+        warp::http::Version::HTTP_2 => http::Version::HTTP_2,
+        warp::http::Version::HTTP_3 => http::Version::HTTP_3,
+        // Default to 1.1 for compatibility.
+        _ => http::Version::HTTP_11,
+    }
+}
+
+// This only runs in the unlikely event of a conversion error.
+fn conversion_error_response(err: String) -> http::Response<CompatBody> {
+    let mut response = http::Response::new(CompatBody::from(WarpBody::from(format!(
+        "Conversion error: {}",
+        err
+    ))));
+    *response.status_mut() = http::StatusCode::INTERNAL_SERVER_ERROR;
+    response.headers_mut().insert(
+        http::header::CONTENT_TYPE,
+        http::HeaderValue::from_static("text/plain"),
+    );
+    response
+}
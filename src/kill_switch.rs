@@ -0,0 +1,95 @@
+use axum::{
+    body::{Body, Bytes},
+    http::{HeaderValue, StatusCode, header::CONTENT_TYPE},
+    response::Response,
+};
+
+use crate::control::Switch;
+
+/// A runtime switch that stops requests from reaching the Warp filter.
+///
+/// While the switch is on, requests that would be served by Warp are answered with
+/// `503 Service Unavailable` and a configurable body instead, so the legacy path can be
+/// disabled during an incident without a redeploy. Requests already routed to Axum by
+/// [`Steering`](crate::Steering) or a [`Migration`](crate::Migration) are still served, as
+/// are requests answered by a [`Denylist`](crate::Denylist).
+///
+/// The switch is an [`admin::Switch`](Switch), so it can be flipped from code or registered
+/// with an [`AdminRouter`](crate::admin::AdminRouter) to be flipped over HTTP. The kill
+/// switch is applied with
+/// [`WarpService::with_kill_switch`](crate::WarpService::with_kill_switch).
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::{KillSwitch, WarpService, admin::Switch};
+/// use warp::Filter;
+///
+/// let filter = warp::path("legacy").map(|| "Hello from Warp").boxed();
+///
+/// let switch = Switch::new(false);
+/// let kill_switch = KillSwitch::new(switch.clone())
+///     .body(r#"{"error":"temporarily unavailable"}"#)
+///     .content_type("application/json");
+///
+/// let service = WarpService::new(filter).with_kill_switch(kill_switch);
+///
+/// // During an incident:
+/// switch.set(true);
+/// ```
+#[derive(Debug, Clone)]
+pub struct KillSwitch {
+    switch: Switch,
+    body: Bytes,
+    content_type: HeaderValue,
+}
+
+impl KillSwitch {
+    /// Creates a kill switch controlled by the given switch, which stops Warp requests while
+    /// it is on.
+    pub fn new(switch: Switch) -> Self {
+        KillSwitch {
+            switch,
+            body: Bytes::from_static(b"Service Unavailable"),
+            content_type: HeaderValue::from_static("text/plain; charset=utf-8"),
+        }
+    }
+
+    /// Sets the response body. Defaults to `Service Unavailable`.
+    pub fn body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Sets the content type of the response body. Defaults to `text/plain; charset=utf-8`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `content_type` is not a valid header value.
+    pub fn content_type(mut self, content_type: &str) -> Self {
+        self.content_type = HeaderValue::try_from(content_type).expect("invalid content type");
+        self
+    }
+
+    /// Returns `true` if Warp requests are currently stopped.
+    pub fn is_engaged(&self) -> bool {
+        self.switch.is_on()
+    }
+
+    /// Returns the response for a stopped request, or `None` if the switch is off.
+    pub(crate) fn check(&self) -> Option<Response> {
+        if !self.switch.is_on() {
+            return None;
+        }
+
+        let mut response = Response::new(Body::from(self.body.clone()));
+        *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+        if !self.body.is_empty() {
+            response
+                .headers_mut()
+                .insert(CONTENT_TYPE, self.content_type.clone());
+        }
+
+        Some(response)
+    }
+}
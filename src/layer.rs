@@ -0,0 +1,149 @@
+use std::{
+    convert::Infallible,
+    sync::{Arc, Mutex},
+};
+
+use axum::{extract::Request, response::Response};
+use tower::{Layer, Service, ServiceExt};
+use warp::{Filter, Rejection, filters::BoxedFilter};
+
+use crate::{
+    convert_request::axum_request_filter,
+    extract::handle_axum_rejection,
+    reply::AxumReply,
+    warp_service::{WarpService, create_conversion_error_response},
+};
+
+/// A Tower layer that applies a Warp `Wrap` (such as `warp::log` or a custom `wrap_fn`)
+/// around an Axum service.
+///
+/// The layer is constructed from a function that applies the wrap to a filter. Warp's `Wrap`
+/// trait cannot be named outside of Warp, so the function is given the filter that forwards
+/// to the inner service and should return the wrapped, boxed filter.
+///
+/// Requests are converted into Warp requests before the wrap runs, and converted back before
+/// they reach the inner service. Request extensions, such as those added by outer layers and
+/// hyper's upgrade extension, are carried through to the inner service.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::{Router, routing::get};
+/// use warpdrive::WarpWrapLayer;
+/// use warp::Filter;
+///
+/// let app: Router = Router::new()
+///     .route("/", get(|| async { "Hello from Axum!" }))
+///     .layer(WarpWrapLayer::new(|filter| {
+///         filter.with(warp::log("legacy::access")).boxed()
+///     }));
+/// ```
+#[derive(Debug, Clone)]
+pub struct WarpWrapLayer<F> {
+    wrap: F,
+}
+
+impl<F> WarpWrapLayer<F> {
+    /// Creates a new `WarpWrapLayer` from a function that applies a Warp wrap to a filter.
+    pub fn new<T>(wrap: F) -> Self
+    where
+        F: Fn(BoxedFilter<(warp::reply::Response,)>) -> BoxedFilter<(T,)>,
+    {
+        WarpWrapLayer { wrap }
+    }
+}
+
+impl<S, F, T> Layer<S> for WarpWrapLayer<F>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send,
+    F: Fn(BoxedFilter<(warp::reply::Response,)>) -> BoxedFilter<(T,)>,
+    T: warp::Reply + Send + Sync + 'static,
+{
+    type Service = WarpService<T>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        WarpService::new((self.wrap)(forward_to(inner)))
+    }
+}
+
+/// A Tower layer that runs a Warp filter as a guard in front of an Axum service.
+///
+/// The filter must extract nothing, such as an API-key check or a rate-limit filter. If the
+/// filter succeeds the request is passed to the inner service, otherwise the rejection is
+/// converted into a response using Warp's default rejection handling. Rejections produced by
+/// [`axum_extract`](crate::axum_extract) are converted into the extractor's own response.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::{Router, routing::get};
+/// use warpdrive::WarpFilterLayer;
+/// use warp::Filter;
+///
+/// let api_key = warp::header::exact("x-api-key", "secret");
+///
+/// let app: Router = Router::new()
+///     .route("/admin", get(|| async { "Welcome" }))
+///     .layer(WarpFilterLayer::new(api_key));
+/// ```
+#[derive(Debug, Clone)]
+pub struct WarpFilterLayer<F> {
+    filter: F,
+}
+
+impl<F> WarpFilterLayer<F>
+where
+    F: Filter<Extract = (), Error = Rejection> + Clone + Send + Sync + 'static,
+{
+    /// Creates a new `WarpFilterLayer` from a unit-extracting Warp filter.
+    pub fn new(filter: F) -> Self {
+        WarpFilterLayer { filter }
+    }
+}
+
+impl<S, F> Layer<S> for WarpFilterLayer<F>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send,
+    F: Filter<Extract = (), Error = Rejection> + Clone + Send + Sync + 'static,
+{
+    type Service = WarpService<warp::reply::Response>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        let filter = self
+            .filter
+            .clone()
+            .and(forward_to(inner))
+            .recover(handle_axum_rejection)
+            .unify();
+
+        WarpService::new(filter.boxed())
+    }
+}
+
+/// Creates a Warp filter that forwards every request to an Axum service.
+pub(crate) fn forward_to<S>(service: S) -> BoxedFilter<(warp::reply::Response,)>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    // Tower services are not required to be `Sync`, but boxed filters are.
+    let service = Arc::new(Mutex::new(service));
+
+    axum_request_filter()
+        .and_then(move |req: Result<Request, String>| {
+            let service = service.lock().unwrap().clone();
+            async move {
+                let response = match req {
+                    Ok(req) => match service.oneshot(req).await {
+                        Ok(response) => response,
+                        Err(never) => match never {},
+                    },
+                    Err(err) => create_conversion_error_response(err),
+                };
+                Ok::<_, Rejection>(warp::Reply::into_response(AxumReply(response)))
+            }
+        })
+        .boxed()
+}
@@ -1,11 +1,16 @@
 //! A compatibility library for running Warp filters within Axum servers.
 //!
 //! This crate enables gradual migration from Warp to Axum by allowing existing
-//! Warp routes to run alongside new Axum routes in the same server.
+//! Warp routes to run alongside new Axum routes in the same server. Services built directly on
+//! hyper 0.14 can be mounted the same way with [`HyperCompatService`], and Axum routers can be
+//! served by hyper 0.14 servers using the [`compat`] module. Warp servers running in another
+//! process can be mounted with [`RemoteWarpService`](remote::RemoteWarpService). Servers that
+//! do not use Axum can host Warp filters on plain `http` 1.0 types with the [`http1`] module.
 //!
 //! # Example
 //!
-//! ```rust
+#![cfg_attr(feature = "axum", doc = "```rust")]
+#![cfg_attr(not(feature = "axum"), doc = "```rust,ignore")]
 //! use axum::{routing::get, Router};
 //! use warpdrive::WarpService;
 //! use warp::Filter;
@@ -25,24 +30,291 @@
 //! # }
 //! ```
 //!
+//! Without the `axum` feature, the same filters can be served to plain `http` 1.0 requests:
+//!
+//! ```rust
+//! use warpdrive::http1::HttpWarpService;
+//! use warp::Filter;
+//!
+//! let warp_routes = warp::path("api")
+//!     .and(warp::get())
+//!     .map(|| "Hello from Warp!")
+//!     .boxed();
+//!
+//! let service = HttpWarpService::new(warp_routes);
+//! ```
+//!
 //! ## Limitations
 //!
-//! - WebSockets are not supported, these should be migrated to Axum first.
+//! - WebSockets are not supported through `WarpService`, these should be migrated to Axum first.
+//!   The `ws` feature provides message converters to help with this.
 //! - Some other advanced Warp features may not work.
 //! - Some conversion overhead from converting `http::Request` and `http::Response` types.
 //!
+//! ## Feature Flags
+//!
+//! - `arbitrary`: Enables `arbitrary::Arbitrary` implementations for the request and response
+//!   generators of the [`fuzz`] module, for use as `cargo fuzz` target inputs.
+//! - `axum`: Enabled by default. Enables everything built on Axum, including [`WarpService`].
+//!   Without it, only the [`http1`] module is available.
+//! - `axum07`: Enables the `axum07` module, which adapts [`WarpService`] to be mounted in
+//!   Axum 0.7 routers. The conversion layer is still built on Axum 0.8, so this feature also
+//!   enables `axum` and a workspace on Axum 0.7 compiles both versions.
+//! - `bench`: Enables the [`bench`] module with workloads for measuring the overhead of the
+//!   conversion boundary, as used by the `benches/` suite.
+//! - `codegen`: Enables the [`codegen`] module for scaffolding Axum handlers and router
+//!   registrations from recorded route usage or a list of routes.
+//! - `cors`: Enables the [`cors`] module with a CORS configuration that builds matching
+//!   settings for Axum and Warp routes.
+//! - `error-reporting`: Enables the [`report`] module with a hook for reporting conversion
+//!   failures, panics, and timeouts to services such as Sentry.
+//! - `fuzz`: Enables the [`fuzz`] module with request and response generators and round-trip
+//!   properties for fuzzing the conversion boundary.
+//! - `macros`: Enables the [`dual_handler`] attribute macro for generating a Warp filter and an
+//!   Axum handler from a single function, and the derive macro for [`RejectionMapper`].
+//! - `openapi`: Enables the [`openapi`] module for documenting legacy routes and merging them
+//!   into the OpenAPI document of the Axum routes, such as one generated by utoipa, and
+//!   OpenAPI skeletons built from a [`UsageReport`].
+//! - `proptest`: Enables proptest strategies for the request and response generators of the
+//!   [`fuzz`] module.
+//! - `test-util`: Enables the [`test`] module with a test client for routers that mix Axum routes
+//!   and Warp services.
+//! - `toml`: Enables loading TOML files with [`Manifest`], in addition to JSON.
+//! - `tracing`: Enables the [`trace`] module with a request tracing configuration that
+//!   instruments Axum and Warp routes identically.
+//! - `utoipa`: Enables merging documented legacy routes directly into a
+//!   `utoipa::openapi::OpenApi`, in addition to the `openapi` feature.
+//! - `ws`: Enables the [`ws`] module with WebSocket message converters, for reusing Warp
+//!   WebSocket logic in Axum WebSocket handlers.
+//!
+//! ## Headers
+//!
+//! Headers are copied as they are in both directions. Values are copied as opaque bytes, so
+//! values that are not valid UTF-8 arrive unchanged, and repeated headers, such as
+//! `Set-Cookie`, keep their count and their order. They are never joined into one value.
+//!
 //! ## Error Handling
 //!
 //! WarpService acts as a transparent wrapper. The existing Warp rejection handling should work
 //! exactly as before. It merely converts the pre-v1.0 `http::Response` into the Axum 0.8-compatible
 //! v1.0 `http::Response` type.
-//! The service only adds 500 errors in the extremely rare case of HTTP format conversion failures.
+//! The service only adds 500 errors in the extremely rare case of HTTP format conversion failures,
+//! 504 errors when a timeout is configured with `WarpService::with_timeout` or a [`Deadline`]
+//! passes, 429 errors when a [`RateLimit`] is exceeded, 431 and 414 errors when requests exceed
+//! the [`RequestLimits`], 400 errors for requests with ambiguous body framing, such as both
+//! `Content-Length` and `Transfer-Encoding`, and 503 errors once a [`Shutdown`] is triggered or
+//! while a [`CircuitBreaker`] is open. These are plain text by default, or RFC 9457 problem
+//! details with [`ProblemDetails`]. While a [`KillSwitch`] is on, requests are answered with 503
+//! and its configured body instead.
+//!
+//! To handle these errors with Tower error handling instead, such as `HandleErrorLayer`, use
+//! [`WarpService::into_fallible`], which returns them as a typed [`Error`].
+
+mod compat_body;
+pub mod http1;
 
+#[cfg(feature = "axum")]
+mod access_log;
+#[cfg(feature = "axum")]
+pub mod admin;
+#[cfg(feature = "axum")]
+mod alarm;
+#[cfg(feature = "axum")]
+mod audit;
+#[cfg(feature = "axum07")]
+pub mod axum07;
+#[cfg(all(feature = "axum", any(test, feature = "bench")))]
+pub mod bench;
+#[cfg(feature = "axum")]
+mod blocking;
+#[cfg(feature = "axum")]
+pub mod body;
+#[cfg(feature = "axum")]
+mod bounded;
+#[cfg(feature = "axum")]
+mod buffer;
+#[cfg(feature = "axum")]
+mod cache;
+#[cfg(feature = "axum")]
+mod canary;
+#[cfg(feature = "axum")]
+mod capture;
+#[cfg(feature = "axum")]
+mod circuit_breaker;
+#[cfg(all(feature = "axum", any(test, feature = "codegen")))]
+pub mod codegen;
+#[cfg(feature = "axum")]
+pub mod compat;
+#[cfg(feature = "axum")]
+mod control;
+#[cfg(feature = "axum")]
 mod convert_request;
+#[cfg(feature = "axum")]
 mod convert_response;
+#[cfg(all(feature = "axum", any(test, feature = "cors")))]
+pub mod cors;
+#[cfg(feature = "axum")]
+mod deadline;
+#[cfg(feature = "axum")]
+mod denylist;
+#[cfg(feature = "axum")]
+mod error;
+#[cfg(feature = "axum")]
+mod error_bridge;
+#[cfg(feature = "axum")]
+mod excerpt;
+#[cfg(feature = "axum")]
+mod extract;
+#[cfg(feature = "axum")]
+mod failover;
+#[cfg(feature = "axum")]
+mod fault;
+#[cfg(feature = "axum")]
+mod filter_ext;
+#[cfg(feature = "axum")]
+mod framing;
+#[cfg(all(feature = "axum", any(test, feature = "fuzz")))]
+pub mod fuzz;
+#[cfg(feature = "axum")]
+mod gauge;
+#[cfg(feature = "axum")]
+mod group;
+#[cfg(feature = "axum")]
+mod header_filter;
+#[cfg(feature = "axum")]
+mod header_rewrite;
+#[cfg(feature = "axum")]
+mod health;
+#[cfg(feature = "axum")]
+mod hedge;
+#[cfg(feature = "axum")]
+mod host;
+#[cfg(feature = "axum")]
+mod kill_switch;
+#[cfg(feature = "axum")]
+mod layer;
+#[cfg(feature = "axum")]
+mod limits;
+#[cfg(feature = "axum")]
+mod location;
+#[cfg(feature = "axum")]
+mod manifest;
+#[cfg(feature = "axum")]
+mod migration;
+#[cfg(feature = "axum")]
+mod normalize;
+#[cfg(all(feature = "axum", any(test, feature = "openapi")))]
+pub mod openapi;
+#[cfg(feature = "axum")]
+mod prefix;
+#[cfg(feature = "axum")]
+mod probe;
+#[cfg(feature = "axum")]
+mod problem;
+#[cfg(feature = "axum")]
+mod query;
+#[cfg(feature = "axum")]
+mod rate_limit;
+#[cfg(feature = "axum")]
+pub mod remote;
+#[cfg(feature = "axum")]
+mod reply;
+#[cfg(all(feature = "axum", any(test, feature = "error-reporting")))]
+pub mod report;
+#[cfg(feature = "axum")]
+mod resource;
+#[cfg(feature = "axum")]
+mod route;
+#[cfg(feature = "axum")]
+mod routes;
+#[cfg(feature = "axum")]
+mod security_headers;
+#[cfg(feature = "axum")]
+mod serve;
+#[cfg(feature = "axum")]
+mod shutdown;
+#[cfg(feature = "axum")]
+mod single_thread;
+#[cfg(feature = "axum")]
+pub mod sse;
+#[cfg(feature = "axum")]
+mod steering;
+#[cfg(all(feature = "axum", any(test, feature = "test-util")))]
+pub mod test;
+#[cfg(all(feature = "axum", any(test, feature = "tracing")))]
+pub mod trace;
+#[cfg(feature = "axum")]
+mod usage;
+#[cfg(feature = "axum")]
 mod warp_service;
+#[cfg(all(feature = "axum", any(test, feature = "ws")))]
+pub mod ws;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "axum"))]
 mod tests;
 
-pub use warp_service::WarpService;
+// Allows macro-generated code to refer to `::warpdrive` from within this crate's tests.
+#[cfg(all(test, feature = "axum"))]
+extern crate self as warpdrive;
+
+#[cfg(feature = "axum")]
+pub use crate::{
+    access_log::AccessLog,
+    alarm::ErrorRateAlarm,
+    audit::{Audit, AuditRecord},
+    cache::ResponseCache,
+    canary::{CanaryMetrics, CanaryStats, LatencyHistogram},
+    capture::{Capture, CapturedExchange},
+    circuit_breaker::{CircuitBreaker, CircuitState},
+    compat::HyperCompatService,
+    deadline::Deadline,
+    denylist::Denylist,
+    error::Error,
+    error_bridge::{ErrorBridge, ErrorChain, RejectionMapper, recover_mapped},
+    extract::{
+        AxumRejection, ExtractFilter, WarpExtract, axum_extract, axum_extract_with_state,
+        handle_axum_rejection,
+    },
+    failover::{Failover, FailoverReason},
+    fault::FaultInjection,
+    filter_ext::FilterExt,
+    gauge::InFlightGauge,
+    group::WarpServiceGroup,
+    header_filter::HeaderFilter,
+    header_rewrite::HeaderRewrite,
+    health::Readiness,
+    hedge::{Hedge, HedgeWinner},
+    host::HostOverride,
+    kill_switch::KillSwitch,
+    layer::{WarpFilterLayer, WarpWrapLayer},
+    limits::RequestLimits,
+    location::LocationRewrite,
+    manifest::Manifest,
+    migration::{Migration, MigrationPhase, ShadowCounts, ShadowMismatch},
+    normalize::PathNormalization,
+    prefix::PrefixConfig,
+    probe::{Probe, ProbeReport, ProbeResult},
+    problem::ProblemDetails,
+    query::original_query,
+    rate_limit::RateLimit,
+    reply::{AxumReply, DualReply, WarpReply},
+    resource::ResourceProvider,
+    routes::WarpRoutes,
+    security_headers::SecurityHeaders,
+    serve::{DualListener, serve},
+    shutdown::Shutdown,
+    single_thread::SingleThreadWarpService,
+    steering::{FlagProvider, Steering},
+    usage::{RouteOwner, RouteUsage, UsageExport, UsageReport},
+    warp_service::{AnyBody, FallibleWarpService, WarpService},
+};
+
+#[cfg(feature = "macros")]
+pub use warpdrive_macros::{RejectionMapper, dual_handler};
+
+#[cfg(feature = "axum")]
+#[doc(hidden)]
+pub mod __private {
+    pub use axum;
+    pub use warp;
+}
@@ -0,0 +1,94 @@
+use axum::extract::Request;
+
+use crate::error::Error;
+
+/// Limits on the size of request heads, enforced before requests reach the Warp filter.
+///
+/// Legacy filters mounted as a fallback see all unmatched traffic, and typically have no
+/// protection against oversized requests of their own. Requests with more headers, or more
+/// header bytes, than allowed are answered with `431 Request Header Fields Too Large`, and
+/// requests whose path and query are longer than allowed with `414 URI Too Long`. When using
+/// [`FallibleWarpService`](crate::FallibleWarpService), they fail with
+/// [`Error::TooManyHeaders`], [`Error::HeadersTooLarge`], or [`Error::UriTooLong`] instead.
+///
+/// No limits are set by default. They are applied with
+/// [`WarpService::with_request_limits`](crate::WarpService::with_request_limits).
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::{RequestLimits, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("legacy").map(|| "Hello").boxed();
+///
+/// let limits = RequestLimits::new()
+///     .max_headers(100)
+///     .max_header_bytes(16 * 1024)
+///     .max_uri_length(8 * 1024);
+///
+/// let service = WarpService::new(filter).with_request_limits(limits);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestLimits {
+    max_headers: Option<usize>,
+    max_header_bytes: Option<usize>,
+    max_uri_length: Option<usize>,
+}
+
+impl RequestLimits {
+    /// Creates limits that allow any request.
+    pub fn new() -> Self {
+        RequestLimits::default()
+    }
+
+    /// Limits the number of header fields, counting each value of a repeated header.
+    pub fn max_headers(mut self, max_headers: usize) -> Self {
+        self.max_headers = Some(max_headers);
+        self
+    }
+
+    /// Limits the total size of header names and values, in bytes.
+    pub fn max_header_bytes(mut self, max_header_bytes: usize) -> Self {
+        self.max_header_bytes = Some(max_header_bytes);
+        self
+    }
+
+    /// Limits the length of the request path and query, in bytes.
+    pub fn max_uri_length(mut self, max_uri_length: usize) -> Self {
+        self.max_uri_length = Some(max_uri_length);
+        self
+    }
+
+    /// Returns an error if the request exceeds a limit.
+    pub(crate) fn check(&self, req: &Request) -> Result<(), Error> {
+        if let Some(limit) = self.max_uri_length {
+            let length = req
+                .uri()
+                .path_and_query()
+                .map_or(0, |path_and_query| path_and_query.as_str().len());
+            if length > limit {
+                return Err(Error::UriTooLong(limit));
+            }
+        }
+
+        if let Some(limit) = self.max_headers
+            && req.headers().len() > limit
+        {
+            return Err(Error::TooManyHeaders(limit));
+        }
+
+        if let Some(limit) = self.max_header_bytes {
+            let bytes: usize = req
+                .headers()
+                .iter()
+                .map(|(name, value)| name.as_str().len() + value.len())
+                .sum();
+            if bytes > limit {
+                return Err(Error::HeadersTooLarge(limit));
+            }
+        }
+
+        Ok(())
+    }
+}
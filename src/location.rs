@@ -0,0 +1,143 @@
+use axum::http::{HeaderMap, HeaderValue, header::LOCATION, uri::Authority};
+
+/// Rewrites the `Location` header of responses from the Warp filter, for legacy handlers that
+/// build redirects with the URLs of the deployment they were written for.
+///
+/// Each rule matches absolute URLs by origin, that is by scheme, host, and port, and either
+/// moves them to another origin with [`origin`](LocationRewrite::origin), or makes them
+/// relative to the current host with [`relative`](LocationRewrite::relative). The path, query,
+/// and fragment are kept. Schemes and hosts are compared case-insensitively, and a missing port
+/// matches the default port of the scheme. The first matching rule applies, and relative URLs,
+/// or URLs from other origins, are left as they are.
+///
+/// Rules apply to every response with a `Location` header, such as redirects and
+/// `201 Created` responses, and are applied with
+/// [`WarpService::with_location_rewrite`](crate::WarpService::with_location_rewrite).
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::{LocationRewrite, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("old")
+///     .map(|| warp::redirect::found(warp::http::Uri::from_static("http://10.0.0.7:8080/new")))
+///     .boxed();
+///
+/// let rewrite = LocationRewrite::new()
+///     .origin("http://10.0.0.7:8080", "https://api.example.com")
+///     .relative("http://localhost:3030");
+///
+/// let service = WarpService::new(filter).with_location_rewrite(rewrite);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LocationRewrite {
+    rules: Vec<(Origin, Option<String>)>,
+}
+
+impl LocationRewrite {
+    /// Creates an empty set of rules.
+    pub fn new() -> Self {
+        LocationRewrite::default()
+    }
+
+    /// Moves URLs from one origin to another, such as from `http://legacy.internal:8080` to
+    /// `https://api.example.com`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` or `to` is not an origin with a scheme and a host.
+    pub fn origin(mut self, from: &str, to: &str) -> Self {
+        let to = to.trim_end_matches('/');
+        assert!(
+            split_origin(to).is_some_and(|(_, rest)| rest.is_empty()),
+            "invalid origin"
+        );
+
+        self.rules.push((parse_origin(from), Some(to.to_string())));
+        self
+    }
+
+    /// Makes URLs from an origin relative to the host the response is served from, such as
+    /// `http://localhost:3030/login` to `/login`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` is not an origin with a scheme and a host.
+    pub fn relative(mut self, from: &str) -> Self {
+        self.rules.push((parse_origin(from), None));
+        self
+    }
+
+    /// Rewrites the `Location` header of a response.
+    pub(crate) fn apply(&self, headers: &mut HeaderMap) {
+        let Some(location) = headers.get(LOCATION).and_then(|value| value.to_str().ok()) else {
+            return;
+        };
+        let Some((origin, rest)) = split_origin(location) else {
+            return;
+        };
+        let Some((_, to)) = self.rules.iter().find(|(from, _)| *from == origin) else {
+            return;
+        };
+
+        let rewritten = match to {
+            Some(to) => format!("{}{}", to, rest),
+            None if rest.starts_with('/') => rest.to_string(),
+            None => format!("/{}", rest),
+        };
+        if let Ok(value) = HeaderValue::try_from(rewritten) {
+            headers.insert(LOCATION, value);
+        }
+    }
+}
+
+/// The scheme, host, and port of an absolute URL, normalized for comparison.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Origin {
+    scheme: String,
+    host: String,
+    port: Option<u16>,
+}
+
+fn parse_origin(origin: &str) -> Origin {
+    match split_origin(origin.trim_end_matches('/')) {
+        Some((origin, "")) => origin,
+        _ => panic!("invalid origin"),
+    }
+}
+
+/// Splits an absolute URL into its origin and the rest, starting at the path, query, or
+/// fragment. Returns `None` for relative URLs and URLs with user information.
+fn split_origin(url: &str) -> Option<(Origin, &str)> {
+    let (scheme, rest) = url.split_once("://")?;
+    if scheme.is_empty()
+        || !scheme
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"+-.".contains(&b))
+    {
+        return None;
+    }
+
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, rest) = rest.split_at(end);
+    if authority.contains('@') {
+        return None;
+    }
+    let authority = Authority::try_from(authority).ok()?;
+
+    let scheme = scheme.to_ascii_lowercase();
+    let default_port = match scheme.as_str() {
+        "http" | "ws" => Some(80),
+        "https" | "wss" => Some(443),
+        _ => None,
+    };
+    let port = authority.port_u16().or(default_port);
+
+    let origin = Origin {
+        scheme,
+        host: authority.host().to_ascii_lowercase(),
+        port,
+    };
+    Some((origin, rest))
+}
@@ -0,0 +1,278 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use axum::body::Bytes;
+use serde_json::Value;
+
+use crate::{Denylist, Migration, MigrationPhase, WarpService};
+
+/// A declarative description of a migration, loaded from a JSON or TOML file.
+///
+/// A manifest lists the phase of each route, as used by [`Migration`], and the paths answered
+/// by a [`Denylist`]. It is applied to a service at startup with
+/// [`apply`](Manifest::apply), and the phases can be reloaded while the service runs with
+/// [`reload`](Manifest::reload) or [`reloader`](Manifest::reloader). The denylist is only
+/// read at startup.
+///
+/// Manifests have the following format, shown in TOML:
+///
+/// ```toml
+/// [[routes]]
+/// prefix = "/api/users"
+/// phase = "shadow"
+///
+/// [[routes]]
+/// prefix = "/api/orders"
+/// phase = "canary"
+/// percentage = 10
+///
+/// [[routes]]
+/// prefix = "/api/health"
+/// phase = "new-only"
+///
+/// [[denylist]]
+/// path = "/v1/users"
+/// status = 410
+/// body = "Moved to /v2/users"
+/// ```
+///
+/// The phases are `legacy-only`, `shadow`, `canary`, and `new-only`, and the denylist
+/// statuses are `404` and `410`. Both sections are optional. TOML manifests require the
+/// `toml` feature.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::{Router, routing::get};
+/// use warpdrive::{Manifest, Migration, WarpService};
+/// use warp::Filter;
+///
+/// let manifest = Manifest::from_json(r#"{
+///     "routes": [{ "prefix": "/api/health", "phase": "new-only" }],
+///     "denylist": [{ "path": "/v1", "status": 410, "body": "Moved to /api" }]
+/// }"#).unwrap();
+///
+/// let filter = warp::path("api").map(|| "Hello from Warp").boxed();
+/// let axum: Router = Router::new().route("/api/health", get(|| async { "OK" }));
+///
+/// let migration = Migration::new(axum);
+/// let service = manifest.apply(WarpService::new(filter), migration.clone());
+///
+/// // Later, for example on SIGHUP:
+/// // Manifest::load("migration.json")?.reload(&migration);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Manifest {
+    routes: Vec<(String, MigrationPhase)>,
+    denylist: Vec<DenyEntry>,
+}
+
+#[derive(Debug, Clone)]
+struct DenyEntry {
+    path: String,
+    gone: bool,
+    body: Bytes,
+}
+
+impl Manifest {
+    /// Loads a manifest from a file, read as TOML if it has a `.toml` extension and as JSON
+    /// otherwise.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)?;
+
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "toml")
+        {
+            #[cfg(any(test, feature = "toml"))]
+            return Manifest::from_toml(&contents);
+            #[cfg(not(any(test, feature = "toml")))]
+            return Err(invalid(
+                "TOML manifests require the `toml` feature".to_string(),
+            ));
+        }
+
+        Manifest::from_json(&contents)
+    }
+
+    /// Parses a JSON manifest.
+    pub fn from_json(json: &str) -> io::Result<Self> {
+        Manifest::from_value(&serde_json::from_str(json)?)
+    }
+
+    /// Parses a TOML manifest.
+    #[cfg(any(test, feature = "toml"))]
+    pub fn from_toml(toml: &str) -> io::Result<Self> {
+        let value: toml::Value = toml::from_str(toml).map_err(|err| invalid(err.to_string()))?;
+        Manifest::from_value(&serde_json::to_value(value)?)
+    }
+
+    fn from_value(value: &Value) -> io::Result<Self> {
+        let mut manifest = Manifest::default();
+
+        for (index, route) in entries(value, "routes")?.iter().enumerate() {
+            let field = |name: &str| {
+                route
+                    .get(name)
+                    .ok_or_else(|| invalid(format!("routes[{}] has no `{}`", index, name)))
+            };
+
+            let prefix = field("prefix")?
+                .as_str()
+                .ok_or_else(|| invalid(format!("routes[{}].prefix is not a string", index)))?;
+            let phase = match field("phase")?.as_str() {
+                Some("legacy-only") => MigrationPhase::LegacyOnly,
+                Some("shadow") => MigrationPhase::Shadow,
+                Some("canary") => {
+                    let percentage = field("percentage")?
+                        .as_u64()
+                        .filter(|percentage| *percentage <= 100)
+                        .ok_or_else(|| {
+                            invalid(format!(
+                                "routes[{}].percentage is not a number from 0 to 100",
+                                index
+                            ))
+                        })?;
+                    MigrationPhase::Canary(percentage as u8)
+                }
+                Some("new-only") => MigrationPhase::NewOnly,
+                _ => {
+                    return Err(invalid(format!(
+                        "routes[{}].phase is not one of `legacy-only`, `shadow`, `canary`, or \
+                         `new-only`",
+                        index
+                    )));
+                }
+            };
+
+            manifest.routes.push((prefix.to_string(), phase));
+        }
+
+        for (index, entry) in entries(value, "denylist")?.iter().enumerate() {
+            let path = entry["path"]
+                .as_str()
+                .ok_or_else(|| invalid(format!("denylist[{}].path is not a string", index)))?;
+            let gone = match entry["status"].as_u64() {
+                Some(410) => true,
+                Some(404) => false,
+                _ => {
+                    return Err(invalid(format!(
+                        "denylist[{}].status is not 404 or 410",
+                        index
+                    )));
+                }
+            };
+            let body = match &entry["body"] {
+                Value::Null => "",
+                body => body
+                    .as_str()
+                    .ok_or_else(|| invalid(format!("denylist[{}].body is not a string", index)))?,
+            };
+
+            manifest.denylist.push(DenyEntry {
+                path: path.to_string(),
+                gone,
+                body: Bytes::copy_from_slice(body.as_bytes()),
+            });
+        }
+
+        Ok(manifest)
+    }
+
+    /// Returns the phase of each route, in the order they are listed.
+    pub fn routes(&self) -> &[(String, MigrationPhase)] {
+        &self.routes
+    }
+
+    /// Returns the denylist described by the manifest.
+    pub fn denylist(&self) -> Denylist {
+        self.denylist
+            .iter()
+            .fold(Denylist::new(), |denylist, entry| {
+                if entry.gone {
+                    denylist.gone(entry.path.clone(), entry.body.clone())
+                } else {
+                    denylist.not_found(entry.path.clone(), entry.body.clone())
+                }
+            })
+    }
+
+    /// Sets the route phases on a migration and applies it to a service, along with the
+    /// denylist if the manifest has one.
+    ///
+    /// Keep a clone of the migration to [`reload`](Manifest::reload) the phases later.
+    pub fn apply<T>(&self, service: WarpService<T>, migration: Migration) -> WarpService<T>
+    where
+        T: warp::Reply + Send + Sync + 'static,
+    {
+        self.reload(&migration);
+
+        let service = service.with_migration(migration);
+        if self.denylist.is_empty() {
+            service
+        } else {
+            service.with_denylist(self.denylist())
+        }
+    }
+
+    /// Replaces the phases of a migration with the routes of this manifest. Routes that are
+    /// no longer listed return to [`LegacyOnly`](MigrationPhase::LegacyOnly).
+    pub fn reload(&self, migration: &Migration) {
+        migration.replace_phases(&self.routes);
+    }
+
+    /// Returns a future that checks the manifest file at every interval and reloads the
+    /// phases of the migration when it changes, to be spawned as a background task.
+    ///
+    /// Manifests that fail to load are ignored, keeping the current phases, and are retried
+    /// when the file changes again.
+    pub fn reloader(
+        path: impl Into<PathBuf>,
+        migration: Migration,
+        interval: Duration,
+    ) -> impl Future<Output = ()> + Send + 'static {
+        let path = path.into();
+        let modified = |path: &Path| fs::metadata(path).and_then(|metadata| metadata.modified());
+
+        async move {
+            let mut last_modified: Option<SystemTime> = modified(&path).ok();
+            let mut ticks = tokio::time::interval(interval);
+            ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately.
+            ticks.tick().await;
+
+            loop {
+                ticks.tick().await;
+
+                let Ok(current) = modified(&path) else {
+                    continue;
+                };
+                if last_modified == Some(current) {
+                    continue;
+                }
+                last_modified = Some(current);
+
+                if let Ok(manifest) = Manifest::load(&path) {
+                    manifest.reload(&migration);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the entries of an optional array section.
+fn entries<'a>(value: &'a Value, section: &str) -> io::Result<&'a [Value]> {
+    match &value[section] {
+        Value::Null => Ok(&[]),
+        Value::Array(entries) => Ok(entries),
+        _ => Err(invalid(format!("`{}` is not a list", section))),
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,619 @@
+use std::{
+    collections::BTreeMap,
+    convert::Infallible,
+    fmt,
+    pin::Pin,
+    sync::{Arc, Mutex, RwLock},
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::Request,
+    http::{
+        HeaderMap, HeaderName, Method, StatusCode,
+        header::{CONTENT_LENGTH, DATE, TRANSFER_ENCODING},
+        request::Parts,
+    },
+    response::Response,
+};
+use futures::future::{self, BoxFuture};
+use http_body::{Body as HttpBody, Frame, SizeHint};
+use tokio::sync::oneshot;
+use tower::{Service, ServiceExt, util::BoxCloneSyncService};
+
+use crate::{
+    body::tee,
+    canary::{CanaryMetrics, CanaryRecorder, CanarySample},
+    error::Error,
+    prefix::{matches_prefix, trim_prefix},
+    route::served_by_warp,
+    steering::{FlagProvider, Steering},
+};
+
+/// The largest amount of request body buffered for the shadow request by default.
+const DEFAULT_SHADOW_BUFFER: usize = 1024 * 1024;
+
+type ShadowHook = Arc<dyn Fn(&Parts, StatusCode, StatusCode) + Send + Sync>;
+
+/// The migration phase of a route, as set on a [`Migration`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationPhase {
+    /// Requests are served by Warp only.
+    LegacyOnly,
+    /// Requests are served by Warp, and a copy is sent to Axum, whose response is compared
+    /// and discarded.
+    Shadow,
+    /// The given percentage of requests, from 0 to 100, is served by Axum, and the rest by
+    /// Warp.
+    Canary(u8),
+    /// Requests are served by Axum only.
+    NewOnly,
+}
+
+/// Per-route migration phases for a [`WarpService`](crate::WarpService), moving each route
+/// from Warp to an Axum service.
+///
+/// Each route is identified by a path prefix and set to a [`MigrationPhase`], which decides
+/// whether its requests are served by Warp, shadowed to Axum, split between the two as a
+/// canary, or served by Axum. Routes that match no prefix are [`LegacyOnly`]. When several
+/// prefixes match a request, the longest one applies, so a route can be moved ahead of the
+/// rest of its prefix.
+///
+/// Shadowing only sends requests with safe methods, `GET`, `HEAD`, and `OPTIONS` by default,
+/// since both implementations handle them. The request body is streamed to both with
+/// [`tee`], and shadow requests whose body falls too far behind are abandoned. Both responses
+/// are compared by status, headers, and body, and each route counts its mismatches by
+/// [`ShadowMismatch`] category in [`shadow_counts`](Migration::shadow_counts), for alerting
+/// when the Axum implementation drifts. Bodies are compared when the Warp response body is no
+/// longer than the [`shadow_buffer`](Migration::shadow_buffer) and has been sent in full. The
+/// statuses of both responses can also be passed to [`on_shadow`](Migration::on_shadow).
+/// Requests answered by the kill switch or the cache are not shadowed, and requests whose
+/// response does not come from the Warp filter, such as rate limited ones, are not compared.
+/// Requests served by Axum have an `x-warpdrive-steering: axum` header.
+///
+/// Canary routes record the latency and status of each request by implementation in
+/// [`canary_metrics`](Migration::canary_metrics), so the two can be compared before moving
+/// the route further.
+///
+/// Phases can be changed at runtime with [`set_phase`](Migration::set_phase) on any clone.
+///
+/// [`LegacyOnly`]: MigrationPhase::LegacyOnly
+///
+/// # Example
+///
+/// ```rust
+/// use axum::{Router, routing::get};
+/// use warpdrive::{Migration, MigrationPhase, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path!("api" / ..).map(|| "Hello from Warp").boxed();
+///
+/// let axum: Router = Router::new()
+///     .route("/api/users", get(|| async { "Users from Axum" }))
+///     .route("/api/orders", get(|| async { "Orders from Axum" }))
+///     .route("/api/health", get(|| async { "OK" }));
+///
+/// let migration = Migration::new(axum)
+///     .phase("/api/users", MigrationPhase::Shadow)
+///     .phase("/api/orders", MigrationPhase::Canary(10))
+///     .phase("/api/health", MigrationPhase::NewOnly)
+///     .on_shadow(|req, warp, axum| {
+///         if warp != axum {
+///             eprintln!("{} {}: Warp {} but Axum {}", req.method, req.uri, warp, axum);
+///         }
+///     });
+///
+/// let service = WarpService::new(filter).with_migration(migration);
+/// ```
+#[derive(Clone)]
+pub struct Migration {
+    axum: BoxCloneSyncService<Request, Response, Infallible>,
+    phases: PhaseTable,
+    steering: Steering,
+    shadow_methods: Vec<Method>,
+    shadow_buffer: usize,
+    shadow_ignored_headers: Arc<Vec<HeaderName>>,
+    on_shadow: Option<ShadowHook>,
+    shadow_counts: Arc<Mutex<BTreeMap<String, ShadowCounts>>>,
+    canary: CanaryRecorder,
+}
+
+#[derive(Clone, Default)]
+struct PhaseTable {
+    phases: Arc<RwLock<Vec<(String, MigrationPhase)>>>,
+}
+
+/// A way in which the Axum response to a shadowed request differed from the Warp response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ShadowMismatch {
+    /// The response statuses differ.
+    Status,
+    /// The response headers differ, other than those ignored with
+    /// [`Migration::shadow_ignore_header`].
+    Header,
+    /// The response bodies differ.
+    Body,
+}
+
+impl ShadowMismatch {
+    /// Returns the mismatch category as a metric label: `status`, `header`, or `body`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShadowMismatch::Status => "status",
+            ShadowMismatch::Header => "header",
+            ShadowMismatch::Body => "body",
+        }
+    }
+}
+
+/// Counts of shadowed requests for a route, as returned by [`Migration::shadow_counts`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShadowCounts {
+    /// The path prefix of the route, as set with [`Migration::phase`].
+    pub route: String,
+    /// The number of shadowed requests whose responses were compared.
+    pub compared: u64,
+    /// The number of responses whose statuses differed.
+    pub status: u64,
+    /// The number of responses whose headers differed.
+    pub header: u64,
+    /// The number of responses whose bodies differed.
+    pub body: u64,
+}
+
+impl ShadowCounts {
+    /// Returns the count for a mismatch category.
+    pub fn mismatches(&self, mismatch: ShadowMismatch) -> u64 {
+        match mismatch {
+            ShadowMismatch::Status => self.status,
+            ShadowMismatch::Header => self.header,
+            ShadowMismatch::Body => self.body,
+        }
+    }
+}
+
+impl PhaseTable {
+    fn lookup(&self, path: &str) -> MigrationPhase {
+        self.lookup_route(path)
+            .map_or(MigrationPhase::LegacyOnly, |(_, phase)| phase)
+    }
+
+    /// Returns the longest prefix matching a path, with its phase.
+    fn lookup_route(&self, path: &str) -> Option<(String, MigrationPhase)> {
+        self.phases
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(prefix, _)| matches_prefix(prefix, path))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .cloned()
+    }
+}
+
+impl FlagProvider for PhaseTable {
+    fn axum_percentage<'a>(&'a self, route: &'a str) -> BoxFuture<'a, Option<u8>> {
+        let percentage = match self.lookup(route) {
+            MigrationPhase::LegacyOnly | MigrationPhase::Shadow => 0,
+            MigrationPhase::Canary(percentage) => percentage,
+            MigrationPhase::NewOnly => 100,
+        };
+        Box::pin(future::ready(Some(percentage)))
+    }
+}
+
+/// A comparison started for a request served by Warp, which is finished with its result.
+pub(crate) enum Comparison {
+    Shadow(Box<ShadowRequest>),
+    Canary(CanarySample),
+}
+
+impl Comparison {
+    /// Sends the shadow request, if any, once the request is not answered by the kill switch
+    /// or the cache.
+    pub(crate) fn start(self) -> Self {
+        match self {
+            Comparison::Shadow(mut shadow) => {
+                if let Some(axum) = shadow.pending.take() {
+                    shadow.axum = Some(tokio::spawn(axum));
+                }
+                Comparison::Shadow(shadow)
+            }
+            Comparison::Canary(sample) => Comparison::Canary(sample),
+        }
+    }
+
+    pub(crate) fn finish(self, result: Result<Response, Error>) -> Result<Response, Error> {
+        match self {
+            Comparison::Shadow(shadow) => shadow.compare(result),
+            Comparison::Canary(sample) => {
+                sample.finish(&result);
+                result
+            }
+        }
+    }
+}
+
+/// A shadow request started for a request served by Warp.
+pub(crate) struct ShadowRequest {
+    parts: Parts,
+    route: String,
+    /// The Axum request, until it is sent.
+    pending: Option<BoxFuture<'static, Response>>,
+    axum: Option<tokio::task::JoinHandle<Response>>,
+    max_body: usize,
+    ignored_headers: Arc<Vec<HeaderName>>,
+    on_shadow: Option<ShadowHook>,
+    counts: Arc<Mutex<BTreeMap<String, ShadowCounts>>>,
+}
+
+impl ShadowRequest {
+    /// Compares the shadow response with the Warp result once it is available, capturing
+    /// the Warp response body as it is sent.
+    ///
+    /// Results not produced by the Warp filter, such as rate limited requests, are not
+    /// compared, and their shadow request is cancelled.
+    pub(crate) fn compare(mut self, result: Result<Response, Error>) -> Result<Response, Error> {
+        let axum = match self.axum.take() {
+            Some(axum) if served_by_warp(&result) => axum,
+            Some(axum) => {
+                axum.abort();
+                return result;
+            }
+            None => return result,
+        };
+
+        let (warp_body, result) = match result {
+            Ok(response) => {
+                let (tx, rx) = oneshot::channel();
+                let status = response.status();
+                let headers = response.headers().clone();
+                let response = response.map(|body| {
+                    Body::new(CaptureBody {
+                        inner: body,
+                        captured: Some(Vec::new()),
+                        max_body: self.max_body,
+                        done: Some(tx),
+                    })
+                });
+                ((status, headers, Some(rx)), Ok(response))
+            }
+            Err(err) => ((err.status(), HeaderMap::new(), None), Err(err)),
+        };
+
+        tokio::spawn(async move {
+            let (warp_status, warp_headers, warp_body) = warp_body;
+            // The shadow request was abandoned or panicked.
+            let Ok(axum) = axum.await else {
+                return;
+            };
+            if let Some(hook) = &self.on_shadow {
+                hook(&self.parts, warp_status, axum.status());
+            }
+
+            let mut mismatches = Vec::new();
+            if warp_status != axum.status() {
+                mismatches.push(ShadowMismatch::Status);
+            }
+            if warp_body.is_some()
+                && !headers_match(&warp_headers, axum.headers(), &self.ignored_headers)
+            {
+                mismatches.push(ShadowMismatch::Header);
+            }
+            // Bodies are only compared if the Warp body was captured in full.
+            if let Some(Ok(Some(warp_body))) = match warp_body {
+                Some(rx) => Some(rx.await),
+                None => None,
+            } {
+                let axum_body = axum::body::to_bytes(axum.into_body(), self.max_body).await;
+                if axum_body.is_ok_and(|axum_body| axum_body != warp_body) {
+                    mismatches.push(ShadowMismatch::Body);
+                }
+            }
+
+            let mut counts = self.counts.lock().unwrap();
+            let counts = counts
+                .entry(self.route.clone())
+                .or_insert_with(|| ShadowCounts {
+                    route: self.route.clone(),
+                    ..ShadowCounts::default()
+                });
+            counts.compared += 1;
+            for mismatch in mismatches {
+                match mismatch {
+                    ShadowMismatch::Status => counts.status += 1,
+                    ShadowMismatch::Header => counts.header += 1,
+                    ShadowMismatch::Body => counts.body += 1,
+                }
+            }
+        });
+
+        result
+    }
+}
+
+/// Returns `true` if both header maps have the same values, other than for ignored headers.
+///
+/// Framing headers are not compared, since they depend on how each body is sent.
+fn headers_match(warp: &HeaderMap, axum: &HeaderMap, ignored: &[HeaderName]) -> bool {
+    let compared = |headers: &HeaderMap| {
+        let mut values: Vec<_> = headers
+            .iter()
+            .filter(|(name, _)| {
+                !ignored.contains(name) && *name != CONTENT_LENGTH && *name != TRANSFER_ENCODING
+            })
+            .map(|(name, value)| (name.as_str().to_string(), value.as_bytes().to_vec()))
+            .collect();
+        values.sort();
+        values
+    };
+    compared(warp) == compared(axum)
+}
+
+/// A body that copies its data, sending the copy once it ends if it was no longer than the
+/// limit.
+struct CaptureBody {
+    inner: Body,
+    /// The data so far, or `None` once it exceeds the limit.
+    captured: Option<Vec<u8>>,
+    max_body: usize,
+    done: Option<oneshot::Sender<Option<Bytes>>>,
+}
+
+impl HttpBody for CaptureBody {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = &mut *self;
+        let frame = Pin::new(&mut this.inner).poll_frame(cx);
+
+        match &frame {
+            Poll::Ready(Some(Ok(frame))) => {
+                if let (Some(captured), Some(data)) = (&mut this.captured, frame.data_ref()) {
+                    if captured.len() + data.len() > this.max_body {
+                        this.captured = None;
+                    } else {
+                        captured.extend_from_slice(data);
+                    }
+                }
+            }
+            Poll::Ready(None) => {
+                if let Some(done) = this.done.take() {
+                    let _ = done.send(this.captured.take().map(Bytes::from));
+                }
+            }
+            Poll::Ready(Some(Err(_))) => this.done = None,
+            Poll::Pending => {}
+        }
+
+        frame
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl Migration {
+    /// Creates a migration to the given Axum service, such as a `Router` with the migrated
+    /// routes, with every route in the [`LegacyOnly`](MigrationPhase::LegacyOnly) phase.
+    pub fn new<S>(axum: S) -> Self
+    where
+        S: Service<Request, Response = Response, Error = Infallible>
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        S::Future: Send + 'static,
+    {
+        let axum = BoxCloneSyncService::new(axum);
+        let phases = PhaseTable::default();
+
+        Migration {
+            steering: Steering::new(axum.clone(), phases.clone()),
+            axum,
+            phases,
+            shadow_methods: vec![Method::GET, Method::HEAD, Method::OPTIONS],
+            shadow_buffer: DEFAULT_SHADOW_BUFFER,
+            shadow_ignored_headers: Arc::new(vec![DATE]),
+            on_shadow: None,
+            shadow_counts: Arc::default(),
+            canary: CanaryRecorder::default(),
+        }
+    }
+
+    /// Sets the phase of the routes under a path prefix.
+    pub fn phase(self, prefix: &str, phase: MigrationPhase) -> Self {
+        self.set_phase(prefix, phase);
+        self
+    }
+
+    /// Changes the phase of the routes under a path prefix at runtime, for every clone of
+    /// this migration.
+    pub fn set_phase(&self, prefix: &str, phase: MigrationPhase) {
+        let (prefix, phase) = normalize(prefix, phase);
+
+        let mut phases = self.phases.phases.write().unwrap();
+        phases.retain(|(existing, _)| *existing != prefix);
+        phases.push((prefix, phase));
+    }
+
+    /// Replaces the phases of every prefix.
+    pub(crate) fn replace_phases(&self, phases: &[(String, MigrationPhase)]) {
+        let mut replaced: Vec<(String, MigrationPhase)> = Vec::new();
+        for (prefix, phase) in phases {
+            let (prefix, phase) = normalize(prefix, *phase);
+            replaced.retain(|(existing, _)| *existing != prefix);
+            replaced.push((prefix, phase));
+        }
+
+        *self.phases.phases.write().unwrap() = replaced;
+    }
+
+    /// Returns the phase that applies to a request path.
+    pub fn phase_for(&self, path: &str) -> MigrationPhase {
+        self.phases.lookup(path)
+    }
+
+    /// Returns the phase of every configured prefix, sorted by prefix.
+    pub fn phases(&self) -> Vec<(String, MigrationPhase)> {
+        let mut phases = self.phases.phases.read().unwrap().clone();
+        phases.sort_by(|a, b| a.0.cmp(&b.0));
+        phases
+    }
+
+    /// Assigns canary requests with the same key, such as a user ID, to the same
+    /// implementation. See [`Steering::sticky`].
+    pub fn sticky<F>(mut self, sticky_key: F) -> Self
+    where
+        F: Fn(&Request) -> Option<String> + Send + Sync + 'static,
+    {
+        self.steering = self.steering.sticky(sticky_key);
+        self
+    }
+
+    /// Sets the methods that are shadowed, replacing the default of `GET`, `HEAD`, and
+    /// `OPTIONS`. Only methods that are safe to handle twice should be shadowed.
+    pub fn shadow_methods(mut self, methods: impl IntoIterator<Item = Method>) -> Self {
+        self.shadow_methods = methods.into_iter().collect();
+        self
+    }
+
+    /// Sets how far, in bytes, the shadow request body may fall behind the Warp request
+    /// body before the shadow request is abandoned, and the longest response bodies that are
+    /// compared. Defaults to 1 MiB.
+    pub fn shadow_buffer(mut self, max_buffered: usize) -> Self {
+        self.shadow_buffer = max_buffered;
+        self
+    }
+
+    /// Ignores a response header when comparing shadowed responses. Can be called more than
+    /// once to ignore several headers. `Date` is ignored by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid header name.
+    pub fn shadow_ignore_header(mut self, name: &str) -> Self {
+        Arc::make_mut(&mut self.shadow_ignored_headers)
+            .push(HeaderName::try_from(name).expect("invalid header name"));
+        self
+    }
+
+    /// Returns the shadowed request and mismatch counts of each route that has been
+    /// shadowed, sorted by route, for every clone of this migration.
+    pub fn shadow_counts(&self) -> Vec<ShadowCounts> {
+        self.shadow_counts
+            .lock()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Returns paired latency and status metrics for the Warp and Axum implementations of
+    /// each route that has been in the [`Canary`](MigrationPhase::Canary) phase, sorted by
+    /// route, for every clone of this migration.
+    pub fn canary_metrics(&self) -> Vec<CanaryMetrics> {
+        self.canary.metrics()
+    }
+
+    /// Calls a function with the Warp and Axum response statuses of each shadowed request.
+    pub fn on_shadow<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Parts, StatusCode, StatusCode) + Send + Sync + 'static,
+    {
+        self.on_shadow = Some(Arc::new(hook));
+        self
+    }
+
+    /// Serves the request with Axum if its phase says so, or returns it to be served by Warp
+    /// along with any shadow request or canary sample.
+    pub(crate) async fn dispatch(
+        &self,
+        req: Request,
+    ) -> Result<Response, (Request, Option<Comparison>)> {
+        let (route, phase) = self
+            .phases
+            .lookup_route(req.uri().path())
+            .unwrap_or((String::new(), MigrationPhase::LegacyOnly));
+        match phase {
+            MigrationPhase::LegacyOnly => Err((req, None)),
+            MigrationPhase::Shadow if self.shadow_methods.contains(req.method()) => {
+                let (parts, body) = req.into_parts();
+                let (primary, shadow) = tee(body, self.shadow_buffer);
+
+                let axum = self
+                    .axum
+                    .clone()
+                    .oneshot(Request::from_parts(parts.clone(), shadow));
+                let shadow = ShadowRequest {
+                    parts: parts.clone(),
+                    route,
+                    pending: Some(Box::pin(async move {
+                        match axum.await {
+                            Ok(response) => response,
+                            Err(never) => match never {},
+                        }
+                    })),
+                    axum: None,
+                    max_body: self.shadow_buffer,
+                    ignored_headers: Arc::clone(&self.shadow_ignored_headers),
+                    on_shadow: self.on_shadow.clone(),
+                    counts: Arc::clone(&self.shadow_counts),
+                };
+
+                Err((
+                    Request::from_parts(parts, primary),
+                    Some(Comparison::Shadow(Box::new(shadow))),
+                ))
+            }
+            MigrationPhase::Shadow => Err((req, None)),
+            MigrationPhase::Canary(_) => {
+                let started = Instant::now();
+                match self.steering.steer(req).await {
+                    Ok(response) => {
+                        self.canary.axum(&route, &response, started);
+                        Ok(response)
+                    }
+                    Err(req) => {
+                        let sample = self.canary.warp(route, started);
+                        Err((req, Some(Comparison::Canary(sample))))
+                    }
+                }
+            }
+            MigrationPhase::NewOnly => self.steering.steer(req).await.map_err(|req| (req, None)),
+        }
+    }
+}
+
+/// Removes trailing slashes from a prefix and caps canary percentages at 100.
+fn normalize(prefix: &str, phase: MigrationPhase) -> (String, MigrationPhase) {
+    let prefix = trim_prefix(prefix);
+    let phase = match phase {
+        MigrationPhase::Canary(percentage) => MigrationPhase::Canary(percentage.min(100)),
+        phase => phase,
+    };
+
+    (prefix, phase)
+}
+
+impl fmt::Debug for Migration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Migration")
+            .field("phases", &self.phases())
+            .field("shadow_methods", &self.shadow_methods)
+            .field("shadow_buffer", &self.shadow_buffer)
+            .field("shadow_ignored_headers", &self.shadow_ignored_headers)
+            .field("on_shadow", &self.on_shadow.is_some())
+            .finish()
+    }
+}
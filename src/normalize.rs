@@ -0,0 +1,129 @@
+use axum::http::{
+    Uri,
+    uri::{Parts, PathAndQuery},
+};
+
+/// Path normalization applied before requests reach a [`WarpService`](crate::WarpService).
+///
+/// Axum and Warp disagree on how unusual paths such as `//users/./42` are matched, so they
+/// may reach different handlers depending on which side of the bridge serves them.
+/// Normalizing paths at the boundary makes the Warp filter, and the denylist and cache, see a
+/// canonical path. The query is left unchanged. Normalization is applied with
+/// [`WarpService::with_path_normalization`](crate::WarpService::with_path_normalization).
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::{PathNormalization, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path!("users" / u32).map(|id| format!("User {}", id)).boxed();
+///
+/// let normalization = PathNormalization::new()
+///     .merge_slashes()
+///     .resolve_dot_segments();
+///
+/// let service = WarpService::new(filter).with_path_normalization(normalization);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PathNormalization {
+    merge_slashes: bool,
+    resolve_dot_segments: bool,
+    trim_trailing_slash: bool,
+}
+
+impl PathNormalization {
+    /// Creates path normalization settings that leave paths unchanged.
+    pub fn new() -> Self {
+        PathNormalization::default()
+    }
+
+    /// Collapses repeated slashes, so `/a//b` becomes `/a/b`.
+    pub fn merge_slashes(mut self) -> Self {
+        self.merge_slashes = true;
+        self
+    }
+
+    /// Resolves `.` and `..` segments as described in RFC 3986, so `/a/./b/../c` becomes
+    /// `/a/c`.
+    pub fn resolve_dot_segments(mut self) -> Self {
+        self.resolve_dot_segments = true;
+        self
+    }
+
+    /// Removes a trailing slash, so `/a/b/` becomes `/a/b`. The root path is unchanged.
+    pub fn trim_trailing_slash(mut self) -> Self {
+        self.trim_trailing_slash = true;
+        self
+    }
+
+    /// Returns the normalized URI, or `None` if it is unchanged.
+    pub(crate) fn normalize(&self, uri: &Uri) -> Option<Uri> {
+        let path = self.normalize_path(uri.path());
+        if path == uri.path() {
+            return None;
+        }
+
+        let path_and_query = match uri.query() {
+            Some(query) => format!("{}?{}", path, query),
+            None => path,
+        };
+
+        let mut parts = Parts::from(uri.clone());
+        parts.path_and_query = Some(PathAndQuery::try_from(path_and_query).ok()?);
+        Uri::from_parts(parts).ok()
+    }
+
+    fn normalize_path(&self, path: &str) -> String {
+        let mut path = path.to_string();
+
+        if self.merge_slashes {
+            let mut merged = String::with_capacity(path.len());
+            for c in path.chars() {
+                if !(c == '/' && merged.ends_with('/')) {
+                    merged.push(c);
+                }
+            }
+            path = merged;
+        }
+
+        if self.resolve_dot_segments {
+            path = remove_dot_segments(&path);
+        }
+
+        if self.trim_trailing_slash {
+            while path.len() > 1 && path.ends_with('/') {
+                path.pop();
+            }
+        }
+
+        path
+    }
+}
+
+/// Removes dot segments from an absolute path, following RFC 3986 section 5.2.4.
+fn remove_dot_segments(path: &str) -> String {
+    let mut output: Vec<&str> = Vec::new();
+    let segments: Vec<&str> = path.split('/').skip(1).collect();
+
+    for (i, segment) in segments.iter().enumerate() {
+        let last = i == segments.len() - 1;
+
+        match *segment {
+            "." => {
+                if last {
+                    output.push("");
+                }
+            }
+            ".." => {
+                output.pop();
+                if last {
+                    output.push("");
+                }
+            }
+            segment => output.push(segment),
+        }
+    }
+
+    format!("/{}", output.join("/"))
+}
@@ -0,0 +1,257 @@
+//! OpenAPI documentation for legacy routes served through a [`WarpService`](crate::WarpService).
+//!
+//! Warp filters carry no route metadata, so the operations of legacy routes are described by
+//! hand and registered alongside the filters, with [`WarpRoutes::document`] or directly on an
+//! [`OpenApiPaths`]. The paths are then merged into the OpenAPI document the Axum side already
+//! serves, so the API documentation covers the legacy endpoints while they are migrated.
+//!
+//! Documents generated by utoipa are merged directly with `OpenApiPaths::merge_into_utoipa`,
+//! with the `utoipa` feature. Documents from any other OpenAPI library are merged once
+//! serialized with `serde_json::to_value`. Operations already in the document are kept, so an
+//! Axum handler that replaces a legacy route documents it as soon as it is mounted.
+//!
+//! Routes that have not been documented yet can be seeded with a skeleton built from the
+//! traffic they served, with [`UsageReport::to_openapi`](crate::UsageReport::to_openapi).
+//!
+//! This module is available with the `openapi` feature.
+//!
+//! [`WarpRoutes::document`]: crate::WarpRoutes::document
+//!
+//! # Example
+//!
+//! ```rust
+//! use axum::http::Method;
+//! use serde_json::json;
+//! use warpdrive::WarpRoutes;
+//! use warp::Filter;
+//!
+//! let routes = WarpRoutes::new()
+//!     .route(warp::path!("users" / u32).map(|id| format!("User {}", id)))
+//!     .document(
+//!         Method::GET,
+//!         "/users/{id}",
+//!         json!({
+//!             "summary": "Get a user",
+//!             "responses": { "200": { "description": "The user" } },
+//!         }),
+//!     );
+//!
+//! // The document served for the Axum routes, such as `serde_json::to_value(ApiDoc::openapi())`.
+//! let mut doc = json!({
+//!     "openapi": "3.1.0",
+//!     "info": { "title": "API", "version": "1.0.0" },
+//!     "paths": { "/health": { "get": { "responses": { "200": { "description": "OK" } } } } },
+//! });
+//! routes.openapi().merge_into(&mut doc);
+//!
+//! assert!(doc["paths"]["/users/{id}"]["get"].is_object());
+//! let service = routes.into_service();
+//! ```
+
+use std::collections::BTreeMap;
+
+use axum::http::{Method, StatusCode};
+use serde_json::{Map, Value, json};
+
+use crate::usage::RouteUsage;
+
+/// OpenAPI operations for legacy routes, keyed by path template and method.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OpenApiPaths {
+    paths: BTreeMap<String, BTreeMap<String, Value>>,
+}
+
+impl OpenApiPaths {
+    /// Creates an empty set of paths.
+    pub fn new() -> Self {
+        OpenApiPaths::default()
+    }
+
+    /// Adds an OpenAPI operation object for a method and a path template, such as
+    /// `/users/{id}`, replacing any operation already added for them.
+    pub fn operation(mut self, method: Method, path: &str, operation: Value) -> Self {
+        self.insert(method, path, operation);
+        self
+    }
+
+    /// Adds the operations of other paths, replacing any operations already added for the
+    /// same method and path.
+    pub fn extend(mut self, other: OpenApiPaths) -> Self {
+        for (path, operations) in other.paths {
+            self.paths.entry(path).or_default().extend(operations);
+        }
+        self
+    }
+
+    /// Returns the number of operations.
+    pub fn len(&self) -> usize {
+        self.paths.values().map(BTreeMap::len).sum()
+    }
+
+    /// Returns `true` if no operations have been added.
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Returns the operations as an OpenAPI paths object.
+    pub fn to_json(&self) -> Value {
+        let paths = self
+            .paths
+            .iter()
+            .map(|(path, operations)| {
+                let operations: Map<_, _> = operations
+                    .iter()
+                    .map(|(method, operation)| (method.clone(), operation.clone()))
+                    .collect();
+                (path.clone(), Value::Object(operations))
+            })
+            .collect();
+
+        Value::Object(paths)
+    }
+
+    /// Merges the operations into the `paths` of an OpenAPI document, creating it if needed.
+    ///
+    /// Operations already in the document are kept.
+    pub fn merge_into(&self, doc: &mut Value) {
+        let Some(doc) = doc.as_object_mut() else {
+            return;
+        };
+        let paths = doc
+            .entry("paths")
+            .or_insert_with(|| Value::Object(Map::new()));
+        let Some(paths) = paths.as_object_mut() else {
+            return;
+        };
+
+        for (path, operations) in &self.paths {
+            let item = paths
+                .entry(path.clone())
+                .or_insert_with(|| Value::Object(Map::new()));
+            let Some(item) = item.as_object_mut() else {
+                continue;
+            };
+            for (method, operation) in operations {
+                item.entry(method.clone())
+                    .or_insert_with(|| operation.clone());
+            }
+        }
+    }
+
+    /// Converts the operations into utoipa paths.
+    ///
+    /// Fails if an operation is not a valid OpenAPI operation object.
+    #[cfg(any(test, feature = "utoipa"))]
+    pub fn to_utoipa(&self) -> Result<utoipa::openapi::Paths, serde_json::Error> {
+        serde_json::from_value(self.to_json())
+    }
+
+    /// Merges the operations into the paths of a utoipa OpenAPI document.
+    ///
+    /// Operations already in the document are kept. Nothing is merged if an operation is not
+    /// a valid OpenAPI operation object.
+    #[cfg(any(test, feature = "utoipa"))]
+    pub fn merge_into_utoipa(
+        &self,
+        doc: &mut utoipa::openapi::OpenApi,
+    ) -> Result<(), serde_json::Error> {
+        doc.paths.merge(self.to_utoipa()?);
+        Ok(())
+    }
+
+    /// Builds a skeleton operation for each route observed by a
+    /// [`UsageReport`](crate::UsageReport), as a starting point for documenting the Axum
+    /// handlers that replace them.
+    ///
+    /// Routes are parsed as `METHOD /path`, and routes that cannot be parsed, such as the
+    /// `(other)` route, are skipped. Each operation has:
+    ///
+    /// - A required string parameter for each `{name}` segment of the path.
+    /// - A response for each status observed, with the content types observed on the
+    ///   successful ones.
+    /// - The route's owner as its tag, if one was assigned.
+    /// - The request counts as an `x-warpdrive-usage` extension.
+    pub fn from_usage(routes: &[RouteUsage]) -> Self {
+        let mut paths = OpenApiPaths::new();
+
+        for usage in routes {
+            let Some((method, path)) = usage.route.split_once(' ') else {
+                continue;
+            };
+            let Ok(method) = Method::from_bytes(method.as_bytes()) else {
+                continue;
+            };
+            if !path.starts_with('/') {
+                continue;
+            }
+
+            paths.insert(method, path, skeleton(usage, path));
+        }
+
+        paths
+    }
+
+    pub(crate) fn insert(&mut self, method: Method, path: &str, operation: Value) {
+        self.paths
+            .entry(path.to_string())
+            .or_default()
+            .insert(method.as_str().to_ascii_lowercase(), operation);
+    }
+}
+
+/// Builds the skeleton operation for a route.
+fn skeleton(usage: &RouteUsage, path: &str) -> Value {
+    let parameters: Vec<_> = path
+        .split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .map(|name| {
+            json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string" },
+            })
+        })
+        .collect();
+
+    let mut responses = Map::new();
+    for status in usage.statuses.keys() {
+        let description = StatusCode::from_u16(*status)
+            .ok()
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or("Observed response");
+        let mut response = json!({ "description": description });
+        if (200..300).contains(status) && !usage.content_types.is_empty() {
+            let content: Map<_, _> = usage
+                .content_types
+                .iter()
+                .map(|content_type| (content_type.clone(), json!({})))
+                .collect();
+            response["content"] = Value::Object(content);
+        }
+        responses.insert(status.to_string(), response);
+    }
+    if responses.is_empty() {
+        responses.insert(
+            "default".to_string(),
+            json!({ "description": "No responses observed" }),
+        );
+    }
+
+    let mut operation = json!({
+        "summary": usage.route,
+        "responses": responses,
+        "x-warpdrive-usage": {
+            "hits": usage.hits,
+            "errors": usage.errors,
+            "on_warp": usage.on_warp,
+        },
+    });
+    if !parameters.is_empty() {
+        operation["parameters"] = Value::Array(parameters);
+    }
+    if let Some(owner) = &usage.owner {
+        operation["tags"] = json!([owner.team]);
+    }
+    operation
+}
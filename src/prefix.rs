@@ -0,0 +1,105 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::problem::ProblemDetails;
+
+/// Boundary settings that override those of a [`WarpService`](crate::WarpService) for
+/// requests under a path prefix.
+///
+/// Settings that are not set keep the service's value. When several prefixes match a
+/// request, the longest one applies. Prefixes match whole segments: `/admin` matches
+/// `/admin` and `/admin/users`, but not `/administrator`. The overrides are applied with
+/// [`WarpService::with_prefix_config`](crate::WarpService::with_prefix_config).
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use warpdrive::{PrefixConfig, ProblemDetails, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("api").map(|| "Hello").boxed();
+///
+/// // Admin routes run long reports and accept large uploads.
+/// let service = WarpService::new(filter)
+///     .with_timeout(Duration::from_secs(5))
+///     .with_body_limit(64 * 1024)
+///     .with_prefix_config(
+///         "/admin",
+///         PrefixConfig::new()
+///             .timeout(Duration::from_secs(60))
+///             .body_limit(16 * 1024 * 1024),
+///     )
+///     .with_prefix_config("/api", PrefixConfig::new().problem_details(ProblemDetails::new()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PrefixConfig {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) body_limit: Option<usize>,
+    pub(crate) problem_details: Option<Arc<ProblemDetails>>,
+    pub(crate) access_log: Option<bool>,
+    pub(crate) blocking: Option<bool>,
+}
+
+impl PrefixConfig {
+    /// Creates overrides that keep every setting of the service.
+    pub fn new() -> Self {
+        PrefixConfig::default()
+    }
+
+    /// Overrides the timeout set with
+    /// [`WarpService::with_timeout`](crate::WarpService::with_timeout).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Overrides the body limit set with
+    /// [`WarpService::with_body_limit`](crate::WarpService::with_body_limit).
+    pub fn body_limit(mut self, limit: usize) -> Self {
+        self.body_limit = Some(limit);
+        self
+    }
+
+    /// Renders boundary errors as problem details, as with
+    /// [`WarpService::with_problem_details`](crate::WarpService::with_problem_details).
+    pub fn problem_details(mut self, problem_details: ProblemDetails) -> Self {
+        self.problem_details = Some(Arc::new(problem_details));
+        self
+    }
+
+    /// Enables or disables the access log set with
+    /// [`WarpService::with_access_log`](crate::WarpService::with_access_log), such as to
+    /// silence health checks.
+    pub fn access_log(mut self, enabled: bool) -> Self {
+        self.access_log = Some(enabled);
+        self
+    }
+
+    /// Enables or disables running the Warp filter on the blocking thread pool, as with
+    /// [`WarpService::with_blocking`](crate::WarpService::with_blocking).
+    pub fn blocking(mut self, enabled: bool) -> Self {
+        self.blocking = Some(enabled);
+        self
+    }
+}
+
+/// Removes trailing slashes from a path prefix, so that `/admin/` registers the same prefix as
+/// `/admin`. The root prefix `/` is kept.
+pub(crate) fn trim_prefix(prefix: &str) -> String {
+    let mut prefix = prefix.to_string();
+    while prefix.len() > 1 && prefix.ends_with('/') {
+        prefix.pop();
+    }
+    prefix
+}
+
+/// Returns `true` if the path is equal to the prefix or below it.
+///
+/// The prefix must have been trimmed with [`trim_prefix`].
+pub(crate) fn matches_prefix(prefix: &str, path: &str) -> bool {
+    prefix == "/"
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
@@ -0,0 +1,230 @@
+use std::{
+    convert::Infallible,
+    fmt,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::Request,
+    http::{HeaderName, HeaderValue, Method, StatusCode},
+    response::Response,
+};
+use tower::{Service, ServiceExt};
+
+/// The largest response body read by a probe.
+const MAX_PROBE_BODY: usize = 1024 * 1024;
+
+/// A synthetic request sent through a [`WarpService`](crate::WarpService) at startup by
+/// [`WarpService::probe`](crate::WarpService::probe), with the response it should get.
+///
+/// Probes expect a `200 OK` by default.
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::{Probe, WarpService};
+/// use warp::Filter;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let filter = warp::path("health").map(|| "ok").boxed();
+/// let service = WarpService::new(filter);
+///
+/// let report = service
+///     .probe([
+///         Probe::get("/health").expect_body_contains("ok"),
+///         Probe::get("/missing").expect_status(404),
+///     ])
+///     .await;
+///
+/// // Fail fast if the legacy routes are broken after an upgrade.
+/// assert!(report.passed(), "{}", report);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Probe {
+    method: Method,
+    uri: String,
+    headers: Vec<(HeaderName, HeaderValue)>,
+    body: Bytes,
+    status: StatusCode,
+    body_contains: Option<String>,
+}
+
+/// The outcome of a single [`Probe`].
+#[derive(Debug, Clone)]
+pub struct ProbeResult {
+    /// The method of the probe request.
+    pub method: Method,
+    /// The URI of the probe request.
+    pub uri: String,
+    /// The response status, or `None` if the service failed.
+    pub status: Option<StatusCode>,
+    /// How long the request took, including reading the response body.
+    pub duration: Duration,
+    /// Why the probe failed, or `None` if it passed.
+    pub failure: Option<String>,
+}
+
+/// The outcomes of a set of [`Probe`]s, in the order they were sent.
+#[derive(Debug, Clone)]
+pub struct ProbeReport {
+    /// The outcome of each probe.
+    pub results: Vec<ProbeResult>,
+}
+
+impl Probe {
+    /// Creates a probe with the given method and URI, such as `/health?verbose=true`.
+    pub fn new(method: Method, uri: impl Into<String>) -> Self {
+        Probe {
+            method,
+            uri: uri.into(),
+            headers: Vec::new(),
+            body: Bytes::new(),
+            status: StatusCode::OK,
+            body_contains: None,
+        }
+    }
+
+    /// Creates a `GET` probe.
+    pub fn get(uri: impl Into<String>) -> Self {
+        Probe::new(Method::GET, uri)
+    }
+
+    /// Adds a request header.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` or `value` is not a valid header name or value.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((
+            HeaderName::try_from(name).expect("invalid header name"),
+            HeaderValue::try_from(value).expect("invalid header value"),
+        ));
+        self
+    }
+
+    /// Sets the request body.
+    pub fn body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Sets the expected response status. Defaults to `200 OK`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `status` is not a valid status code.
+    pub fn expect_status(mut self, status: u16) -> Self {
+        self.status = StatusCode::from_u16(status).expect("invalid status code");
+        self
+    }
+
+    /// Expects the response body to contain the given text.
+    pub fn expect_body_contains(mut self, text: impl Into<String>) -> Self {
+        self.body_contains = Some(text.into());
+        self
+    }
+
+    /// Sends the probe through a service and checks the response.
+    pub(crate) async fn run<S>(&self, service: S) -> ProbeResult
+    where
+        S: Service<Request, Response = Response, Error = Infallible>,
+    {
+        let started = Instant::now();
+        let (status, failure) = match self.send(service).await {
+            Ok((status, failure)) => (Some(status), failure),
+            Err(failure) => (None, Some(failure)),
+        };
+
+        ProbeResult {
+            method: self.method.clone(),
+            uri: self.uri.clone(),
+            status,
+            duration: started.elapsed(),
+            failure,
+        }
+    }
+
+    async fn send<S>(&self, service: S) -> Result<(StatusCode, Option<String>), String>
+    where
+        S: Service<Request, Response = Response, Error = Infallible>,
+    {
+        let mut builder = Request::builder()
+            .method(self.method.clone())
+            .uri(self.uri.as_str());
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        let req = builder
+            .body(Body::from(self.body.clone()))
+            .map_err(|e| format!("Invalid probe request: {}", e))?;
+
+        let response = match service.oneshot(req).await {
+            Ok(response) => response,
+            Err(never) => match never {},
+        };
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), MAX_PROBE_BODY)
+            .await
+            .map_err(|e| format!("Failed to read response body: {}", e))?;
+
+        if status != self.status {
+            return Ok((
+                status,
+                Some(format!("Expected status {}, got {}", self.status, status)),
+            ));
+        }
+
+        let failure = match &self.body_contains {
+            Some(text) if !String::from_utf8_lossy(&body).contains(text.as_str()) => {
+                Some(format!("Expected body to contain {:?}", text))
+            }
+            _ => None,
+        };
+        Ok((status, failure))
+    }
+}
+
+impl ProbeResult {
+    /// Returns `true` if the probe got the expected response.
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+impl ProbeReport {
+    /// Returns `true` if every probe passed.
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(ProbeResult::passed)
+    }
+
+    /// Returns the probes that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &ProbeResult> {
+        self.results.iter().filter(|result| !result.passed())
+    }
+}
+
+impl fmt::Display for ProbeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let failed = self.failures().count();
+        write!(
+            f,
+            "{} of {} probes passed",
+            self.results.len() - failed,
+            self.results.len()
+        )?;
+
+        for result in self.failures() {
+            write!(
+                f,
+                "\n{} {}: {}",
+                result.method,
+                result.uri,
+                result.failure.as_deref().unwrap_or_default()
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use axum::{
+    body::Body,
+    http::{HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use http_body_util::LengthLimitError;
+
+use crate::error::Error;
+
+/// The kinds of boundary error rendered as problem details, in the order they are documented.
+const KINDS: [&str; 11] = [
+    "conversion",
+    "timeout",
+    "internal",
+    "payload-too-large",
+    "rate-limited",
+    "shutting-down",
+    "upstream",
+    "circuit-open",
+    "headers-too-large",
+    "uri-too-long",
+    "invalid-framing",
+];
+
+/// Renders errors from a [`WarpService`](crate::WarpService) as RFC 9457
+/// `application/problem+json` documents.
+///
+/// Only errors generated at the boundary are affected; responses from the Warp filter,
+/// including rejections, are passed through unchanged. Each error is identified by a kind,
+/// which selects its `type` URI:
+///
+/// - `conversion`: the request or response could not be converted (`500`).
+/// - `timeout`: the request timed out or its [`Deadline`](crate::Deadline) passed (`504`).
+/// - `internal`: a layer or wrapped service failed (`500`).
+/// - `payload-too-large`: the request body exceeded the
+///   [body limit](crate::WarpService::with_body_limit), or a layer such as
+///   `RequestBodyLimitLayer` rejected it (`413`).
+/// - `rate-limited`: a [`RateLimit`](crate::RateLimit) was exceeded (`429`).
+/// - `shutting-down`: a [`Shutdown`](crate::Shutdown) was triggered (`503`).
+/// - `upstream`: the request could not be forwarded to an upstream server (`502`).
+/// - `circuit-open`: the route's [`CircuitBreaker`](crate::CircuitBreaker) is open (`503`).
+/// - `headers-too-large`: the request headers exceeded the
+///   [request limits](crate::RequestLimits) (`431`).
+/// - `uri-too-long`: the request URI exceeded the [request limits](crate::RequestLimits)
+///   (`414`).
+/// - `invalid-framing`: the request had ambiguous body framing, such as both
+///   `Content-Length` and `Transfer-Encoding` (`400`).
+///
+/// Without configuration, the `type` is `about:blank`. The problem details are applied with
+/// [`WarpService::with_problem_details`](crate::WarpService::with_problem_details).
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::{ProblemDetails, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("api").map(|| "Hello").boxed();
+///
+/// // Renders timeouts with `"type": "https://errors.example.com/timeout"`, and uses a
+/// // dedicated page for rate limiting.
+/// let problems = ProblemDetails::new()
+///     .base_uri("https://errors.example.com/")
+///     .type_uri("rate-limited", "https://example.com/docs/rate-limits");
+///
+/// let service = WarpService::new(filter).with_problem_details(problems);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProblemDetails {
+    base_uri: Option<String>,
+    type_uris: HashMap<&'static str, String>,
+}
+
+impl ProblemDetails {
+    /// Creates problem details with every `type` set to `about:blank`.
+    pub fn new() -> Self {
+        ProblemDetails::default()
+    }
+
+    /// Sets the `type` of each error to the base URI followed by its kind, such as
+    /// `https://errors.example.com/timeout`.
+    pub fn base_uri(mut self, uri: impl Into<String>) -> Self {
+        self.base_uri = Some(uri.into());
+        self
+    }
+
+    /// Sets the `type` for one kind of error, taking precedence over the base URI.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `kind` is not one of the kinds listed on [`ProblemDetails`].
+    pub fn type_uri(mut self, kind: &str, uri: impl Into<String>) -> Self {
+        let kind = KINDS
+            .into_iter()
+            .find(|known| *known == kind)
+            .expect("unknown problem details kind");
+        self.type_uris.insert(kind, uri.into());
+        self
+    }
+
+    /// Converts the error into a problem details response.
+    pub(crate) fn render(&self, err: Error) -> Response {
+        let (kind, status) = match &err {
+            Error::Conversion(_) => ("conversion", err.status()),
+            Error::Timeout(_) => ("timeout", err.status()),
+            Error::Layer(source) | Error::Service(source) if is_length_limit(source.as_ref()) => {
+                ("payload-too-large", StatusCode::PAYLOAD_TOO_LARGE)
+            }
+            Error::PayloadTooLarge(_) => ("payload-too-large", err.status()),
+            Error::RateLimited(_) => ("rate-limited", err.status()),
+            Error::ShuttingDown => ("shutting-down", err.status()),
+            Error::Upstream(_) => ("upstream", err.status()),
+            Error::CircuitOpen => ("circuit-open", err.status()),
+            Error::TooManyHeaders(_) | Error::HeadersTooLarge(_) => {
+                ("headers-too-large", err.status())
+            }
+            Error::UriTooLong(_) => ("uri-too-long", err.status()),
+            Error::InvalidFraming(_) => ("invalid-framing", err.status()),
+            _ => ("internal", err.status()),
+        };
+
+        let type_uri = match (self.type_uris.get(kind), &self.base_uri) {
+            (Some(uri), _) => uri.clone(),
+            (None, Some(base_uri)) => format!("{}{}", base_uri, kind),
+            (None, None) => "about:blank".to_string(),
+        };
+
+        let document = serde_json::json!({
+            "type": type_uri,
+            "title": status.canonical_reason().unwrap_or("Error"),
+            "status": status.as_u16(),
+            "detail": err.to_string(),
+        });
+
+        // Keeps headers such as `Retry-After` from the plain text response.
+        let (mut parts, _) = err.into_response().into_parts();
+        parts.status = status;
+        parts.headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/problem+json"),
+        );
+        parts.headers.remove(header::CONTENT_LENGTH);
+
+        Response::from_parts(parts, Body::from(document.to_string()))
+    }
+}
+
+fn is_length_limit(err: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = Some(err);
+    while let Some(err) = source {
+        if err.is::<LengthLimitError>() {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
@@ -0,0 +1,66 @@
+use std::convert::Infallible;
+
+use axum::{extract::OriginalUri, extract::Request};
+use warp::Filter;
+
+use crate::convert_request::{CarriedExtensions, raw_query};
+
+/// The query of a request as it reached the service, recorded by
+/// [`WarpService::with_original_query`](crate::WarpService::with_original_query).
+#[derive(Debug, Clone)]
+pub(crate) struct OriginalQuery(Option<String>);
+
+impl OriginalQuery {
+    /// Records the query of a request, as sent by the client if Axum recorded the original
+    /// URI.
+    pub(crate) fn record(req: &mut Request) {
+        let uri = match req.extensions().get::<OriginalUri>() {
+            Some(OriginalUri(uri)) => uri,
+            None => req.uri(),
+        };
+        let query = OriginalQuery(uri.query().map(str::to_string));
+        req.extensions_mut().insert(query);
+    }
+}
+
+/// A Warp filter that extracts the raw query of the request, exactly as the client sent it,
+/// for filters that parse the query themselves.
+///
+/// Queries pass through the conversion byte for byte, including plus signs, percent-encoded
+/// bytes, brackets, repeated keys, and empty values, so this is the same as the query Warp
+/// sees unless the request was rewritten before reaching the filter, such as by an Axum
+/// middleware or [`WarpService::with_map_request`](crate::WarpService::with_map_request).
+/// When the service is built with
+/// [`WarpService::with_original_query`](crate::WarpService::with_original_query), the query
+/// recorded as the request reached the service, or before Axum routing if Axum recorded the
+/// original URI, is extracted instead.
+///
+/// Unlike `warp::query::raw`, the filter never rejects: a request without a query extracts
+/// `None`, and a request with an empty query, such as `/search?`, extracts `Some("")`.
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::{WarpService, original_query};
+/// use warp::Filter;
+///
+/// let filter = warp::path("search")
+///     .and(original_query())
+///     .map(|query: Option<String>| format!("Query: {}", query.unwrap_or_default()))
+///     .boxed();
+///
+/// let service = WarpService::new(filter).with_original_query();
+/// ```
+pub fn original_query() -> impl Filter<Extract = (Option<String>,), Error = Infallible> + Clone {
+    warp::ext::optional::<CarriedExtensions>()
+        .and(raw_query())
+        .map(|extensions: Option<CarriedExtensions>, query| {
+            match extensions
+                .as_ref()
+                .and_then(CarriedExtensions::get::<OriginalQuery>)
+            {
+                Some(OriginalQuery(original)) => original.clone(),
+                None => query,
+            }
+        })
+}
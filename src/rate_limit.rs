@@ -0,0 +1,165 @@
+use std::{
+    fmt,
+    net::SocketAddr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use axum::extract::{ConnectInfo, Request};
+
+use crate::bounded::{BoundedMap, DEFAULT_MAX_KEYS};
+
+type KeyFn = Box<dyn Fn(&Request) -> Option<String> + Send + Sync>;
+
+/// A per-key rate limiter for a [`WarpService`](crate::WarpService).
+///
+/// Each key, such as a client IP address or API key, may make `requests` requests per
+/// `period`, refilled continuously, with bursts of up to `requests` requests. Requests over
+/// the limit are rejected with [`Error::RateLimited`](crate::Error::RateLimited), which is
+/// converted into a `429 Too Many Requests` response with a `Retry-After` header. Requests
+/// for which the key function returns `None` are not limited.
+///
+/// At most [`max_keys`](RateLimit::max_keys) keys are tracked. Once that many are tracked,
+/// keys whose bucket has refilled are forgotten, and new keys that still do not fit share a
+/// single bucket, so a flood of unique keys cannot grow the limiter without bound.
+///
+/// The rate limiter is applied with
+/// [`WarpService::with_rate_limit`](crate::WarpService::with_rate_limit), before the request
+/// reaches the Warp filter.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use warpdrive::{RateLimit, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("api").map(|| "Hello").boxed();
+///
+/// // 100 requests per minute for each API key.
+/// let limit = RateLimit::per_header("x-api-key", 100, Duration::from_secs(60));
+///
+/// let service = WarpService::new(filter).with_rate_limit(limit);
+/// ```
+pub struct RateLimit {
+    key: KeyFn,
+    capacity: f64,
+    refill_per_sec: f64,
+    buckets: Mutex<BoundedMap<Bucket>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimit {
+    /// Creates a rate limiter keyed by the given function.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests` is zero or `period` is zero.
+    pub fn new<F>(key: F, requests: u32, period: Duration) -> Self
+    where
+        F: Fn(&Request) -> Option<String> + Send + Sync + 'static,
+    {
+        assert!(requests > 0, "rate limit must allow at least one request");
+        assert!(!period.is_zero(), "rate limit period must be non-zero");
+
+        RateLimit {
+            key: Box::new(key),
+            capacity: requests as f64,
+            refill_per_sec: requests as f64 / period.as_secs_f64(),
+            // Buckets refill completely within a period, so scanning more often finds nothing.
+            buckets: Mutex::new(BoundedMap::new(DEFAULT_MAX_KEYS, period)),
+        }
+    }
+
+    /// Sets the largest number of keys tracked, after which new keys share one bucket.
+    /// Defaults to 10,000.
+    pub fn max_keys(mut self, max_keys: usize) -> Self {
+        self.buckets.get_mut().unwrap().set_max_keys(max_keys);
+        self
+    }
+
+    /// Creates a rate limiter keyed by the value of a request header, such as an API key.
+    ///
+    /// Requests without the header are not limited.
+    pub fn per_header(name: &'static str, requests: u32, period: Duration) -> Self {
+        RateLimit::new(
+            move |req| {
+                req.headers()
+                    .get(name)
+                    .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+            },
+            requests,
+            period,
+        )
+    }
+
+    /// Creates a rate limiter keyed by the client IP address.
+    ///
+    /// The address is read from Axum's `ConnectInfo<SocketAddr>`, so the router must be
+    /// served with `into_make_service_with_connect_info::<SocketAddr>()`. Requests without
+    /// connection info are not limited.
+    pub fn per_ip(requests: u32, period: Duration) -> Self {
+        RateLimit::new(
+            |req| {
+                req.extensions()
+                    .get::<ConnectInfo<SocketAddr>>()
+                    .map(|ConnectInfo(addr)| addr.ip().to_string())
+            },
+            requests,
+            period,
+        )
+    }
+
+    /// Takes a token for the request's key, returning how long to wait if none are left.
+    pub(crate) fn check(&self, req: &Request) -> Result<(), Duration> {
+        let Some(key) = (self.key)(req) else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+
+        // Buckets that would have refilled completely are equivalent to new ones.
+        let key = buckets.admit(key, now, |_, bucket| {
+            self.refilled(bucket, now) >= self.capacity
+        });
+        let bucket = buckets.entry(key).or_insert(Bucket {
+            tokens: self.capacity,
+            updated: now,
+        });
+
+        bucket.tokens = self.refilled(bucket, now);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let wait = (1.0 - bucket.tokens) / self.refill_per_sec;
+            Err(Duration::from_secs_f64(wait))
+        }
+    }
+
+    fn refilled(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity)
+    }
+}
+
+impl fmt::Debug for RateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let buckets = self.buckets.lock().unwrap();
+        f.debug_struct("RateLimit")
+            .field("capacity", &self.capacity)
+            .field("refill_per_sec", &self.refill_per_sec)
+            .field("keys", &buckets.len())
+            .field("max_keys", &buckets.max_keys())
+            .finish()
+    }
+}
@@ -0,0 +1,322 @@
+use std::{
+    convert::Infallible,
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use axum::{
+    extract::Request,
+    response::{IntoResponse, Response},
+};
+use futures::{Future, future::BoxFuture};
+use tower::{BoxError, Service, ServiceExt};
+use warp::{
+    http::{
+        HeaderMap as WarpHeaderMap, Uri, Version,
+        header::{CONNECTION, HeaderName},
+        uri::{Authority, PathAndQuery, Scheme},
+    },
+    hyper::{Body as WarpBody, Client, client::HttpConnector},
+};
+
+use crate::{
+    convert_request::into_warp_request, convert_response::into_axum_response, error::Error,
+};
+
+/// Headers that apply to a single connection, and are not forwarded.
+const HOP_BY_HOP_HEADERS: [&str; 8] = [
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// A Tower service that forwards requests to a Warp server running in another process.
+///
+/// This allows the same fallback pattern as [`WarpService`](crate::WarpService) when the
+/// legacy routes cannot be linked into the new binary. Requests are forwarded over HTTP/1.1
+/// with their method, path, query, and headers, including `Host`, and request and response
+/// bodies are streamed in both directions. Hop-by-hop headers such as `Connection` are not
+/// forwarded.
+///
+/// Connections are pooled and reused across requests. Bodies are streamed as the receiving
+/// side reads them, so a slow client or upstream applies backpressure rather than being
+/// buffered in memory. Failed connection attempts are retried, twice by default, before any
+/// of the request is sent, so retries are safe for every method.
+///
+/// Requests that cannot be forwarded, such as when the upstream server is unreachable, are
+/// answered with `502 Bad Gateway`, and requests that time out with `504 Gateway Timeout`.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::{Router, routing::get};
+/// use warpdrive::remote::RemoteWarpService;
+///
+/// let app: Router = Router::new()
+///     .route("/", get(|| async { "Hello from Axum!" }))
+///     .fallback_service(RemoteWarpService::new("http://127.0.0.1:3030"));
+/// ```
+///
+/// The connection pool and timeouts can be configured:
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use warpdrive::remote::RemoteWarpService;
+///
+/// let service = RemoteWarpService::new("http://127.0.0.1:3030")
+///     .with_timeout(Duration::from_secs(30))
+///     .with_connect_timeout(Duration::from_secs(1))
+///     .with_connect_retries(3)
+///     .with_pool_max_idle_per_host(32)
+///     .with_pool_idle_timeout(Duration::from_secs(60));
+/// ```
+#[derive(Clone)]
+pub struct RemoteWarpService {
+    client: Client<RetryConnector, WarpBody>,
+    config: Config,
+    scheme: Scheme,
+    authority: Authority,
+}
+
+#[derive(Debug, Clone)]
+struct Config {
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    connect_retries: u32,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            timeout: None,
+            connect_timeout: Some(Duration::from_secs(5)),
+            connect_retries: 2,
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            pool_max_idle_per_host: usize::MAX,
+        }
+    }
+}
+
+impl Config {
+    fn client(&self) -> Client<RetryConnector, WarpBody> {
+        let mut http = HttpConnector::new();
+        http.set_connect_timeout(self.connect_timeout);
+        http.set_nodelay(true);
+
+        Client::builder()
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .retry_canceled_requests(true)
+            .build(RetryConnector {
+                inner: http,
+                retries: self.connect_retries,
+            })
+    }
+}
+
+impl RemoteWarpService {
+    /// Creates a service that forwards requests to the given upstream server, such as
+    /// `http://127.0.0.1:3030`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `upstream` is not an absolute `http` URI. The path of the URI is ignored.
+    pub fn new(upstream: &str) -> Self {
+        let uri = Uri::try_from(upstream).expect("invalid upstream URI");
+        let scheme = uri
+            .scheme()
+            .cloned()
+            .expect("upstream URI must have a scheme");
+        assert!(scheme == Scheme::HTTP, "upstream URI must use http");
+
+        let config = Config::default();
+
+        RemoteWarpService {
+            client: config.client(),
+            config,
+            scheme,
+            authority: uri
+                .authority()
+                .cloned()
+                .expect("upstream URI must have an authority"),
+        }
+    }
+
+    /// Sets a timeout for receiving the response head from the upstream server, including
+    /// connecting. The timeout does not apply to streaming the response body. There is no
+    /// timeout by default.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets a timeout for each connection attempt. Defaults to 5 seconds.
+    pub fn with_connect_timeout(self, timeout: Duration) -> Self {
+        self.configure(|config| config.connect_timeout = Some(timeout))
+    }
+
+    /// Sets how many times a failed connection attempt is retried, with an exponential
+    /// backoff starting at 50 milliseconds. Defaults to 2.
+    pub fn with_connect_retries(self, retries: u32) -> Self {
+        self.configure(|config| config.connect_retries = retries)
+    }
+
+    /// Sets how long idle pooled connections are kept open. Defaults to 90 seconds.
+    pub fn with_pool_idle_timeout(self, timeout: Duration) -> Self {
+        self.configure(|config| config.pool_idle_timeout = Some(timeout))
+    }
+
+    /// Sets the largest number of idle connections kept open to the upstream server.
+    /// Defaults to no limit.
+    pub fn with_pool_max_idle_per_host(self, max_idle: usize) -> Self {
+        self.configure(|config| config.pool_max_idle_per_host = max_idle)
+    }
+
+    /// Updates the client settings, creating a new connection pool.
+    fn configure(mut self, update: impl FnOnce(&mut Config)) -> Self {
+        update(&mut self.config);
+        self.client = self.config.client();
+        self
+    }
+
+    async fn forward(
+        client: Client<RetryConnector, WarpBody>,
+        scheme: Scheme,
+        authority: Authority,
+        req: Request,
+    ) -> Result<Response, Error> {
+        let mut req = into_warp_request(req).await?;
+
+        let path_and_query = req
+            .uri()
+            .path_and_query()
+            .cloned()
+            .unwrap_or_else(|| PathAndQuery::from_static("/"));
+        *req.uri_mut() = Uri::builder()
+            .scheme(scheme)
+            .authority(authority)
+            .path_and_query(path_and_query)
+            .build()
+            .map_err(|e| Error::Conversion(format!("Invalid upstream URI: {}", e)))?;
+        *req.version_mut() = Version::HTTP_11;
+        remove_hop_by_hop_headers(req.headers_mut());
+
+        let mut response = client
+            .request(req)
+            .await
+            .map_err(|err| Error::Upstream(err.into()))?;
+        remove_hop_by_hop_headers(response.headers_mut());
+
+        Ok(into_axum_response(response)?)
+    }
+}
+
+/// Connects to the upstream server, retrying failed attempts.
+///
+/// Retrying at the connector means no part of the request has been sent, so any request can
+/// be retried.
+#[derive(Clone)]
+struct RetryConnector {
+    inner: HttpConnector,
+    retries: u32,
+}
+
+impl Service<Uri> for RetryConnector {
+    type Response = <HttpConnector as Service<Uri>>::Response;
+    type Error = BoxError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let connector = self.inner.clone();
+        let retries = self.retries;
+
+        Box::pin(async move {
+            let mut backoff = Duration::from_millis(50);
+            let mut attempt = 0;
+
+            loop {
+                match connector.clone().oneshot(uri.clone()).await {
+                    Ok(stream) => return Ok(stream),
+                    Err(err) if attempt >= retries => return Err(err.into()),
+                    Err(_) => {
+                        tokio::time::sleep(backoff).await;
+                        backoff *= 2;
+                        attempt += 1;
+                    }
+                }
+            }
+        })
+    }
+}
+
+fn remove_hop_by_hop_headers(headers: &mut WarpHeaderMap) {
+    // Headers named in `Connection` are also specific to the connection.
+    let named: Vec<HeaderName> = headers
+        .get_all(CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|name| HeaderName::try_from(name.trim()).ok())
+        .collect();
+
+    for name in named {
+        headers.remove(name);
+    }
+    for name in HOP_BY_HOP_HEADERS {
+        headers.remove(name);
+    }
+}
+
+impl Service<Request> for RemoteWarpService {
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let future = Self::forward(
+            self.client.clone(),
+            self.scheme.clone(),
+            self.authority.clone(),
+            req,
+        );
+        let timeout = self.config.timeout;
+
+        Box::pin(async move {
+            let response = match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, future)
+                    .await
+                    .unwrap_or(Err(Error::Timeout(timeout))),
+                None => future.await,
+            };
+
+            Ok(response.unwrap_or_else(IntoResponse::into_response))
+        })
+    }
+}
+
+impl fmt::Debug for RemoteWarpService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteWarpService")
+            .field("upstream", &format!("{}://{}", self.scheme, self.authority))
+            .field("config", &self.config)
+            .finish()
+    }
+}
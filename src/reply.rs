@@ -0,0 +1,202 @@
+use axum::{
+    body::{Body as AxumBody, Bytes},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header::CONTENT_TYPE},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use warp::hyper::body::Body as WarpBody;
+
+use crate::{
+    compat_body::to_warp_headers,
+    convert_response::{into_axum_response, into_warp_response},
+    warp_service::create_conversion_error_response,
+};
+
+/// A reply that implements both [`warp::Reply`] and Axum's [`IntoResponse`].
+///
+/// Shared business logic can return a `DualReply` and be called from both Warp and Axum
+/// handlers while a route exists in both stacks.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::http::StatusCode;
+/// use warpdrive::DualReply;
+/// use warp::Filter;
+///
+/// fn create_user(name: &str) -> DualReply {
+///     DualReply::json(&format!("Created {}", name)).with_status(StatusCode::CREATED)
+/// }
+///
+/// // Usable from a Warp filter...
+/// let warp_route = warp::any().map(|| create_user("warp"));
+///
+/// // ...and from an Axum handler.
+/// async fn axum_handler() -> DualReply {
+///     create_user("axum")
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DualReply {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl DualReply {
+    /// Creates a `200 OK` reply with the given body and no content type.
+    pub fn new(body: impl Into<Bytes>) -> Self {
+        DualReply {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Creates a `200 OK` plain text reply.
+    pub fn text(body: impl Into<String>) -> Self {
+        DualReply::new(body.into()).with_header(
+            CONTENT_TYPE,
+            HeaderValue::from_static("text/plain; charset=utf-8"),
+        )
+    }
+
+    /// Creates a `200 OK` JSON reply.
+    ///
+    /// If serialization fails, the reply is an empty `500 Internal Server Error`, matching
+    /// `warp::reply::json`.
+    pub fn json<T: Serialize + ?Sized>(value: &T) -> Self {
+        match serde_json::to_vec(value) {
+            Ok(body) => DualReply::new(body)
+                .with_header(CONTENT_TYPE, HeaderValue::from_static("application/json")),
+            Err(_) => DualReply::new(Bytes::new()).with_status(StatusCode::INTERNAL_SERVER_ERROR),
+        }
+    }
+
+    /// Sets the status code of the reply.
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Appends a header to the reply.
+    pub fn with_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Returns the status code of the reply.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns the headers of the reply.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Returns the body of the reply.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+}
+
+impl IntoResponse for DualReply {
+    fn into_response(self) -> Response {
+        let mut response = Response::new(AxumBody::from(self.body));
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers;
+        response
+    }
+}
+
+impl warp::Reply for DualReply {
+    fn into_response(self) -> warp::reply::Response {
+        let mut response = warp::reply::Response::new(WarpBody::from(self.body));
+
+        *response.status_mut() = warp::http::StatusCode::from_u16(self.status.as_u16())
+            .unwrap_or(warp::http::StatusCode::INTERNAL_SERVER_ERROR);
+
+        *response.headers_mut() = to_warp_headers(&self.headers);
+
+        response
+    }
+}
+
+/// A wrapper that allows any [`warp::Reply`] to be returned from an Axum handler.
+///
+/// The Warp reply is converted into an Axum response when the handler returns.
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::WarpReply;
+///
+/// async fn handler() -> WarpReply<warp::reply::Json> {
+///     WarpReply(warp::reply::json(&"Hello"))
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WarpReply<T>(pub T);
+
+impl<T> WarpReply<T> {
+    /// Consumes the wrapper, returning the Warp reply.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> IntoResponse for WarpReply<T>
+where
+    T: warp::Reply,
+{
+    fn into_response(self) -> Response {
+        match into_axum_response(warp::Reply::into_response(self.0)) {
+            Ok(response) => response,
+            Err(err) => create_conversion_error_response(err),
+        }
+    }
+}
+
+/// A wrapper that allows any Axum [`IntoResponse`] to be returned from a Warp handler.
+///
+/// This lets new response types (Axum JSON, SSE, typed errors) be used from Warp handlers
+/// that have not been migrated yet.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::Json;
+/// use warpdrive::AxumReply;
+/// use warp::Filter;
+///
+/// let route = warp::path("hello").map(|| AxumReply(Json("Hello")));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AxumReply<T>(pub T);
+
+impl<T> AxumReply<T> {
+    /// Consumes the wrapper, returning the Axum response type.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> warp::Reply for AxumReply<T>
+where
+    T: IntoResponse + Send,
+{
+    fn into_response(self) -> warp::reply::Response {
+        match into_warp_response(IntoResponse::into_response(self.0)) {
+            Ok(response) => response,
+            Err(err) => create_warp_conversion_error_response(err),
+        }
+    }
+}
+
+// This only runs in the unlikely event of a conversion error.
+fn create_warp_conversion_error_response(err: String) -> warp::reply::Response {
+    let mut response = warp::reply::Response::new(format!("Conversion error: {}", err).into());
+    *response.status_mut() = warp::http::StatusCode::INTERNAL_SERVER_ERROR;
+    response
+}
@@ -0,0 +1,220 @@
+//! Error reporting for failures at the boundary, for services such as Sentry.
+//!
+//! An [`ErrorReporter`] is called when the boundary fails a request: when a request or
+//! response cannot be converted, when the Warp filter panics, or when a request times out.
+//! Each [`ErrorReport`] carries the request metadata and the error, whose
+//! [`chain`](ErrorReport::chain) of sources can be forwarded to an error tracker, so legacy
+//! routes are reported the same way as new Axum code.
+//!
+//! # Example
+//!
+//! ```rust
+//! use warpdrive::{WarpService, report::ErrorReporter};
+//! use warp::Filter;
+//!
+//! let filter = warp::path("orders").map(|| "Orders").boxed();
+//!
+//! let reporter = ErrorReporter::new(|report| {
+//!     let chain: Vec<String> = report.chain().map(|err| err.to_string()).collect();
+//!     eprintln!(
+//!         "{} {} failed ({}): {}",
+//!         report.method,
+//!         report.uri,
+//!         report.kind,
+//!         chain.join(": "),
+//!     );
+//! })
+//! .header("x-request-id");
+//!
+//! let service = WarpService::new(filter).with_error_reporter(reporter);
+//! ```
+
+use std::{
+    any::Any, error::Error as StdError, fmt, future::Future, iter, panic::AssertUnwindSafe,
+    sync::Arc,
+};
+
+use axum::{
+    extract::Request,
+    http::{HeaderMap, HeaderName, Method, Uri, Version},
+};
+use futures::FutureExt;
+
+use crate::Error;
+
+type ReportHook = Arc<dyn Fn(&ErrorReport<'_>) + Send + Sync>;
+
+/// A hook called with an [`ErrorReport`] when the boundary fails a request.
+///
+/// Only failures of the boundary itself are reported: responses returned by the Warp filter
+/// are not, whatever their status. A panic is reported and then resumed, so it is still
+/// handled by a [`Failover`](crate::Failover) or the server as before. The hook is applied with
+/// [`WarpService::with_error_reporter`](crate::WarpService::with_error_reporter).
+#[derive(Clone)]
+pub struct ErrorReporter {
+    hook: ReportHook,
+    headers: Vec<HeaderName>,
+}
+
+/// The kind of failure in an [`ErrorReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReportKind {
+    /// The request or response could not be converted between HTTP versions.
+    Conversion,
+    /// The Warp filter panicked.
+    Panic,
+    /// The request did not complete within the configured timeout or deadline.
+    Timeout,
+}
+
+/// A failed request, as passed to an [`ErrorReporter`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ErrorReport<'a> {
+    /// The kind of failure.
+    pub kind: ReportKind,
+    /// The request method.
+    pub method: &'a Method,
+    /// The request URI.
+    pub uri: &'a Uri,
+    /// The request HTTP version.
+    pub version: Version,
+    /// The request headers selected with [`ErrorReporter::header`].
+    pub headers: &'a HeaderMap,
+    /// The error. For panics, this is the panic message.
+    pub error: &'a (dyn StdError + 'static),
+}
+
+impl ErrorReport<'_> {
+    /// Returns the error followed by each of its sources, outermost first.
+    pub fn chain(&self) -> impl Iterator<Item = &(dyn StdError + 'static)> {
+        iter::successors(Some(self.error), |&err| err.source())
+    }
+}
+
+impl fmt::Display for ReportKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ReportKind::Conversion => "conversion",
+            ReportKind::Panic => "panic",
+            ReportKind::Timeout => "timeout",
+        })
+    }
+}
+
+impl fmt::Debug for ErrorReporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErrorReporter")
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The payload of a panic in the Warp filter.
+#[derive(Debug)]
+struct Panic(String);
+
+impl fmt::Display for Panic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Warp filter panicked: {}", self.0)
+    }
+}
+
+impl StdError for Panic {}
+
+/// The metadata of a request that may be reported.
+pub(crate) struct Reported {
+    reporter: ErrorReporter,
+    method: Method,
+    uri: Uri,
+    version: Version,
+    headers: HeaderMap,
+}
+
+impl ErrorReporter {
+    /// Creates a reporter that calls the given function for each failed request.
+    pub fn new<F>(hook: F) -> Self
+    where
+        F: Fn(&ErrorReport<'_>) + Send + Sync + 'static,
+    {
+        ErrorReporter {
+            hook: Arc::new(hook),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Includes a request header in reports. Can be called more than once to include several
+    /// headers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid header name.
+    pub fn header(mut self, name: &str) -> Self {
+        self.headers
+            .push(HeaderName::try_from(name).expect("invalid header name"));
+        self
+    }
+
+    /// Captures the metadata of a request, in case it fails.
+    pub(crate) fn start(&self, req: &Request) -> Reported {
+        let mut headers = HeaderMap::new();
+        for name in &self.headers {
+            for value in req.headers().get_all(name) {
+                headers.append(name, value.clone());
+            }
+        }
+
+        Reported {
+            reporter: self.clone(),
+            method: req.method().clone(),
+            uri: req.uri().clone(),
+            version: req.version(),
+            headers,
+        }
+    }
+}
+
+impl Reported {
+    /// Reports a boundary error, if it is a conversion failure or a timeout.
+    pub(crate) fn error(&self, err: &Error) {
+        let kind = match err {
+            Error::Conversion(_) => ReportKind::Conversion,
+            Error::Timeout(_) => ReportKind::Timeout,
+            _ => return,
+        };
+        self.report(kind, err);
+    }
+
+    /// Runs a future, reporting and then resuming any panic.
+    pub(crate) async fn catch_panic<F: Future>(&self, future: F) -> F::Output {
+        match AssertUnwindSafe(future).catch_unwind().await {
+            Ok(output) => output,
+            Err(payload) => {
+                self.panic(&*payload);
+                std::panic::resume_unwind(payload)
+            }
+        }
+    }
+
+    /// Reports a panic from the Warp filter.
+    fn panic(&self, payload: &(dyn Any + Send)) {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        self.report(ReportKind::Panic, &Panic(message));
+    }
+
+    fn report(&self, kind: ReportKind, error: &(dyn StdError + 'static)) {
+        (self.reporter.hook)(&ErrorReport {
+            kind,
+            method: &self.method,
+            uri: &self.uri,
+            version: self.version,
+            headers: &self.headers,
+            error,
+        });
+    }
+}
@@ -0,0 +1,95 @@
+use std::{convert::Infallible, fmt};
+
+use axum::Extension;
+use warp::Filter;
+
+/// A shared resource, such as a database pool or an HTTP client, exposed to both Axum
+/// handlers and Warp filters from one place.
+///
+/// During a migration, the same pool is often built twice, or threaded separately into the
+/// Axum state and into each Warp filter. A `ResourceProvider` is created once, and hands out
+/// clones of the resource: to Warp filters with [`filter`](ResourceProvider::filter), to Axum
+/// handlers as `Extension<T>` with [`layer`](ResourceProvider::layer), and as `State<T>` with
+/// `Router::with_state(provider.get())`, or as a field of a larger state.
+///
+/// The resource is cloned for each request, so it should be cheap to clone, as pools and
+/// clients are. Wrap other resources in an `Arc`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Arc;
+///
+/// use axum::{Extension, Router, extract::State, routing::get};
+/// use warpdrive::{ResourceProvider, WarpService};
+/// use warp::Filter;
+///
+/// #[derive(Clone)]
+/// struct Pool(Arc<String>);
+///
+/// let pool = ResourceProvider::new(Pool(Arc::new("postgres://db".to_string())));
+///
+/// // Warp filters extract the pool as a value.
+/// let legacy = warp::path("legacy")
+///     .and(pool.filter())
+///     .map(|pool: Pool| format!("Warp on {}", pool.0))
+///     .boxed();
+///
+/// // Axum handlers extract it as state, or as an extension.
+/// async fn users(State(pool): State<Pool>) -> String {
+///     format!("Axum on {}", pool.0)
+/// }
+///
+/// async fn orders(Extension(pool): Extension<Pool>) -> String {
+///     format!("Axum on {}", pool.0)
+/// }
+///
+/// let app: Router = Router::new()
+///     .route("/users", get(users))
+///     .route("/orders", get(orders))
+///     .fallback_service(WarpService::new(legacy))
+///     .layer(pool.layer())
+///     .with_state(pool.get());
+/// ```
+#[derive(Clone, Default)]
+pub struct ResourceProvider<T> {
+    resource: T,
+}
+
+impl<T> ResourceProvider<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// Creates a provider for a resource.
+    pub fn new(resource: T) -> Self {
+        ResourceProvider { resource }
+    }
+
+    /// Returns a clone of the resource, such as for `Router::with_state`.
+    pub fn get(&self) -> T {
+        self.resource.clone()
+    }
+
+    /// Returns a Warp filter that extracts a clone of the resource and never rejects.
+    pub fn filter(&self) -> impl Filter<Extract = (T,), Error = Infallible> + Clone + use<T> {
+        let resource = self.resource.clone();
+        warp::any().map(move || resource.clone())
+    }
+
+    /// Returns an Axum layer that adds a clone of the resource to the extensions of each
+    /// request, for handlers that extract `Extension<T>`.
+    ///
+    /// Extensions are carried through [`WarpService`](crate::WarpService), so Axum extractors
+    /// run from Warp filters with [`axum_extract`](crate::axum_extract) see the resource too.
+    pub fn layer(&self) -> Extension<T> {
+        Extension(self.resource.clone())
+    }
+}
+
+impl<T> fmt::Debug for ResourceProvider<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResourceProvider")
+            .field("resource", &std::any::type_name::<T>())
+            .finish()
+    }
+}
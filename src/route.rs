@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use axum::{extract::Request, response::Response};
+
+use crate::error::Error;
+
+/// The route of every request when no route key is set.
+pub(crate) const SERVICE_ROUTE: &str = "*";
+
+/// A function identifying the route of a request, such as by its method and path.
+pub(crate) type RouteKeyFn = Arc<dyn Fn(&Request) -> String + Send + Sync>;
+
+/// Returns the route of a request, or [`SERVICE_ROUTE`] if no route key is set.
+pub(crate) fn route_of(route_key: Option<&RouteKeyFn>, req: &Request) -> String {
+    match route_key {
+        Some(route_key) => route_key(req),
+        None => SERVICE_ROUTE.to_string(),
+    }
+}
+
+/// Returns `true` if the result of a request served by the Warp filter counts against the
+/// health of its route, or `None` if it says nothing about it, as for rate limited requests.
+pub(crate) fn route_failed(result: &Result<Response, Error>) -> Option<bool> {
+    match result {
+        Ok(response) => Some(response.status().is_server_error()),
+        Err(Error::RateLimited(_)) => None,
+        Err(_) => Some(true),
+    }
+}
+
+/// Returns `false` if a result was produced without the Warp filter, such as a cache hit or a
+/// rate limited request, or by an Axum service standing in for it.
+pub(crate) fn served_by_warp(result: &Result<Response, Error>) -> bool {
+    match result {
+        Ok(response) => {
+            let headers = response.headers();
+            !headers.contains_key("x-warpdrive-failover")
+                && !headers.contains_key("x-warpdrive-circuit")
+                && headers
+                    .get("x-warpdrive-hedge")
+                    .is_none_or(|winner| winner == "warp")
+                && headers
+                    .get("x-warpdrive-cache")
+                    .is_none_or(|cache| cache != "hit")
+        }
+        Err(Error::RateLimited(_) | Error::CircuitOpen) => false,
+        Err(_) => true,
+    }
+}
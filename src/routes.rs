@@ -0,0 +1,181 @@
+use std::fmt;
+
+use warp::{Filter, Rejection, Reply, filters::BoxedFilter};
+
+use crate::warp_service::WarpService;
+
+type BoxedReply = Box<dyn Reply + Send + Sync>;
+
+/// Collects Warp filters contributed by several modules into a single [`WarpService`].
+///
+/// Each module can expose a registration function that adds its routes, so legacy routes are
+/// registered where they are defined rather than in one central `or()` chain. Filters are
+/// tried in the order they are added, as with `or()`, and their replies are boxed so filters
+/// with different reply types can be combined. See also the [`warp_routes!`](crate::warp_routes)
+/// macro.
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::WarpRoutes;
+/// use warp::Filter;
+///
+/// mod billing {
+///     use warpdrive::WarpRoutes;
+///     use warp::Filter;
+///
+///     pub fn register(routes: WarpRoutes) -> WarpRoutes {
+///         routes
+///             .route(warp::path!("billing" / "invoices").map(|| "Invoices"))
+///             .route(warp::path!("billing" / "total").map(|| warp::reply::json(&42)))
+///     }
+/// }
+///
+/// mod accounts {
+///     use warpdrive::WarpRoutes;
+///     use warp::Filter;
+///
+///     pub fn register(routes: WarpRoutes) -> WarpRoutes {
+///         routes.route(warp::path!("accounts" / u32).map(|id| format!("Account {}", id)))
+///     }
+/// }
+///
+/// let service = WarpRoutes::new()
+///     .register(billing::register)
+///     .register(accounts::register)
+///     .into_service();
+/// ```
+#[derive(Default)]
+pub struct WarpRoutes {
+    filters: Vec<BoxedFilter<(BoxedReply,)>>,
+    #[cfg(any(test, feature = "openapi"))]
+    openapi: crate::openapi::OpenApiPaths,
+}
+
+impl WarpRoutes {
+    /// Creates an empty collection of routes.
+    pub fn new() -> Self {
+        WarpRoutes::default()
+    }
+
+    /// Adds a filter, tried after the filters already added.
+    pub fn route<F, R>(mut self, filter: F) -> Self
+    where
+        F: Filter<Extract = (R,), Error = Rejection> + Clone + Send + Sync + 'static,
+        R: Reply + Send + Sync + 'static,
+    {
+        self.filters
+            .push(filter.map(|reply: R| Box::new(reply) as BoxedReply).boxed());
+        self
+    }
+
+    /// Adds the routes from a registration function, such as one exposed by a module that
+    /// owns them.
+    pub fn register<F>(self, register: F) -> Self
+    where
+        F: FnOnce(WarpRoutes) -> WarpRoutes,
+    {
+        register(self)
+    }
+
+    /// Documents the OpenAPI operation of a route for a method and a path template, such as
+    /// `/users/{id}`, usually right after adding the route's filter.
+    ///
+    /// See the [`openapi`](crate::openapi) module for details. Available with the `openapi`
+    /// feature.
+    #[cfg(any(test, feature = "openapi"))]
+    pub fn document(
+        mut self,
+        method: axum::http::Method,
+        path: &str,
+        operation: serde_json::Value,
+    ) -> Self {
+        self.openapi.insert(method, path, operation);
+        self
+    }
+
+    /// Returns the OpenAPI operations documented so far.
+    ///
+    /// Available with the `openapi` feature.
+    #[cfg(any(test, feature = "openapi"))]
+    pub fn openapi(&self) -> &crate::openapi::OpenApiPaths {
+        &self.openapi
+    }
+
+    /// Returns the number of filters added.
+    pub fn len(&self) -> usize {
+        self.filters.len()
+    }
+
+    /// Returns `true` if no filters have been added.
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    /// Combines the filters into one, trying each in turn until one matches.
+    ///
+    /// With no filters, every request is rejected as not found.
+    pub fn into_filter(self) -> BoxedFilter<(BoxedReply,)> {
+        let mut filters = self.filters.into_iter();
+
+        let Some(first) = filters.next() else {
+            return warp::any()
+                .and_then(|| async { Err::<BoxedReply, _>(warp::reject::not_found()) })
+                .boxed();
+        };
+
+        filters.fold(first, |combined, filter| {
+            combined.or(filter).unify().boxed()
+        })
+    }
+
+    /// Combines the filters into a [`WarpService`].
+    pub fn into_service(self) -> WarpService {
+        WarpService::new(self.into_filter())
+    }
+}
+
+impl fmt::Debug for WarpRoutes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WarpRoutes")
+            .field("filters", &self.filters.len())
+            .finish()
+    }
+}
+
+/// Builds a [`WarpService`] from a list of Warp filters, tried in order.
+///
+/// Each argument is a filter, or `register: f` to add the routes from a registration
+/// function, as with [`WarpRoutes::register`]. Filters may have different reply types.
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::{WarpRoutes, warp_routes};
+/// use warp::Filter;
+///
+/// fn legacy_routes(routes: WarpRoutes) -> WarpRoutes {
+///     routes.route(warp::path("legacy").map(|| "Legacy"))
+/// }
+///
+/// let service = warp_routes![
+///     warp::path("hello").map(|| "Hello"),
+///     warp::path("json").map(|| warp::reply::json(&"Hello")),
+///     register: legacy_routes,
+/// ];
+/// ```
+#[macro_export]
+macro_rules! warp_routes {
+    (@add $routes:expr $(,)?) => {
+        $routes
+    };
+    (@add $routes:expr, register: $register:expr $(, $($rest:tt)*)?) => {
+        $crate::warp_routes!(@add $routes.register($register) $(, $($rest)*)?)
+    };
+    (@add $routes:expr, $filter:expr $(, $($rest:tt)*)?) => {
+        $crate::warp_routes!(@add $routes.route($filter) $(, $($rest)*)?)
+    };
+    ($($args:tt)*) => {
+        $crate::warp_routes!(@add $crate::WarpRoutes::new(), $($args)*).into_service()
+    };
+}
@@ -0,0 +1,97 @@
+use axum::{
+    http::{HeaderMap, HeaderName, HeaderValue, header},
+    response::Response,
+};
+
+/// Security headers added to every response from a [`WarpService`](crate::WarpService).
+///
+/// The defaults are:
+///
+/// - `Strict-Transport-Security: max-age=31536000; includeSubDomains`
+/// - `X-Content-Type-Options: nosniff`
+/// - `X-Frame-Options: DENY`
+/// - `Referrer-Policy: strict-origin-when-cross-origin`
+///
+/// Headers already set by the Warp filter are left unchanged. The headers are applied with
+/// [`WarpService::with_security_headers`](crate::WarpService::with_security_headers).
+///
+/// # Example
+///
+/// ```rust
+/// use warpdrive::{SecurityHeaders, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("legacy").map(|| "Hello").boxed();
+///
+/// let headers = SecurityHeaders::new()
+///     .header("content-security-policy", "default-src 'self'")
+///     .without("strict-transport-security");
+///
+/// let service = WarpService::new(filter).with_security_headers(headers);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SecurityHeaders {
+    headers: HeaderMap,
+}
+
+impl Default for SecurityHeaders {
+    fn default() -> Self {
+        SecurityHeaders::new()
+    }
+}
+
+impl SecurityHeaders {
+    /// Creates the default set of security headers.
+    pub fn new() -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::STRICT_TRANSPORT_SECURITY,
+            HeaderValue::from_static("max-age=31536000; includeSubDomains"),
+        );
+        headers.insert(
+            header::X_CONTENT_TYPE_OPTIONS,
+            HeaderValue::from_static("nosniff"),
+        );
+        headers.insert(header::X_FRAME_OPTIONS, HeaderValue::from_static("DENY"));
+        headers.insert(
+            header::REFERRER_POLICY,
+            HeaderValue::from_static("strict-origin-when-cross-origin"),
+        );
+
+        SecurityHeaders { headers }
+    }
+
+    /// Creates an empty set of security headers, to be filled with
+    /// [`header`](SecurityHeaders::header).
+    pub fn empty() -> Self {
+        SecurityHeaders {
+            headers: HeaderMap::new(),
+        }
+    }
+
+    /// Adds a header, replacing any default with the same name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` or `value` is not valid.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        let name = HeaderName::try_from(name).expect("invalid security header name");
+        let value = HeaderValue::try_from(value).expect("invalid security header value");
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Removes a header from the set.
+    pub fn without(mut self, name: &str) -> Self {
+        self.headers.remove(name);
+        self
+    }
+
+    pub(crate) fn apply(&self, response: &mut Response) {
+        for (name, value) in &self.headers {
+            if !response.headers().contains_key(name) {
+                response.headers_mut().insert(name, value.clone());
+            }
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::{future::Future, io, net::SocketAddr};
+
+use axum::Router;
+use futures::{FutureExt, future};
+use tokio::{
+    net::{TcpListener, ToSocketAddrs},
+    sync::watch,
+};
+
+use crate::WarpService;
+
+/// Serves a [`WarpService`] on its own, without an Axum `Router`.
+///
+/// This runs the service on Axum's hyper 1.x server stack, for binaries whose routes are all
+/// still Warp filters. The server stops accepting connections once `signal` resolves, and
+/// returns after in-flight connections have finished. The client address is available to the
+/// service as a `ConnectInfo<SocketAddr>` extension, as with
+/// [`WarpService::into_make_service_with_connect_info`]. Pair it with a
+/// [`Shutdown`](crate::Shutdown) handle to also reject new requests on open connections and
+/// end streaming responses.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use warpdrive::WarpService;
+/// use warp::Filter;
+///
+/// # #[tokio::main]
+/// # async fn main() -> std::io::Result<()> {
+/// let filter = warp::path("hello").map(|| "Hello from Warp!").boxed();
+///
+/// warpdrive::serve(WarpService::new(filter), "0.0.0.0:3000", async {
+///     tokio::signal::ctrl_c().await.ok();
+/// })
+/// .await
+/// # }
+/// ```
+pub async fn serve<T>(
+    service: WarpService<T>,
+    addr: impl ToSocketAddrs,
+    signal: impl Future<Output = ()> + Send + 'static,
+) -> io::Result<()>
+where
+    T: warp::Reply + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind(addr).await?;
+
+    axum::serve(
+        listener,
+        service.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(signal)
+    .await
+}
+
+/// Serves a [`WarpService`] alone on one address and the combined Axum `Router` on another,
+/// for the transition period in which clients of the old port must only reach legacy routes.
+///
+/// Both listeners are bound before either starts serving, so a port that is already in use
+/// is reported before any traffic is accepted. Both servers stop accepting connections once
+/// the shared shutdown signal resolves, and [`serve`](DualListener::serve) returns after the
+/// in-flight connections of both have finished. If either server fails, the other is shut
+/// down gracefully as if the signal had resolved, and the error is returned once its
+/// connections have finished too. The client address is available to both as a
+/// `ConnectInfo<SocketAddr>` extension.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use axum::{Router, routing::get};
+/// use warpdrive::{DualListener, WarpService};
+/// use warp::Filter;
+///
+/// # #[tokio::main]
+/// # async fn main() -> std::io::Result<()> {
+/// let filter = warp::path("hello").map(|| "Hello from Warp!").boxed();
+/// let service = WarpService::new(filter);
+///
+/// let app = Router::new()
+///     .route("/new", get(|| async { "Hello from Axum!" }))
+///     .fallback_service(service.clone());
+///
+/// DualListener::new("0.0.0.0:8080".parse().unwrap(), "0.0.0.0:3000".parse().unwrap())
+///     .serve(service, app, async {
+///         tokio::signal::ctrl_c().await.ok();
+///     })
+///     .await
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DualListener {
+    legacy: SocketAddr,
+    app: SocketAddr,
+}
+
+impl DualListener {
+    /// Creates a configuration that serves legacy routes on `legacy` and the combined app on
+    /// `app`.
+    pub fn new(legacy: SocketAddr, app: SocketAddr) -> Self {
+        DualListener { legacy, app }
+    }
+
+    /// Binds both addresses and serves `service` on the legacy address and `app` on the other
+    /// until `signal` resolves.
+    pub async fn serve<T>(
+        self,
+        service: WarpService<T>,
+        app: Router,
+        signal: impl Future<Output = ()> + Send + 'static,
+    ) -> io::Result<()>
+    where
+        T: warp::Reply + Send + Sync + 'static,
+    {
+        let legacy_listener = TcpListener::bind(self.legacy).await?;
+        let app_listener = TcpListener::bind(self.app).await?;
+        // The servers also stop if the other one fails, so neither is dropped mid-request.
+        let (stop, mut stopped) = watch::channel(false);
+        let failed = async move {
+            let _ = stopped.wait_for(|failed| *failed).await;
+        };
+        let signal = future::select(Box::pin(signal), Box::pin(failed))
+            .map(|_| ())
+            .shared();
+
+        let legacy = axum::serve(
+            legacy_listener,
+            service.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(signal.clone());
+        let app = axum::serve(
+            app_listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(signal);
+
+        join_servers(legacy.into_future(), app.into_future(), || {
+            stop.send_replace(true);
+        })
+        .await
+    }
+}
+
+/// Waits for both servers to return, calling `stop` as soon as either fails so the other
+/// shuts down, and returns an error if either failed.
+pub(crate) async fn join_servers(
+    first: impl Future<Output = io::Result<()>>,
+    second: impl Future<Output = io::Result<()>>,
+    stop: impl Fn(),
+) -> io::Result<()> {
+    let first = first.inspect(|result| {
+        if result.is_err() {
+            stop();
+        }
+    });
+    let second = second.inspect(|result| {
+        if result.is_err() {
+            stop();
+        }
+    });
+
+    let (first, second) = future::join(first, second).await;
+    first.and(second)
+}
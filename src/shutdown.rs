@@ -0,0 +1,209 @@
+use std::{
+    pin::{Pin, pin},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::{Body, Bytes},
+    response::Response,
+};
+use futures::{FutureExt, future::BoxFuture};
+use http_body::{Body as HttpBody, Frame, SizeHint};
+use tokio::sync::Notify;
+
+/// A handle for gracefully shutting down [`WarpService`](crate::WarpService)s.
+///
+/// Once [`trigger`](Shutdown::trigger) is called, services sharing the handle answer new
+/// requests with `503 Service Unavailable`, and [`drained`](Shutdown::drained) resolves
+/// when every in-flight request has finished, including streaming its response body. By
+/// default, streaming responses such as server-sent events are left to finish on their own;
+/// with [`terminate_streams`](Shutdown::terminate_streams), they are ended cleanly on
+/// shutdown instead.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use axum::Router;
+/// use warpdrive::{Shutdown, WarpService};
+/// use warp::Filter;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let shutdown = Shutdown::new().terminate_streams();
+///
+/// let filter = warp::path("events").map(|| "Hello").boxed();
+/// let app: Router =
+///     Router::new().fallback_service(WarpService::new(filter).with_shutdown(shutdown.clone()));
+///
+/// let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
+/// let signal = shutdown.clone();
+/// tokio::spawn(async move {
+///     tokio::signal::ctrl_c().await.unwrap();
+///     signal.trigger();
+/// });
+///
+/// axum::serve(listener, app)
+///     .with_graceful_shutdown(shutdown.triggered())
+///     .await
+///     .unwrap();
+/// shutdown.drained().await;
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Shutdown {
+    state: Arc<State>,
+    terminate_streams: bool,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    triggered: AtomicBool,
+    in_flight: AtomicUsize,
+    notify: Notify,
+}
+
+impl Shutdown {
+    /// Creates a shutdown handle that has not been triggered.
+    pub fn new() -> Self {
+        Shutdown::default()
+    }
+
+    /// Ends streaming response bodies cleanly when shutdown is triggered, rather than
+    /// waiting for them to finish.
+    pub fn terminate_streams(mut self) -> Self {
+        self.terminate_streams = true;
+        self
+    }
+
+    /// Starts shutting down, rejecting new requests.
+    pub fn trigger(&self) {
+        self.state.triggered.store(true, Ordering::SeqCst);
+        self.state.notify.notify_waiters();
+    }
+
+    /// Returns `true` if shutdown has been triggered.
+    pub fn is_triggered(&self) -> bool {
+        self.state.triggered.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of requests that have not finished, including streaming their
+    /// response bodies.
+    pub fn in_flight(&self) -> usize {
+        self.state.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Resolves when shutdown is triggered.
+    pub fn triggered(&self) -> impl Future<Output = ()> + Send + 'static {
+        let state = Arc::clone(&self.state);
+        async move {
+            state
+                .wait_until(|state| state.triggered.load(Ordering::SeqCst))
+                .await
+        }
+    }
+
+    /// Resolves when shutdown has been triggered and every in-flight request has finished.
+    pub fn drained(&self) -> impl Future<Output = ()> + Send + 'static {
+        let state = Arc::clone(&self.state);
+        async move {
+            state
+                .wait_until(|state| {
+                    state.triggered.load(Ordering::SeqCst)
+                        && state.in_flight.load(Ordering::SeqCst) == 0
+                })
+                .await
+        }
+    }
+
+    /// Registers a new request, or returns `None` if shutdown has been triggered.
+    pub(crate) fn start(&self) -> Option<InFlight> {
+        self.state.in_flight.fetch_add(1, Ordering::SeqCst);
+        let in_flight = InFlight {
+            state: Arc::clone(&self.state),
+        };
+
+        // Checked after registering, so `drained` cannot miss a request that is let in.
+        (!self.is_triggered()).then_some(in_flight)
+    }
+
+    /// Ties the in-flight request to the response body, so it finishes when the body does.
+    pub(crate) fn track(&self, response: Response, in_flight: InFlight) -> Response {
+        // Bodies of a known length cannot be ended early without being truncated.
+        let streaming = response.body().size_hint().exact().is_none();
+        let terminate = (self.terminate_streams && streaming).then(|| self.triggered().boxed());
+
+        response.map(|body| {
+            Body::new(DrainBody {
+                inner: body,
+                terminate,
+                _in_flight: in_flight,
+            })
+        })
+    }
+}
+
+impl State {
+    async fn wait_until(&self, condition: impl Fn(&State) -> bool) {
+        loop {
+            let mut notified = pin!(self.notify.notified());
+            notified.as_mut().enable();
+
+            if condition(self) {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Marks a request as in flight until dropped.
+pub(crate) struct InFlight {
+    state: Arc<State>,
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if self.state.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.state.notify.notify_waiters();
+        }
+    }
+}
+
+struct DrainBody {
+    inner: Body,
+    terminate: Option<BoxFuture<'static, ()>>,
+    _in_flight: InFlight,
+}
+
+impl HttpBody for DrainBody {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+
+        if let Some(terminate) = &mut this.terminate
+            && terminate.as_mut().poll(cx).is_ready()
+        {
+            this.terminate = None;
+            this.inner = Body::empty();
+        }
+
+        Pin::new(&mut this.inner).poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    any::Any,
+    convert::Infallible,
+    fmt,
+    panic::AssertUnwindSafe,
+    task::{Context, Poll},
+    thread,
+};
+
+use axum::{
+    extract::Request,
+    response::{IntoResponse, Response},
+};
+use futures::{FutureExt, future::BoxFuture};
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::LocalSet,
+};
+use tower::Service;
+use warp::{
+    Filter, Rejection, Reply, http::Request as WarpRequest, hyper::Body as WarpBody,
+    reply::Response as WarpResponse,
+};
+
+use crate::{
+    convert_request::into_warp_request, convert_response::into_axum_response, error::Error,
+};
+
+type Job = (
+    WarpRequest<WarpBody>,
+    oneshot::Sender<Result<WarpResponse, Box<dyn Any + Send>>>,
+);
+
+/// A Tower service that runs a Warp filter whose state is tied to a single thread.
+///
+/// [`WarpService`](crate::WarpService) needs a [`BoxedFilter`](warp::filters::BoxedFilter),
+/// which must be `Send` and `Sync`. `SingleThreadWarpService` instead builds the filter on a
+/// thread of its own, running a current-thread runtime and a [`LocalSet`], and sends requests
+/// to it over a channel. The filter only ever lives on that thread, so it need not be `Sync`, and
+/// every request sees the same thread-local state.
+///
+/// This does not let a filter capture an `Rc` or any other value that is not `Send`. Warp
+/// requires the future of every filter to be `Send`, and its combinators move clones of their
+/// closures into those futures, so whatever the closures capture must be `Send` too. State
+/// that is not `Send` has to live in a `thread_local!` that the filter reads on each request,
+/// as in the example below; the service only guarantees that it is always the same thread.
+///
+/// Each request is spawned as a local task, so requests are still served concurrently while
+/// they wait, but a filter that blocks holds up every other request. The service itself is
+/// `Send`, `Sync` and cheap to clone. The thread stops once every clone has been dropped and
+/// the requests in flight have finished.
+///
+/// Unlike `WarpService`, the boundary settings are not available; requests are converted,
+/// served by the filter and converted back, and conversion errors are answered with a
+/// `500 Internal Server Error`. Panics in the filter are resumed on the calling task.
+///
+/// # Example
+///
+/// ```rust
+/// use std::{cell::Cell, rc::Rc};
+///
+/// use axum::Router;
+/// use warpdrive::SingleThreadWarpService;
+/// use warp::Filter;
+///
+/// thread_local! {
+///     static SESSIONS: Rc<Cell<u64>> = Rc::new(Cell::new(0));
+/// }
+///
+/// let service = SingleThreadWarpService::new(|| {
+///     warp::path("sessions").map(|| {
+///         let sessions = SESSIONS.with(Rc::clone);
+///         sessions.set(sessions.get() + 1);
+///         format!("Session {}", sessions.get())
+///     })
+/// });
+///
+/// let app: Router = Router::new().fallback_service(service);
+/// ```
+#[derive(Clone)]
+pub struct SingleThreadWarpService {
+    jobs: mpsc::UnboundedSender<Job>,
+}
+
+impl SingleThreadWarpService {
+    /// Starts a thread that builds the filter with `build` and serves requests with it.
+    ///
+    /// The filter need not be `Sync`, but Warp still requires the closures it is built from
+    /// to be `Send`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thread or its runtime cannot be created.
+    pub fn new<B, F, T>(build: B) -> Self
+    where
+        B: FnOnce() -> F + Send + 'static,
+        F: Filter<Extract = (T,), Error = Rejection> + Clone + 'static,
+        T: Reply,
+    {
+        let (jobs, mut receiver) = mpsc::unbounded_channel::<Job>();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to build the filter thread runtime");
+
+        thread::Builder::new()
+            .name("warpdrive-filter".to_string())
+            .spawn(move || {
+                let local = LocalSet::new();
+                local.spawn_local(async move {
+                    let filter = build();
+                    while let Some((req, respond)) = receiver.recv().await {
+                        let mut service = warp::service(filter.clone());
+                        tokio::task::spawn_local(async move {
+                            let response = AssertUnwindSafe(service.call(req))
+                                .catch_unwind()
+                                .await
+                                .map(|result| match result {
+                                    Ok(response) => response,
+                                    Err(never) => match never {},
+                                });
+                            let _ = respond.send(response);
+                        });
+                    }
+                });
+                runtime.block_on(local);
+            })
+            .expect("failed to spawn the filter thread");
+
+        SingleThreadWarpService { jobs }
+    }
+
+    /// Serves a request on the filter's thread.
+    fn serve(&self, req: Request) -> BoxFuture<'static, Result<Response, Error>> {
+        let jobs = self.jobs.clone();
+
+        Box::pin(async move {
+            let req = into_warp_request(req).await.map_err(Error::Conversion)?;
+            let (respond, response) = oneshot::channel();
+            jobs.send((req, respond)).map_err(|_| stopped())?;
+
+            match response.await.map_err(|_| stopped())? {
+                Ok(response) => into_axum_response(response).map_err(Error::Conversion),
+                Err(panic) => std::panic::resume_unwind(panic),
+            }
+        })
+    }
+}
+
+/// The error returned once the filter's thread has stopped, which only happens if building
+/// the filter panicked.
+fn stopped() -> Error {
+    Error::Service("the filter thread has stopped".into())
+}
+
+impl Service<Request> for SingleThreadWarpService {
+    type Response = Response;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Response, Infallible>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let future = self.serve(req);
+        Box::pin(async move { Ok(future.await.unwrap_or_else(IntoResponse::into_response)) })
+    }
+}
+
+impl fmt::Debug for SingleThreadWarpService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SingleThreadWarpService")
+            .field("stopped", &self.jobs.is_closed())
+            .finish()
+    }
+}
@@ -0,0 +1,240 @@
+use std::{convert::Infallible, time::Duration};
+
+use axum::response::{IntoResponse, sse::Event as AxumEvent, sse::Sse};
+use futures::{FutureExt, Stream, TryStreamExt, stream};
+use warp::filters::sse::Event as WarpEvent;
+
+/// A framework-neutral Server-Sent Event.
+///
+/// `SseEvent` converts to and from both `warp::sse::Event` and `axum::response::sse::Event`,
+/// so a single event-producing stream can feed either framework's SSE reply.
+///
+/// # Example
+///
+/// ```rust
+/// use futures::stream;
+/// use std::convert::Infallible;
+/// use warpdrive::sse::{SseEvent, axum_event_stream, warp_event_stream};
+///
+/// fn events() -> impl futures::Stream<Item = Result<SseEvent, Infallible>> {
+///     stream::iter([Ok(SseEvent::default().event("tick").data("1"))])
+/// }
+///
+/// let axum_sse = axum::response::sse::Sse::new(axum_event_stream(events()));
+/// let warp_sse = warp::sse::reply(warp_event_stream(events()));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SseEvent {
+    data: Option<String>,
+    event: Option<String>,
+    id: Option<String>,
+    retry: Option<Duration>,
+    comment: Option<String>,
+}
+
+impl SseEvent {
+    /// Sets the data field. Multi-line data is sent as multiple `data` lines.
+    pub fn data(mut self, data: impl Into<String>) -> Self {
+        self.data = Some(data.into());
+        self
+    }
+
+    /// Sets the event name.
+    pub fn event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    /// Sets the event id.
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the reconnection time.
+    pub fn retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Sets the comment. Multi-line comments are sent as multiple comment lines to Axum and
+    /// flattened onto one line for Warp.
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    /// Returns the data field.
+    pub fn get_data(&self) -> Option<&str> {
+        self.data.as_deref()
+    }
+
+    /// Returns the event name.
+    pub fn get_event(&self) -> Option<&str> {
+        self.event.as_deref()
+    }
+
+    /// Returns the event id.
+    pub fn get_id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Returns the reconnection time.
+    pub fn get_retry(&self) -> Option<Duration> {
+        self.retry
+    }
+
+    /// Returns the comment.
+    pub fn get_comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// Converts into a Warp SSE event.
+    ///
+    /// Warp holds a single comment line and writes it without splitting, so a multi-line
+    /// comment is flattened onto one line, joined by spaces, to keep the event framing intact.
+    pub fn into_warp(self) -> WarpEvent {
+        let mut event = WarpEvent::default();
+        if let Some(comment) = self.comment {
+            event = event.comment(comment.lines().collect::<Vec<_>>().join(" "));
+        }
+        if let Some(name) = self.event {
+            event = event.event(name);
+        }
+        if let Some(data) = self.data {
+            event = event.data(data);
+        }
+        if let Some(id) = self.id {
+            event = event.id(id);
+        }
+        if let Some(retry) = self.retry {
+            event = event.retry(retry);
+        }
+        event
+    }
+
+    /// Converts into an Axum SSE event.
+    pub fn into_axum(self) -> AxumEvent {
+        let mut event = AxumEvent::default();
+        if let Some(comment) = self.comment {
+            for line in comment.lines() {
+                event = event.comment(line);
+            }
+        }
+        if let Some(name) = self.event {
+            event = event.event(name);
+        }
+        if let Some(data) = self.data {
+            event = event.data(data);
+        }
+        if let Some(id) = self.id {
+            event = event.id(id);
+        }
+        if let Some(retry) = self.retry {
+            event = event.retry(retry);
+        }
+        event
+    }
+
+    // Parses a single event in the `text/event-stream` wire format.
+    fn parse(wire: &str) -> Self {
+        let mut parsed = SseEvent::default();
+        let mut data: Option<Vec<&str>> = None;
+        let mut comments: Option<Vec<&str>> = None;
+
+        for line in wire.lines() {
+            if let Some(comment) = line.strip_prefix(':') {
+                comments
+                    .get_or_insert_with(Vec::new)
+                    .push(comment.strip_prefix(' ').unwrap_or(comment));
+                continue;
+            }
+
+            let (field, value) = match line.split_once(':') {
+                Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+                None => (line, ""),
+            };
+
+            match field {
+                "data" => data.get_or_insert_with(Vec::new).push(value),
+                "event" => parsed.event = Some(value.to_string()),
+                "id" => parsed.id = Some(value.to_string()),
+                "retry" => {
+                    if let Ok(millis) = value.parse() {
+                        parsed.retry = Some(Duration::from_millis(millis));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        parsed.data = data.map(|lines| lines.join("\n"));
+        parsed.comment = comments.map(|lines| lines.join("\n"));
+        parsed
+    }
+}
+
+impl From<WarpEvent> for SseEvent {
+    fn from(event: WarpEvent) -> Self {
+        SseEvent::parse(&event.to_string())
+    }
+}
+
+impl From<AxumEvent> for SseEvent {
+    fn from(event: AxumEvent) -> Self {
+        // Axum does not expose event fields, so render the event through an `Sse` body.
+        // The stream is always ready, so the body resolves on the first poll.
+        let body = Sse::new(stream::iter([Ok::<_, Infallible>(event)]))
+            .into_response()
+            .into_body();
+
+        match axum::body::to_bytes(body, usize::MAX).now_or_never() {
+            Some(Ok(bytes)) => SseEvent::parse(&String::from_utf8_lossy(&bytes)),
+            _ => SseEvent::default(),
+        }
+    }
+}
+
+impl From<SseEvent> for WarpEvent {
+    fn from(event: SseEvent) -> Self {
+        event.into_warp()
+    }
+}
+
+impl From<SseEvent> for AxumEvent {
+    fn from(event: SseEvent) -> Self {
+        event.into_axum()
+    }
+}
+
+/// Converts a Warp SSE event into an Axum SSE event.
+pub fn warp_to_axum_event(event: WarpEvent) -> AxumEvent {
+    SseEvent::from(event).into_axum()
+}
+
+/// Converts an Axum SSE event into a Warp SSE event.
+pub fn axum_to_warp_event(event: AxumEvent) -> WarpEvent {
+    SseEvent::from(event).into_warp()
+}
+
+/// Adapts a stream of events into a stream of Axum SSE events.
+///
+/// The input can yield [`SseEvent`]s or either framework's event type.
+pub fn axum_event_stream<S, T, E>(stream: S) -> impl Stream<Item = Result<AxumEvent, E>>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: Into<SseEvent>,
+{
+    stream.map_ok(|event| event.into().into_axum())
+}
+
+/// Adapts a stream of events into a stream of Warp SSE events.
+///
+/// The input can yield [`SseEvent`]s or either framework's event type.
+pub fn warp_event_stream<S, T, E>(stream: S) -> impl Stream<Item = Result<WarpEvent, E>>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: Into<SseEvent>,
+{
+    stream.map_ok(|event| event.into().into_warp())
+}
@@ -0,0 +1,217 @@
+use std::{
+    collections::hash_map::RandomState,
+    convert::Infallible,
+    fmt,
+    hash::BuildHasher,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use axum::{extract::Request, http::HeaderValue, response::Response};
+use futures::future::{self, BoxFuture};
+use tower::{Service, ServiceExt, util::BoxCloneSyncService};
+
+use crate::control::Percentage;
+
+type KeyFn = Arc<dyn Fn(&Request) -> Option<String> + Send + Sync>;
+
+/// A source of cutover flags, such as a feature flag service.
+///
+/// The provider is consulted for every request with the request's route key, and returns
+/// the percentage of the route's requests to send to the Axum implementation, from 0 to 100.
+/// Returning `None` uses the default set with [`Steering::default_percentage`]. Values above
+/// 100 are treated as 100.
+///
+/// Providers are usually backed by a cache that the flag service keeps up to date, so that
+/// lookups are fast. Synchronous functions and [`Percentage`] controls implement this trait
+/// directly.
+///
+/// # Example
+///
+/// ```rust
+/// use futures::future::BoxFuture;
+/// use warpdrive::FlagProvider;
+///
+/// struct Flags {
+///     client: FlagClient,
+/// }
+///
+/// impl FlagProvider for Flags {
+///     fn axum_percentage<'a>(&'a self, route: &'a str) -> BoxFuture<'a, Option<u8>> {
+///         Box::pin(async move { self.client.number(&format!("axum-cutover{}", route)).await })
+///     }
+/// }
+/// # struct FlagClient;
+/// # impl FlagClient {
+/// #     async fn number(&self, _flag: &str) -> Option<u8> { None }
+/// # }
+/// ```
+pub trait FlagProvider: Send + Sync + 'static {
+    /// Returns the percentage of requests for the route to send to Axum.
+    fn axum_percentage<'a>(&'a self, route: &'a str) -> BoxFuture<'a, Option<u8>>;
+}
+
+impl<F> FlagProvider for F
+where
+    F: Fn(&str) -> Option<u8> + Send + Sync + 'static,
+{
+    fn axum_percentage<'a>(&'a self, route: &'a str) -> BoxFuture<'a, Option<u8>> {
+        Box::pin(future::ready(self(route)))
+    }
+}
+
+impl FlagProvider for Percentage {
+    fn axum_percentage<'a>(&'a self, _route: &'a str) -> BoxFuture<'a, Option<u8>> {
+        Box::pin(future::ready(Some(self.get())))
+    }
+}
+
+/// Steers requests between a [`WarpService`](crate::WarpService) and an Axum service, as
+/// decided per request by a [`FlagProvider`].
+///
+/// This allows routes to be cut over to Axum gradually, and rolled back, without
+/// redeploying. Requests steered to Axum are sent to the Axum service without reaching the
+/// Warp filter, and have an `x-warpdrive-steering: axum` header.
+///
+/// Routes are identified by request path by default. Requests are assigned to Warp or Axum
+/// at random, or consistently per client with [`sticky`](Steering::sticky), so that a
+/// client's requests are all served by the same implementation while a percentage holds.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::routing::get;
+/// use warpdrive::{Steering, WarpService};
+/// use warp::Filter;
+///
+/// let filter = warp::path("users").map(|| "Users from Warp").boxed();
+///
+/// let steering = Steering::new(get(|| async { "Users from Axum" }), |route: &str| {
+///     // Looked up in a feature flag service in practice.
+///     (route == "/users").then_some(10)
+/// })
+/// .sticky(|req| {
+///     req.headers()
+///         .get("x-user-id")
+///         .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+/// });
+///
+/// let service = WarpService::new(filter).with_steering(steering);
+/// ```
+#[derive(Clone)]
+pub struct Steering {
+    axum: BoxCloneSyncService<Request, Response, Infallible>,
+    flags: Arc<dyn FlagProvider>,
+    route_key: Option<KeyFn>,
+    sticky_key: Option<KeyFn>,
+    default_percentage: u8,
+    hasher: RandomState,
+    counter: Arc<AtomicU64>,
+}
+
+impl Steering {
+    /// Creates steering that sends requests to the given Axum service as decided by the
+    /// flag provider.
+    pub fn new<S, F>(axum: S, flags: F) -> Self
+    where
+        S: Service<Request, Response = Response, Error = Infallible>
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        S::Future: Send + 'static,
+        F: FlagProvider,
+    {
+        Steering {
+            axum: BoxCloneSyncService::new(axum),
+            flags: Arc::new(flags),
+            route_key: None,
+            sticky_key: None,
+            default_percentage: 0,
+            hasher: RandomState::new(),
+            counter: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Identifies routes with the given function instead of by path. Requests for which it
+    /// returns `None` use the default percentage without consulting the flag provider.
+    pub fn route_key<F>(mut self, route_key: F) -> Self
+    where
+        F: Fn(&Request) -> Option<String> + Send + Sync + 'static,
+    {
+        self.route_key = Some(Arc::new(route_key));
+        self
+    }
+
+    /// Assigns requests with the same key, such as a user ID, to the same implementation.
+    /// Requests for which the function returns `None` are assigned at random.
+    pub fn sticky<F>(mut self, sticky_key: F) -> Self
+    where
+        F: Fn(&Request) -> Option<String> + Send + Sync + 'static,
+    {
+        self.sticky_key = Some(Arc::new(sticky_key));
+        self
+    }
+
+    /// Sets the percentage of requests sent to Axum when the flag provider returns `None`.
+    /// Defaults to 0.
+    pub fn default_percentage(mut self, percentage: u8) -> Self {
+        self.default_percentage = percentage.min(100);
+        self
+    }
+
+    /// Sends the request to Axum if it is steered there, or returns it to be handled by
+    /// Warp.
+    pub(crate) async fn steer(&self, req: Request) -> Result<Response, Request> {
+        let route = match &self.route_key {
+            Some(route_key) => route_key(&req),
+            None => Some(req.uri().path().to_string()),
+        };
+
+        let percentage = match route {
+            Some(route) => self.flags.axum_percentage(&route).await,
+            None => None,
+        }
+        .unwrap_or(self.default_percentage)
+        .min(100);
+
+        let bucket = match self
+            .sticky_key
+            .as_ref()
+            .and_then(|sticky_key| sticky_key(&req))
+        {
+            Some(key) => self.hasher.hash_one(key) % 100,
+            None => {
+                self.hasher
+                    .hash_one(self.counter.fetch_add(1, Ordering::Relaxed))
+                    % 100
+            }
+        };
+
+        if bucket >= u64::from(percentage) {
+            return Err(req);
+        }
+
+        let mut response = match self.axum.clone().oneshot(req).await {
+            Ok(response) => response,
+            Err(never) => match never {},
+        };
+        response
+            .headers_mut()
+            .insert("x-warpdrive-steering", HeaderValue::from_static("axum"));
+
+        Ok(response)
+    }
+}
+
+impl fmt::Debug for Steering {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Steering")
+            .field("default_percentage", &self.default_percentage)
+            .field("route_key", &self.route_key.is_some())
+            .field("sticky", &self.sticky_key.is_some())
+            .finish()
+    }
+}
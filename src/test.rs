@@ -0,0 +1,498 @@
+//! Test utilities for routers that mix Axum routes and Warp services.
+//!
+//! [`MixedTestClient`] sends requests through a `Router`, including any `WarpService`
+//! fallbacks, and collects the responses for assertions, similar to `warp::test::request()`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use axum::{Router, routing::get};
+//! use warpdrive::{WarpService, test::MixedTestClient};
+//! use warp::Filter;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let warp_routes = warp::path("legacy").map(|| "Hello from Warp!").boxed();
+//!
+//! let app = Router::new()
+//!     .route("/", get(|| async { "Hello from Axum!" }))
+//!     .fallback_service(WarpService::new(warp_routes));
+//!
+//! let client = MixedTestClient::new(app);
+//!
+//! client
+//!     .get("/legacy")
+//!     .send()
+//!     .await
+//!     .assert_status(200)
+//!     .assert_text("Hello from Warp!");
+//! # }
+//! ```
+//!
+//! While a route exists in both stacks, [`assert_parity`] checks that the Warp and Axum
+//! implementations respond identically. [`assert_golden`] records exchanges into golden files
+//! and replays them, to detect behavioral drift after upgrading Warp, Axum, or this crate.
+//! [`MockWarpService`] stands in for a legacy `WarpService` with programmed responses, and
+//! [`TestResponse::snapshot`] renders responses for snapshot tests.
+
+use std::{convert::Infallible, fmt};
+
+use axum::{
+    Router,
+    body::{Body, Bytes},
+    http::{HeaderMap, HeaderName, HeaderValue, Method, Request, StatusCode, header},
+    response::Response,
+};
+use serde::{Serialize, de::DeserializeOwned};
+use tower::{Service, ServiceExt};
+use warp::filters::BoxedFilter;
+
+use crate::WarpService;
+
+mod golden;
+mod mock;
+mod snapshot;
+
+pub use golden::{GoldenMismatch, UPDATE_GOLDEN_ENV, assert_golden, check_golden, record_golden};
+pub use mock::MockWarpService;
+pub use snapshot::snapshot_response;
+
+/// A test client that sends requests through a `Router`.
+///
+/// The client can be cloned cheaply, and each request is sent to a clone of the router.
+#[derive(Debug, Clone)]
+pub struct MixedTestClient {
+    router: Router,
+}
+
+impl MixedTestClient {
+    /// Creates a new `MixedTestClient` for the given router.
+    pub fn new(router: Router) -> Self {
+        MixedTestClient { router }
+    }
+
+    /// Starts building a request with the given method and URI.
+    pub fn request(&self, method: Method, uri: &str) -> TestRequest {
+        TestRequest {
+            router: self.router.clone(),
+            method,
+            uri: uri.to_string(),
+            headers: HeaderMap::new(),
+            body: Bytes::new(),
+        }
+    }
+
+    /// Starts building a `GET` request.
+    pub fn get(&self, uri: &str) -> TestRequest {
+        self.request(Method::GET, uri)
+    }
+
+    /// Starts building a `POST` request.
+    pub fn post(&self, uri: &str) -> TestRequest {
+        self.request(Method::POST, uri)
+    }
+
+    /// Starts building a `PUT` request.
+    pub fn put(&self, uri: &str) -> TestRequest {
+        self.request(Method::PUT, uri)
+    }
+
+    /// Starts building a `PATCH` request.
+    pub fn patch(&self, uri: &str) -> TestRequest {
+        self.request(Method::PATCH, uri)
+    }
+
+    /// Starts building a `DELETE` request.
+    pub fn delete(&self, uri: &str) -> TestRequest {
+        self.request(Method::DELETE, uri)
+    }
+}
+
+/// A request being built by a [`MixedTestClient`].
+#[derive(Debug)]
+#[must_use = "requests do nothing unless sent"]
+pub struct TestRequest {
+    router: Router,
+    method: Method,
+    uri: String,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl TestRequest {
+    /// Adds a header to the request.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the header name or value is invalid.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        let name = HeaderName::try_from(name).expect("invalid header name");
+        let value = HeaderValue::try_from(value).expect("invalid header value");
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Sets the request body.
+    pub fn body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Sets the request body to the JSON serialization of `value`, and sets the
+    /// `content-type` header.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value cannot be serialized.
+    pub fn json(mut self, value: &impl Serialize) -> Self {
+        self.body = serde_json::to_vec(value)
+            .expect("failed to serialize JSON body")
+            .into();
+        self.headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+        self
+    }
+
+    /// Sends the request and collects the response.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the request cannot be built or the response body cannot be read.
+    pub async fn send(self) -> TestResponse {
+        let mut request = Request::builder()
+            .method(self.method)
+            .uri(&self.uri)
+            .body(Body::from(self.body))
+            .expect("invalid request");
+        *request.headers_mut() = self.headers;
+
+        send(self.router, request).await
+    }
+}
+
+async fn send<S>(service: S, request: Request<Body>) -> TestResponse
+where
+    S: Service<Request<Body>, Response = Response, Error = Infallible>,
+{
+    let response = match service.oneshot(request).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    };
+
+    let (parts, body) = response.into_parts();
+    let body = axum::body::to_bytes(body, usize::MAX)
+        .await
+        .expect("failed to read response body");
+
+    TestResponse {
+        status: parts.status,
+        headers: parts.headers,
+        body,
+    }
+}
+
+/// A collected response from a [`MixedTestClient`].
+///
+/// The `assert_*` methods panic with a descriptive message on failure, and return the
+/// response so assertions can be chained.
+#[derive(Debug, Clone)]
+pub struct TestResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl TestResponse {
+    /// Returns the response status.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns the response headers.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Returns the first value of the given header as a string, if present and valid UTF-8.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+
+    /// Returns the response body.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Returns the response body as a string, replacing invalid UTF-8.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// Deserializes the response body as JSON.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the body is not valid JSON for `T`.
+    #[track_caller]
+    pub fn json<T: DeserializeOwned>(&self) -> T {
+        match serde_json::from_slice(&self.body) {
+            Ok(value) => value,
+            Err(err) => panic!("failed to parse JSON body: {}\nbody: {}", err, self.text()),
+        }
+    }
+
+    /// Asserts that the response has the given status.
+    #[track_caller]
+    pub fn assert_status(&self, status: u16) -> &Self {
+        assert_eq!(
+            self.status.as_u16(),
+            status,
+            "unexpected status\nbody: {}",
+            self.text()
+        );
+        self
+    }
+
+    /// Asserts that the response has the given header value.
+    #[track_caller]
+    pub fn assert_header(&self, name: &str, value: &str) -> &Self {
+        assert_eq!(
+            self.header(name),
+            Some(value),
+            "unexpected value for header '{}'",
+            name
+        );
+        self
+    }
+
+    /// Asserts that the response body is the given text.
+    #[track_caller]
+    pub fn assert_text(&self, text: &str) -> &Self {
+        assert_eq!(self.text(), text, "unexpected body");
+        self
+    }
+
+    /// Asserts that the response body is JSON equal to the serialization of `expected`.
+    ///
+    /// Values are compared as JSON, so object key order and formatting are ignored.
+    #[track_caller]
+    pub fn assert_json(&self, expected: &impl Serialize) -> &Self {
+        let expected = serde_json::to_value(expected).expect("failed to serialize expected JSON");
+        let actual: serde_json::Value = self.json();
+        assert_eq!(actual, expected, "unexpected JSON body");
+        self
+    }
+}
+
+/// Runs each request through a Warp filter and an Axum service, returning any differences.
+///
+/// The Warp filter is run through a [`WarpService`], as it would be when mounted in Axum.
+/// Statuses, headers, and bodies are compared. The `date` header is ignored, since it
+/// changes between requests, as are `content-length` and `transfer-encoding`, since the
+/// server sets these when writing the body and the bodies are compared directly.
+///
+/// # Panics
+///
+/// Panics if a response body cannot be read.
+pub async fn check_parity<T, S>(
+    warp_filter: BoxedFilter<(T,)>,
+    axum_service: S,
+    requests: impl IntoIterator<Item = Request<Bytes>>,
+) -> Vec<ParityMismatch>
+where
+    T: warp::Reply + Send + Sync + 'static,
+    S: Service<Request<Body>, Response = Response, Error = Infallible> + Clone,
+{
+    let warp_service = WarpService::new(warp_filter);
+    let mut mismatches = Vec::new();
+
+    for request in requests {
+        let warp_response = send(warp_service.clone(), clone_request(&request)).await;
+        let axum_response = send(axum_service.clone(), clone_request(&request)).await;
+
+        let differences = compare_responses(&warp_response, &axum_response);
+        if !differences.is_empty() {
+            mismatches.push(ParityMismatch {
+                method: request.method().clone(),
+                uri: request.uri().to_string(),
+                differences,
+            });
+        }
+    }
+
+    mismatches
+}
+
+/// Asserts that a Warp filter and an Axum service respond identically to each request.
+///
+/// See [`check_parity`] for how responses are compared.
+///
+/// # Panics
+///
+/// Panics with a report of every difference if any responses differ.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::{Router, body::Bytes, http::Request, routing::get};
+/// use warpdrive::test::assert_parity;
+/// use warp::Filter;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let warp_route = warp::path("hello").map(|| "Hello!").boxed();
+/// let axum_route: Router = Router::new().route("/hello", get(|| async { "Hello!" }));
+///
+/// let requests = [Request::get("/hello").body(Bytes::new()).unwrap()];
+///
+/// assert_parity(warp_route, axum_route, requests).await;
+/// # }
+/// ```
+#[track_caller]
+pub fn assert_parity<T, S>(
+    warp_filter: BoxedFilter<(T,)>,
+    axum_service: S,
+    requests: impl IntoIterator<Item = Request<Bytes>>,
+) -> impl Future<Output = ()>
+where
+    T: warp::Reply + Send + Sync + 'static,
+    S: Service<Request<Body>, Response = Response, Error = Infallible> + Clone,
+{
+    let caller = std::panic::Location::caller();
+    let requests: Vec<_> = requests.into_iter().collect();
+
+    async move {
+        let mismatches = check_parity(warp_filter, axum_service, requests).await;
+
+        if !mismatches.is_empty() {
+            let report: Vec<String> = mismatches.iter().map(ToString::to_string).collect();
+            panic!(
+                "Warp and Axum responses differ (asserted at {}):\n{}",
+                caller,
+                report.join("\n")
+            );
+        }
+    }
+}
+
+/// The differences between the Warp and Axum responses to a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParityMismatch {
+    /// The request method.
+    pub method: Method,
+    /// The request URI.
+    pub uri: String,
+    /// The differences between the responses.
+    pub differences: Vec<ParityDifference>,
+}
+
+impl fmt::Display for ParityMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {}:", self.method, self.uri)?;
+        for difference in &self.differences {
+            writeln!(f, "  {}", difference)?;
+        }
+        Ok(())
+    }
+}
+
+/// A single difference between the Warp and Axum responses to a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParityDifference {
+    /// The statuses differ.
+    Status {
+        /// The Warp response status.
+        warp: StatusCode,
+        /// The Axum response status.
+        axum: StatusCode,
+    },
+    /// The values of a header differ, or the header is missing from one response.
+    Header {
+        /// The header name.
+        name: HeaderName,
+        /// The Warp response values, in order.
+        warp: Vec<HeaderValue>,
+        /// The Axum response values, in order.
+        axum: Vec<HeaderValue>,
+    },
+    /// The bodies differ.
+    Body {
+        /// The Warp response body.
+        warp: Bytes,
+        /// The Axum response body.
+        axum: Bytes,
+    },
+}
+
+impl fmt::Display for ParityDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParityDifference::Status { warp, axum } => {
+                write!(f, "status: warp {} != axum {}", warp, axum)
+            }
+            ParityDifference::Header { name, warp, axum } => {
+                write!(f, "header '{}': warp {:?} != axum {:?}", name, warp, axum)
+            }
+            ParityDifference::Body { warp, axum } => {
+                write!(f, "body: warp {:?} != axum {:?}", warp, axum)
+            }
+        }
+    }
+}
+
+fn clone_request(request: &Request<Bytes>) -> Request<Body> {
+    let mut cloned = Request::new(Body::from(request.body().clone()));
+    *cloned.method_mut() = request.method().clone();
+    *cloned.uri_mut() = request.uri().clone();
+    *cloned.version_mut() = request.version();
+    *cloned.headers_mut() = request.headers().clone();
+    cloned
+}
+
+const IGNORED_HEADERS: [HeaderName; 3] = [
+    header::DATE,
+    header::CONTENT_LENGTH,
+    header::TRANSFER_ENCODING,
+];
+
+fn compare_responses(warp: &TestResponse, axum: &TestResponse) -> Vec<ParityDifference> {
+    let mut differences = Vec::new();
+
+    if warp.status != axum.status {
+        differences.push(ParityDifference::Status {
+            warp: warp.status,
+            axum: axum.status,
+        });
+    }
+
+    let mut names: Vec<&HeaderName> = warp.headers.keys().chain(axum.headers.keys()).collect();
+    names.sort_by_key(|name| name.as_str());
+    names.dedup();
+
+    for name in names {
+        if IGNORED_HEADERS.contains(name) {
+            continue;
+        }
+
+        let warp_values: Vec<_> = warp.headers.get_all(name).iter().cloned().collect();
+        let axum_values: Vec<_> = axum.headers.get_all(name).iter().cloned().collect();
+        if warp_values != axum_values {
+            differences.push(ParityDifference::Header {
+                name: name.clone(),
+                warp: warp_values,
+                axum: axum_values,
+            });
+        }
+    }
+
+    if warp.body != axum.body {
+        differences.push(ParityDifference::Body {
+            warp: warp.body.clone(),
+            axum: axum.body.clone(),
+        });
+    }
+
+    differences
+}
@@ -0,0 +1,330 @@
+use std::{convert::Infallible, fmt, fs, io, path::Path};
+
+use axum::{
+    body::{Body, Bytes},
+    http::{HeaderMap, Request, header::HeaderName},
+    response::Response,
+};
+use serde_json::{Value, json};
+use tower::Service;
+
+use super::{IGNORED_HEADERS, TestResponse, clone_request, send};
+
+/// The version of the golden file format written by [`record_golden`].
+const FORMAT_VERSION: u64 = 1;
+
+/// The environment variable that makes [`assert_golden`] re-record golden files.
+pub const UPDATE_GOLDEN_ENV: &str = "WARPDRIVE_UPDATE_GOLDEN";
+
+/// Sends each request through a service and records the exchanges into a golden file.
+///
+/// The file is JSON, with a format version and the version of this crate that recorded it.
+/// Bodies are stored as text when they are valid UTF-8, and as hex otherwise. Headers that
+/// change between runs, such as `date` and `content-length`, are not recorded.
+///
+/// # Panics
+///
+/// Panics if a response body cannot be read.
+pub async fn record_golden<S>(
+    path: impl AsRef<Path>,
+    service: S,
+    requests: impl IntoIterator<Item = Request<Bytes>>,
+) -> io::Result<()>
+where
+    S: Service<Request<Body>, Response = Response, Error = Infallible> + Clone,
+{
+    let mut exchanges = Vec::new();
+
+    for request in requests {
+        let response = send(service.clone(), clone_request(&request)).await;
+
+        exchanges.push(json!({
+            "request": {
+                "method": request.method().as_str(),
+                "uri": request.uri().to_string(),
+                "headers": headers_to_json(request.headers()),
+                "body": body_to_json(request.body()),
+            },
+            "response": {
+                "status": response.status.as_u16(),
+                "headers": headers_to_json(&response.headers),
+                "body": body_to_json(&response.body),
+            },
+        }));
+    }
+
+    let golden = json!({
+        "format": FORMAT_VERSION,
+        "warpdrive": env!("CARGO_PKG_VERSION"),
+        "exchanges": exchanges,
+    });
+
+    if let Some(parent) = path.as_ref().parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut contents = serde_json::to_string_pretty(&golden).map_err(io::Error::other)?;
+    contents.push('\n');
+    fs::write(path, contents)
+}
+
+/// Replays the requests in a golden file through a service, returning any exchanges whose
+/// responses have drifted from the recording.
+///
+/// # Panics
+///
+/// Panics if a response body cannot be read.
+pub async fn check_golden<S>(path: impl AsRef<Path>, service: S) -> io::Result<Vec<GoldenMismatch>>
+where
+    S: Service<Request<Body>, Response = Response, Error = Infallible> + Clone,
+{
+    let contents = fs::read_to_string(path)?;
+    let golden: Value = serde_json::from_str(&contents).map_err(invalid_data)?;
+
+    match golden["format"].as_u64() {
+        Some(FORMAT_VERSION) => {}
+        format => {
+            return Err(invalid_data(format!(
+                "unsupported golden file format {:?}",
+                format
+            )));
+        }
+    }
+
+    let exchanges = golden["exchanges"]
+        .as_array()
+        .ok_or_else(|| invalid_data("missing exchanges"))?;
+
+    let mut mismatches = Vec::new();
+
+    for exchange in exchanges {
+        let request = request_from_json(&exchange["request"])?;
+        let expected = response_from_json(&exchange["response"])?;
+
+        let actual = send(service.clone(), clone_request(&request)).await;
+
+        let differences = compare(&expected, &actual);
+        if !differences.is_empty() {
+            mismatches.push(GoldenMismatch {
+                method: request.method().to_string(),
+                uri: request.uri().to_string(),
+                differences,
+            });
+        }
+    }
+
+    Ok(mismatches)
+}
+
+/// Asserts that a service responds to the requests in a golden file as recorded.
+///
+/// If the golden file does not exist, or the `WARPDRIVE_UPDATE_GOLDEN` environment variable
+/// is set, the requests are sent and the golden file is recorded instead. Commit the golden
+/// files, and re-record them deliberately when behavior is meant to change.
+///
+/// # Panics
+///
+/// Panics with a report of every difference if any responses have drifted, or if the
+/// golden file cannot be read or written.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use axum::{body::Bytes, http::Request};
+/// use warpdrive::{WarpService, test::assert_golden};
+/// use warp::Filter;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let service = WarpService::new(warp::path("hello").map(|| "Hello!").boxed());
+///
+/// let requests = [Request::get("/hello").body(Bytes::new()).unwrap()];
+///
+/// assert_golden("tests/golden/hello.json", service, requests).await;
+/// # }
+/// ```
+pub async fn assert_golden<S>(
+    path: impl AsRef<Path>,
+    service: S,
+    requests: impl IntoIterator<Item = Request<Bytes>>,
+) where
+    S: Service<Request<Body>, Response = Response, Error = Infallible> + Clone,
+{
+    let path = path.as_ref();
+
+    if !path.exists() || std::env::var_os(UPDATE_GOLDEN_ENV).is_some() {
+        if let Err(err) = record_golden(path, service, requests).await {
+            panic!("failed to record golden file {}: {}", path.display(), err);
+        }
+        return;
+    }
+
+    let mismatches = match check_golden(path, service).await {
+        Ok(mismatches) => mismatches,
+        Err(err) => panic!("failed to read golden file {}: {}", path.display(), err),
+    };
+
+    if !mismatches.is_empty() {
+        let report: Vec<String> = mismatches.iter().map(ToString::to_string).collect();
+        panic!(
+            "responses have drifted from golden file {} (set {} to re-record):\n{}",
+            path.display(),
+            UPDATE_GOLDEN_ENV,
+            report.join("\n")
+        );
+    }
+}
+
+/// The differences between a recorded exchange and the replayed response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenMismatch {
+    /// The request method.
+    pub method: String,
+    /// The request URI.
+    pub uri: String,
+    /// A description of each difference.
+    pub differences: Vec<String>,
+}
+
+impl fmt::Display for GoldenMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {}:", self.method, self.uri)?;
+        for difference in &self.differences {
+            writeln!(f, "  {}", difference)?;
+        }
+        Ok(())
+    }
+}
+
+struct RecordedResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+fn compare(expected: &RecordedResponse, actual: &TestResponse) -> Vec<String> {
+    let mut differences = Vec::new();
+
+    if expected.status != actual.status.as_u16() {
+        differences.push(format!(
+            "status: recorded {} != actual {}",
+            expected.status,
+            actual.status.as_u16()
+        ));
+    }
+
+    let actual_headers = header_pairs(&actual.headers);
+    if expected.headers != actual_headers {
+        differences.push(format!(
+            "headers: recorded {:?} != actual {:?}",
+            expected.headers, actual_headers
+        ));
+    }
+
+    if expected.body != actual.body {
+        differences.push(format!(
+            "body: recorded {:?} != actual {:?}",
+            Bytes::from(expected.body.clone()),
+            actual.body
+        ));
+    }
+
+    differences
+}
+
+fn header_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    let mut pairs: Vec<_> = headers
+        .iter()
+        .filter(|(name, _)| !IGNORED_HEADERS.contains(name))
+        .map(|(name, value)| {
+            let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+            (name.as_str().to_string(), value)
+        })
+        .collect();
+    // Sort by name only, keeping the order of repeated headers.
+    pairs.sort_by(|a, b| a.0.cmp(&b.0));
+    pairs
+}
+
+fn headers_to_json(headers: &HeaderMap) -> Value {
+    header_pairs(headers)
+        .into_iter()
+        .map(|(name, value)| json!([name, value]))
+        .collect()
+}
+
+fn headers_from_json(value: &Value) -> io::Result<Vec<(String, String)>> {
+    value
+        .as_array()
+        .ok_or_else(|| invalid_data("headers must be an array"))?
+        .iter()
+        .map(|pair| match (pair[0].as_str(), pair[1].as_str()) {
+            (Some(name), Some(value)) => Ok((name.to_string(), value.to_string())),
+            _ => Err(invalid_data("header must be a [name, value] pair")),
+        })
+        .collect()
+}
+
+fn body_to_json(body: &[u8]) -> Value {
+    match std::str::from_utf8(body) {
+        Ok(text) => json!({ "text": text }),
+        Err(_) => {
+            let hex: String = body.iter().map(|byte| format!("{:02x}", byte)).collect();
+            json!({ "hex": hex })
+        }
+    }
+}
+
+fn body_from_json(value: &Value) -> io::Result<Vec<u8>> {
+    if let Some(text) = value["text"].as_str() {
+        return Ok(text.as_bytes().to_vec());
+    }
+
+    let hex = value["hex"]
+        .as_str()
+        .ok_or_else(|| invalid_data("body must have a text or hex field"))?;
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| invalid_data("invalid hex body"))
+        })
+        .collect()
+}
+
+fn request_from_json(value: &Value) -> io::Result<Request<Bytes>> {
+    let method = value["method"]
+        .as_str()
+        .ok_or_else(|| invalid_data("request must have a method"))?;
+    let uri = value["uri"]
+        .as_str()
+        .ok_or_else(|| invalid_data("request must have a uri"))?;
+
+    let mut builder = Request::builder().method(method).uri(uri);
+    for (name, value) in headers_from_json(&value["headers"])? {
+        builder = builder.header(HeaderName::try_from(name).map_err(invalid_data)?, value);
+    }
+
+    builder
+        .body(body_from_json(&value["body"])?.into())
+        .map_err(invalid_data)
+}
+
+fn response_from_json(value: &Value) -> io::Result<RecordedResponse> {
+    let status = value["status"]
+        .as_u64()
+        .and_then(|status| u16::try_from(status).ok())
+        .ok_or_else(|| invalid_data("response must have a status"))?;
+
+    Ok(RecordedResponse {
+        status,
+        headers: headers_from_json(&value["headers"])?,
+        body: body_from_json(&value["body"])?,
+    })
+}
+
+fn invalid_data(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
@@ -0,0 +1,127 @@
+use std::{
+    convert::Infallible,
+    fmt,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{Method, StatusCode, Uri},
+    response::{IntoResponse, Response},
+};
+use futures::Future;
+use tower::Service;
+
+use crate::DualReply;
+
+type Matcher = Box<dyn Fn(&Request) -> bool + Send + Sync>;
+
+/// A stand-in for a [`WarpService`](crate::WarpService) that returns programmed responses.
+///
+/// `MockWarpService` has the same `Service<Request>` signature as `WarpService`, so Axum code
+/// that depends on a legacy fallback can be tested without constructing Warp filters. Each
+/// request is answered by the first matching rule, or with an empty `404 Not Found` response,
+/// like Warp's default rejection. Clones share their rules and received requests.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::{Router, http::Method};
+/// use warpdrive::{DualReply, test::{MixedTestClient, MockWarpService}};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let legacy = MockWarpService::new()
+///     .respond(Method::GET, "/users/1", DualReply::text("Alice"));
+///
+/// let app = Router::new().fallback_service(legacy.clone());
+///
+/// MixedTestClient::new(app)
+///     .get("/users/1")
+///     .send()
+///     .await
+///     .assert_text("Alice");
+///
+/// assert_eq!(legacy.received(), [(Method::GET, "/users/1".parse().unwrap())]);
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct MockWarpService {
+    rules: Arc<Mutex<Vec<(Matcher, DualReply)>>>,
+    received: Arc<Mutex<Vec<(Method, Uri)>>>,
+}
+
+impl MockWarpService {
+    /// Creates a `MockWarpService` with no rules.
+    pub fn new() -> Self {
+        MockWarpService::default()
+    }
+
+    /// Responds to requests with the given method and path.
+    pub fn respond(self, method: Method, path: &str, reply: DualReply) -> Self {
+        let path = path.to_string();
+        self.respond_when(
+            move |req| req.method() == method && req.uri().path() == path,
+            reply,
+        )
+    }
+
+    /// Responds to requests for which `matcher` returns `true`.
+    pub fn respond_when<F>(self, matcher: F, reply: DualReply) -> Self
+    where
+        F: Fn(&Request) -> bool + Send + Sync + 'static,
+    {
+        self.rules.lock().unwrap().push((Box::new(matcher), reply));
+        self
+    }
+
+    /// Returns the method and URI of each request received so far, in order.
+    pub fn received(&self) -> Vec<(Method, Uri)> {
+        self.received.lock().unwrap().clone()
+    }
+
+    fn reply(&self, req: &Request) -> Response {
+        self.received
+            .lock()
+            .unwrap()
+            .push((req.method().clone(), req.uri().clone()));
+
+        let rules = self.rules.lock().unwrap();
+        match rules.iter().find(|(matcher, _)| matcher(req)) {
+            Some((_, reply)) => reply.clone().into_response(),
+            None => {
+                let mut response = Response::new(Body::empty());
+                *response.status_mut() = StatusCode::NOT_FOUND;
+                response
+            }
+        }
+    }
+}
+
+impl fmt::Debug for MockWarpService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockWarpService")
+            .field("rules", &self.rules.lock().unwrap().len())
+            .field("received", &self.received.lock().unwrap())
+            .finish()
+    }
+}
+
+impl Service<Request> for MockWarpService {
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let response = self.reply(&req);
+
+        Box::pin(async move { Ok(response) })
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt::Write;
+
+use axum::{http::header, response::Response};
+
+use super::{IGNORED_HEADERS, TestResponse};
+
+impl TestResponse {
+    /// Renders the response into a stable text format for snapshot tests.
+    ///
+    /// The snapshot contains the status line, the headers sorted by name, and the body.
+    /// Headers that change between runs or depend on the server, such as `date` and
+    /// `content-length`, are omitted. JSON bodies are pretty-printed with sorted keys, other
+    /// UTF-8 bodies are included as is, and binary bodies are rendered as hex.
+    ///
+    /// The output is plain text, so it can be used with `insta::assert_snapshot!` or
+    /// compared against a checked-in file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use axum::Router;
+    /// use warpdrive::{WarpService, test::MixedTestClient};
+    /// use warp::Filter;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let filter = warp::path("hello").map(|| "Hello!").boxed();
+    /// let client = MixedTestClient::new(Router::new().fallback_service(WarpService::new(filter)));
+    ///
+    /// let snapshot = client.get("/hello").send().await.snapshot();
+    ///
+    /// assert_eq!(
+    ///     snapshot,
+    ///     "200 OK\ncontent-type: text/plain; charset=utf-8\n\nHello!\n"
+    /// );
+    /// # }
+    /// ```
+    pub fn snapshot(&self) -> String {
+        let mut snapshot = format!("{}\n", self.status);
+
+        let mut headers: Vec<_> = self
+            .headers
+            .iter()
+            .filter(|(name, _)| !IGNORED_HEADERS.contains(name))
+            .collect();
+        // The sort is stable, so repeated headers keep their order.
+        headers.sort_by_key(|(name, _)| name.as_str());
+
+        for (name, value) in headers {
+            let _ = writeln!(
+                snapshot,
+                "{}: {}",
+                name,
+                String::from_utf8_lossy(value.as_bytes())
+            );
+        }
+
+        snapshot.push('\n');
+        snapshot.push_str(&self.snapshot_body());
+        snapshot
+    }
+
+    fn snapshot_body(&self) -> String {
+        if self.body.is_empty() {
+            return String::new();
+        }
+
+        let is_json = self
+            .header(header::CONTENT_TYPE.as_str())
+            .is_some_and(|content_type| content_type.contains("json"));
+
+        if is_json && let Ok(value) = serde_json::from_slice::<serde_json::Value>(&self.body) {
+            return format!("{:#}\n", value);
+        }
+
+        match std::str::from_utf8(&self.body) {
+            Ok(text) if text.ends_with('\n') => text.to_string(),
+            Ok(text) => format!("{}\n", text),
+            Err(_) => {
+                let mut hex = format!("<{} bytes>\n", self.body.len());
+                for line in self.body.chunks(16) {
+                    let line: Vec<String> =
+                        line.iter().map(|byte| format!("{:02x}", byte)).collect();
+                    let _ = writeln!(hex, "{}", line.join(" "));
+                }
+                hex
+            }
+        }
+    }
+}
+
+/// Collects a response and renders it with [`TestResponse::snapshot`].
+///
+/// # Panics
+///
+/// Panics if the response body cannot be read.
+pub async fn snapshot_response(response: Response) -> String {
+    let (parts, body) = response.into_parts();
+    let body = axum::body::to_bytes(body, usize::MAX)
+        .await
+        .expect("failed to read response body");
+
+    TestResponse {
+        status: parts.status,
+        headers: parts.headers,
+        body,
+    }
+    .snapshot()
+}
@@ -0,0 +1,67 @@
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, UNIX_EPOCH},
+};
+
+use axum::{
+    body::Body as AxumBody,
+    extract::{ConnectInfo, Request as AxumRequest},
+};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{AccessLog, PrefixConfig, WarpService, access_log::clf_time};
+
+#[tokio::test]
+async fn test_access_log_lines() {
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let sink = lines.clone();
+    let filter = warp::any().map(|| Box::new("hello") as Box<dyn warp::Reply + Send + Sync>);
+    let service = WarpService::new(filter.boxed())
+        .with_access_log(AccessLog::combined(move |line| {
+            sink.lock().unwrap().push(line.to_string())
+        }))
+        .with_prefix_config("/health", PrefixConfig::new().access_log(false));
+
+    let req = AxumRequest::builder()
+        .uri("/users?page=2")
+        .header("user-agent", "curl/8.0")
+        .extension(ConnectInfo("10.0.0.1:4000".parse::<SocketAddr>().unwrap()))
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.clone().oneshot(req).await.unwrap();
+
+    // The line is written once the body has been sent.
+    assert!(lines.lock().unwrap().is_empty());
+    axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+
+    let line = lines.lock().unwrap().remove(0);
+    let (start, rest) = line.split_once(" [").unwrap();
+    assert_eq!(start, "10.0.0.1 - -");
+    assert_eq!(
+        rest.split_once("] ").unwrap().1,
+        "\"GET /users?page=2 HTTP/1.1\" 200 5 \"-\" \"curl/8.0\""
+    );
+
+    let req = AxumRequest::builder()
+        .uri("/health")
+        .body(AxumBody::empty())
+        .unwrap();
+    drop(service.oneshot(req).await.unwrap());
+    assert!(lines.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_clf_time() {
+    assert_eq!(
+        clf_time(UNIX_EPOCH + Duration::from_secs(971_186_136)),
+        "10/Oct/2000:13:55:36 +0000"
+    );
+    assert_eq!(
+        clf_time(UNIX_EPOCH + Duration::from_secs(1_709_210_096)),
+        "29/Feb/2024:12:34:56 +0000"
+    );
+}
@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+use axum::{
+    Router,
+    body::Body as AxumBody,
+    extract::Request as AxumRequest,
+    http::StatusCode,
+    middleware::{self, Next},
+    response::Response,
+    routing::get,
+};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{
+    Migration, MigrationPhase, UsageReport, WarpService,
+    admin::{AdminRouter, Percentage, Switch},
+};
+
+async fn require_token(req: AxumRequest, next: Next) -> Result<Response, StatusCode> {
+    match req.headers().get("authorization") {
+        Some(token) if token == "Bearer secret" => Ok(next.run(req).await),
+        _ => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+async fn send(app: &Router, method: &str, uri: &str, body: &'static str) -> (StatusCode, String) {
+    let req = AxumRequest::builder()
+        .method(method)
+        .uri(uri)
+        .header("authorization", "Bearer secret")
+        .header("content-type", "application/json")
+        .body(AxumBody::from(body))
+        .unwrap();
+    let response = app.clone().oneshot(req).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn test_admin_router_exposes_config_and_usage() {
+    let report = UsageReport::new();
+    let service = WarpService::new(warp::path("users").map(|| "users").boxed())
+        .with_timeout(Duration::from_secs(5))
+        .with_usage_report(report.clone());
+
+    let app: Router = Router::new()
+        .nest(
+            "/_warpdrive",
+            AdminRouter::new()
+                .service("users", &service)
+                .usage_report("users", report)
+                .into_router(middleware::from_fn(require_token)),
+        )
+        .fallback_service(service);
+
+    send(&app, "GET", "/users", "").await;
+
+    let (status, body) = send(&app, "GET", "/_warpdrive/config", "").await;
+    assert_eq!(status, StatusCode::OK);
+    let config: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(config["services"]["users"]["timeout_ms"], 5000);
+    assert_eq!(config["services"]["users"]["usage_report"], true);
+
+    let (_, body) = send(&app, "GET", "/_warpdrive/usage", "").await;
+    let usage: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(usage["usage"]["users"]["routes"][0]["route"], "GET /users");
+
+    // Requests without the token are rejected by the auth layer.
+    let req = AxumRequest::builder()
+        .uri("/_warpdrive/config")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = app.oneshot(req).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_admin_router_controls() {
+    let switch = Switch::new(true);
+    let percentage = Percentage::new(5);
+
+    let app: Router = AdminRouter::new()
+        .switch("legacy-enabled", switch.clone())
+        .percentage("canary", percentage.clone())
+        .into_router(middleware::from_fn(require_token));
+
+    assert_eq!(
+        send(&app, "PUT", "/switches/legacy-enabled", "false").await,
+        (StatusCode::OK, r#"{"legacy-enabled":false}"#.to_string())
+    );
+    assert!(!switch.is_on());
+    assert_eq!(
+        send(&app, "GET", "/switches", "").await.1,
+        r#"{"switches":{"legacy-enabled":false}}"#
+    );
+
+    assert_eq!(
+        send(&app, "PUT", "/percentages/canary", "25").await.0,
+        StatusCode::OK
+    );
+    assert_eq!(percentage.get(), 25);
+    assert_eq!(
+        send(&app, "PUT", "/percentages/canary", "101").await.0,
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+    assert_eq!(percentage.get(), 25);
+
+    assert_eq!(
+        send(&app, "PUT", "/switches/unknown", "true").await.0,
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn test_admin_router_exposes_migration_metrics() {
+    let axum = Router::new()
+        .route("/api/users", get(|| async { "axum" }))
+        .route(
+            "/api/orders",
+            get(|| async { (StatusCode::INTERNAL_SERVER_ERROR, "axum") }),
+        );
+    let migration = Migration::new(axum)
+        .phase("/api/users", MigrationPhase::Shadow)
+        .phase("/api/orders", MigrationPhase::Canary(100));
+    let filter = warp::any().map(|| Box::new("warp") as Box<dyn warp::Reply + Send + Sync>);
+    let service = WarpService::new(filter.boxed()).with_migration(migration.clone());
+
+    let app: Router = Router::new()
+        .nest(
+            "/_warpdrive",
+            AdminRouter::new()
+                .migration("api", migration.clone())
+                .into_router(middleware::from_fn(require_token)),
+        )
+        .fallback_service(service);
+
+    send(&app, "GET", "/api/users", "").await;
+    send(&app, "GET", "/api/orders", "").await;
+    while migration.shadow_counts().iter().all(|c| c.compared == 0) {
+        tokio::task::yield_now().await;
+    }
+
+    let (status, body) = send(&app, "GET", "/_warpdrive/migrations/api/shadow", "").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body,
+        r#"{"shadow":[{"body":1,"compared":1,"header":0,"route":"/api/users","status":0}]}"#
+    );
+
+    let (status, body) = send(&app, "GET", "/_warpdrive/migrations/api/canary", "").await;
+    assert_eq!(status, StatusCode::OK);
+    let canary: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let orders = &canary["canary"][0];
+    assert_eq!(orders["route"], "/api/orders");
+    assert_eq!(orders["axum"]["requests"], 1);
+    assert_eq!(orders["axum"]["success_ratio"], 0.0);
+    assert_eq!(orders["axum"]["statuses"]["500"], 1);
+    assert_eq!(orders["axum"]["latency"]["count"], 1);
+    assert_eq!(orders["warp"]["requests"], 0);
+    assert_eq!(orders["warp"]["success_ratio"], serde_json::Value::Null);
+
+    assert_eq!(
+        send(&app, "GET", "/_warpdrive/migrations/unknown/shadow", "")
+            .await
+            .0,
+        StatusCode::NOT_FOUND
+    );
+}
@@ -0,0 +1,158 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{ErrorRateAlarm, WarpService, admin::Switch};
+
+async fn call(service: &WarpService, uri: &str) {
+    let req = AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    service.clone().oneshot(req).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_error_rate_alarm() {
+    let failing = Arc::new(Mutex::new(true));
+    let filter = warp::any().map({
+        let failing = Arc::clone(&failing);
+        move || {
+            let status = if *failing.lock().unwrap() {
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR
+            } else {
+                warp::http::StatusCode::OK
+            };
+            Box::new(warp::reply::with_status("warp", status)) as Box<dyn warp::Reply + Send + Sync>
+        }
+    });
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let switch = Switch::new(false);
+    let alarm = ErrorRateAlarm::new(0.5)
+        .window(Duration::from_secs(60))
+        .min_requests(4)
+        .on_alarm({
+            let events = Arc::clone(&events);
+            move |route, _, raised| events.lock().unwrap().push((route.to_string(), raised))
+        })
+        .switch(switch.clone());
+    let service = WarpService::new(filter.boxed()).with_error_rate_alarm(alarm);
+
+    for _ in 0..3 {
+        call(&service, "/users").await;
+    }
+    assert!(!switch.is_on());
+
+    call(&service, "/users").await;
+    assert!(switch.is_on());
+    assert_eq!(*events.lock().unwrap(), vec![("*".to_string(), true)]);
+
+    *failing.lock().unwrap() = false;
+    for _ in 0..4 {
+        call(&service, "/users").await;
+    }
+    assert!(switch.is_on());
+    call(&service, "/users").await;
+    assert!(!switch.is_on());
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![("*".to_string(), true), ("*".to_string(), false)]
+    );
+}
+
+#[tokio::test]
+async fn test_error_rate_alarm_latches_without_requests() {
+    let failing = Arc::new(Mutex::new(true));
+    let filter = warp::any().map({
+        let failing = Arc::clone(&failing);
+        move || {
+            let status = if *failing.lock().unwrap() {
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR
+            } else {
+                warp::http::StatusCode::OK
+            };
+            Box::new(warp::reply::with_status("warp", status)) as Box<dyn warp::Reply + Send + Sync>
+        }
+    });
+
+    let switch = Switch::new(false);
+    let alarm = ErrorRateAlarm::new(0.5)
+        .window(Duration::from_millis(20))
+        .min_requests(2)
+        .switch(switch.clone());
+    let service = WarpService::new(filter.boxed()).with_error_rate_alarm(alarm);
+
+    call(&service, "/users").await;
+    call(&service, "/users").await;
+    assert!(switch.is_on());
+
+    // The failures leave the window, but the alarm is only re-evaluated on the next request.
+    *failing.lock().unwrap() = false;
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    assert!(switch.is_on());
+
+    call(&service, "/users").await;
+    assert!(!switch.is_on());
+}
+
+#[tokio::test]
+async fn test_error_rate_alarm_route_key() {
+    let filter = warp::path::full().map(|path: warp::path::FullPath| {
+        let status = if path.as_str().starts_with("/users") {
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR
+        } else {
+            warp::http::StatusCode::OK
+        };
+        Box::new(warp::reply::with_status("warp", status)) as Box<dyn warp::Reply + Send + Sync>
+    });
+
+    let raised = Arc::new(Mutex::new(Vec::new()));
+    let alarm = ErrorRateAlarm::new(0.5)
+        .min_requests(2)
+        .route_key(|req| req.uri().path().split('/').nth(1).unwrap().to_string())
+        .on_alarm({
+            let raised = Arc::clone(&raised);
+            move |route, _, _| raised.lock().unwrap().push(route.to_string())
+        });
+    let service = WarpService::new(filter.boxed()).with_error_rate_alarm(alarm);
+
+    // Requests for different ids of a route are counted together.
+    call(&service, "/users/1").await;
+    call(&service, "/users/2").await;
+    call(&service, "/orders/1").await;
+    call(&service, "/orders/2").await;
+
+    assert_eq!(*raised.lock().unwrap(), ["users"]);
+}
+
+#[test]
+fn test_idle_routes_are_pruned() {
+    let alarm = ErrorRateAlarm::new(0.5)
+        .window(Duration::from_millis(10))
+        .route_key(|req| req.uri().path().to_string())
+        .max_routes(2);
+    let ok = || {
+        Ok(axum::response::IntoResponse::into_response(
+            axum::http::StatusCode::NOT_FOUND,
+        ))
+    };
+
+    for i in 0..4 {
+        alarm.record(format!("/missing/{}", i), &ok());
+    }
+    // Routes past the limit are tracked together.
+    assert!(format!("{:?}", alarm).contains("routes: 3 "));
+    assert_eq!(alarm.error_rate("(other)"), Some(0.0));
+
+    // Routes without requests in the window are pruned to make room for new ones.
+    std::thread::sleep(Duration::from_millis(20));
+    alarm.record("/users".to_string(), &ok());
+    assert!(format!("{:?}", alarm).contains("routes: 1 "));
+    assert_eq!(alarm.error_rate("/users"), Some(0.0));
+}
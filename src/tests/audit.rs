@@ -0,0 +1,74 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{Audit, AuditRecord, WarpService};
+
+fn service(records: Arc<Mutex<Vec<AuditRecord>>>) -> WarpService {
+    let echo = warp::path("echo")
+        .and(warp::body::bytes())
+        .map(|body: warp::hyper::body::Bytes| body.to_vec());
+    let slow = warp::path("slow").and_then(|| async {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        Ok::<_, warp::Rejection>(Vec::new())
+    });
+    let filter = echo
+        .or(slow)
+        .unify()
+        .map(|body| Box::new(body) as Box<dyn warp::Reply + Send + Sync>);
+
+    let audit = Audit::new(move |record| records.lock().unwrap().push(record.clone()))
+        .header("x-user-id")
+        .max_excerpt(5);
+    WarpService::new(filter.boxed())
+        .with_timeout(Duration::from_millis(20))
+        .with_audit(audit)
+}
+
+#[tokio::test]
+async fn test_audit_records_requests() {
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let service = service(records.clone());
+
+    let req = AxumRequest::builder()
+        .method("POST")
+        .uri("/echo?verbose=1")
+        .header("x-user-id", "42")
+        .header("authorization", "secret")
+        .body(AxumBody::from("hello world"))
+        .unwrap();
+    let response = service.clone().oneshot(req).await.unwrap();
+
+    // The hook is called once the response body has been sent.
+    assert!(records.lock().unwrap().is_empty());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "hello world");
+
+    let record = records.lock().unwrap().remove(0);
+    assert_eq!(record.method, "POST");
+    assert_eq!(record.uri, "/echo?verbose=1");
+    assert_eq!(record.status, StatusCode::OK);
+    assert_eq!(record.headers.len(), 1);
+    assert_eq!(record.headers["x-user-id"], "42");
+    assert_eq!(record.request_body, "hello");
+    assert!(record.request_body_truncated);
+    assert_eq!(record.response_body, "hello");
+    assert!(record.response_body_truncated);
+
+    let req = AxumRequest::builder()
+        .uri("/slow")
+        .body(AxumBody::empty())
+        .unwrap();
+    service.oneshot(req).await.unwrap();
+
+    let record = records.lock().unwrap().remove(0);
+    assert_eq!(record.status, StatusCode::GATEWAY_TIMEOUT);
+    assert!(record.response_body.is_empty());
+}
@@ -0,0 +1,34 @@
+use axum07::{Router, body::Body as Axum07Body, extract::Request, routing::get};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::WarpService;
+
+#[tokio::test]
+async fn test_warp_service_in_axum07_router() {
+    let warp_routes = warp::path("echo")
+        .and(warp::body::bytes())
+        .map(|body: warp::hyper::body::Bytes| body.to_vec())
+        .boxed();
+
+    let app: Router = Router::new()
+        .route("/", get(|| async { "Hello from Axum 0.7!" }))
+        .fallback_service(WarpService::new(warp_routes).into_axum07());
+
+    let request = Request::post("/echo")
+        .body(Axum07Body::from("Hello from Warp!"))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body = axum07::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "Hello from Warp!");
+
+    let request = Request::get("/").body(Axum07Body::empty()).unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let body = axum07::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "Hello from Axum 0.7!");
+}
@@ -0,0 +1,12 @@
+use crate::{WarpService, bench};
+
+#[tokio::test]
+async fn test_bench_workloads_agree() {
+    let service = WarpService::new(bench::echo_filter());
+
+    let bridged = bench::call_service(&service, bench::request(1024)).await;
+    let direct = bench::call_warp(&bench::echo_filter(), bench::warp_request(1024)).await;
+
+    assert_eq!(bridged.len(), 1024);
+    assert_eq!(bridged, direct);
+}
@@ -0,0 +1,60 @@
+use std::{
+    sync::{Arc, Mutex},
+    thread::{self, ThreadId},
+};
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{Failover, PrefixConfig, WarpService};
+
+#[tokio::test]
+async fn test_blocking_runs_filter_off_the_runtime_thread() {
+    let threads: Arc<Mutex<Vec<(String, ThreadId)>>> = Arc::default();
+    let filter = warp::path::full().map({
+        let threads = Arc::clone(&threads);
+        move |path: warp::path::FullPath| {
+            threads
+                .lock()
+                .unwrap()
+                .push((path.as_str().to_string(), thread::current().id()));
+            Box::new("rendered") as Box<dyn warp::Reply + Send + Sync>
+        }
+    });
+    let service = WarpService::new(filter.boxed())
+        .with_prefix_config("/reports", PrefixConfig::new().blocking(true));
+
+    for uri in ["/reports/daily", "/users"] {
+        let req = AxumRequest::builder()
+            .uri(uri)
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = service.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let threads = threads.lock().unwrap();
+    let runtime = thread::current().id();
+    assert_eq!(threads[0].0, "/reports/daily");
+    assert_ne!(threads[0].1, runtime);
+    assert_eq!(threads[1], ("/users".to_string(), runtime));
+}
+
+#[tokio::test]
+async fn test_blocking_panics_still_fail_over() {
+    let filter = warp::any().map(|| -> Box<dyn warp::Reply + Send + Sync> { panic!("boom") });
+    let service = WarpService::new(filter.boxed())
+        .with_blocking()
+        .with_failover(Failover::new(axum::routing::any(|| async { "axum" })));
+
+    let req = AxumRequest::builder()
+        .uri("/")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.oneshot(req).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "axum");
+}
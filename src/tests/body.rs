@@ -0,0 +1,72 @@
+use axum::body::Body as AxumBody;
+use warp::hyper::body::{Body as WarpBody, HttpBody as _};
+
+use crate::{
+    Error,
+    body::{tee, to_axum_body, to_axum_body_buffered, to_warp_body, to_warp_body_buffered},
+};
+
+#[tokio::test]
+async fn test_streaming_body_conversion() {
+    let warp_body = to_warp_body(AxumBody::from("Hello"));
+    let bytes = warp::hyper::body::to_bytes(warp_body).await.unwrap();
+    assert_eq!(bytes, "Hello");
+
+    let axum_body = to_axum_body(WarpBody::from("World"));
+    let bytes = axum::body::to_bytes(axum_body, usize::MAX).await.unwrap();
+    assert_eq!(bytes, "World");
+}
+
+#[tokio::test]
+async fn test_buffered_body_conversion() {
+    let chunks = futures::stream::iter([Ok::<_, std::convert::Infallible>("Hel"), Ok("lo")]);
+
+    let warp_body = to_warp_body_buffered(AxumBody::from_stream(chunks), 1024)
+        .await
+        .unwrap();
+    assert_eq!(warp_body.size_hint().exact(), Some(5));
+
+    let axum_body = to_axum_body_buffered(WarpBody::from("Hello"), 1024)
+        .await
+        .unwrap();
+    assert_eq!(http_body::Body::size_hint(&axum_body).exact(), Some(5));
+
+    let err = to_axum_body_buffered(WarpBody::from("Hello"), 4)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Conversion(_)));
+}
+
+fn chunked(chunks: &'static [&'static str]) -> AxumBody {
+    AxumBody::from_stream(futures::stream::iter(
+        chunks
+            .iter()
+            .map(|chunk| Ok::<_, std::convert::Infallible>(*chunk)),
+    ))
+}
+
+#[tokio::test]
+async fn test_tee_duplicates_streaming_body() {
+    let (primary, shadow) = tee(chunked(&["Hel", "lo, ", "world"]), 1024);
+
+    let (primary, shadow) = futures::join!(
+        axum::body::to_bytes(primary, usize::MAX),
+        axum::body::to_bytes(shadow, usize::MAX)
+    );
+    assert_eq!(primary.unwrap(), "Hello, world");
+    assert_eq!(shadow.unwrap(), "Hello, world");
+}
+
+#[tokio::test]
+async fn test_tee_shadow_fails_without_affecting_primary() {
+    // The shadow is not read while the primary is, so it falls behind.
+    let (primary, shadow) = tee(chunked(&["Hel", "lo, ", "world"]), 8);
+    let primary = axum::body::to_bytes(primary, usize::MAX).await.unwrap();
+    assert_eq!(primary, "Hello, world");
+    assert!(axum::body::to_bytes(shadow, usize::MAX).await.is_err());
+
+    // The primary was dropped before reading the whole body.
+    let (primary, shadow) = tee(chunked(&["Hel", "lo"]), 1024);
+    drop(primary);
+    assert!(axum::body::to_bytes(shadow, usize::MAX).await.is_err());
+}
@@ -0,0 +1,62 @@
+use std::{future::poll_fn, sync::Arc, task::Poll};
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode};
+use tokio::sync::Semaphore;
+use tower::{Service, ServiceExt};
+use warp::Filter;
+
+use crate::WarpService;
+
+fn request() -> AxumRequest {
+    AxumRequest::builder()
+        .uri("/")
+        .body(AxumBody::empty())
+        .unwrap()
+}
+
+async fn settle() {
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+    }
+}
+
+#[tokio::test]
+async fn test_buffer_applies_backpressure() {
+    let release = Arc::new(Semaphore::new(0));
+    let filter = warp::any().and_then({
+        let release = Arc::clone(&release);
+        move || {
+            let release = Arc::clone(&release);
+            async move {
+                release.acquire().await.unwrap().forget();
+                Ok::<_, warp::Rejection>("done")
+            }
+        }
+    });
+    let service = WarpService::new(filter.boxed()).with_buffer(1, 1);
+
+    // The first request runs, and the others wait for it, filling the queue.
+    let first = tokio::spawn(service.clone().oneshot(request()));
+    settle().await;
+    let waiting: Vec<_> = (0..2)
+        .map(|_| tokio::spawn(service.clone().oneshot(request())))
+        .collect();
+    settle().await;
+
+    let mut next = service.clone();
+    let ready = poll_fn(|cx| Poll::Ready(next.poll_ready(cx).is_ready())).await;
+    assert!(!ready);
+
+    release.add_permits(1);
+    assert_eq!(first.await.unwrap().unwrap().status(), StatusCode::OK);
+    settle().await;
+    let ready = poll_fn(|cx| Poll::Ready(next.poll_ready(cx).is_ready())).await;
+    assert!(ready);
+
+    release.add_permits(3);
+    let response = next.call(request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    for waiting in waiting {
+        assert_eq!(waiting.await.unwrap().unwrap().status(), StatusCode::OK);
+    }
+}
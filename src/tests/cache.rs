@@ -0,0 +1,202 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{ResponseCache, WarpService};
+
+/// A service that counts calls to the Warp filter and echoes the call number.
+fn counting_service(
+    cache: ResponseCache,
+) -> (WarpService<warp::reply::Response>, Arc<AtomicUsize>) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&calls);
+
+    let filter = warp::any()
+        .and(warp::path::full())
+        .map(move |path: warp::path::FullPath| {
+            let call = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            let mut response = warp::reply::Reply::into_response(call.to_string());
+            if path.as_str() == "/vary" {
+                response.headers_mut().insert(
+                    "vary",
+                    warp::http::HeaderValue::from_static("accept-language"),
+                );
+            }
+            if path.as_str() == "/private" {
+                response.headers_mut().insert(
+                    "cache-control",
+                    warp::http::HeaderValue::from_static("private"),
+                );
+            }
+            if path.as_str() == "/public" {
+                response.headers_mut().insert(
+                    "cache-control",
+                    warp::http::HeaderValue::from_static("public, max-age=60"),
+                );
+            }
+            response
+        });
+
+    (WarpService::new(filter.boxed()).with_cache(cache), calls)
+}
+
+async fn get(
+    service: &WarpService<warp::reply::Response>,
+    uri: &str,
+    language: &str,
+) -> (String, String) {
+    let request = AxumRequest::get(uri)
+        .header("accept-language", language)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.clone().oneshot(request).await.unwrap();
+    let cache = response
+        .headers()
+        .get("x-warpdrive-cache")
+        .map(|value| value.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+
+    (String::from_utf8(body.to_vec()).unwrap(), cache)
+}
+
+#[tokio::test]
+async fn test_cache_hits_by_path_and_query() {
+    let (service, calls) = counting_service(ResponseCache::new(Duration::from_secs(60), 1024));
+
+    assert_eq!(
+        get(&service, "/a?x=1", "en").await,
+        ("1".into(), "miss".into())
+    );
+    assert_eq!(
+        get(&service, "/a?x=1", "en").await,
+        ("1".into(), "hit".into())
+    );
+    assert_eq!(
+        get(&service, "/a?x=2", "en").await,
+        ("2".into(), "miss".into())
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    // Uncacheable responses are not stored.
+    get(&service, "/private", "en").await;
+    assert_eq!(get(&service, "/private", "en").await.1, "miss");
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn test_cache_is_vary_aware() {
+    let (service, calls) = counting_service(ResponseCache::new(Duration::from_secs(60), 1024));
+
+    assert_eq!(get(&service, "/vary", "en").await.0, "1");
+    assert_eq!(get(&service, "/vary", "fr").await.0, "2");
+    assert_eq!(
+        get(&service, "/vary", "en").await,
+        ("1".into(), "hit".into())
+    );
+    assert_eq!(
+        get(&service, "/vary", "fr").await,
+        ("2".into(), "hit".into())
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_cache_expires_and_selects_routes() {
+    let cache = ResponseCache::new(Duration::from_millis(20), 1024).route("/cached");
+    let (service, _) = counting_service(cache);
+
+    assert_eq!(get(&service, "/cached", "en").await.0, "1");
+    assert_eq!(get(&service, "/cached", "en").await.0, "1");
+
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    assert_eq!(get(&service, "/cached", "en").await.0, "2");
+
+    assert_eq!(
+        get(&service, "/other", "en").await,
+        ("3".into(), String::new())
+    );
+    assert_eq!(
+        get(&service, "/other", "en").await,
+        ("4".into(), String::new())
+    );
+
+    // Routes match whole path segments.
+    assert_eq!(get(&service, "/cached/1", "en").await.0, "5");
+    assert_eq!(get(&service, "/cached/1", "en").await.0, "5");
+    assert_eq!(
+        get(&service, "/cachedx", "en").await,
+        ("6".into(), String::new())
+    );
+}
+
+#[tokio::test]
+async fn test_cache_route_ignores_trailing_slash() {
+    let cache = ResponseCache::new(Duration::from_secs(60), 1024).route("/reports/");
+    let (service, calls) = counting_service(cache);
+
+    assert_eq!(get(&service, "/reports/42", "en").await.1, "miss");
+    assert_eq!(
+        get(&service, "/reports/42", "en").await,
+        ("1".into(), "hit".into())
+    );
+    assert_eq!(get(&service, "/reports", "en").await.1, "miss");
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_cache_skips_requests_with_credentials() {
+    let (service, calls) = counting_service(ResponseCache::new(Duration::from_secs(60), 1024));
+
+    let get_with = |uri: &'static str, name: &'static str, value: &'static str| {
+        let service = service.clone();
+        async move {
+            let request = AxumRequest::get(uri)
+                .header(name, value)
+                .body(AxumBody::empty())
+                .unwrap();
+            let response = service.oneshot(request).await.unwrap();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            String::from_utf8(body.to_vec()).unwrap()
+        }
+    };
+
+    // Each user's response reaches the backend, and is not stored for anyone else.
+    assert_eq!(get_with("/me", "authorization", "Bearer alice").await, "1");
+    assert_eq!(get_with("/me", "authorization", "Bearer bob").await, "2");
+    assert_eq!(get_with("/me", "cookie", "session=carol").await, "3");
+    assert_eq!(
+        get(&service, "/me", "en").await,
+        ("4".into(), "miss".into())
+    );
+
+    // Responses cached for anonymous requests are not served to users with credentials.
+    assert_eq!(get_with("/me", "authorization", "Bearer alice").await, "5");
+    assert_eq!(calls.load(Ordering::SeqCst), 5);
+
+    // Responses explicitly marked as public are shared.
+    assert_eq!(
+        get_with("/public", "authorization", "Bearer alice").await,
+        "6"
+    );
+    assert_eq!(
+        get_with("/public", "authorization", "Bearer bob").await,
+        "6"
+    );
+    assert_eq!(
+        get(&service, "/public", "en").await,
+        ("6".into(), "hit".into())
+    );
+}
@@ -0,0 +1,95 @@
+use axum::{Router, body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode};
+use tower::{ServiceExt, layer::util::Identity};
+use warp::Filter;
+
+use crate::{Capture, WarpService, admin::AdminRouter};
+
+async fn send(app: &Router, method: &str, uri: &str, body: &'static str) -> (StatusCode, String) {
+    let req = AxumRequest::builder()
+        .method(method)
+        .uri(uri)
+        .header("authorization", "Bearer secret")
+        .header("x-request-id", "abc")
+        .body(AxumBody::from(body))
+        .unwrap();
+    let response = app.clone().oneshot(req).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn test_capture_keeps_sampled_exchanges() {
+    let filter = warp::body::bytes().map(|body: warp::hyper::body::Bytes| {
+        let reply = format!("echo {}", String::from_utf8_lossy(&body));
+        Box::new(warp::reply::with_header(reply, "set-cookie", "session=1"))
+            as Box<dyn warp::Reply + Send + Sync>
+    });
+    let capture = Capture::new("/orders")
+        .sample_every(2)
+        .capacity(2)
+        .max_body(6)
+        .redact_header("x-request-id");
+    let service = WarpService::new(filter.boxed()).with_capture(capture.clone());
+
+    let app: Router = Router::new()
+        .nest(
+            "/_warpdrive",
+            AdminRouter::new()
+                .capture("orders", capture.clone())
+                .into_router(Identity::new()),
+        )
+        .fallback_service(service);
+
+    for body in ["one", "two", "three", "four", "five", "six"] {
+        send(&app, "POST", "/orders", body).await;
+    }
+    send(&app, "POST", "/users", "seven").await;
+
+    let exchanges = capture.exchanges();
+    assert_eq!(exchanges.len(), 2);
+    let exchange = &exchanges[1];
+    assert_eq!(exchange.uri, "/orders");
+    assert_eq!(exchange.status, StatusCode::OK);
+    assert_eq!(exchange.request_body, "five");
+    assert!(!exchange.request_body_truncated);
+    assert_eq!(exchange.response_body, "echo f");
+    assert!(exchange.response_body_truncated);
+    assert_eq!(exchange.request_headers["authorization"], "[redacted]");
+    assert_eq!(exchange.request_headers["x-request-id"], "[redacted]");
+    assert_eq!(exchange.response_headers["set-cookie"], "[redacted]");
+    assert_eq!(exchanges[0].request_body, "three");
+
+    let (status, body) = send(&app, "GET", "/_warpdrive/captures/orders", "").await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["exchanges"][1]["request_body"], "five");
+    assert_eq!(json["exchanges"][1]["status"], 200);
+
+    let (status, _) = send(&app, "DELETE", "/_warpdrive/captures/orders", "").await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert!(capture.exchanges().is_empty());
+    let (status, _) = send(&app, "GET", "/_warpdrive/captures/missing", "").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_capture_prefix_ignores_trailing_slash() {
+    let filter = warp::any().map(|| "ok");
+    let capture = Capture::new("/orders/");
+    let app: Router = Router::new()
+        .fallback_service(WarpService::new(filter.boxed()).with_capture(capture.clone()));
+
+    send(&app, "GET", "/orders/42", "").await;
+    send(&app, "GET", "/orders", "").await;
+    send(&app, "GET", "/orders-old", "").await;
+
+    let uris: Vec<_> = capture
+        .exchanges()
+        .into_iter()
+        .map(|exchange| exchange.uri)
+        .collect();
+    assert_eq!(uris, ["/orders/42", "/orders"]);
+}
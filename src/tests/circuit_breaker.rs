@@ -0,0 +1,285 @@
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use axum::{
+    body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode, routing::get,
+};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{CircuitBreaker, CircuitState, Error, WarpService};
+
+async fn call(service: &WarpService, uri: &str) -> (StatusCode, String) {
+    let req = AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.clone().oneshot(req).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+/// A Warp filter that fails while `broken` is set.
+fn service(broken: Arc<AtomicBool>, breaker: CircuitBreaker) -> WarpService {
+    let filter = warp::any().map(move || {
+        let status = if broken.load(Ordering::SeqCst) {
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR
+        } else {
+            warp::http::StatusCode::OK
+        };
+        Box::new(warp::reply::with_status("warp", status)) as Box<dyn warp::Reply + Send + Sync>
+    });
+    WarpService::new(filter.boxed()).with_circuit_breaker(breaker)
+}
+
+#[tokio::test]
+async fn test_circuit_opens_and_recovers() {
+    let broken = Arc::new(AtomicBool::new(true));
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let recorded = changes.clone();
+    let service = service(
+        broken.clone(),
+        CircuitBreaker::new()
+            .route_key(|req| req.uri().path().split('/').nth(1).unwrap().to_string())
+            .min_requests(2)
+            .open_for(Duration::from_millis(50))
+            .on_state_change(move |route, state| {
+                recorded.lock().unwrap().push((route.to_string(), state));
+            }),
+    );
+
+    // Requests for different ids of a route share its circuit.
+    assert_eq!(
+        call(&service, "/users/1").await.0,
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+    assert_eq!(
+        call(&service, "/users/2").await.0,
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+
+    // The circuit is open, so requests fail without reaching Warp.
+    assert_eq!(
+        call(&service, "/users").await,
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Circuit breaker is open".to_string()
+        )
+    );
+    // Other routes are unaffected.
+    assert_eq!(
+        call(&service, "/orders").await.0,
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+
+    // A failed probe opens the circuit again.
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(
+        call(&service, "/users").await.0,
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+    assert_eq!(
+        call(&service, "/users").await.0,
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+
+    // A successful probe closes it.
+    broken.store(false, Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(call(&service, "/users").await.0, StatusCode::OK);
+    assert_eq!(call(&service, "/users").await.0, StatusCode::OK);
+
+    assert_eq!(
+        *changes.lock().unwrap(),
+        [
+            ("users".to_string(), CircuitState::Open),
+            ("users".to_string(), CircuitState::HalfOpen),
+            ("users".to_string(), CircuitState::Open),
+            ("users".to_string(), CircuitState::HalfOpen),
+            ("users".to_string(), CircuitState::Closed),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_open_circuit_serves_fallback() {
+    let broken = Arc::new(AtomicBool::new(true));
+    let breaker = CircuitBreaker::new()
+        .min_requests(1)
+        .fallback(get(|| async { "axum" }));
+    let service = service(broken, breaker);
+
+    assert_eq!(
+        call(&service, "/users").await.0,
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+
+    let req = AxumRequest::builder()
+        .uri("/users")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.clone().oneshot(req).await.unwrap();
+    assert_eq!(response.headers()["x-warpdrive-circuit"], "open");
+    assert_eq!(
+        axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap(),
+        "axum"
+    );
+
+    // Without a route key, the whole service shares one circuit.
+    assert_eq!(
+        call(&service, "/orders").await,
+        (StatusCode::OK, "axum".to_string())
+    );
+}
+
+async fn run(breaker: &CircuitBreaker, uri: &str, status: StatusCode) -> StatusCode {
+    let req = AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    let result = breaker
+        .run(req, |_| async {
+            Ok(axum::response::IntoResponse::into_response(status))
+        })
+        .await;
+    match result {
+        Ok(response) => response.status(),
+        Err(err) => err.status(),
+    }
+}
+
+#[tokio::test]
+async fn test_idle_circuits_are_pruned() {
+    let breaker = CircuitBreaker::new()
+        .route_key(|req| req.uri().path().to_string())
+        .window(Duration::from_millis(10))
+        .max_routes(2);
+
+    for uri in ["/missing/1", "/missing/2", "/missing/3", "/missing/4"] {
+        assert_eq!(
+            run(&breaker, uri, StatusCode::NOT_FOUND).await,
+            StatusCode::NOT_FOUND
+        );
+    }
+    // Routes past the limit share one circuit.
+    assert!(format!("{:?}", breaker).contains("circuits: 3 "));
+
+    // Once their window has passed, closed circuits are pruned to make room for new ones.
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(
+        run(&breaker, "/users", StatusCode::OK).await,
+        StatusCode::OK
+    );
+    assert!(format!("{:?}", breaker).contains("circuits: 1 "));
+}
+
+#[tokio::test]
+async fn test_stale_open_circuits_are_pruned() {
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let breaker = CircuitBreaker::new()
+        .route_key(|req| req.uri().path().to_string())
+        .window(Duration::from_millis(10))
+        .open_for(Duration::from_millis(10))
+        .min_requests(1)
+        .max_routes(1)
+        .on_state_change({
+            let changes = changes.clone();
+            move |route, state| changes.lock().unwrap().push((route.to_string(), state))
+        });
+
+    let failed = run(&breaker, "/orders", StatusCode::INTERNAL_SERVER_ERROR).await;
+    assert_eq!(failed, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(breaker.state("/orders"), CircuitState::Open);
+
+    // Open circuits that were never probed are forgotten, and reported as closed.
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    assert_eq!(
+        run(&breaker, "/users", StatusCode::OK).await,
+        StatusCode::OK
+    );
+    assert_eq!(breaker.state("/orders"), CircuitState::Closed);
+    assert_eq!(
+        *changes.lock().unwrap(),
+        [
+            ("/orders".to_string(), CircuitState::Open),
+            ("/orders".to_string(), CircuitState::Closed),
+        ]
+    );
+}
+
+async fn rate_limited(breaker: &CircuitBreaker) -> StatusCode {
+    let req = AxumRequest::builder()
+        .uri("/users")
+        .body(AxumBody::empty())
+        .unwrap();
+    let result = breaker
+        .run(req, |_| async {
+            Err(Error::RateLimited(Duration::from_secs(1)))
+        })
+        .await;
+    result.map_or_else(|err| err.status(), |response| response.status())
+}
+
+#[tokio::test]
+async fn test_rate_limited_requests_are_not_recorded() {
+    let breaker = CircuitBreaker::new()
+        .min_requests(2)
+        .open_for(Duration::from_millis(10));
+
+    // Rate limited requests do not dilute the error rate of a closed circuit.
+    run(&breaker, "/users", StatusCode::INTERNAL_SERVER_ERROR).await;
+    for _ in 0..3 {
+        assert_eq!(rate_limited(&breaker).await, StatusCode::TOO_MANY_REQUESTS);
+    }
+    run(&breaker, "/users", StatusCode::INTERNAL_SERVER_ERROR).await;
+    assert_eq!(breaker.state("*"), CircuitState::Open);
+
+    // A rate limited probe leaves the circuit open, and the next request probes instead.
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(rate_limited(&breaker).await, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(breaker.open_routes(), ["*"]);
+    assert_eq!(
+        run(&breaker, "/users", StatusCode::OK).await,
+        StatusCode::OK
+    );
+    assert_eq!(breaker.state("*"), CircuitState::Closed);
+}
+
+#[tokio::test]
+async fn test_dropped_requests_are_not_recorded() {
+    let breaker = CircuitBreaker::new()
+        .min_requests(1)
+        .open_for(Duration::from_millis(10));
+    let dropped = || async {
+        let req = AxumRequest::builder()
+            .uri("/users")
+            .body(AxumBody::empty())
+            .unwrap();
+        let request = breaker.run(req, |_| std::future::pending());
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), request)
+                .await
+                .is_err()
+        );
+    };
+
+    // A client disconnecting from a slow request does not trip the circuit.
+    dropped().await;
+    assert_eq!(breaker.state("*"), CircuitState::Closed);
+
+    // A dropped probe counts as a failure, so the circuit does not stay half-open.
+    run(&breaker, "/users", StatusCode::INTERNAL_SERVER_ERROR).await;
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    dropped().await;
+    assert_eq!(breaker.state("*"), CircuitState::Open);
+}
@@ -0,0 +1,105 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::SystemTime,
+};
+
+use axum::http::Method;
+
+use crate::{RouteOwner, RouteUsage, codegen::Scaffold};
+
+fn usage(route: &str) -> RouteUsage {
+    RouteUsage {
+        route: route.to_string(),
+        hits: 10,
+        errors: 1,
+        last_seen: SystemTime::UNIX_EPOCH,
+        on_warp: true,
+        owner: None,
+        statuses: BTreeMap::new(),
+        content_types: BTreeSet::new(),
+    }
+}
+
+#[test]
+fn test_scaffold_from_usage() {
+    let mut users = usage("GET /users/{id}");
+    users.statuses = BTreeMap::from([(200, 9), (500, 1)]);
+    users.content_types = BTreeSet::from(["application/json".to_string()]);
+    users.owner = Some(RouteOwner::new("identity"));
+
+    let scaffold = Scaffold::from_usage(&[
+        users,
+        usage("DELETE /users/{id}"),
+        usage("POST /orgs/{org}/members/{user}"),
+        usage("GET /"),
+        usage("CONNECT /tunnel"),
+        usage("(other)"),
+    ]);
+    assert_eq!(scaffold.len(), 5);
+
+    assert_eq!(
+        scaffold.render(),
+        r#"//! Axum handlers scaffolded from legacy Warp routes.
+
+#![allow(unused_variables)]
+
+use axum::{Router, extract::Path, response::Response, routing::{delete, get, post}};
+
+/// `GET /`
+///
+/// Served 10 requests, 1 with server errors.
+pub async fn get_root() -> Response {
+    todo!("migrate GET /")
+}
+
+/// `POST /orgs/{org}/members/{user}`
+///
+/// Served 10 requests, 1 with server errors.
+pub async fn post_orgs_org_members_user(Path((org, user)): Path<(String, String)>) -> Response {
+    todo!("migrate POST /orgs/{{org}}/members/{{user}}")
+}
+
+/// `DELETE /users/{id}`
+///
+/// Served 10 requests, 1 with server errors.
+pub async fn delete_users_id(Path(id): Path<String>) -> Response {
+    todo!("migrate DELETE /users/{{id}}")
+}
+
+/// `GET /users/{id}`
+///
+/// Served 10 requests, 1 with server errors.
+/// Responded with 200, 500.
+/// Content types: `application/json`.
+/// Owned by identity.
+pub async fn get_users_id(Path(id): Path<String>) -> Response {
+    todo!("migrate GET /users/{{id}}")
+}
+
+// Routes without an `axum::routing` function:
+// - `CONNECT /tunnel`
+
+/// Registers the scaffolded handlers.
+pub fn router() -> Router {
+    Router::new()
+        .route("/", get(get_root))
+        .route("/orgs/{org}/members/{user}", post(post_orgs_org_members_user))
+        .route("/users/{id}", delete(delete_users_id).get(get_users_id))
+}
+"#
+    );
+}
+
+#[test]
+fn test_scaffold_identifiers() {
+    let source = Scaffold::new()
+        .route(Method::GET, "/items/{type}")
+        .route(Method::GET, "/items-{type}")
+        .route(Method::GET, "/2fa/{*rest}")
+        .render();
+
+    // Keywords and leading digits are prefixed, and clashing names are numbered.
+    assert!(source.contains("pub async fn get_items_type() -> Response {"));
+    assert!(source.contains("pub async fn get_items_type_2(Path(r_type): Path<String>)"));
+    assert!(source.contains("pub async fn get_2fa_rest(Path(rest): Path<String>)"));
+}
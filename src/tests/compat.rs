@@ -0,0 +1,230 @@
+use std::convert::Infallible;
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest};
+use tower::ServiceExt;
+use warp::http::{Request as WarpRequest, Response as WarpResponse};
+use warp::hyper::body::Body as WarpBody;
+
+use crate::{
+    HyperCompatService,
+    compat::{CompatBody, HttpCompatLayer, HttpCompatService},
+};
+
+#[tokio::test]
+async fn test_hyper_service_in_router() {
+    let legacy = tower::service_fn(|req: WarpRequest<WarpBody>| async move {
+        let body = warp::hyper::body::to_bytes(req.into_body()).await.unwrap();
+        let response = WarpResponse::builder()
+            .status(201)
+            .header("x-legacy", "true")
+            .body(WarpBody::from(format!(
+                "Echo: {}",
+                String::from_utf8_lossy(&body)
+            )))
+            .unwrap();
+        Ok::<_, Infallible>(response)
+    });
+
+    let app = axum::Router::new()
+        .route("/axum", axum::routing::get(|| async { "Axum" }))
+        .fallback_service(HyperCompatService::new(legacy));
+
+    let request = AxumRequest::builder()
+        .method("POST")
+        .uri("/legacy")
+        .body(AxumBody::from("ping"))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), 201);
+    assert_eq!(response.headers().get("x-legacy").unwrap(), "true");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "Echo: ping");
+}
+
+#[tokio::test]
+async fn test_hyper_service_error_returns_500() {
+    let legacy = tower::service_fn(|_req: WarpRequest<WarpBody>| async {
+        Err::<WarpResponse<WarpBody>, _>(std::io::Error::other("backend down"))
+    });
+
+    let service = HyperCompatService::new(legacy);
+
+    let request = AxumRequest::builder()
+        .uri("/")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = service.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), 500);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "Service error: backend down");
+}
+
+#[tokio::test]
+async fn test_axum_router_called_with_http02_request() {
+    let router =
+        axum::Router::new().route(
+            "/users/{id}",
+            axum::routing::get(
+                |axum::extract::Path(id): axum::extract::Path<u32>| async move {
+                    format!("User {}", id)
+                },
+            ),
+        );
+
+    let service = tower::ServiceBuilder::new()
+        .layer(HttpCompatLayer::new())
+        .service(router);
+
+    let request = WarpRequest::builder()
+        .uri("/users/7")
+        .body(WarpBody::empty())
+        .unwrap();
+
+    let response: WarpResponse<CompatBody> = service.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), 200);
+    let body = warp::hyper::body::to_bytes(response.into_body())
+        .await
+        .unwrap();
+    assert_eq!(body, "User 7");
+}
+
+#[tokio::test]
+async fn test_compat_body_passes_hyper_trailers_to_axum() {
+    let (mut sender, body) = WarpBody::channel();
+    tokio::spawn(async move {
+        sender.send_data("data".into()).await.unwrap();
+        let mut trailers = warp::http::HeaderMap::new();
+        trailers.insert("grpc-status", "0".parse().unwrap());
+        sender.send_trailers(trailers).await.unwrap();
+    });
+
+    let collected = http_body_util::BodyExt::collect(CompatBody::from(body))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        collected.trailers().unwrap().get("grpc-status").unwrap(),
+        "0"
+    );
+    assert_eq!(collected.to_bytes(), "data");
+}
+
+#[tokio::test]
+async fn test_compat_body_passes_axum_trailers_to_hyper() {
+    use warp::hyper::body::HttpBody;
+
+    let mut trailers = axum::http::HeaderMap::new();
+    trailers.insert("grpc-status", "0".parse().unwrap());
+    let frames = futures::stream::iter([
+        Ok::<_, Infallible>(http_body::Frame::data(axum::body::Bytes::from("data"))),
+        Ok(http_body::Frame::trailers(trailers)),
+    ]);
+    let body = axum::body::Body::new(http_body_util::StreamBody::new(frames));
+
+    let mut body = CompatBody::from(body);
+
+    assert_eq!(body.data().await.unwrap().unwrap(), "data");
+    assert!(body.data().await.is_none());
+    let trailers = body.trailers().await.unwrap().unwrap();
+    assert_eq!(trailers.get("grpc-status").unwrap(), "0");
+}
+
+#[tokio::test]
+async fn test_router_behind_hyper_server() {
+    let router =
+        axum::Router::new().route("/", axum::routing::get(|| async { "Hello from Axum!" }));
+
+    let server = warp::hyper::Server::bind(&([127, 0, 0, 1], 0).into())
+        .serve(HttpCompatService::new(router).into_hyper_make_service());
+    let addr = server.local_addr();
+    tokio::spawn(server);
+
+    let uri = format!("http://{}/", addr).parse().unwrap();
+    let response = warp::hyper::Client::new().get(uri).await.unwrap();
+
+    assert_eq!(response.status(), 200);
+    let body = warp::hyper::body::to_bytes(response.into_body())
+        .await
+        .unwrap();
+    assert_eq!(body, "Hello from Axum!");
+}
+
+#[tokio::test]
+async fn test_grpc_trailers_survive_bridge() {
+    let legacy = tower::service_fn(|req: WarpRequest<WarpBody>| async move {
+        assert_eq!(req.headers().get("te").unwrap(), "trailers");
+        assert_eq!(req.version(), warp::http::Version::HTTP_2);
+
+        let (mut sender, body) = WarpBody::channel();
+        tokio::spawn(async move {
+            sender.send_data("\0\0\0\0\0".into()).await.unwrap();
+            let mut trailers = warp::http::HeaderMap::new();
+            trailers.insert("grpc-status", "0".parse().unwrap());
+            sender.send_trailers(trailers).await.unwrap();
+        });
+
+        let response = WarpResponse::builder()
+            .header("content-type", "application/grpc")
+            .body(body)
+            .unwrap();
+        Ok::<_, Infallible>(response)
+    });
+
+    let request = AxumRequest::builder()
+        .method("POST")
+        .uri("/helloworld.Greeter/SayHello")
+        .version(axum::http::Version::HTTP_2)
+        .header("content-type", "application/grpc")
+        .header("te", "trailers")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = HyperCompatService::new(legacy)
+        .oneshot(request)
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    let collected = http_body_util::BodyExt::collect(response.into_body())
+        .await
+        .unwrap();
+    assert_eq!(
+        collected.trailers().unwrap().get("grpc-status").unwrap(),
+        "0"
+    );
+}
+
+#[tokio::test]
+async fn test_grpc_error_returns_grpc_status() {
+    let legacy = tower::service_fn(|_req: WarpRequest<WarpBody>| async {
+        Err::<WarpResponse<WarpBody>, _>(std::io::Error::other("backend down"))
+    });
+
+    let request = AxumRequest::builder()
+        .method("POST")
+        .uri("/helloworld.Greeter/SayHello")
+        .header("content-type", "application/grpc+proto")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = HyperCompatService::new(legacy)
+        .oneshot(request)
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers().get("grpc-status").unwrap(), "13");
+    assert_eq!(
+        response.headers().get("grpc-message").unwrap(),
+        "Service error: backend down"
+    );
+}
@@ -0,0 +1,104 @@
+use std::{collections::BTreeSet, time::Duration};
+
+use axum::{
+    Router,
+    body::Body as AxumBody,
+    extract::Request as AxumRequest,
+    http::{HeaderMap, Method, StatusCode, header},
+    routing::post,
+};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{WarpService, cors::CorsConfig};
+
+fn app(cors: &CorsConfig) -> Router {
+    let warp_routes = warp::path("warp")
+        .and(warp::post())
+        .map(|| "warp")
+        .with(cors.warp())
+        .boxed();
+
+    Router::new()
+        .route("/axum", post(|| async { "axum" }))
+        .layer(cors.layer())
+        .fallback_service(WarpService::new(warp_routes))
+}
+
+async fn preflight(app: &Router, uri: &str, origin: &str) -> (StatusCode, HeaderMap) {
+    let req = AxumRequest::builder()
+        .method(Method::OPTIONS)
+        .uri(uri)
+        .header(header::ORIGIN, origin)
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+        .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = app.clone().oneshot(req).await.unwrap();
+    (response.status(), response.headers().clone())
+}
+
+fn list(headers: &HeaderMap, name: header::HeaderName) -> BTreeSet<String> {
+    headers
+        .get(name)
+        .map(|value| value.to_str().unwrap())
+        .unwrap_or_default()
+        .split(',')
+        .map(|item| item.trim().to_ascii_lowercase())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_cors_config_matches_on_both_stacks() {
+    let cors = CorsConfig::new()
+        .allow_origin("http://example.com")
+        .allow_methods([Method::GET, Method::POST])
+        .allow_headers([header::CONTENT_TYPE])
+        .allow_credentials(true)
+        .max_age(Duration::from_secs(600));
+    let app = app(&cors);
+
+    let (axum_status, axum) = preflight(&app, "/axum", "http://example.com").await;
+    let (warp_status, warp) = preflight(&app, "/warp", "http://example.com").await;
+    assert_eq!(axum_status, StatusCode::OK);
+    assert_eq!(warp_status, StatusCode::OK);
+
+    for name in [
+        header::ACCESS_CONTROL_ALLOW_ORIGIN,
+        header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+        header::ACCESS_CONTROL_MAX_AGE,
+    ] {
+        assert_eq!(axum.get(&name), warp.get(&name), "{}", name);
+    }
+    assert_eq!(
+        warp[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+        "http://example.com"
+    );
+    for name in [
+        header::ACCESS_CONTROL_ALLOW_METHODS,
+        header::ACCESS_CONTROL_ALLOW_HEADERS,
+    ] {
+        assert_eq!(list(&axum, name.clone()), list(&warp, name));
+    }
+}
+
+#[tokio::test]
+async fn test_cors_config_any_origin_echoes_origin() {
+    let cors = CorsConfig::new()
+        .allow_any_origin()
+        .allow_methods([Method::POST])
+        .allow_headers([header::CONTENT_TYPE]);
+    let app = app(&cors);
+
+    for uri in ["/axum", "/warp"] {
+        let (status, headers) = preflight(&app, uri, "http://other.example").await;
+        assert_eq!(status, StatusCode::OK, "{}", uri);
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "http://other.example",
+            "{}",
+            uri
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{Deadline, Error, WarpService};
+
+fn slow_service() -> WarpService<&'static str> {
+    let filter = warp::any().and_then(|| async {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        Ok::<_, warp::Rejection>("slow")
+    });
+
+    WarpService::new(filter.boxed())
+}
+
+fn request(deadline: Option<Deadline>) -> AxumRequest {
+    let mut request = AxumRequest::builder()
+        .uri("/")
+        .body(AxumBody::empty())
+        .unwrap();
+    if let Some(deadline) = deadline {
+        request.extensions_mut().insert(deadline);
+    }
+    request
+}
+
+#[tokio::test]
+async fn test_deadline_cancels_filter() {
+    let service = slow_service();
+
+    let response = service
+        .clone()
+        .oneshot(request(Some(Deadline::after(Duration::from_millis(10)))))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+
+    // A deadline that has already passed fails without running the filter.
+    let err = service
+        .into_fallible()
+        .oneshot(request(Some(Deadline::after(Duration::ZERO))))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Timeout(Duration::ZERO)));
+}
+
+#[tokio::test]
+async fn test_sooner_of_deadline_and_timeout_applies() {
+    let service = slow_service().with_timeout(Duration::from_millis(10));
+
+    let response = service
+        .oneshot(request(Some(Deadline::after(Duration::from_secs(60)))))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+}
+
+#[tokio::test]
+async fn test_deadline_forwarded_as_header() {
+    let filter = warp::header::optional::<u64>("x-request-budget-ms")
+        .map(|budget: Option<u64>| format!("{:?}", budget.map(|budget| budget > 0)));
+    let service = WarpService::new(filter.boxed()).with_deadline_header("x-request-budget-ms");
+
+    let response = service
+        .clone()
+        .oneshot(request(Some(Deadline::after(Duration::from_secs(60)))))
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "Some(true)");
+
+    let response = service.oneshot(request(None)).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "None");
+}
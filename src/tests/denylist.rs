@@ -0,0 +1,44 @@
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{Denylist, WarpService};
+
+async fn send(service: &WarpService<&'static str>, uri: &str) -> (StatusCode, String) {
+    let request = AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn test_denylist_answers_migrated_paths() {
+    let denylist = Denylist::new()
+        .gone("/v1/users/", "Moved to /v2/users")
+        .not_found("/v1/internal", "");
+    let service = WarpService::new(warp::any().map(|| "warp").boxed()).with_denylist(denylist);
+
+    assert_eq!(
+        send(&service, "/v1/users").await,
+        (StatusCode::GONE, "Moved to /v2/users".to_string())
+    );
+    assert_eq!(send(&service, "/v1/users/42?x=1").await.0, StatusCode::GONE);
+    assert_eq!(
+        send(&service, "/v1/internal/debug").await,
+        (StatusCode::NOT_FOUND, String::new())
+    );
+
+    // Only whole path segments match.
+    assert_eq!(
+        send(&service, "/v1/users-old").await,
+        (StatusCode::OK, "warp".to_string())
+    );
+    assert_eq!(send(&service, "/v1").await.0, StatusCode::OK);
+}
@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use axum::{
+    body::Body as AxumBody, error_handling::HandleErrorLayer, extract::Request as AxumRequest,
+    http::StatusCode,
+};
+use tower::{ServiceBuilder, ServiceExt};
+use warp::Filter;
+
+use crate::{Error, WarpService};
+
+fn slow_filter() -> warp::filters::BoxedFilter<(&'static str,)> {
+    warp::path("slow")
+        .and_then(|| async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            Ok::<_, warp::Rejection>("done")
+        })
+        .boxed()
+}
+
+fn slow_request() -> AxumRequest {
+    AxumRequest::builder()
+        .uri("/slow")
+        .body(AxumBody::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_timeout_returns_gateway_timeout() {
+    let service = WarpService::new(slow_filter()).with_timeout(Duration::from_millis(10));
+
+    let response = service.oneshot(slow_request()).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+}
+
+#[tokio::test]
+async fn test_fallible_service_returns_typed_error() {
+    let service = WarpService::new(slow_filter())
+        .with_timeout(Duration::from_millis(10))
+        .into_fallible();
+
+    let err = service.oneshot(slow_request()).await.unwrap_err();
+
+    assert!(matches!(err, Error::Timeout(timeout) if timeout == Duration::from_millis(10)));
+    assert_eq!(err.status(), StatusCode::GATEWAY_TIMEOUT);
+}
+
+#[tokio::test]
+async fn test_fallible_service_with_handle_error_layer() {
+    let service = ServiceBuilder::new()
+        .layer(HandleErrorLayer::new(|err: Error| async move {
+            (StatusCode::SERVICE_UNAVAILABLE, err.to_string())
+        }))
+        .service(
+            WarpService::new(slow_filter())
+                .with_timeout(Duration::from_millis(10))
+                .into_fallible(),
+        );
+    let app = axum::Router::new().fallback_service(service);
+
+    let response = app.oneshot(slow_request()).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "Request timed out after 10ms");
+}
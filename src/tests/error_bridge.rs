@@ -0,0 +1,114 @@
+use std::{error::Error as StdError, fmt};
+
+use axum::{
+    Router, body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode,
+    middleware::map_response, response::Response,
+};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{ErrorBridge, ErrorChain, WarpService};
+
+#[derive(Debug)]
+struct Io;
+
+impl fmt::Display for Io {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("connection reset")
+    }
+}
+
+impl StdError for Io {}
+
+#[derive(Debug)]
+struct Query(Io);
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("query failed")
+    }
+}
+
+impl StdError for Query {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&self.0)
+    }
+}
+
+#[derive(Debug)]
+struct AppError(Box<dyn StdError + Send + Sync>);
+
+impl warp::reject::Reject for AppError {}
+
+fn bridge() -> ErrorBridge {
+    ErrorBridge::new(|rejection| {
+        rejection
+            .find::<AppError>()
+            .map(|err| &*err.0 as &(dyn StdError + 'static))
+    })
+}
+
+fn app(bridge: ErrorBridge) -> Router {
+    let failing = warp::path("fail").and_then(|| async {
+        Err::<String, _>(warp::reject::custom(AppError(Box::new(Query(Io)))))
+    });
+    let filter = failing.recover(bridge.handler()).boxed();
+
+    Router::new()
+        .fallback_service(WarpService::new(filter))
+        .layer(map_response(|mut response: Response| async move {
+            if let Some(chain) = response.extensions().get::<ErrorChain>().cloned() {
+                response
+                    .headers_mut()
+                    .insert("x-error-chain", chain.to_string().parse().unwrap());
+            }
+            response
+        }))
+}
+
+async fn call(app: Router, uri: &str) -> Response {
+    let request = AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    app.oneshot(request).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_error_chain_reaches_axum() {
+    let response = call(app(bridge()), "/fail").await;
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(
+        response.headers()["x-error-chain"],
+        "query failed: connection reset"
+    );
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert!(body.is_empty());
+}
+
+#[tokio::test]
+async fn test_custom_error_response() {
+    let bridge = bridge().respond(|chain| {
+        assert_eq!(chain.messages(), ["query failed", "connection reset"]);
+        (StatusCode::BAD_GATEWAY, chain.root_cause().to_string())
+    });
+    let response = call(app(bridge), "/fail").await;
+
+    assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+    assert!(response.headers().contains_key("x-error-chain"));
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "connection reset");
+}
+
+#[tokio::test]
+async fn test_other_rejections_pass_through() {
+    let response = call(app(bridge()), "/missing").await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(!response.headers().contains_key("x-error-chain"));
+}
@@ -0,0 +1,158 @@
+use axum::{
+    Router,
+    body::Body as AxumBody,
+    extract::{FromRequestParts, Request as AxumRequest},
+    http::{Method, StatusCode, request::Parts},
+    routing::get,
+};
+use tower::ServiceExt;
+use warp::{Filter, filters::BoxedFilter};
+
+use crate::extract::{ExtractFilter, WarpExtract, axum_extract, handle_axum_rejection};
+
+struct ApiKey;
+
+impl ExtractFilter for ApiKey {
+    type Extract = String;
+
+    fn filter() -> BoxedFilter<(String,)> {
+        warp::header::<String>("x-api-key").boxed()
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct Payload {
+    message: String,
+}
+
+struct JsonPayload;
+
+impl ExtractFilter for JsonPayload {
+    type Extract = Payload;
+
+    fn filter() -> BoxedFilter<(Payload,)> {
+        warp::body::json::<Payload>().boxed()
+    }
+}
+
+fn app() -> Router {
+    Router::new()
+        .route(
+            "/key",
+            get(
+                |WarpExtract(key, _): WarpExtract<String, ApiKey>| async move {
+                    format!("Key: {}", key)
+                },
+            ),
+        )
+        .route(
+            "/json",
+            axum::routing::post(|payload: WarpExtract<Payload, JsonPayload>| async move {
+                format!("Got: {}", payload.message)
+            }),
+        )
+}
+
+#[tokio::test]
+async fn test_extracts_value() {
+    let request = AxumRequest::builder()
+        .method("GET")
+        .uri("/key")
+        .header("x-api-key", "secret")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = app().oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), 200);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "Key: secret");
+}
+
+#[tokio::test]
+async fn test_extracts_body() {
+    let request = AxumRequest::builder()
+        .method("POST")
+        .uri("/json")
+        .header("content-type", "application/json")
+        .body(AxumBody::from(r#"{"message": "hello"}"#))
+        .unwrap();
+
+    let response = app().oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), 200);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "Got: hello");
+}
+
+#[tokio::test]
+async fn test_rejection_becomes_response() {
+    let request = AxumRequest::builder()
+        .method("GET")
+        .uri("/key")
+        // Missing x-api-key header
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = app().oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_axum_extract_in_warp_route() {
+    let warp_filter = warp::path("method")
+        .and(axum_extract::<Method>())
+        .map(|method: Method| format!("Method: {}", method));
+
+    let response = warp::test::request()
+        .method("PUT")
+        .path("/method")
+        .reply(&warp_filter)
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.body(), "Method: PUT");
+}
+
+#[tokio::test]
+async fn test_axum_extract_rejection_is_recovered() {
+    struct RequireToken;
+
+    impl<S: Send + Sync> FromRequestParts<S> for RequireToken {
+        type Rejection = (StatusCode, &'static str);
+
+        async fn from_request_parts(
+            parts: &mut Parts,
+            _state: &S,
+        ) -> Result<Self, Self::Rejection> {
+            match parts.headers.get("x-token") {
+                Some(_) => Ok(RequireToken),
+                None => Err((StatusCode::UNAUTHORIZED, "missing token")),
+            }
+        }
+    }
+
+    let warp_filter = warp::path("secure")
+        .and(axum_extract::<RequireToken>())
+        .map(|_| "ok")
+        .recover(handle_axum_rejection);
+
+    let response = warp::test::request()
+        .path("/secure")
+        .reply(&warp_filter)
+        .await;
+    assert_eq!(response.status(), 401);
+    assert_eq!(response.body(), "missing token");
+
+    let response = warp::test::request()
+        .path("/secure")
+        .header("x-token", "abc")
+        .reply(&warp_filter)
+        .await;
+    assert_eq!(response.status(), 200);
+}
@@ -0,0 +1,117 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use axum::{
+    body::Body as AxumBody,
+    extract::Request as AxumRequest,
+    http::StatusCode,
+    routing::{any, post},
+};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{Failover, FailoverReason, WarpService};
+
+fn request(uri: &str) -> AxumRequest {
+    AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap()
+}
+
+async fn body_string(response: axum::response::Response) -> String {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+fn service() -> WarpService {
+    let ok = warp::path("ok").map(|| Box::new("warp") as Box<dyn warp::Reply + Send + Sync>);
+    let broken = warp::path("broken").map(|| {
+        Box::new(warp::reply::with_status(
+            "broken",
+            warp::http::StatusCode::BAD_GATEWAY,
+        )) as Box<dyn warp::Reply + Send + Sync>
+    });
+    let panics = warp::path("panic")
+        .map(|| -> Box<dyn warp::Reply + Send + Sync> { panic!("filter panicked") });
+
+    WarpService::new(ok.or(broken).unify().or(panics).unify().boxed())
+}
+
+#[tokio::test]
+async fn test_failover_on_server_error() {
+    let failovers = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&failovers);
+
+    let failover = Failover::new(any(|| async { "axum" })).on_failover(move |req, reason| {
+        assert_eq!(req.uri.path(), "/broken");
+        assert_eq!(
+            *reason,
+            FailoverReason::Status(axum::http::StatusCode::BAD_GATEWAY)
+        );
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+    let service = service().with_failover(failover);
+
+    let response = service.clone().oneshot(request("/ok")).await.unwrap();
+    assert!(response.headers().get("x-warpdrive-failover").is_none());
+    assert_eq!(body_string(response).await, "warp");
+
+    let response = service.oneshot(request("/broken")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("x-warpdrive-failover").unwrap(),
+        "status"
+    );
+    assert_eq!(body_string(response).await, "axum");
+
+    assert_eq!(failovers.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_failover_on_panic_and_timeout() {
+    let slow = warp::path("slow").and_then(|| async {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        Ok::<_, warp::Rejection>("slow")
+    });
+    let slow_service = WarpService::new(slow.boxed())
+        .with_timeout(Duration::from_millis(10))
+        .with_failover(Failover::new(any(|| async { "axum" })));
+
+    let response = slow_service.oneshot(request("/slow")).await.unwrap();
+    assert_eq!(
+        response.headers().get("x-warpdrive-failover").unwrap(),
+        "timeout"
+    );
+    assert_eq!(body_string(response).await, "axum");
+
+    let service = service().with_failover(Failover::new(any(|| async { "axum" })));
+    let response = service.oneshot(request("/panic")).await.unwrap();
+    assert_eq!(
+        response.headers().get("x-warpdrive-failover").unwrap(),
+        "panic"
+    );
+    assert_eq!(body_string(response).await, "axum");
+}
+
+#[tokio::test]
+async fn test_failover_replays_body() {
+    let filter = warp::body::bytes()
+        .map(|_| warp::reply::with_status("", warp::http::StatusCode::INTERNAL_SERVER_ERROR));
+    let service = WarpService::new(filter.boxed())
+        .with_failover(Failover::new(post(|body: String| async move { body })));
+
+    let request = AxumRequest::post("/echo")
+        .body(AxumBody::from("replayed"))
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+
+    assert_eq!(body_string(response).await, "replayed");
+}
@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{FaultInjection, WarpService};
+
+fn service() -> WarpService<&'static str> {
+    let filter = warp::path!("legacy" / String)
+        .map(|_| "0123456789")
+        .or(warp::path("other").map(|| "other"))
+        .unify();
+
+    WarpService::new(filter.boxed())
+}
+
+fn request(uri: &str) -> AxumRequest {
+    AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_injected_error_matches_path_prefix() {
+    let faults = FaultInjection::new().error("/legacy", StatusCode::SERVICE_UNAVAILABLE, 1.0);
+    let service = service().with_fault_injection(faults);
+
+    let response = service.clone().oneshot(request("/legacy/a")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+        response.headers().get("x-warpdrive-fault").unwrap(),
+        "error"
+    );
+
+    let response = service.oneshot(request("/other")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_injected_faults_match_whole_segments() {
+    let faults = FaultInjection::new().error("/legacy/", StatusCode::SERVICE_UNAVAILABLE, 1.0);
+    let service = service().with_fault_injection(faults);
+
+    let response = service.clone().oneshot(request("/legacy/a")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let response = service.oneshot(request("/legacy-v2")).await.unwrap();
+    assert!(response.headers().get("x-warpdrive-fault").is_none());
+}
+
+#[tokio::test]
+async fn test_injected_latency_counts_towards_timeout() {
+    let faults = FaultInjection::new().latency("/", Duration::from_millis(200), 1.0);
+    let service = service()
+        .with_fault_injection(faults)
+        .with_timeout(Duration::from_millis(10));
+
+    let response = service.oneshot(request("/legacy/a")).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+}
+
+#[tokio::test]
+async fn test_truncated_body_fails_after_limit() {
+    let faults = FaultInjection::new().truncate_body("/legacy", 4, 1.0);
+    let service = service().with_fault_injection(faults);
+
+    let response = service.oneshot(request("/legacy/a")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut data = response.into_body().into_data_stream();
+    let first = futures::StreamExt::next(&mut data).await.unwrap().unwrap();
+    assert_eq!(first, "0123");
+    assert!(futures::StreamExt::next(&mut data).await.unwrap().is_err());
+}
+
+#[tokio::test]
+async fn test_seeded_probability_is_deterministic() {
+    let count_errors = |seed| async move {
+        let faults =
+            FaultInjection::new()
+                .seed(seed)
+                .error("/", StatusCode::INTERNAL_SERVER_ERROR, 0.5);
+        let service = service().with_fault_injection(faults);
+
+        let mut errors = 0;
+        for _ in 0..100 {
+            let response = service.clone().oneshot(request("/other")).await.unwrap();
+            if response.status() == StatusCode::INTERNAL_SERVER_ERROR {
+                errors += 1;
+            }
+        }
+        errors
+    };
+
+    let errors = count_errors(7).await;
+    assert_eq!(errors, count_errors(7).await);
+    assert!((25..75).contains(&errors), "{} errors", errors);
+}
@@ -0,0 +1,47 @@
+use axum::{
+    Router, body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode, routing::get,
+};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::FilterExt;
+
+async fn get_body(app: &Router, uri: &str) -> (StatusCode, String) {
+    let req = AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = app.clone().oneshot(req).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn test_filter_into_axum_router_and_service() {
+    let users = warp::path!("users" / u32)
+        .map(|id| format!("user {}", id))
+        .into_axum_router("/users/{id}");
+    let legacy = warp::path("legacy").map(|| "legacy").into_axum_service();
+
+    let app: Router = Router::new()
+        .route("/", get(|| async { "axum" }))
+        .merge(users)
+        .fallback_service(legacy);
+
+    assert_eq!(
+        get_body(&app, "/").await,
+        (StatusCode::OK, "axum".to_string())
+    );
+    assert_eq!(
+        get_body(&app, "/users/5").await,
+        (StatusCode::OK, "user 5".to_string())
+    );
+    assert_eq!(
+        get_body(&app, "/legacy").await,
+        (StatusCode::OK, "legacy".to_string())
+    );
+    assert_eq!(get_body(&app, "/missing").await.0, StatusCode::NOT_FOUND);
+}
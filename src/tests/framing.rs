@@ -0,0 +1,67 @@
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{Error, WarpService};
+
+fn service() -> WarpService {
+    let filter = warp::body::bytes().map(|body: warp::hyper::body::Bytes| {
+        Box::new(body.len().to_string()) as Box<dyn warp::Reply + Send + Sync>
+    });
+    WarpService::new(filter.boxed())
+}
+
+fn request(headers: &[(&str, &str)]) -> AxumRequest {
+    let mut builder = AxumRequest::builder().method("POST").uri("/upload");
+    for (name, value) in headers {
+        builder = builder.header(*name, *value);
+    }
+    builder.body(AxumBody::from("hello")).unwrap()
+}
+
+#[tokio::test]
+async fn test_ambiguous_framing_is_rejected() {
+    let cases = [
+        (vec![("content-length", "5")], StatusCode::OK),
+        (vec![("content-length", "5, 5")], StatusCode::OK),
+        (vec![("transfer-encoding", "gzip, chunked")], StatusCode::OK),
+        (
+            vec![("content-length", "5"), ("transfer-encoding", "chunked")],
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            vec![("content-length", "5"), ("content-length", "6")],
+            StatusCode::BAD_REQUEST,
+        ),
+        (vec![("content-length", "+5")], StatusCode::BAD_REQUEST),
+        (
+            vec![("transfer-encoding", "chunked, gzip")],
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            vec![
+                ("transfer-encoding", "chunked"),
+                ("transfer-encoding", "chunked"),
+            ],
+            StatusCode::BAD_REQUEST,
+        ),
+    ];
+
+    for (headers, status) in cases {
+        let response = service().oneshot(request(&headers)).await.unwrap();
+        assert_eq!(response.status(), status, "{:?}", headers);
+    }
+}
+
+#[tokio::test]
+async fn test_ambiguous_framing_error_is_typed() {
+    let err = service()
+        .into_fallible()
+        .oneshot(request(&[
+            ("content-length", "5"),
+            ("transfer-encoding", "chunked"),
+        ]))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidFraming(_)));
+}
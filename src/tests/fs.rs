@@ -0,0 +1,216 @@
+// Tests to ensure that files served by `warp::fs` keep their range and conditional request
+// handling, and their framing, through the service wrapper.
+use std::{path::PathBuf, sync::OnceLock};
+
+use axum::{
+    body::Body as AxumBody,
+    extract::Request as AxumRequest,
+    http::{StatusCode, header},
+    response::Response,
+};
+use http_body::Body as _;
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::warp_service::WarpService;
+
+// Larger than hyper's and warp's read buffers, so the file is streamed in many chunks.
+const LARGE: usize = 4 * 1024 * 1024 + 7;
+
+fn fixture_dir() -> PathBuf {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+
+    DIR.get_or_init(|| {
+        let dir = std::env::temp_dir().join(format!("warpdrive-fs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let data: Vec<u8> = (0..LARGE).map(|i| (i % 251) as u8).collect();
+        std::fs::write(dir.join("large.bin"), data).unwrap();
+        std::fs::write(dir.join("hello.txt"), "Hello from a file!").unwrap();
+
+        dir
+    })
+    .clone()
+}
+
+fn service() -> WarpService<warp::fs::File> {
+    WarpService::new(
+        warp::path("files")
+            .and(warp::fs::dir(fixture_dir()))
+            .boxed(),
+    )
+}
+
+async fn get(uri: &str, headers: &[(header::HeaderName, &str)]) -> Response {
+    let mut request = AxumRequest::builder().uri(uri);
+    for (name, value) in headers {
+        request = request.header(name, *value);
+    }
+    service()
+        .oneshot(request.body(AxumBody::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+async fn body(response: Response) -> axum::body::Bytes {
+    axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_file_headers() {
+    let response = get("/files/hello.txt", &[]).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_LENGTH], "18");
+    assert_eq!(response.headers()[header::ACCEPT_RANGES], "bytes");
+    assert!(response.headers().contains_key(header::LAST_MODIFIED));
+    assert!(!response.headers().contains_key(header::TRANSFER_ENCODING));
+    assert_eq!(response.body().size_hint().exact(), Some(18));
+    assert_eq!(body(response).await, "Hello from a file!");
+}
+
+#[tokio::test]
+async fn test_large_file_streams() {
+    let response = get("/files/large.bin", &[]).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CONTENT_LENGTH],
+        LARGE.to_string().as_str()
+    );
+    assert_eq!(response.body().size_hint().exact(), Some(LARGE as u64));
+
+    // The body is streamed in chunks rather than buffered into one.
+    let mut body = response.into_body();
+    let mut chunks = 0;
+    let mut received = Vec::with_capacity(LARGE);
+    while let Some(frame) = http_body_util::BodyExt::frame(&mut body).await {
+        let data = frame.unwrap().into_data().unwrap();
+        received.extend_from_slice(&data);
+        chunks += 1;
+    }
+    assert!(chunks > 1);
+    assert_eq!(received.len(), LARGE);
+    assert!(
+        received
+            .iter()
+            .enumerate()
+            .all(|(i, b)| *b == (i % 251) as u8)
+    );
+}
+
+#[tokio::test]
+async fn test_range_request() {
+    let response = get("/files/large.bin", &[(header::RANGE, "bytes=1000-1999")]).await;
+
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()[header::CONTENT_LENGTH], "1000");
+    assert_eq!(
+        response.headers()[header::CONTENT_RANGE],
+        format!("bytes 1000-1999/{}", LARGE).as_str()
+    );
+    assert_eq!(response.body().size_hint().exact(), Some(1000));
+
+    let body = body(response).await;
+    assert_eq!(body.len(), 1000);
+    assert!(
+        body.iter()
+            .enumerate()
+            .all(|(i, b)| *b == ((i + 1000) % 251) as u8)
+    );
+}
+
+#[tokio::test]
+async fn test_suffix_range_matches_warp() {
+    // warp 0.3 serves `bytes=-5` as the first six bytes rather than the last five. The
+    // conversion must not change what the filter serves, quirks included.
+    let filter = warp::path("files").and(warp::fs::dir(fixture_dir()));
+    let expected = warp::test::request()
+        .path("/files/hello.txt")
+        .header("range", "bytes=-5")
+        .reply(&filter)
+        .await;
+
+    let response = get("/files/hello.txt", &[(header::RANGE, "bytes=-5")]).await;
+
+    assert_eq!(response.status().as_u16(), expected.status().as_u16());
+    assert_eq!(
+        response.headers()[header::CONTENT_RANGE].as_bytes(),
+        expected.headers()["content-range"].as_bytes()
+    );
+    assert_eq!(body(response).await, expected.body());
+}
+
+#[tokio::test]
+async fn test_unsatisfiable_range() {
+    let response = get("/files/hello.txt", &[(header::RANGE, "bytes=100-200")]).await;
+
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes */18");
+}
+
+#[tokio::test]
+async fn test_if_modified_since() {
+    let response = get("/files/hello.txt", &[]).await;
+    let last_modified = response.headers()[header::LAST_MODIFIED]
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let response = get(
+        "/files/hello.txt",
+        &[(header::IF_MODIFIED_SINCE, &last_modified)],
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert!(!response.headers().contains_key(header::TRANSFER_ENCODING));
+    assert!(body(response).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_if_range_with_stale_date_returns_full_file() {
+    let response = get(
+        "/files/hello.txt",
+        &[
+            (header::RANGE, "bytes=0-4"),
+            (header::IF_RANGE, "Thu, 01 Jan 1970 00:00:00 GMT"),
+        ],
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body(response).await, "Hello from a file!");
+}
+
+#[tokio::test]
+async fn test_served_over_tcp() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = axum::Router::new().fallback_service(service());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(
+            b"GET /files/large.bin HTTP/1.1\r\nhost: localhost\r\nrange: bytes=10-19\r\n\
+              connection: close\r\n\r\n",
+        )
+        .await
+        .unwrap();
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await.unwrap();
+
+    let (head, body) =
+        response.split_at(response.windows(4).position(|w| w == b"\r\n\r\n").unwrap() + 4);
+    let head = String::from_utf8_lossy(head).to_ascii_lowercase();
+    assert!(head.starts_with("http/1.1 206 partial content\r\n"));
+    assert!(head.contains("content-length: 10\r\n"));
+    assert!(!head.contains("transfer-encoding"));
+    let expected: Vec<u8> = (10..20).map(|i| (i % 251) as u8).collect();
+    assert_eq!(body, expected);
+}
@@ -0,0 +1,95 @@
+use arbitrary::{Arbitrary, Unstructured};
+use proptest::{prelude::ProptestConfig, proptest};
+
+use crate::fuzz::{
+    ArbitraryRequest, ArbitraryResponse, arbitrary_request, arbitrary_response,
+    check_request_round_trip, check_response_round_trip, request_strategy, response_strategy,
+};
+
+/// Produces deterministic pseudo-random inputs, standing in for a fuzzer.
+fn inputs(count: usize) -> impl Iterator<Item = Vec<u8>> {
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    (0..count).map(move |i| {
+        (0..(i % 512))
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect()
+    })
+}
+
+#[tokio::test]
+async fn test_request_round_trip() {
+    for input in inputs(500) {
+        let request = arbitrary_request(&input);
+        let description = format!("{:?}", request);
+
+        if let Err(err) = check_request_round_trip(request).await {
+            panic!("{}\nrequest: {}", err, description);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_response_round_trip() {
+    for input in inputs(500) {
+        let response = arbitrary_response(&input);
+        let description = format!("{:?}", response);
+
+        if let Err(err) = check_response_round_trip(response).await {
+            panic!("{}\nresponse: {}", err, description);
+        }
+    }
+}
+
+#[test]
+fn test_generators_are_deterministic() {
+    let input = b"the same input every time";
+
+    assert_eq!(
+        format!("{:?}", arbitrary_request(input)),
+        format!("{:?}", arbitrary_request(input))
+    );
+    assert_eq!(arbitrary_request(b"").method(), "GET");
+    assert_eq!(arbitrary_request(b"").uri(), "/");
+}
+
+#[tokio::test]
+async fn test_arbitrary_impls() {
+    for input in inputs(100) {
+        let mut u = Unstructured::new(&input);
+        let ArbitraryRequest(request) = ArbitraryRequest::arbitrary(&mut u).unwrap();
+        let ArbitraryResponse(response) = ArbitraryResponse::arbitrary(&mut u).unwrap();
+
+        check_request_round_trip(request).await.unwrap();
+        check_response_round_trip(response).await.unwrap();
+    }
+
+    let ArbitraryRequest(request) =
+        ArbitraryRequest::arbitrary_take_rest(Unstructured::new(b"")).unwrap();
+    assert_eq!(request.uri(), "/");
+}
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn test_request_strategy_round_trip(request in request_strategy()) {
+        block_on(check_request_round_trip(request)).unwrap();
+    }
+
+    #[test]
+    fn test_response_strategy_round_trip(response in response_strategy()) {
+        block_on(check_response_round_trip(response)).unwrap();
+    }
+}
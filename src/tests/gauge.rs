@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest};
+use tokio::sync::Semaphore;
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{InFlightGauge, WarpService};
+
+#[tokio::test]
+async fn test_in_flight_gauge() {
+    let release = Arc::new(Semaphore::new(0));
+    let filter = warp::any().and_then({
+        let release = Arc::clone(&release);
+        move || {
+            let release = Arc::clone(&release);
+            async move {
+                release.acquire().await.unwrap().forget();
+                Ok::<_, warp::Rejection>("done")
+            }
+        }
+    });
+    let gauge = InFlightGauge::new();
+    let service = WarpService::new(filter.boxed()).with_in_flight_gauge(gauge.clone());
+
+    let request = || {
+        AxumRequest::builder()
+            .uri("/")
+            .body(AxumBody::empty())
+            .unwrap()
+    };
+    let first = tokio::spawn(service.clone().oneshot(request()));
+    let second = tokio::spawn(service.oneshot(request()));
+    while gauge.get() < 2 {
+        tokio::task::yield_now().await;
+    }
+    assert_eq!(gauge.peak(), 2);
+
+    release.add_permits(2);
+    let first = first.await.unwrap().unwrap();
+    let second = second.await.unwrap().unwrap();
+
+    // Requests stay in flight until their response bodies finish.
+    assert_eq!(gauge.get(), 2);
+    let body = axum::body::to_bytes(first.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "done");
+    assert_eq!(gauge.get(), 1);
+    drop(second);
+    assert_eq!(gauge.get(), 0);
+    assert_eq!(gauge.peak(), 2);
+}
@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+use axum::{body::Bytes, http::Request};
+use warp::Filter;
+
+use crate::{
+    WarpService,
+    test::{check_golden, record_golden},
+};
+
+fn golden_path(name: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join(format!("warpdrive-golden-{}", std::process::id()))
+        .join(format!("{}.json", name))
+}
+
+fn requests() -> Vec<Request<Bytes>> {
+    vec![
+        Request::get("/hello").body(Bytes::new()).unwrap(),
+        Request::post("/echo")
+            .header("content-type", "application/octet-stream")
+            .body(Bytes::from_static(&[0xff, 0x00, 0x7f]))
+            .unwrap(),
+    ]
+}
+
+fn service(greeting: &'static str) -> WarpService<warp::reply::Response> {
+    let hello = warp::path("hello").map(move || warp::reply::Reply::into_response(greeting));
+    let echo = warp::path("echo")
+        .and(warp::body::bytes())
+        .map(|body: Bytes| warp::reply::Reply::into_response(body.to_vec()));
+
+    WarpService::new(hello.or(echo).unify().boxed())
+}
+
+#[tokio::test]
+async fn test_golden_replay_matches_recording() {
+    let path = golden_path("matches");
+
+    record_golden(&path, service("Hello!"), requests())
+        .await
+        .unwrap();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(contents.contains("\"format\": 1"));
+    assert!(contents.contains("\"hex\": \"ff007f\""));
+
+    let mismatches = check_golden(&path, service("Hello!")).await.unwrap();
+    assert!(mismatches.is_empty(), "{:?}", mismatches);
+}
+
+#[tokio::test]
+async fn test_golden_replay_detects_drift() {
+    let path = golden_path("drift");
+
+    record_golden(&path, service("Hello!"), requests())
+        .await
+        .unwrap();
+
+    let mismatches = check_golden(&path, service("Howdy!")).await.unwrap();
+
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0].uri, "/hello");
+    assert_eq!(
+        mismatches[0].differences,
+        vec!["body: recorded b\"Hello!\" != actual b\"Howdy!\"".to_string()]
+    );
+}
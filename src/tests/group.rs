@@ -0,0 +1,82 @@
+use axum::{
+    Router, body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode, routing::get,
+};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{WarpService, WarpServiceGroup};
+
+async fn get_body(service: &WarpServiceGroup, uri: &str) -> (StatusCode, String) {
+    let req = AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.clone().oneshot(req).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+fn group() -> WarpServiceGroup {
+    let billing = warp::path!("billing" / "invoices").map(|| "invoices");
+    let reports = warp::path!("billing" / "reports" / String).map(|id| format!("report {}", id));
+    let accounts = warp::path!("accounts" / u32).map(|id| format!("account {}", id));
+
+    WarpServiceGroup::new()
+        .route("/billing", WarpService::new(billing.boxed()))
+        .route("/billing/reports", WarpService::new(reports.boxed()))
+        .route("/accounts/", WarpService::new(accounts.boxed()))
+}
+
+#[tokio::test]
+async fn test_group_routes_by_longest_prefix() {
+    let group = group();
+
+    assert_eq!(
+        get_body(&group, "/billing/invoices").await,
+        (StatusCode::OK, "invoices".to_string())
+    );
+    assert_eq!(
+        get_body(&group, "/billing/reports/q3").await,
+        (StatusCode::OK, "report q3".to_string())
+    );
+    assert_eq!(
+        get_body(&group, "/accounts/7").await,
+        (StatusCode::OK, "account 7".to_string())
+    );
+
+    // The prefix matches, so the rejection from its filter is returned.
+    let (status, _) = get_body(&group, "/billing/unknown").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Prefixes match whole segments only.
+    let (status, body) = get_body(&group, "/billing-old/invoices").await;
+    assert_eq!((status, body.as_str()), (StatusCode::NOT_FOUND, ""));
+}
+
+#[tokio::test]
+async fn test_group_fallback() {
+    let group = group().fallback(get(|| async { "from axum" }));
+
+    assert_eq!(
+        get_body(&group, "/users").await,
+        (StatusCode::OK, "from axum".to_string())
+    );
+    assert_eq!(
+        format!("{:?}", group),
+        r#"WarpServiceGroup { prefixes: ["/accounts", "/billing", "/billing/reports"], fallback: true }"#
+    );
+
+    // The group can be mounted in a router.
+    let app: Router = Router::new()
+        .route("/health", get(|| async { "healthy" }))
+        .fallback_service(group);
+    let req = AxumRequest::builder()
+        .uri("/accounts/1")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = app.oneshot(req).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
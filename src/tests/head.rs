@@ -0,0 +1,61 @@
+use axum::{
+    body::Body as AxumBody,
+    extract::Request as AxumRequest,
+    http::{Method, header::CONTENT_LENGTH},
+};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::WarpService;
+
+async fn send(
+    service: &WarpService<warp::reply::Response>,
+    method: Method,
+    uri: &str,
+) -> (Option<String>, usize) {
+    let request = AxumRequest::builder()
+        .method(method)
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.clone().oneshot(request).await.unwrap();
+    let content_length = response
+        .headers()
+        .get(CONTENT_LENGTH)
+        .map(|value| value.to_str().unwrap().to_string());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+
+    (content_length, body.len())
+}
+
+#[tokio::test]
+async fn test_head_response_body_elided() {
+    // Warp filters matching any method answer HEAD requests with a body.
+    let sized = warp::path("sized").map(|| warp::reply::Reply::into_response("hello"));
+    let explicit = warp::path("explicit").map(|| {
+        let mut response = warp::reply::Reply::into_response("hello");
+        response
+            .headers_mut()
+            .insert("content-length", warp::http::HeaderValue::from_static("5"));
+        response
+    });
+    let streaming = warp::path("streaming").map(|| {
+        let chunks = futures::stream::iter([Ok::<_, std::convert::Infallible>("hello")]);
+        warp::reply::Response::new(warp::hyper::Body::wrap_stream(chunks))
+    });
+    let service = WarpService::new(sized.or(explicit).unify().or(streaming).unify().boxed());
+
+    assert_eq!(
+        send(&service, Method::HEAD, "/sized").await,
+        (Some("5".into()), 0)
+    );
+    assert_eq!(
+        send(&service, Method::HEAD, "/explicit").await,
+        (Some("5".into()), 0)
+    );
+    assert_eq!(send(&service, Method::HEAD, "/streaming").await, (None, 0));
+
+    assert_eq!(send(&service, Method::GET, "/sized").await.1, 5);
+}
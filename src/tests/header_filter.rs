@@ -0,0 +1,112 @@
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, http::HeaderMap};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{HeaderFilter, WarpService};
+
+/// A service that echoes the names of the request headers it receives, and adds debug
+/// headers to the response.
+fn service(header_filter: HeaderFilter) -> WarpService<warp::reply::Response> {
+    let filter = warp::header::headers_cloned().map(|headers: warp::http::HeaderMap| {
+        let mut names: Vec<_> = headers.keys().map(|name| name.as_str()).collect();
+        names.sort();
+
+        let mut response = warp::reply::Reply::into_response(names.join(","));
+        let response_headers = response.headers_mut();
+        response_headers.insert("server", warp::http::HeaderValue::from_static("legacy/1.0"));
+        response_headers.insert(
+            "x-debug-sql",
+            warp::http::HeaderValue::from_static("SELECT 1"),
+        );
+        response_headers.insert("x-request-id", warp::http::HeaderValue::from_static("1"));
+        response
+    });
+
+    WarpService::new(filter.boxed()).with_header_filter(header_filter)
+}
+
+async fn send(service: WarpService<warp::reply::Response>) -> (HeaderMap, String) {
+    let request = AxumRequest::builder()
+        .uri("/")
+        .header("accept", "*/*")
+        .header("x-internal-user", "admin")
+        .header("X-Internal-Trace", "1")
+        .header("x-request-id", "1")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    let headers = response.headers().clone();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+
+    (headers, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn test_header_denylist() {
+    let header_filter = HeaderFilter::new()
+        .deny_request("x-internal-*")
+        .deny_response("Server")
+        .deny_response("x-debug-*");
+
+    let (headers, received) = send(service(header_filter)).await;
+
+    assert_eq!(received, "accept,x-request-id");
+    assert!(headers.get("server").is_none());
+    assert!(headers.get("x-debug-sql").is_none());
+    assert_eq!(headers.get("x-request-id").unwrap(), "1");
+}
+
+#[tokio::test]
+async fn test_header_allowlist() {
+    let header_filter = HeaderFilter::new()
+        .allow_request("accept")
+        .allow_response("content-*")
+        .allow_response("x-*")
+        .deny_response("x-debug-*");
+
+    let (headers, received) = send(service(header_filter)).await;
+
+    assert_eq!(received, "accept");
+    assert!(headers.get("server").is_none());
+    assert!(headers.get("x-debug-sql").is_none());
+    assert_eq!(headers.get("x-request-id").unwrap(), "1");
+    assert!(headers.get("content-type").is_some());
+}
+
+#[tokio::test]
+async fn test_default_request_headers() {
+    let filter = warp::header::<String>("x-client-id")
+        .and(warp::header::<String>("x-region"))
+        .map(|client: String, region: String| format!("{} {}", client, region));
+    let service = WarpService::new(filter.boxed())
+        .with_header_filter(HeaderFilter::new().deny_request("x-region"))
+        .with_default_request_header("x-client-id", "edge")
+        .with_default_request_header("x-region", "eu");
+
+    let body = |req: AxumRequest| {
+        let service = service.clone();
+        async move {
+            let response = service.oneshot(req).await.unwrap();
+            axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap()
+        }
+    };
+
+    let bare = AxumRequest::builder()
+        .uri("/")
+        .body(AxumBody::empty())
+        .unwrap();
+    assert_eq!(body(bare).await, "edge eu");
+
+    // Present headers are kept, unless they were filtered out.
+    let provided = AxumRequest::builder()
+        .uri("/")
+        .header("x-client-id", "mobile")
+        .header("x-region", "us")
+        .body(AxumBody::empty())
+        .unwrap();
+    assert_eq!(body(provided).await, "mobile eu");
+}
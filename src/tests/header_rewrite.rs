@@ -0,0 +1,63 @@
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{HeaderFilter, HeaderRewrite, WarpService};
+
+async fn headers(service: &WarpService, uri: &str) -> axum::http::HeaderMap {
+    let req = AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    service
+        .clone()
+        .oneshot(req)
+        .await
+        .unwrap()
+        .headers()
+        .clone()
+}
+
+#[tokio::test]
+async fn test_header_rewrite_rules() {
+    let filter = warp::any().map(|| {
+        let reply = warp::reply::with_header("hello", "x-api-version", "1");
+        let reply = warp::reply::with_header(reply, "x-powered-by", "warp");
+        Box::new(warp::reply::with_header(reply, "x-debug", "on"))
+            as Box<dyn warp::Reply + Send + Sync>
+    });
+    let service = WarpService::new(filter.boxed())
+        .with_header_rewrite(
+            HeaderRewrite::new()
+                .rename("x-api-version", "api-version")
+                .remove("x-powered-by")
+                .prefix(
+                    "/v1",
+                    HeaderRewrite::new()
+                        .set("deprecation", "true")
+                        .prefix("/v1/users", HeaderRewrite::new().set("sunset", "soon")),
+                ),
+        )
+        .with_header_filter(HeaderFilter::new().deny_response("x-debug"));
+
+    let current = headers(&service, "/v2/users").await;
+    assert_eq!(current["api-version"], "1");
+    assert!(!current.contains_key("x-api-version"));
+    assert!(!current.contains_key("x-powered-by"));
+    assert!(!current.contains_key("x-debug"));
+    assert!(!current.contains_key("deprecation"));
+
+    let legacy = headers(&service, "/v1/orders").await;
+    assert_eq!(legacy["deprecation"], "true");
+    assert!(!legacy.contains_key("sunset"));
+
+    let users = headers(&service, "/v1/users/7").await;
+    assert_eq!(users["deprecation"], "true");
+    assert_eq!(users["sunset"], "soon");
+
+    assert!(
+        !headers(&service, "/v1-beta")
+            .await
+            .contains_key("deprecation")
+    );
+}
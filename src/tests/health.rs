@@ -0,0 +1,78 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode};
+use serde_json::{Value, json};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{Readiness, Shutdown, WarpService};
+
+async fn probe(router: &axum::Router, uri: &str) -> (StatusCode, Value) {
+    let req = AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = router.clone().oneshot(req).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        serde_json::from_slice(&body).unwrap_or_else(|_| json!(String::from_utf8_lossy(&body))),
+    )
+}
+
+#[tokio::test]
+async fn test_readiness_reflects_services_and_checks() {
+    let shutdown = Shutdown::new();
+    let service =
+        WarpService::new(warp::any().map(|| "warp").boxed()).with_shutdown(shutdown.clone());
+    let database_up = Arc::new(AtomicBool::new(true));
+    let database = database_up.clone();
+
+    let router = Readiness::new()
+        .service("legacy", &service)
+        .check("database", move || {
+            let up = database.load(Ordering::SeqCst);
+            async move { up }
+        })
+        .into_router();
+
+    assert_eq!(
+        probe(&router, "/healthz").await,
+        (StatusCode::OK, json!("ok"))
+    );
+    assert_eq!(
+        probe(&router, "/readyz").await,
+        (
+            StatusCode::OK,
+            json!({
+                "ready": true,
+                "services": {
+                    "legacy": {
+                        "ready": true,
+                        "draining": false,
+                        "kill_switch": false,
+                        "open_circuits": [],
+                    },
+                },
+                "checks": { "database": true },
+            })
+        )
+    );
+
+    database_up.store(false, Ordering::SeqCst);
+    let (status, body) = probe(&router, "/readyz").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["checks"]["database"], false);
+
+    database_up.store(true, Ordering::SeqCst);
+    shutdown.trigger();
+    let (status, body) = probe(&router, "/readyz").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["services"]["legacy"]["draining"], true);
+}
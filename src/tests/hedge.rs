@@ -0,0 +1,127 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{
+    body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode, routing::any,
+};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{Hedge, HedgeWinner, RateLimit, WarpService};
+
+async fn send(service: &WarpService, method: &str, uri: &str) -> (String, Option<String>) {
+    let req = AxumRequest::builder()
+        .method(method)
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.clone().oneshot(req).await.unwrap();
+    let winner = response
+        .headers()
+        .get("x-warpdrive-hedge")
+        .map(|value| value.to_str().unwrap().to_string());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (String::from_utf8(body.to_vec()).unwrap(), winner)
+}
+
+fn service(winners: Arc<Mutex<Vec<HedgeWinner>>>) -> WarpService {
+    // Warp takes 200ms on `/slow` and fails on `/broken`, and Axum always takes 50ms.
+    let filter = warp::path::full().and_then(|path: warp::path::FullPath| async move {
+        if path.as_str() == "/slow" {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+        let status = if path.as_str() == "/broken" {
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR
+        } else {
+            warp::http::StatusCode::OK
+        };
+        Ok::<_, warp::Rejection>(Box::new(warp::reply::with_status("warp", status))
+            as Box<dyn warp::Reply + Send + Sync>)
+    });
+
+    let axum = any(|| async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        "axum"
+    });
+
+    WarpService::new(filter.boxed()).with_hedge(
+        Hedge::new(axum).on_winner(move |_, winner| winners.lock().unwrap().push(winner)),
+    )
+}
+
+#[tokio::test]
+async fn test_hedge_serves_first_successful_response() {
+    let winners = Arc::new(Mutex::new(Vec::new()));
+    let service = service(Arc::clone(&winners));
+
+    assert_eq!(
+        send(&service, "GET", "/fast").await,
+        ("warp".to_string(), Some("warp".to_string()))
+    );
+    assert_eq!(
+        send(&service, "GET", "/slow").await,
+        ("axum".to_string(), Some("axum".to_string()))
+    );
+
+    // The Warp failure finishes first, so the Axum response is awaited.
+    assert_eq!(
+        send(&service, "GET", "/broken").await,
+        ("axum".to_string(), Some("axum".to_string()))
+    );
+
+    assert_eq!(
+        *winners.lock().unwrap(),
+        [HedgeWinner::Warp, HedgeWinner::Axum, HedgeWinner::Axum]
+    );
+}
+
+#[tokio::test]
+async fn test_unsafe_methods_are_not_hedged() {
+    let winners = Arc::new(Mutex::new(Vec::new()));
+    let service = service(Arc::clone(&winners));
+
+    assert_eq!(
+        send(&service, "POST", "/slow").await,
+        ("warp".to_string(), None)
+    );
+
+    let req = AxumRequest::builder()
+        .method("POST")
+        .uri("/broken")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.oneshot(req).await.unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    assert!(winners.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_rate_limited_requests_are_not_hedged() {
+    let winners = Arc::new(Mutex::new(Vec::new()));
+    let service = service(Arc::clone(&winners)).with_rate_limit(RateLimit::new(
+        |_| Some("all".to_string()),
+        1,
+        Duration::from_secs(60),
+    ));
+
+    assert_eq!(
+        send(&service, "GET", "/slow").await,
+        ("axum".to_string(), Some("axum".to_string()))
+    );
+
+    // The Axum service would answer, but the rate limit still applies.
+    let req = AxumRequest::builder()
+        .uri("/slow")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.oneshot(req).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(!response.headers().contains_key("x-warpdrive-hedge"));
+
+    assert_eq!(*winners.lock().unwrap(), [HedgeWinner::Axum]);
+}
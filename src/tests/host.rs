@@ -0,0 +1,79 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    body::Body as AxumBody,
+    extract::Request as AxumRequest,
+    http::{Version, header::HOST},
+};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{HostOverride, WarpService};
+
+async fn host_seen(host_override: HostOverride, req: AxumRequest) -> String {
+    let filter = warp::header::<String>("host")
+        .map(|host: String| Box::new(host) as Box<dyn warp::Reply + Send + Sync>);
+    let service = WarpService::new(filter.boxed()).with_host_override(host_override);
+
+    let response = service.oneshot(req).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+fn with_host(host: &str) -> AxumRequest {
+    AxumRequest::builder()
+        .uri("/")
+        .header(HOST, host)
+        .body(AxumBody::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_host_override_sets_header_and_authority() {
+    let uri = Arc::new(Mutex::new(None));
+    let filter = warp::header::<String>("host")
+        .map(|host: String| Box::new(host) as Box<dyn warp::Reply + Send + Sync>);
+    let service = WarpService::new(filter.boxed())
+        .with_host_override(HostOverride::set("legacy.internal:8080"))
+        .with_map_request({
+            let uri = Arc::clone(&uri);
+            move |req| {
+                *uri.lock().unwrap() = Some(req.uri().to_string());
+                req
+            }
+        });
+
+    let req = AxumRequest::builder()
+        .version(Version::HTTP_2)
+        .uri("https://api.example.com/users?page=2")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.oneshot(req).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+
+    assert_eq!(body, "legacy.internal:8080");
+    assert_eq!(
+        uri.lock().unwrap().as_deref(),
+        Some("https://legacy.internal:8080/users?page=2")
+    );
+}
+
+#[tokio::test]
+async fn test_host_override_normalizes_host() {
+    let seen = host_seen(HostOverride::normalize(), with_host("API.Example.com.:80")).await;
+    assert_eq!(seen, "api.example.com");
+
+    let seen = host_seen(HostOverride::normalize(), with_host("api.example.com:8080")).await;
+    assert_eq!(seen, "api.example.com:8080");
+
+    let seen = host_seen(
+        HostOverride::normalize().strip_port(true),
+        with_host("api.example.com:8080"),
+    )
+    .await;
+    assert_eq!(seen, "api.example.com");
+}
@@ -0,0 +1,55 @@
+use http_body_util::{BodyExt, Full};
+use tower::ServiceExt;
+use warp::{Filter, hyper::body::Bytes};
+
+use crate::http1::HttpWarpService;
+
+#[tokio::test]
+async fn test_http_warp_service() {
+    let filter = warp::path("echo")
+        .and(warp::post())
+        .and(warp::body::bytes())
+        .map(|body: Bytes| warp::reply::with_header(body.to_vec(), "x-echo", "true"));
+    let service = HttpWarpService::new(filter.boxed());
+
+    let request = http::Request::post("/echo")
+        .header("content-type", "text/plain")
+        .body(Full::new(Bytes::from("Hello")))
+        .unwrap();
+    let response = service.clone().oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), http::StatusCode::OK);
+    assert_eq!(response.headers().get("x-echo").unwrap(), "true");
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert_eq!(body, "Hello");
+
+    // Rejections are answered by Warp as usual.
+    let request = http::Request::get("/missing")
+        .body(Full::new(Bytes::new()))
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_non_utf8_header_values_are_echoed() {
+    let filter = warp::header::headers_cloned().map(|headers: warp::http::HeaderMap| {
+        let mut response = warp::reply::Response::new(warp::hyper::Body::empty());
+        response
+            .headers_mut()
+            .insert("x-echo", headers["x-name"].clone());
+        response
+    });
+    let service = HttpWarpService::new(filter.boxed());
+
+    let request = http::Request::get("/")
+        .header("x-name", &b"\xc0\xff\x80 opaque"[..])
+        .body(Full::new(Bytes::new()))
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+
+    assert_eq!(
+        response.headers()["x-echo"].as_bytes(),
+        b"\xc0\xff\x80 opaque"
+    );
+}
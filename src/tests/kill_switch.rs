@@ -0,0 +1,58 @@
+use axum::{
+    body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode, routing::get,
+};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{KillSwitch, Migration, MigrationPhase, WarpService, admin::Switch};
+
+async fn call(service: &WarpService, uri: &str) -> (StatusCode, Option<String>, String) {
+    let req = AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.clone().oneshot(req).await.unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .map(|value| value.to_str().unwrap().to_string());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        content_type,
+        String::from_utf8(body.to_vec()).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn test_kill_switch_stops_warp_requests() {
+    let switch = Switch::new(false);
+    let filter = warp::any().map(|| Box::new("warp") as Box<dyn warp::Reply + Send + Sync>);
+    let migration = Migration::new(get(|| async { "axum" })).phase("/new", MigrationPhase::NewOnly);
+    let service = WarpService::new(filter.boxed())
+        .with_migration(migration)
+        .with_kill_switch(
+            KillSwitch::new(switch.clone())
+                .body(r#"{"error":"disabled"}"#)
+                .content_type("application/json"),
+        );
+
+    assert_eq!(call(&service, "/legacy").await.2, "warp");
+
+    switch.set(true);
+    assert_eq!(
+        call(&service, "/legacy").await,
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Some("application/json".to_string()),
+            r#"{"error":"disabled"}"#.to_string()
+        )
+    );
+    assert_eq!(call(&service, "/new").await.2, "axum");
+
+    switch.set(false);
+    assert_eq!(call(&service, "/legacy").await.0, StatusCode::OK);
+}
@@ -0,0 +1,237 @@
+use axum::{
+    Router,
+    body::Body as AxumBody,
+    extract::Request as AxumRequest,
+    routing::{get, post},
+};
+use tower::ServiceExt;
+use warp::{Filter, http::StatusCode as WarpStatusCode};
+
+use crate::{
+    extract::axum_extract,
+    layer::{WarpFilterLayer, WarpWrapLayer},
+};
+
+#[tokio::test]
+async fn test_wrap_layer_modifies_response() {
+    let app = Router::new()
+        .route("/hello", get(|| async { "Hello from Axum!" }))
+        .layer(WarpWrapLayer::new(|filter| {
+            filter
+                .with(warp::reply::with::header("x-wrapped", "yes"))
+                .boxed()
+        }));
+
+    let request = AxumRequest::builder()
+        .uri("/hello")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers().get("x-wrapped").unwrap(), "yes");
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "Hello from Axum!");
+}
+
+#[tokio::test]
+async fn test_wrap_layer_forwards_request() {
+    let app = Router::new()
+        .route(
+            "/echo",
+            post(|req: AxumRequest| async move {
+                let query = req.uri().query().unwrap_or_default().to_string();
+                let header = req.headers()["x-custom"].to_str().unwrap().to_string();
+                let body = axum::body::to_bytes(req.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                format!("{} {} {}", query, header, String::from_utf8_lossy(&body))
+            }),
+        )
+        .layer(WarpWrapLayer::new(|filter| {
+            filter.with(warp::log("warpdrive::test")).boxed()
+        }));
+
+    let request = AxumRequest::builder()
+        .method("POST")
+        .uri("/echo?a=1&b=2")
+        .header("x-custom", "value")
+        .body(AxumBody::from("payload"))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), 200);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "a=1&b=2 value payload");
+}
+
+#[tokio::test]
+async fn test_wrap_layer_can_short_circuit() {
+    let app = Router::new()
+        .route("/guarded", get(|| async { "secret" }))
+        .layer(WarpWrapLayer::new(|filter| {
+            warp::header::exact("x-api-key", "letmein")
+                .and(filter)
+                .recover(|_| async {
+                    Ok::<_, std::convert::Infallible>(warp::reply::with_status(
+                        "denied",
+                        WarpStatusCode::UNAUTHORIZED,
+                    ))
+                })
+                .boxed()
+        }));
+
+    let request = AxumRequest::builder()
+        .uri("/guarded")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), 401);
+
+    let request = AxumRequest::builder()
+        .uri("/guarded")
+        .header("x-api-key", "letmein")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), 200);
+}
+
+#[tokio::test]
+async fn test_filter_layer_guards_routes() {
+    let app = Router::new()
+        .route("/admin", get(|| async { "Welcome" }))
+        .layer(WarpFilterLayer::new(warp::header::exact(
+            "x-api-key",
+            "secret",
+        )));
+
+    let request = AxumRequest::builder()
+        .uri("/admin")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), 400);
+
+    let request = AxumRequest::builder()
+        .uri("/admin")
+        .header("x-api-key", "secret")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "Welcome");
+}
+
+#[tokio::test]
+async fn test_filter_layer_preserves_body() {
+    let app = Router::new()
+        .route("/echo", post(|body: String| async move { body }))
+        .layer(WarpFilterLayer::new(warp::post()));
+
+    let request = AxumRequest::builder()
+        .method("POST")
+        .uri("/echo")
+        .body(AxumBody::from("payload"))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "payload");
+}
+
+#[tokio::test]
+async fn test_filter_layer_with_axum_extractor_rejection() {
+    let guard = axum_extract::<axum::extract::Query<std::collections::HashMap<String, u32>>>()
+        .map(|_| ())
+        .untuple_one();
+
+    let app = Router::new()
+        .route("/search", get(|| async { "results" }))
+        .layer(WarpFilterLayer::new(guard));
+
+    let request = AxumRequest::builder()
+        .uri("/search?limit=abc")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_filter_layer_preserves_extensions() {
+    #[derive(Clone)]
+    struct User(&'static str);
+
+    let app = Router::new()
+        .route(
+            "/me",
+            get(|axum::Extension(User(name)): axum::Extension<User>| async move { name }),
+        )
+        .layer(WarpFilterLayer::new(warp::get()))
+        .layer(axum::Extension(User("alice")));
+
+    let request = AxumRequest::builder()
+        .uri("/me")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), 200);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "alice");
+}
+
+#[tokio::test]
+async fn test_filter_layer_preserves_upgrades() {
+    use axum::extract::ws::WebSocketUpgrade;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let app = Router::new()
+        .route(
+            "/ws",
+            get(|ws: WebSocketUpgrade| async move { ws.on_upgrade(|_socket| async {}) }),
+        )
+        .layer(WarpFilterLayer::new(warp::get()));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(
+            b"GET /ws HTTP/1.1\r\n\
+              Host: localhost\r\n\
+              Connection: upgrade\r\n\
+              Upgrade: websocket\r\n\
+              Sec-WebSocket-Version: 13\r\n\
+              Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+        )
+        .await
+        .unwrap();
+
+    let mut response = [0; 12];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(&response, b"HTTP/1.1 101");
+}
@@ -0,0 +1,65 @@
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{Error, RequestLimits, WarpService};
+
+fn service() -> WarpService {
+    let filter = warp::any().map(|| Box::new("ok") as Box<dyn warp::Reply + Send + Sync>);
+    WarpService::new(filter.boxed()).with_request_limits(
+        RequestLimits::new()
+            .max_headers(3)
+            .max_header_bytes(64)
+            .max_uri_length(32),
+    )
+}
+
+fn request(uri: &str, headers: &[(&str, &str)]) -> AxumRequest {
+    let mut builder = AxumRequest::builder().uri(uri);
+    for (name, value) in headers {
+        builder = builder.header(*name, *value);
+    }
+    builder.body(AxumBody::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn test_request_limits_reject_oversized_requests() {
+    let cases = [
+        (
+            request("/users?page=2", &[("a", "1"), ("b", "2")]),
+            StatusCode::OK,
+        ),
+        (
+            request(
+                "/users?page=2",
+                &[("a", "1"), ("b", "2"), ("c", "3"), ("d", "4")],
+            ),
+            StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+        ),
+        (
+            request("/", &[("x-large", &"x".repeat(64))]),
+            StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+        ),
+        (
+            request(&format!("/search?q={}", "a".repeat(32)), &[]),
+            StatusCode::URI_TOO_LONG,
+        ),
+    ];
+
+    for (req, status) in cases {
+        let uri = req.uri().clone();
+        let response = service().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), status, "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn test_request_limits_errors_are_typed() {
+    let service = service().into_fallible();
+
+    let err = service
+        .oneshot(request(&format!("/{}", "a".repeat(40)), &[]))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::UriTooLong(32)));
+}
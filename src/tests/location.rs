@@ -0,0 +1,64 @@
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{LocationRewrite, WarpService};
+
+/// A service that redirects to the URL in the `x-to` header.
+fn service(rewrite: LocationRewrite) -> WarpService {
+    let filter = warp::header::<String>("x-to").map(|to: String| {
+        Box::new(warp::reply::with_header(
+            warp::http::StatusCode::FOUND,
+            "location",
+            to,
+        )) as Box<dyn warp::Reply + Send + Sync>
+    });
+    WarpService::new(filter.boxed()).with_location_rewrite(rewrite)
+}
+
+async fn location(service: &WarpService, to: &str) -> String {
+    let req = AxumRequest::builder()
+        .header("x-to", to)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.clone().oneshot(req).await.unwrap();
+    assert_eq!(response.status(), 302);
+    response.headers()["location"].to_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_location_rewrite() {
+    let service = service(
+        LocationRewrite::new()
+            .origin("http://10.0.0.7:8080", "https://api.example.com/")
+            .relative("http://localhost")
+            .origin("http://localhost", "https://unused.example.com"),
+    );
+
+    let cases = [
+        // Moved to the new origin, keeping the path, query, and fragment.
+        (
+            "http://10.0.0.7:8080/users?page=2#top",
+            "https://api.example.com/users?page=2#top",
+        ),
+        ("HTTP://10.0.0.7:8080", "https://api.example.com"),
+        // Made relative, with the default port matching a missing one.
+        ("http://LOCALHOST:80/login", "/login"),
+        ("http://localhost?next=1", "/?next=1"),
+        // Other origins, ports, and relative URLs are left alone.
+        ("http://10.0.0.7/users", "http://10.0.0.7/users"),
+        ("https://localhost/login", "https://localhost/login"),
+        ("http://user@localhost/login", "http://user@localhost/login"),
+        ("/already/relative", "/already/relative"),
+    ];
+
+    for (to, expected) in cases {
+        assert_eq!(location(&service, to).await, expected, "{}", to);
+    }
+}
+
+#[test]
+#[should_panic(expected = "invalid origin")]
+fn test_location_rewrite_rejects_urls_with_paths() {
+    let _ = LocationRewrite::new().relative("http://localhost/app");
+}
@@ -0,0 +1,143 @@
+use axum::{Router, body::Body as AxumBody, extract::Request as AxumRequest, routing::post};
+use tower::ServiceExt;
+use warp::Filter;
+use warpdrive_macros::{RejectionMapper, dual_handler};
+
+use crate::{error_bridge::recover_mapped, reply::DualReply, warp_service::WarpService};
+
+#[derive(serde::Deserialize)]
+struct Options {
+    verbose: bool,
+}
+
+#[derive(serde::Deserialize)]
+struct Payload {
+    message: String,
+}
+
+#[dual_handler]
+async fn update_post(
+    #[path] user_id: u32,
+    #[path] post_id: u32,
+    #[query] options: Options,
+    #[header("x-request-id")] request_id: String,
+    #[json] payload: Payload,
+) -> DualReply {
+    DualReply::text(format!(
+        "{}/{} {} {} {}",
+        user_id, post_id, options.verbose, request_id, payload.message
+    ))
+}
+
+fn request(uri: &str) -> AxumRequest {
+    AxumRequest::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("x-request-id", "abc")
+        .body(AxumBody::from(r#"{"message": "hi"}"#))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_generated_warp_filter() {
+    let warp_filter = warp::path("users")
+        .and(warp::post())
+        .and(update_post_filter())
+        .boxed();
+
+    let service = WarpService::new(warp_filter);
+
+    let response = service
+        .oneshot(request("/users/1/2?verbose=true"))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "1/2 true abc hi");
+}
+
+#[tokio::test]
+async fn test_generated_axum_handler() {
+    let app = Router::new().route("/users/{user_id}/{post_id}", post(update_post_handler));
+
+    let response = app
+        .oneshot(request("/users/1/2?verbose=true"))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "1/2 true abc hi");
+}
+
+#[tokio::test]
+async fn test_generated_axum_handler_missing_header() {
+    let app = Router::new().route("/users/{user_id}/{post_id}", post(update_post_handler));
+
+    let request = AxumRequest::builder()
+        .method("POST")
+        .uri("/users/1/2?verbose=false")
+        .header("content-type", "application/json")
+        .body(AxumBody::from(r#"{"message": "hi"}"#))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), 400);
+}
+
+#[derive(Debug, thiserror::Error, RejectionMapper)]
+#[rejection(status = 500)]
+enum ApiError {
+    #[error("user {0} not found")]
+    #[rejection(status = 404)]
+    NotFound(u32),
+    #[error("user {name} is suspended")]
+    #[rejection(status = 403, message = "Forbidden")]
+    Suspended { name: String },
+    #[error("database unavailable")]
+    Database,
+}
+
+impl warp::reject::Reject for ApiError {}
+
+#[tokio::test]
+async fn test_derived_rejection_mapper() {
+    let filter = warp::path!("users" / u32)
+        .and_then(|id| async move {
+            Err::<String, _>(warp::reject::custom(match id {
+                1 => ApiError::NotFound(id),
+                2 => ApiError::Suspended {
+                    name: "ada".to_string(),
+                },
+                _ => ApiError::Database,
+            }))
+        })
+        .recover(recover_mapped::<ApiError>)
+        .boxed();
+    let service = WarpService::new(filter);
+
+    for (uri, status, message) in [
+        ("/users/1", 404, "user 1 not found"),
+        ("/users/2", 403, "Forbidden"),
+        ("/users/3", 500, "database unavailable"),
+    ] {
+        let request = AxumRequest::builder()
+            .uri(uri)
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = service.clone().oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), status);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, message);
+    }
+}
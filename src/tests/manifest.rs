@@ -0,0 +1,103 @@
+use axum::{
+    body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode, routing::get,
+};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{Manifest, Migration, MigrationPhase, WarpService};
+
+async fn call(service: &WarpService, uri: &str) -> (StatusCode, String) {
+    let req = AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.clone().oneshot(req).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+const MANIFEST: &str = r#"
+[[routes]]
+prefix = "/api/users"
+phase = "new-only"
+
+[[routes]]
+prefix = "/api/orders"
+phase = "canary"
+percentage = 0
+
+[[denylist]]
+path = "/v1"
+status = 410
+body = "Moved to /api"
+"#;
+
+#[tokio::test]
+async fn test_manifest_builds_topology() {
+    let manifest = Manifest::from_toml(MANIFEST).unwrap();
+    assert_eq!(
+        manifest.routes(),
+        [
+            ("/api/users".to_string(), MigrationPhase::NewOnly),
+            ("/api/orders".to_string(), MigrationPhase::Canary(0)),
+        ]
+    );
+
+    let filter = warp::any().map(|| Box::new("warp") as Box<dyn warp::Reply + Send + Sync>);
+    let migration =
+        Migration::new(axum::Router::new().route("/api/{*rest}", get(|| async { "axum" })));
+    let service = manifest.apply(WarpService::new(filter.boxed()), migration.clone());
+
+    assert_eq!(
+        call(&service, "/api/users/1").await,
+        (StatusCode::OK, "axum".to_string())
+    );
+    assert_eq!(
+        call(&service, "/api/orders").await,
+        (StatusCode::OK, "warp".to_string())
+    );
+    assert_eq!(
+        call(&service, "/v1/users").await,
+        (StatusCode::GONE, "Moved to /api".to_string())
+    );
+
+    let reloaded =
+        Manifest::from_json(r#"{ "routes": [{ "prefix": "/api/orders", "phase": "new-only" }] }"#)
+            .unwrap();
+    reloaded.reload(&migration);
+
+    assert_eq!(
+        call(&service, "/api/users/1").await,
+        (StatusCode::OK, "warp".to_string())
+    );
+    assert_eq!(
+        call(&service, "/api/orders").await,
+        (StatusCode::OK, "axum".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_manifests() {
+    let errors = [
+        r#"{ "routes": [{ "prefix": "/api", "phase": "halfway" }] }"#,
+        r#"{ "routes": [{ "prefix": "/api", "phase": "canary" }] }"#,
+        r#"{ "routes": [{ "prefix": "/api", "phase": "canary", "percentage": 101 }] }"#,
+        r#"{ "denylist": [{ "path": "/v1", "status": 500 }] }"#,
+        r#"{ "routes": {} }"#,
+    ]
+    .map(|json| Manifest::from_json(json).unwrap_err().to_string());
+
+    assert_eq!(
+        errors,
+        [
+            "routes[0].phase is not one of `legacy-only`, `shadow`, `canary`, or `new-only`",
+            "routes[0] has no `percentage`",
+            "routes[0].percentage is not a number from 0 to 100",
+            "denylist[0].status is not 404 or 410",
+            "`routes` is not a list",
+        ]
+    );
+}
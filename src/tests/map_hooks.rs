@@ -0,0 +1,84 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, http::Version};
+use tower::ServiceExt;
+use tower_http::limit::RequestBodyLimitLayer;
+use warp::Filter;
+
+use crate::WarpService;
+
+#[tokio::test]
+async fn test_map_request_and_response() {
+    let filter = warp::header::<String>("x-tenant").map(|tenant: String| tenant);
+    let service = WarpService::new(filter.boxed())
+        .with_map_request(|mut req| {
+            req.headers_mut()
+                .insert("x-tenant", warp::http::HeaderValue::from_static("default"));
+            req
+        })
+        .with_map_response(|mut res| {
+            res.headers_mut()
+                .insert("x-mapped", warp::http::HeaderValue::from_static("true"));
+            res
+        })
+        // Hooks apply inside layers.
+        .layer(RequestBodyLimitLayer::new(1024));
+
+    let request = AxumRequest::builder()
+        .uri("/")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+
+    assert_eq!(response.headers().get("x-mapped").unwrap(), "true");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "default");
+}
+
+#[tokio::test]
+async fn test_map_reply() {
+    let filter = warp::path!("users" / u32).map(|id| warp::reply::json(&id));
+    let service = WarpService::new(filter.boxed())
+        .with_body_limit(1024)
+        .map_reply(|reply| warp::reply::with_header(reply, "x-api-version", "2"));
+
+    let request = AxumRequest::builder()
+        .uri("/users/7")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+
+    assert_eq!(response.headers().get("x-api-version").unwrap(), "2");
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/json"
+    );
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "7");
+}
+
+#[tokio::test]
+async fn test_force_version() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let filter = warp::any().map(|| "ok").with(warp::log::custom({
+        let seen = Arc::clone(&seen);
+        move |info| seen.lock().unwrap().push(info.version())
+    }));
+    let service = WarpService::new(filter.boxed())
+        .force_version(Version::HTTP_11)
+        .layer(RequestBodyLimitLayer::new(1024));
+
+    let req = AxumRequest::builder()
+        .version(Version::HTTP_2)
+        .uri("/")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.oneshot(req).await.unwrap();
+
+    assert_eq!(response.version(), Version::HTTP_2);
+    assert_eq!(*seen.lock().unwrap(), [warp::http::Version::HTTP_11]);
+}
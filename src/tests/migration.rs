@@ -0,0 +1,276 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{
+    body::Body as AxumBody,
+    extract::Request as AxumRequest,
+    http::StatusCode,
+    routing::{get, post},
+};
+use tokio::sync::mpsc;
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{
+    Failover, KillSwitch, Migration, MigrationPhase, RateLimit, ResponseCache, ShadowCounts,
+    ShadowMismatch, WarpService, admin::Switch,
+};
+
+async fn served_by(service: &WarpService, method: &str, uri: &str) -> String {
+    let req = AxumRequest::builder()
+        .method(method)
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.clone().oneshot(req).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+fn service(migration: Migration) -> WarpService {
+    let filter = warp::any().map(|| Box::new("warp") as Box<dyn warp::Reply + Send + Sync>);
+    WarpService::new(filter.boxed()).with_migration(migration)
+}
+
+fn axum_routes() -> axum::Router {
+    axum::Router::new()
+        .route("/api/{*rest}", get(|| async { "axum" }))
+        .route("/api/orders", post(|| async { "axum" }))
+}
+
+#[tokio::test]
+async fn test_migration_phases() {
+    let migration = Migration::new(axum_routes())
+        .phase("/api", MigrationPhase::LegacyOnly)
+        .phase("/api/users", MigrationPhase::NewOnly)
+        .phase("/api/orders", MigrationPhase::Canary(0));
+    let service = service(migration.clone());
+
+    assert_eq!(served_by(&service, "GET", "/api/users/1").await, "axum");
+    assert_eq!(served_by(&service, "GET", "/api/orders").await, "warp");
+    assert_eq!(served_by(&service, "GET", "/api/items").await, "warp");
+    assert_eq!(served_by(&service, "GET", "/other").await, "warp");
+
+    migration.set_phase("/api/orders", MigrationPhase::Canary(100));
+    assert_eq!(served_by(&service, "POST", "/api/orders").await, "axum");
+    assert_eq!(
+        migration.phase_for("/api/orders/7"),
+        MigrationPhase::Canary(100)
+    );
+    assert_eq!(migration.phase_for("/apix"), MigrationPhase::LegacyOnly);
+}
+
+#[tokio::test]
+async fn test_shadow_phase_compares_statuses() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let axum = axum::Router::new().route(
+        "/api/{*rest}",
+        get(|| async { (StatusCode::NOT_FOUND, "axum") }),
+    );
+    let migration = Migration::new(axum)
+        .phase("/api", MigrationPhase::Shadow)
+        .on_shadow(move |parts, warp, axum| {
+            tx.send((parts.uri.path().to_string(), warp, axum)).unwrap();
+        });
+    let service = service(migration);
+
+    assert_eq!(served_by(&service, "GET", "/api/users").await, "warp");
+    assert_eq!(
+        rx.recv().await.unwrap(),
+        (
+            "/api/users".to_string(),
+            StatusCode::OK,
+            StatusCode::NOT_FOUND
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_shadow_skips_unsafe_methods() {
+    let hits = Arc::new(Mutex::new(0));
+    let axum = axum::Router::new().route(
+        "/api/orders",
+        post({
+            let hits = hits.clone();
+            move || async move {
+                *hits.lock().unwrap() += 1;
+                "axum"
+            }
+        }),
+    );
+    let migration = Migration::new(axum).phase("/api", MigrationPhase::Shadow);
+    let service = service(migration);
+
+    assert_eq!(served_by(&service, "POST", "/api/orders").await, "warp");
+    tokio::task::yield_now().await;
+    assert_eq!(*hits.lock().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_shadow_skips_requests_not_served_by_warp() {
+    let hits = Arc::new(Mutex::new(0));
+    let axum = axum::Router::new().route(
+        "/api/{*rest}",
+        get({
+            let hits = hits.clone();
+            move || async move {
+                *hits.lock().unwrap() += 1;
+                "warp"
+            }
+        }),
+    );
+    let migration = Migration::new(axum).phase("/api", MigrationPhase::Shadow);
+    let switch = Switch::new(false);
+    let service = service(migration.clone())
+        .with_kill_switch(KillSwitch::new(switch.clone()))
+        .with_cache(ResponseCache::new(Duration::from_secs(60), 1024))
+        .with_rate_limit(RateLimit::new(
+            |_| Some("all".to_string()),
+            2,
+            Duration::from_secs(60),
+        ));
+    let compared = || {
+        migration
+            .shadow_counts()
+            .iter()
+            .map(|counts| counts.compared)
+            .sum::<u64>()
+    };
+
+    // Requests answered by the cache or the kill switch are not shadowed.
+    served_by(&service, "GET", "/api/users").await;
+    served_by(&service, "GET", "/api/users").await;
+    switch.set(true);
+    served_by(&service, "GET", "/api/orders").await;
+    switch.set(false);
+    while compared() < 1 {
+        tokio::task::yield_now().await;
+    }
+    assert_eq!(*hits.lock().unwrap(), 1);
+
+    // Rate limited requests are not compared.
+    served_by(&service, "GET", "/api/items").await;
+    assert!(
+        served_by(&service, "GET", "/api/other")
+            .await
+            .starts_with("Rate limit exceeded")
+    );
+    while compared() < 2 {
+        tokio::task::yield_now().await;
+    }
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+    }
+    assert_eq!(compared(), 2);
+}
+
+#[tokio::test]
+async fn test_shadow_mismatch_counts() {
+    let axum = axum::Router::new()
+        .route("/api/users", get(|| async { "warp" }))
+        .route("/api/orders", get(|| async { "axum" }))
+        .route(
+            "/api/items/{id}",
+            get(|| async { (StatusCode::NOT_FOUND, [("x-version", "2")], "warp") }),
+        );
+    let migration = Migration::new(axum)
+        .phase("/api/users", MigrationPhase::Shadow)
+        .phase("/api/orders", MigrationPhase::Shadow)
+        .phase("/api/items", MigrationPhase::Shadow);
+    let service = service(migration.clone());
+
+    for uri in ["/api/users", "/api/orders", "/api/items/1", "/api/items/2"] {
+        assert_eq!(served_by(&service, "GET", uri).await, "warp");
+    }
+    while migration
+        .shadow_counts()
+        .iter()
+        .map(|c| c.compared)
+        .sum::<u64>()
+        < 4
+    {
+        tokio::task::yield_now().await;
+    }
+
+    let counts = migration.shadow_counts();
+    let routes: Vec<_> = counts.iter().map(|counts| counts.route.as_str()).collect();
+    assert_eq!(routes, ["/api/items", "/api/orders", "/api/users"]);
+    assert_eq!(
+        counts[0],
+        ShadowCounts {
+            route: "/api/items".to_string(),
+            compared: 2,
+            status: 2,
+            header: 2,
+            body: 0,
+        }
+    );
+    assert_eq!(counts[1].mismatches(ShadowMismatch::Body), 1);
+    assert_eq!(counts[1].mismatches(ShadowMismatch::Status), 0);
+    assert_eq!(counts[2].compared, 1);
+    assert_eq!(counts[2].status + counts[2].header + counts[2].body, 0);
+}
+
+#[tokio::test]
+async fn test_canary_metrics() {
+    let axum = axum::Router::new().route(
+        "/api/{*rest}",
+        get(|| async { (StatusCode::INTERNAL_SERVER_ERROR, "axum") }),
+    );
+    let migration = Migration::new(axum)
+        .phase("/api/orders", MigrationPhase::Canary(50))
+        .phase("/api/users", MigrationPhase::NewOnly);
+    let service = service(migration.clone());
+
+    let mut served = Vec::new();
+    for _ in 0..40 {
+        served.push(served_by(&service, "GET", "/api/orders/1").await);
+    }
+    served_by(&service, "GET", "/api/users").await;
+
+    let metrics = migration.canary_metrics();
+    assert_eq!(metrics.len(), 1);
+    let metrics = &metrics[0];
+    assert_eq!(metrics.route, "/api/orders");
+
+    let warp = served.iter().filter(|by| *by == "warp").count() as u64;
+    assert_eq!(metrics.warp.requests, warp);
+    assert_eq!(metrics.axum.requests, 40 - warp);
+    assert_eq!(metrics.warp.success_ratio(), Some(1.0));
+    assert_eq!(metrics.axum.success_ratio(), Some(0.0));
+    assert_eq!(metrics.axum.statuses.get(&500), Some(&(40 - warp)));
+    assert_eq!(metrics.warp.latency.count(), warp);
+    assert_eq!(metrics.warp.latency.buckets().last(), Some(&(None, warp)));
+}
+
+#[tokio::test]
+async fn test_canary_metrics_only_count_warp_responses() {
+    let migration = Migration::new(axum_routes()).phase("/api", MigrationPhase::Canary(0));
+    let filter = warp::path!("api" / "broken")
+        .map(|| {
+            Box::new(warp::reply::with_status(
+                "warp",
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            )) as Box<dyn warp::Reply + Send + Sync>
+        })
+        .or(warp::any().map(|| Box::new("warp") as Box<dyn warp::Reply + Send + Sync>))
+        .unify();
+    let service = WarpService::new(filter.boxed())
+        .with_migration(migration.clone())
+        .with_cache(ResponseCache::new(Duration::from_secs(60), 1024))
+        .with_failover(Failover::new(get(|| async { "axum" })));
+
+    // Cache hits and failed over responses are not counted as served by Warp.
+    assert_eq!(served_by(&service, "GET", "/api/users").await, "warp");
+    assert_eq!(served_by(&service, "GET", "/api/users").await, "warp");
+    assert_eq!(served_by(&service, "GET", "/api/broken").await, "axum");
+
+    let metrics = migration.canary_metrics();
+    assert_eq!(metrics[0].warp.requests, 1);
+    assert_eq!(metrics[0].warp.success_ratio(), Some(1.0));
+    assert_eq!(metrics[0].axum.requests, 0);
+}
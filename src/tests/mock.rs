@@ -0,0 +1,45 @@
+use axum::{
+    http::{Method, StatusCode},
+    routing::get,
+};
+
+use crate::{
+    DualReply,
+    test::{MixedTestClient, MockWarpService},
+};
+
+#[tokio::test]
+async fn test_mock_serves_programmed_responses() {
+    let legacy = MockWarpService::new()
+        .respond(
+            Method::GET,
+            "/users/1",
+            DualReply::json(&serde_json::json!({ "name": "Alice" })),
+        )
+        .respond_when(
+            |req| req.uri().path().starts_with("/admin"),
+            DualReply::text("Forbidden").with_status(StatusCode::FORBIDDEN),
+        );
+
+    let app = axum::Router::new()
+        .route("/", get(|| async { "Axum" }))
+        .fallback_service(legacy.clone());
+    let client = MixedTestClient::new(app);
+
+    client
+        .get("/users/1")
+        .send()
+        .await
+        .assert_status(200)
+        .assert_json(&serde_json::json!({ "name": "Alice" }));
+    client.get("/admin/panel").send().await.assert_status(403);
+    client.post("/users/1").send().await.assert_status(404);
+    client.get("/").send().await.assert_text("Axum");
+
+    let paths: Vec<_> = legacy
+        .received()
+        .into_iter()
+        .map(|(method, uri)| format!("{} {}", method, uri))
+        .collect();
+    assert_eq!(paths, ["GET /users/1", "GET /admin/panel", "POST /users/1"]);
+}
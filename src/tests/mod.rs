@@ -1,4 +1,75 @@
+mod access_log;
+mod admin;
+mod alarm;
+mod audit;
+#[cfg(feature = "axum07")]
+mod axum07;
+mod bench;
+mod blocking;
+mod body;
+mod buffer;
+mod cache;
+mod capture;
+mod circuit_breaker;
+mod codegen;
+mod compat;
+mod cors;
+mod deadline;
+mod denylist;
+mod error;
+mod error_bridge;
+mod extract;
+mod failover;
+mod fault;
+mod filter_ext;
+mod framing;
+mod fs;
+mod fuzz;
+mod gauge;
+mod golden;
+mod group;
+mod head;
+mod header_filter;
+mod header_rewrite;
+mod health;
+mod hedge;
+mod host;
+mod http1;
+mod kill_switch;
+mod layer;
+mod limits;
+mod location;
+mod macros;
+mod manifest;
+mod map_hooks;
+mod migration;
+mod mock;
+mod normalize;
+mod openapi;
+mod prefix;
+mod probe;
+mod problem;
+mod query;
+mod rate_limit;
 mod rejection;
+mod remote;
+mod reply;
+mod report;
 mod request;
+mod resource;
 mod response;
+mod routes;
+mod security_headers;
+mod serve;
+mod server_timing;
 mod service;
+mod shutdown;
+mod single_thread;
+mod snapshot;
+mod sse;
+mod steering;
+mod test_client;
+mod trace;
+mod usage;
+mod warmup;
+mod ws;
@@ -0,0 +1,186 @@
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{Denylist, PathNormalization, WarpService};
+
+async fn received(normalization: PathNormalization, uri: &str) -> String {
+    let filter = warp::path::full()
+        .and(warp::query::raw().or(warp::any().map(String::new)).unify())
+        .map(|path: warp::path::FullPath, query: String| format!("{}?{}", path.as_str(), query));
+    let service = WarpService::new(filter.boxed()).with_path_normalization(normalization);
+
+    let request = AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_path_normalization() {
+    let all = PathNormalization::new()
+        .merge_slashes()
+        .resolve_dot_segments()
+        .trim_trailing_slash();
+
+    assert_eq!(received(all, "//a///b/?x=//").await, "/a/b?x=//");
+    assert_eq!(received(all, "/a/./b/../c").await, "/a/c?");
+    assert_eq!(received(all, "/../../a/..").await, "/?");
+    assert_eq!(received(all, "/").await, "/?");
+
+    // Each option is independent.
+    let slashes = PathNormalization::new().merge_slashes();
+    assert_eq!(received(slashes, "//a/./b/").await, "/a/./b/?");
+    let dots = PathNormalization::new().resolve_dot_segments();
+    assert_eq!(received(dots, "/a//b/..").await, "/a//?");
+    assert_eq!(received(PathNormalization::new(), "//a/.").await, "//a/.?");
+}
+
+#[tokio::test]
+async fn test_path_normalization_applies_before_denylist() {
+    let service = WarpService::new(warp::any().map(|| "ok").boxed())
+        .with_path_normalization(PathNormalization::new().merge_slashes())
+        .with_denylist(Denylist::new().gone("/v1/users", ""));
+
+    let request = AxumRequest::builder()
+        .uri("//v1//users")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::GONE);
+}
+
+/// Serves the path the Warp filter receives and its host, as `<path> <host>`.
+fn path_and_host(preserve_empty_path: bool) -> WarpService<String> {
+    let filter = warp::path::end()
+        .and(warp::header::<String>("x-path"))
+        .and(warp::header::optional::<String>("host"))
+        .map(|path: String, host: Option<String>| {
+            format!("{:?} {}", path, host.unwrap_or_default())
+        });
+    let service = WarpService::new(filter.boxed()).with_map_request(|mut req| {
+        let path = req.uri().path().to_string();
+        req.headers_mut().insert("x-path", path.parse().unwrap());
+        req
+    });
+
+    if preserve_empty_path {
+        service.preserve_empty_path()
+    } else {
+        service
+    }
+}
+
+async fn body(response: axum::response::Response) -> String {
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_empty_path_is_normalized_to_root() {
+    let app: axum::Router = axum::Router::new().nest_service("/api", path_and_host(false));
+    let request = AxumRequest::builder()
+        .uri("/api?x=1")
+        .header("host", "example.com")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(body(response).await, "\"/\" example.com");
+
+    // An authority-form target has an empty path, and its authority becomes the host.
+    let request = AxumRequest::builder()
+        .uri("example.com:8080")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = path_and_host(false).oneshot(request).await.unwrap();
+    assert_eq!(body(response).await, "\"/\" example.com:8080");
+}
+
+#[tokio::test]
+async fn test_preserve_empty_path() {
+    let request = AxumRequest::builder()
+        .uri("example.com:8080")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = path_and_host(true).oneshot(request).await.unwrap();
+    assert_eq!(body(response).await, "\"\" ");
+}
+
+/// Serves the URI the Warp filter receives and its host, as `<uri> <host>`.
+fn uri_and_host(service: fn(WarpService<String>) -> WarpService<String>) -> WarpService<String> {
+    let filter = warp::header::<String>("x-uri")
+        .and(warp::header::optional::<String>("host"))
+        .map(|uri: String, host: Option<String>| format!("{} {}", uri, host.unwrap_or_default()));
+    service(WarpService::new(filter.boxed())).with_map_request(|mut req| {
+        let uri = req.uri().to_string();
+        req.headers_mut().insert("x-uri", uri.parse().unwrap());
+        req
+    })
+}
+
+#[tokio::test]
+async fn test_absolute_form_is_normalized_to_origin_form() {
+    let request = AxumRequest::builder()
+        .uri("http://example.com:8080/users?page=2")
+        .header("host", "proxy.internal")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = uri_and_host(|service| service)
+        .oneshot(request)
+        .await
+        .unwrap();
+    assert_eq!(body(response).await, "/users?page=2 example.com:8080");
+
+    // HTTP/2 requests always carry the authority, and are left as they are.
+    let request = AxumRequest::builder()
+        .uri("https://example.com/users")
+        .version(axum::http::Version::HTTP_2)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = uri_and_host(|service| service)
+        .oneshot(request)
+        .await
+        .unwrap();
+    assert_eq!(body(response).await, "https://example.com/users ");
+}
+
+#[tokio::test]
+async fn test_preserve_absolute_form() {
+    let request = AxumRequest::builder()
+        .uri("http://example.com:8080/users?page=2")
+        .header("host", "proxy.internal")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = uri_and_host(WarpService::preserve_absolute_form)
+        .oneshot(request)
+        .await
+        .unwrap();
+    assert_eq!(
+        body(response).await,
+        "http://example.com:8080/users?page=2 proxy.internal"
+    );
+}
+
+#[tokio::test]
+async fn test_preserve_empty_path_without_other_hooks() {
+    // Without a request hook, the preserved authority-form target shows as no `Host` header.
+    let filter = warp::header::optional::<String>("host")
+        .map(|host: Option<String>| host.unwrap_or_default());
+    let service = WarpService::new(filter.boxed()).preserve_empty_path();
+
+    let request = AxumRequest::builder()
+        .uri("example.com:8080")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(body(response).await, "");
+}
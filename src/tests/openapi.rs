@@ -0,0 +1,188 @@
+use axum::http::Method;
+use serde_json::json;
+use warp::Filter;
+
+use crate::{WarpRoutes, openapi::OpenApiPaths};
+
+fn billing(routes: WarpRoutes) -> WarpRoutes {
+    routes
+        .route(warp::path!("billing" / "invoices").map(|| "invoices"))
+        .document(
+            Method::GET,
+            "/billing/invoices",
+            json!({ "summary": "List invoices" }),
+        )
+}
+
+#[test]
+fn test_documented_routes_merge_into_document() {
+    let routes = WarpRoutes::new()
+        .register(billing)
+        .route(warp::path!("users" / u32).map(|id| format!("user {}", id)))
+        .document(
+            Method::GET,
+            "/users/{id}",
+            json!({ "summary": "Legacy user" }),
+        )
+        .document(
+            Method::DELETE,
+            "/users/{id}",
+            json!({ "summary": "Delete user" }),
+        );
+    assert_eq!(routes.openapi().len(), 3);
+
+    // The Axum side already documents `GET /users/{id}`, which it now serves.
+    let mut doc = json!({
+        "openapi": "3.1.0",
+        "paths": { "/users/{id}": { "get": { "summary": "User" } } },
+    });
+    routes.openapi().merge_into(&mut doc);
+
+    assert_eq!(
+        doc["paths"],
+        json!({
+            "/billing/invoices": { "get": { "summary": "List invoices" } },
+            "/users/{id}": {
+                "get": { "summary": "User" },
+                "delete": { "summary": "Delete user" },
+            },
+        })
+    );
+}
+
+#[test]
+fn test_openapi_paths() {
+    let paths = OpenApiPaths::new()
+        .operation(Method::GET, "/a", json!({ "summary": "A" }))
+        .extend(OpenApiPaths::new().operation(Method::GET, "/a", json!({ "summary": "B" })));
+    assert_eq!(paths.len(), 1);
+    assert_eq!(
+        paths.to_json(),
+        json!({ "/a": { "get": { "summary": "B" } } })
+    );
+
+    // A document without paths gets them.
+    let mut doc = json!({ "openapi": "3.1.0" });
+    paths.merge_into(&mut doc);
+    assert_eq!(doc["paths"], paths.to_json());
+}
+
+#[test]
+fn test_documented_routes_merge_into_utoipa() {
+    use utoipa::openapi::{HttpMethod, OpenApiBuilder, PathsBuilder, path::OperationBuilder};
+
+    let paths = OpenApiPaths::new()
+        .operation(
+            Method::GET,
+            "/users/{id}",
+            json!({ "summary": "Legacy user", "responses": {} }),
+        )
+        .operation(
+            Method::DELETE,
+            "/users/{id}",
+            json!({ "summary": "Delete user", "responses": {} }),
+        )
+        .operation(
+            Method::GET,
+            "/health",
+            json!({ "summary": "Health", "responses": {} }),
+        );
+
+    // The Axum side already documents `GET /users/{id}`, which it now serves.
+    let mut doc = OpenApiBuilder::new()
+        .paths(PathsBuilder::new().path(
+            "/users/{id}",
+            utoipa::openapi::PathItem::new(
+                HttpMethod::Get,
+                OperationBuilder::new().summary(Some("User")),
+            ),
+        ))
+        .build();
+    paths.merge_into_utoipa(&mut doc).unwrap();
+
+    let summary = |path, method| {
+        doc.paths
+            .get_path_operation(path, method)
+            .and_then(|operation| operation.summary.clone())
+    };
+    assert_eq!(
+        summary("/users/{id}", HttpMethod::Get).as_deref(),
+        Some("User")
+    );
+    assert_eq!(
+        summary("/users/{id}", HttpMethod::Delete).as_deref(),
+        Some("Delete user")
+    );
+    assert_eq!(
+        summary("/health", HttpMethod::Get).as_deref(),
+        Some("Health")
+    );
+
+    // Operations without responses are invalid, and reported instead of merged.
+    let invalid = OpenApiPaths::new().operation(Method::GET, "/bad", json!({ "summary": "Bad" }));
+    assert!(invalid.merge_into_utoipa(&mut doc).is_err());
+    assert!(doc.paths.get_path_item("/bad").is_none());
+}
+
+#[tokio::test]
+async fn test_openapi_skeleton_from_usage() {
+    use axum::{body::Body as AxumBody, extract::Request as AxumRequest};
+    use tower::ServiceExt;
+
+    use crate::{RouteOwner, UsageReport, WarpService};
+
+    let report = UsageReport::new()
+        .route_key(|req| {
+            let path: Vec<_> = req
+                .uri()
+                .path()
+                .split('/')
+                .map(|segment| match segment.parse::<u64>() {
+                    Ok(_) => "{id}",
+                    Err(_) => segment,
+                })
+                .collect();
+            format!("{} {}", req.method(), path.join("/"))
+        })
+        .owner("/users", RouteOwner::new("identity"));
+    let filter = warp::path!("users" / u64).map(|id: u64| {
+        if id == 0 {
+            Box::new(warp::http::StatusCode::NOT_FOUND) as Box<dyn warp::Reply + Send + Sync>
+        } else {
+            Box::new(warp::reply::json(&id))
+        }
+    });
+    let service = WarpService::new(filter.boxed()).with_usage_report(report.clone());
+
+    for uri in ["/users/1", "/users/2", "/users/0"] {
+        let request = AxumRequest::builder()
+            .uri(uri)
+            .body(AxumBody::empty())
+            .unwrap();
+        service.clone().oneshot(request).await.unwrap();
+    }
+
+    let doc: serde_json::Value = serde_json::from_str(&report.to_openapi()).unwrap();
+    assert_eq!(doc["openapi"], "3.1.0");
+    assert_eq!(
+        doc["paths"]["/users/{id}"]["get"],
+        json!({
+            "summary": "GET /users/{id}",
+            "tags": ["identity"],
+            "parameters": [{
+                "name": "id",
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string" },
+            }],
+            "responses": {
+                "200": {
+                    "description": "OK",
+                    "content": { "application/json": {} },
+                },
+                "404": { "description": "Not Found" },
+            },
+            "x-warpdrive-usage": { "hits": 3, "errors": 0, "on_warp": true },
+        })
+    );
+}
@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{PrefixConfig, ProblemDetails, WarpService};
+
+fn service() -> WarpService {
+    let filter = warp::path::tail().and(warp::body::bytes()).and_then(
+        |tail: warp::path::Tail, body: warp::hyper::body::Bytes| async move {
+            if tail.as_str().ends_with("slow") {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            Ok::<_, warp::Rejection>(
+                Box::new(format!("{} bytes", body.len())) as Box<dyn warp::Reply + Send + Sync>
+            )
+        },
+    );
+
+    WarpService::new(filter.boxed())
+        .with_timeout(Duration::from_millis(20))
+        .with_body_limit(4)
+        .with_prefix_config(
+            "/admin/",
+            PrefixConfig::new()
+                .timeout(Duration::from_secs(5))
+                .body_limit(1024),
+        )
+        .with_prefix_config(
+            "/admin/api",
+            PrefixConfig::new().problem_details(ProblemDetails::new()),
+        )
+}
+
+async fn post(service: &WarpService, uri: &str, body: &'static str) -> (StatusCode, String) {
+    let req = AxumRequest::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-length", body.len())
+        .body(AxumBody::from(body))
+        .unwrap();
+    let response = service.clone().oneshot(req).await.unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .map(|value| value.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    (status, content_type)
+}
+
+#[tokio::test]
+async fn test_prefix_config_overrides_timeout_and_body_limit() {
+    let service = service();
+
+    assert_eq!(
+        post(&service, "/slow", "").await.0,
+        StatusCode::GATEWAY_TIMEOUT
+    );
+    assert_eq!(post(&service, "/admin/slow", "").await.0, StatusCode::OK);
+
+    assert_eq!(
+        post(&service, "/upload", "too long").await,
+        (StatusCode::PAYLOAD_TOO_LARGE, "text/plain".to_string())
+    );
+    assert_eq!(
+        post(&service, "/admin/upload", "too long").await.0,
+        StatusCode::OK
+    );
+
+    // Prefixes match whole segments only.
+    assert_eq!(
+        post(&service, "/administrator/slow", "").await.0,
+        StatusCode::GATEWAY_TIMEOUT
+    );
+}
+
+#[tokio::test]
+async fn test_longest_prefix_config_applies() {
+    let service = service();
+
+    // `/admin/api` only overrides the error format, so the service's limit applies.
+    let (status, content_type) = post(&service, "/admin/api/upload", "too long").await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(content_type, "application/problem+json");
+}
@@ -0,0 +1,44 @@
+use axum::http::{Method, StatusCode};
+use warp::Filter;
+
+use crate::{Probe, WarpService};
+
+#[tokio::test]
+async fn test_probe_reports_results() {
+    let filter = warp::path("health")
+        .map(|| "ok")
+        .or(warp::path("echo")
+            .and(warp::post())
+            .and(warp::body::bytes())
+            .map(|body: warp::hyper::body::Bytes| String::from_utf8(body.to_vec()).unwrap()))
+        .map(|reply| Box::new(reply) as Box<dyn warp::Reply + Send + Sync>);
+    let service = WarpService::new(filter.boxed());
+
+    let report = service
+        .probe([
+            Probe::get("/health").expect_body_contains("ok"),
+            Probe::new(Method::POST, "/echo")
+                .body("ping")
+                .expect_body_contains("ping"),
+            Probe::get("/missing").expect_status(404),
+            Probe::get("/health").expect_body_contains("degraded"),
+            Probe::get("/missing"),
+        ])
+        .await;
+
+    assert!(!report.passed());
+    assert_eq!(report.results.len(), 5);
+    assert_eq!(report.results[2].status, Some(StatusCode::NOT_FOUND));
+
+    let failures: Vec<_> = report
+        .failures()
+        .map(|result| result.uri.as_str())
+        .collect();
+    assert_eq!(failures, ["/health", "/missing"]);
+    assert_eq!(
+        report.to_string(),
+        "3 of 5 probes passed\n\
+         GET /health: Expected body to contain \"degraded\"\n\
+         GET /missing: Expected status 200 OK, got 404 Not Found"
+    );
+}
@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use axum::{
+    body::Body as AxumBody,
+    extract::Request as AxumRequest,
+    http::{Response, StatusCode},
+};
+use http_body_util::{BodyExt, Limited};
+use tower::{BoxError, ServiceExt, util::AndThenLayer};
+use warp::Filter;
+
+use crate::{ProblemDetails, RateLimit, WarpService};
+
+fn request(uri: &str) -> AxumRequest {
+    AxumRequest::builder()
+        .uri(uri)
+        .header("x-api-key", "key")
+        .body(AxumBody::empty())
+        .unwrap()
+}
+
+async fn problem(response: Response<AxumBody>) -> serde_json::Value {
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/problem+json"
+    );
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_timeout_rendered_as_problem_details() {
+    let filter = warp::any().and_then(|| async {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        Ok::<_, warp::Rejection>("late")
+    });
+    let service = WarpService::new(filter.boxed())
+        .with_timeout(Duration::from_millis(10))
+        .with_problem_details(ProblemDetails::new().base_uri("https://errors.example.com/"));
+
+    let response = service.oneshot(request("/")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+
+    let problem = problem(response).await;
+    assert_eq!(problem["type"], "https://errors.example.com/timeout");
+    assert_eq!(problem["title"], "Gateway Timeout");
+    assert_eq!(problem["status"], 504);
+    assert_eq!(problem["detail"], "Request timed out after 10ms");
+}
+
+#[tokio::test]
+async fn test_rate_limit_problem_keeps_retry_after() {
+    let filter = warp::any().map(|| "ok");
+    let service = WarpService::new(filter.boxed())
+        .with_rate_limit(RateLimit::per_header(
+            "x-api-key",
+            1,
+            Duration::from_secs(60),
+        ))
+        .with_problem_details(
+            ProblemDetails::new()
+                .base_uri("https://errors.example.com/")
+                .type_uri("rate-limited", "https://example.com/docs/rate-limits"),
+        );
+
+    let response = service.clone().oneshot(request("/")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = service.oneshot(request("/")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.headers().get("retry-after").unwrap(), "60");
+
+    let problem = problem(response).await;
+    assert_eq!(problem["type"], "https://example.com/docs/rate-limits");
+    assert_eq!(problem["status"], 429);
+}
+
+#[tokio::test]
+async fn test_body_limit_error_rendered_as_payload_too_large() {
+    let filter = warp::any().map(|| "too long");
+    let service = WarpService::new(filter.boxed())
+        .layer(AndThenLayer::new(
+            |response: Response<AxumBody>| async move {
+                let (parts, body) = response.into_parts();
+                let body = Limited::new(body, 1).collect().await?.to_bytes();
+                Ok::<_, BoxError>(Response::from_parts(parts, AxumBody::from(body)))
+            },
+        ))
+        .with_problem_details(ProblemDetails::new());
+
+    let response = service.oneshot(request("/")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    let problem = problem(response).await;
+    assert_eq!(problem["type"], "about:blank");
+    assert_eq!(problem["title"], "Payload Too Large");
+}
@@ -0,0 +1,68 @@
+use axum::{Router, body::Body as AxumBody, extract::Request as AxumRequest};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{
+    WarpService,
+    convert_request::{into_axum_request, into_warp_request},
+    original_query,
+};
+
+const QUERIES: [&str; 6] = [
+    "q=a+b&plus=%2B",
+    "ids[]=1&ids[]=2&filter[name]=x",
+    "tag=a&tag=b&tag=a",
+    "empty=&flag&=value&&",
+    "q=caf%C3%A9&bytes=%FF%00",
+    "",
+];
+
+#[tokio::test]
+async fn test_query_round_trips_byte_for_byte() {
+    for query in QUERIES {
+        let uri = format!("/search?{}", query);
+        let axum_request = AxumRequest::builder()
+            .uri(&uri)
+            .body(AxumBody::empty())
+            .unwrap();
+
+        let warp_request = into_warp_request(axum_request).await.unwrap();
+        assert_eq!(warp_request.uri().query(), Some(query), "{}", uri);
+
+        let axum_request = into_axum_request(warp_request).unwrap();
+        assert_eq!(axum_request.uri().query(), Some(query), "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn test_original_query_reaches_filter() {
+    let filter = original_query().map(|query: Option<String>| {
+        Box::new(format!("{:?}", query)) as Box<dyn warp::Reply + Send + Sync>
+    });
+    let service = WarpService::new(filter.boxed())
+        .with_original_query()
+        .with_map_request(|mut req| {
+            *req.uri_mut() = warp::http::Uri::from_static("/search?rewritten=1");
+            req
+        });
+    let app: Router = Router::new().nest_service("/api", service);
+
+    for (uri, expected) in [
+        (
+            "/api/search?q=a+b&tag=x&tag=",
+            r#"Some("q=a+b&tag=x&tag=")"#,
+        ),
+        ("/api/search?", r#"Some("")"#),
+        ("/api/search", "None"),
+    ] {
+        let req = AxumRequest::builder()
+            .uri(uri)
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, expected, "{}", uri);
+    }
+}
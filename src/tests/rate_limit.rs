@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{Error, RateLimit, WarpService};
+
+fn service(limit: RateLimit) -> WarpService<&'static str> {
+    WarpService::new(warp::any().map(|| "ok").boxed()).with_rate_limit(limit)
+}
+
+fn request(api_key: Option<&str>) -> AxumRequest {
+    let mut builder = AxumRequest::builder().uri("/");
+    if let Some(api_key) = api_key {
+        builder = builder.header("x-api-key", api_key);
+    }
+    builder.body(AxumBody::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn test_rate_limit_per_key() {
+    let service = service(RateLimit::per_header(
+        "x-api-key",
+        2,
+        Duration::from_secs(60),
+    ));
+
+    for _ in 0..2 {
+        let response = service.clone().oneshot(request(Some("a"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = service.clone().oneshot(request(Some("a"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.headers().get("retry-after").unwrap(), "30");
+
+    // Other keys and unkeyed requests are unaffected.
+    let response = service.clone().oneshot(request(Some("b"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = service.oneshot(request(None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_rate_limit_refills() {
+    let service = service(RateLimit::per_header(
+        "x-api-key",
+        1,
+        Duration::from_millis(50),
+    ));
+
+    let response = service.clone().oneshot(request(Some("a"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = service.clone().oneshot(request(Some("a"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+
+    let response = service.oneshot(request(Some("a"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_rate_limit_fallible_error() {
+    let service = service(RateLimit::new(
+        |_| Some("all".to_string()),
+        1,
+        Duration::from_secs(1),
+    ))
+    .into_fallible();
+
+    service.clone().oneshot(request(None)).await.unwrap();
+    let err = service.oneshot(request(None)).await.unwrap_err();
+
+    assert!(matches!(err, Error::RateLimited(_)));
+}
+
+#[tokio::test]
+async fn test_rate_limit_caps_tracked_keys() {
+    let service =
+        service(RateLimit::per_header("x-api-key", 1, Duration::from_millis(50)).max_keys(2));
+
+    for key in ["a", "b"] {
+        let response = service.clone().oneshot(request(Some(key))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    // Once the limit is reached, new keys share one bucket.
+    let response = service.clone().oneshot(request(Some("c"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = service.clone().oneshot(request(Some("d"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // Buckets that have refilled are forgotten to make room for new keys.
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let response = service.clone().oneshot(request(Some("e"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = service.oneshot(request(Some("e"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
@@ -0,0 +1,130 @@
+use std::{net::SocketAddr, time::Duration};
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::remote::RemoteWarpService;
+
+/// Starts a legacy Warp server that echoes the request back.
+fn spawn_upstream() -> SocketAddr {
+    let echo = warp::method()
+        .and(warp::path::full())
+        .and(warp::query::raw().or(warp::any().map(String::new)).unify())
+        .and(warp::header::headers_cloned())
+        .and(warp::body::bytes())
+        .map(
+            |method: warp::http::Method,
+             path: warp::path::FullPath,
+             query: String,
+             headers: warp::http::HeaderMap,
+             body: warp::hyper::body::Bytes| {
+                let connection_specific = headers.contains_key("x-connection-specific");
+                warp::reply::with_header(
+                    format!(
+                        "{} {}?{} {} {}",
+                        method,
+                        path.as_str(),
+                        query,
+                        headers["host"].to_str().unwrap(),
+                        String::from_utf8_lossy(&body)
+                    ),
+                    "x-connection-specific",
+                    connection_specific.to_string(),
+                )
+            },
+        );
+
+    let (addr, server) = warp::serve(echo).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+    addr
+}
+
+#[tokio::test]
+async fn test_remote_service_forwards_requests() {
+    let addr = spawn_upstream();
+    let service = RemoteWarpService::new(&format!("http://{}", addr));
+
+    let chunks = futures::stream::iter([Ok::<_, std::convert::Infallible>("Hel"), Ok("lo")]);
+    let req = AxumRequest::builder()
+        .method("POST")
+        .uri("/legacy/users?page=2")
+        .header("host", "api.example.com")
+        .header("connection", "x-connection-specific")
+        .header("x-connection-specific", "1")
+        .body(AxumBody::from_stream(chunks))
+        .unwrap();
+
+    let response = service.oneshot(req).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("x-connection-specific").unwrap(),
+        "false"
+    );
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "POST /legacy/users?page=2 api.example.com Hello");
+}
+
+#[tokio::test]
+async fn test_unreachable_upstream_is_bad_gateway() {
+    // Bind and drop a listener to find a port with nothing listening.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    let service = RemoteWarpService::new(&format!("http://{}", addr));
+    let req = AxumRequest::builder()
+        .uri("/legacy")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = service.oneshot(req).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+}
+
+#[tokio::test]
+async fn test_connect_failures_are_retried() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    // The upstream server starts after the first connection attempt fails.
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        warp::serve(warp::any().map(|| "late start"))
+            .run(addr)
+            .await;
+    });
+
+    let service = RemoteWarpService::new(&format!("http://{}", addr)).with_connect_retries(5);
+    let req = AxumRequest::builder()
+        .uri("/legacy")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = service.oneshot(req).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_upstream_timeout() {
+    let slow = warp::any().and_then(|| async {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        Ok::<_, warp::Rejection>("late")
+    });
+    let (addr, server) = warp::serve(slow).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+
+    let service =
+        RemoteWarpService::new(&format!("http://{}", addr)).with_timeout(Duration::from_millis(20));
+    let req = AxumRequest::builder()
+        .uri("/legacy")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = service.oneshot(req).await.unwrap();
+    assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+}
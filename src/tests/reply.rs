@@ -0,0 +1,136 @@
+use axum::{
+    body::Body as AxumBody,
+    extract::Request as AxumRequest,
+    http::{HeaderName, HeaderValue, StatusCode},
+    response::IntoResponse,
+};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{
+    reply::{AxumReply, DualReply, WarpReply},
+    warp_service::WarpService,
+};
+
+fn shared_reply() -> DualReply {
+    DualReply::json(&serde_json::json!({ "message": "shared" }))
+        .with_status(StatusCode::CREATED)
+        .with_header(
+            HeaderName::from_static("x-shared"),
+            HeaderValue::from_static("yes"),
+        )
+}
+
+#[tokio::test]
+async fn test_dual_reply_into_axum_response() {
+    let response = shared_reply().into_response();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/json"
+    );
+    assert_eq!(response.headers().get("x-shared").unwrap(), "yes");
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, r#"{"message":"shared"}"#);
+}
+
+#[tokio::test]
+async fn test_dual_reply_from_warp_filter() {
+    let warp_filter = warp::path("shared").map(shared_reply);
+
+    let service = WarpService::new(warp_filter.boxed());
+
+    let request = AxumRequest::builder()
+        .uri("/shared")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = service.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/json"
+    );
+    assert_eq!(response.headers().get("x-shared").unwrap(), "yes");
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, r#"{"message":"shared"}"#);
+}
+
+#[tokio::test]
+async fn test_dual_reply_text() {
+    let response = warp::Reply::into_response(DualReply::text("hello"));
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/plain; charset=utf-8"
+    );
+}
+
+#[tokio::test]
+async fn test_warp_reply_from_axum_handler() {
+    let app = axum::Router::new().route(
+        "/legacy",
+        axum::routing::get(|| async {
+            WarpReply(warp::reply::with_status(
+                warp::reply::with_header("legacy", "x-legacy", "true"),
+                warp::http::StatusCode::ACCEPTED,
+            ))
+        }),
+    );
+
+    let request = AxumRequest::builder()
+        .uri("/legacy")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    assert_eq!(response.headers().get("x-legacy").unwrap(), "true");
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "legacy");
+}
+
+#[tokio::test]
+async fn test_axum_reply_from_warp_filter() {
+    let warp_filter = warp::path("new").map(|| {
+        AxumReply((
+            StatusCode::CREATED,
+            [("x-new", "true")],
+            axum::Json(serde_json::json!({ "created": true })),
+        ))
+    });
+
+    let service = WarpService::new(warp_filter.boxed());
+
+    let request = AxumRequest::builder()
+        .uri("/new")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = service.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(response.headers().get("x-new").unwrap(), "true");
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/json"
+    );
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, r#"{"created":true}"#);
+}
@@ -0,0 +1,93 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{
+    body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode, routing::any,
+};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{
+    Failover, WarpService,
+    report::{ErrorReporter, ReportKind},
+};
+
+type Reports = Arc<Mutex<Vec<(ReportKind, String, Option<String>, Vec<String>)>>>;
+
+fn reporter(reports: &Reports) -> ErrorReporter {
+    let reports = Arc::clone(reports);
+    ErrorReporter::new(move |report| {
+        reports.lock().unwrap().push((
+            report.kind,
+            report.uri.to_string(),
+            report
+                .headers
+                .get("x-request-id")
+                .map(|value| value.to_str().unwrap().to_string()),
+            report.chain().map(|err| err.to_string()).collect(),
+        ));
+    })
+    .header("x-request-id")
+}
+
+fn request(uri: &str) -> AxumRequest {
+    AxumRequest::builder()
+        .uri(uri)
+        .header("x-request-id", "abc")
+        .header("authorization", "secret")
+        .body(AxumBody::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_reports_timeouts_and_not_responses() {
+    let slow = warp::path("slow").and_then(|| async {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        Ok::<_, warp::Rejection>(Box::new("slow") as Box<dyn warp::Reply + Send + Sync>)
+    });
+    let missing = warp::path("missing").map(|| {
+        Box::new(warp::reply::with_status(
+            "missing",
+            warp::http::StatusCode::NOT_FOUND,
+        )) as Box<dyn warp::Reply + Send + Sync>
+    });
+    let reports = Reports::default();
+    let service = WarpService::new(slow.or(missing).unify().boxed())
+        .with_timeout(Duration::from_millis(20))
+        .with_error_reporter(reporter(&reports));
+
+    let response = service.clone().oneshot(request("/missing")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(reports.lock().unwrap().is_empty());
+
+    let response = service.oneshot(request("/slow")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+    assert_eq!(
+        *reports.lock().unwrap(),
+        vec![(
+            ReportKind::Timeout,
+            "/slow".to_string(),
+            Some("abc".to_string()),
+            vec!["Request timed out after 20ms".to_string()],
+        )]
+    );
+}
+
+#[tokio::test]
+async fn test_reports_panics_before_failover() {
+    let filter = warp::any().map(|| -> Box<dyn warp::Reply + Send + Sync> { panic!("boom") });
+    let reports = Reports::default();
+    let service = WarpService::new(filter.boxed())
+        .with_failover(Failover::new(any(|| async { "axum" })))
+        .with_error_reporter(reporter(&reports));
+
+    let response = service.oneshot(request("/orders")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let reports = reports.lock().unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].0, ReportKind::Panic);
+    assert_eq!(reports[0].3, vec!["Warp filter panicked: boom".to_string()]);
+}
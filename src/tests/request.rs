@@ -171,3 +171,103 @@ async fn test_complex_request() {
         body
     );
 }
+
+#[tokio::test]
+async fn test_asterisk_form_request() {
+    let axum_request = AxumRequest::builder()
+        .method("OPTIONS")
+        .uri("*")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let warp_request = into_warp_request(axum_request).await.unwrap();
+    assert_eq!(warp_request.method(), "OPTIONS");
+    assert_eq!(warp_request.uri(), "*");
+
+    let axum_request = crate::convert_request::into_axum_request(warp_request).unwrap();
+    assert_eq!(axum_request.uri(), "*");
+}
+
+#[tokio::test]
+async fn test_asterisk_form_reaches_filter() {
+    use tower::ServiceExt;
+    use warp::Filter;
+
+    let filter = warp::options()
+        .and(warp::path::full())
+        .map(|path: warp::filters::path::FullPath| path.as_str().to_string());
+    let service = crate::WarpService::new(filter.boxed());
+
+    let request = AxumRequest::builder()
+        .method("OPTIONS")
+        .uri("*")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), 200);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "*");
+}
+
+#[tokio::test]
+async fn test_non_utf8_header_values() {
+    // "café" in Latin-1, as sent by some legacy clients.
+    let latin1 = b"caf\xe9";
+    let mut authorization = axum::http::HeaderValue::from_static("Basic dXNlcjpwYXNz");
+    authorization.set_sensitive(true);
+
+    let axum_request = AxumRequest::builder()
+        .uri("/")
+        .header("x-name", &latin1[..])
+        .header("x-name", "plain")
+        .header(axum::http::header::AUTHORIZATION, authorization)
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let warp_request = into_warp_request(axum_request).await.unwrap();
+    let values: Vec<_> = warp_request
+        .headers()
+        .get_all("x-name")
+        .iter()
+        .map(|value| value.as_bytes().to_vec())
+        .collect();
+    assert_eq!(values, [latin1.to_vec(), b"plain".to_vec()]);
+    assert!(warp_request.headers()["authorization"].is_sensitive());
+
+    // And back again.
+    let axum_request = crate::convert_request::into_axum_request(warp_request).unwrap();
+    assert_eq!(axum_request.headers()["x-name"].as_bytes(), latin1);
+    assert!(axum_request.headers()["x-name"].to_str().is_err());
+    assert!(axum_request.headers()["authorization"].is_sensitive());
+}
+
+#[tokio::test]
+async fn test_repeated_headers_keep_order() {
+    let axum_request = AxumRequest::builder()
+        .uri("/")
+        .header("x-forwarded-for", "203.0.113.7")
+        .header(axum::http::header::ACCEPT, "text/html")
+        .header("x-forwarded-for", "198.51.100.2")
+        .header("x-forwarded-for", "192.0.2.1")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let warp_request = into_warp_request(axum_request).await.unwrap();
+    let forwarded: Vec<_> = warp_request
+        .headers()
+        .get_all("x-forwarded-for")
+        .iter()
+        .collect();
+    assert_eq!(forwarded, ["203.0.113.7", "198.51.100.2", "192.0.2.1"]);
+
+    let axum_request = crate::convert_request::into_axum_request(warp_request).unwrap();
+    let forwarded: Vec<_> = axum_request
+        .headers()
+        .get_all("x-forwarded-for")
+        .iter()
+        .collect();
+    assert_eq!(forwarded, ["203.0.113.7", "198.51.100.2", "192.0.2.1"]);
+}
@@ -0,0 +1,68 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use axum::{
+    Extension, Router, body::Body as AxumBody, extract::Request as AxumRequest, extract::State,
+    routing::get,
+};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{ResourceProvider, WarpService, axum_extract};
+
+/// A stand-in for a pool, counting the requests served through any of its clones.
+#[derive(Clone, Default)]
+struct Pool(Arc<AtomicUsize>);
+
+impl Pool {
+    fn query(&self) -> String {
+        format!("query {}", self.0.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+}
+
+async fn get_body(app: &Router, uri: &str) -> String {
+    let request = AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_resource_shared_between_stacks() {
+    let pool = ResourceProvider::new(Pool::default());
+
+    let warp_routes = warp::path("warp")
+        .and(pool.filter())
+        .map(|pool: Pool| pool.query())
+        .or(warp::path("extract")
+            .and(axum_extract::<Extension<Pool>>())
+            .map(|Extension(pool): Extension<Pool>| pool.query()))
+        .boxed();
+
+    let app = Router::new()
+        .route(
+            "/state",
+            get(|State(pool): State<Pool>| async move { pool.query() }),
+        )
+        .route(
+            "/extension",
+            get(|Extension(pool): Extension<Pool>| async move { pool.query() }),
+        )
+        .fallback_service(WarpService::new(warp_routes))
+        .layer(pool.layer())
+        .with_state(pool.get());
+
+    // Every route reaches the same pool.
+    assert_eq!(get_body(&app, "/warp").await, "query 1");
+    assert_eq!(get_body(&app, "/state").await, "query 2");
+    assert_eq!(get_body(&app, "/extension").await, "query 3");
+    assert_eq!(get_body(&app, "/extract").await, "query 4");
+    assert_eq!(pool.get().0.load(Ordering::SeqCst), 4);
+}
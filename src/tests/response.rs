@@ -16,7 +16,7 @@ async fn test_basic_response() {
         .body(WarpBody::from("Hello World!"))
         .unwrap();
 
-    let axum_response = into_axum_response(warp_response).await.unwrap();
+    let axum_response = into_axum_response(warp_response).unwrap();
 
     assert_eq!(axum_response.status(), AxumStatusCode::OK);
 }
@@ -28,7 +28,7 @@ async fn test_response_with_status() {
         .body(WarpBody::empty())
         .unwrap();
 
-    let axum_response = into_axum_response(warp_response).await.unwrap();
+    let axum_response = into_axum_response(warp_response).unwrap();
 
     assert_eq!(axum_response.status(), AxumStatusCode::NOT_FOUND);
 }
@@ -40,7 +40,7 @@ async fn test_response_with_headers() {
         .body(WarpBody::empty())
         .unwrap();
 
-    let axum_response = into_axum_response(warp_response).await.unwrap();
+    let axum_response = into_axum_response(warp_response).unwrap();
 
     assert_eq!(
         axum_response
@@ -58,7 +58,7 @@ async fn test_json_response() {
     });
     let warp_response = json(&data).into_response();
 
-    let axum_response = into_axum_response(warp_response).await.unwrap();
+    let axum_response = into_axum_response(warp_response).unwrap();
 
     assert_eq!(
         axum_response
@@ -78,7 +78,7 @@ async fn test_multiple_headers() {
     )
     .into_response();
 
-    let axum_response = into_axum_response(response).await.unwrap();
+    let axum_response = into_axum_response(response).unwrap();
 
     assert_eq!(
         axum_response.headers().get("X-Custom-Header").unwrap(),
@@ -109,7 +109,7 @@ async fn test_different_http_versions() {
             .body(WarpBody::from("Hello"))
             .unwrap();
 
-        let axum_response = into_axum_response(response).await.unwrap();
+        let axum_response = into_axum_response(response).unwrap();
 
         // Version should be preserved or fallback to HTTP_11
         assert!(matches!(
@@ -140,7 +140,7 @@ async fn test_complex_response() {
     )
     .into_response();
 
-    let axum_response = into_axum_response(response).await.unwrap();
+    let axum_response = into_axum_response(response).unwrap();
 
     assert_eq!(axum_response.status(), AxumStatusCode::OK);
     assert_eq!(
@@ -152,3 +152,157 @@ async fn test_complex_response() {
     );
     assert_eq!(axum_response.headers().get("X-Rate-Limit").unwrap(), "100");
 }
+
+#[tokio::test]
+async fn test_bodyless_statuses_drop_body_and_framing() {
+    use http_body::Body as _;
+
+    for (status, content_length) in [
+        (WarpStatusCode::NO_CONTENT, None),
+        (WarpStatusCode::NOT_MODIFIED, Some("42")),
+    ] {
+        let stray = futures::stream::iter([Ok::<_, std::io::Error>("stray")]);
+        let warp_response = WarpResponse::builder()
+            .status(status)
+            .header("content-length", "42")
+            .header("transfer-encoding", "chunked")
+            .header("etag", "\"v1\"")
+            .body(WarpBody::wrap_stream(stray))
+            .unwrap();
+
+        let axum_response = into_axum_response(warp_response).unwrap();
+
+        let headers = axum_response.headers();
+        assert_eq!(
+            headers
+                .get("content-length")
+                .map(|value| value.to_str().unwrap()),
+            content_length,
+            "{}",
+            status
+        );
+        assert!(!headers.contains_key("transfer-encoding"));
+        assert_eq!(headers["etag"], "\"v1\"");
+        assert!(axum_response.body().is_end_stream());
+        assert_eq!(axum_response.body().size_hint().exact(), Some(0));
+    }
+}
+
+#[tokio::test]
+async fn test_empty_no_content_reply_over_tcp() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use warp::Filter;
+
+    // An empty streamed body, as some legacy replies attach, has no known length.
+    let filter = warp::any().map(|| {
+        WarpResponse::builder()
+            .status(WarpStatusCode::NO_CONTENT)
+            .body(WarpBody::wrap_stream(futures::stream::empty::<
+                Result<Vec<u8>, std::io::Error>,
+            >()))
+            .unwrap()
+    });
+    let app = axum::Router::new().fallback_service(crate::WarpService::new(filter.boxed()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+        .await
+        .unwrap();
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await.unwrap();
+
+    let response = String::from_utf8(response).unwrap().to_ascii_lowercase();
+    assert!(response.starts_with("http/1.1 204 no content\r\n"));
+    assert!(!response.contains("transfer-encoding"));
+    assert!(!response.contains("content-length"));
+    assert!(response.ends_with("\r\n\r\n"));
+}
+
+#[tokio::test]
+async fn test_non_utf8_header_values() {
+    // "naïve" in Latin-1, as set by some legacy replies.
+    let latin1 = b"na\xefve";
+
+    let warp_response = WarpResponse::builder()
+        .header("x-name", &latin1[..])
+        .body(WarpBody::empty())
+        .unwrap();
+    let axum_response = into_axum_response(warp_response).unwrap();
+    assert_eq!(axum_response.headers()["x-name"].as_bytes(), latin1);
+
+    let warp_response = crate::convert_response::into_warp_response(axum_response).unwrap();
+    assert_eq!(warp_response.headers()["x-name"].as_bytes(), latin1);
+}
+
+#[tokio::test]
+async fn test_repeated_headers_keep_order() {
+    let cookies = [
+        "session=abc; HttpOnly",
+        "theme=dark",
+        "session=def; Path=/admin",
+    ];
+
+    let mut builder = WarpResponse::builder();
+    for (i, cookie) in cookies.iter().enumerate() {
+        builder = builder
+            .header(warp::http::header::SET_COOKIE, *cookie)
+            .header("x-index", i.to_string());
+    }
+    let warp_response = builder.body(WarpBody::empty()).unwrap();
+
+    let axum_response = into_axum_response(warp_response).unwrap();
+    let set_cookie: Vec<_> = axum_response
+        .headers()
+        .get_all(axum::http::header::SET_COOKIE)
+        .iter()
+        .collect();
+    assert_eq!(set_cookie, cookies);
+    assert_eq!(axum_response.headers().get_all("x-index").iter().count(), 3);
+
+    let warp_response = crate::convert_response::into_warp_response(axum_response).unwrap();
+    let set_cookie: Vec<_> = warp_response
+        .headers()
+        .get_all(warp::http::header::SET_COOKIE)
+        .iter()
+        .collect();
+    assert_eq!(set_cookie, cookies);
+}
+
+#[tokio::test]
+async fn test_repeated_set_cookie_over_tcp() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use warp::Filter;
+
+    let filter = warp::any().map(|| {
+        WarpResponse::builder()
+            .header("set-cookie", "a=1")
+            .header("set-cookie", "b=2")
+            .header("set-cookie", "c=3")
+            .body(WarpBody::from("ok"))
+            .unwrap()
+    });
+    let app = axum::Router::new().fallback_service(crate::WarpService::new(filter.boxed()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+        .await
+        .unwrap();
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await.unwrap();
+
+    // Each cookie is sent as its own header line, in order.
+    let response = String::from_utf8(response).unwrap().to_ascii_lowercase();
+    let cookies: Vec<_> = response
+        .lines()
+        .filter_map(|line| line.strip_prefix("set-cookie: "))
+        .collect();
+    assert_eq!(cookies, ["a=1", "b=2", "c=3"]);
+}
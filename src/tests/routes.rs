@@ -0,0 +1,75 @@
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{WarpRoutes, WarpService};
+
+async fn get_body(service: &WarpService, uri: &str) -> (StatusCode, String) {
+    let req = AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.clone().oneshot(req).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+fn billing(routes: WarpRoutes) -> WarpRoutes {
+    routes
+        .route(warp::path!("billing" / "invoices").map(|| "invoices"))
+        .route(warp::path!("billing" / "total").map(|| warp::reply::json(&42)))
+}
+
+#[tokio::test]
+async fn test_routes_registered_from_modules() {
+    let routes = WarpRoutes::new()
+        .register(billing)
+        .route(warp::path!("accounts" / u32).map(|id| format!("account {}", id)));
+    assert_eq!(routes.len(), 3);
+
+    let service = routes.into_service();
+    assert_eq!(
+        get_body(&service, "/billing/invoices").await,
+        (StatusCode::OK, "invoices".to_string())
+    );
+    assert_eq!(
+        get_body(&service, "/billing/total").await,
+        (StatusCode::OK, "42".to_string())
+    );
+    assert_eq!(
+        get_body(&service, "/accounts/3").await,
+        (StatusCode::OK, "account 3".to_string())
+    );
+    assert_eq!(
+        get_body(&service, "/missing").await.0,
+        StatusCode::NOT_FOUND
+    );
+
+    let empty = WarpRoutes::new().into_service();
+    assert_eq!(get_body(&empty, "/").await.0, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_warp_routes_macro() {
+    let service = crate::warp_routes![
+        warp::path("hello").map(|| "hello"),
+        register: billing,
+        warp::path("json").map(|| warp::reply::json(&"json")),
+    ];
+
+    assert_eq!(
+        get_body(&service, "/hello").await,
+        (StatusCode::OK, "hello".to_string())
+    );
+    assert_eq!(
+        get_body(&service, "/billing/invoices").await,
+        (StatusCode::OK, "invoices".to_string())
+    );
+    assert_eq!(
+        get_body(&service, "/json").await,
+        (StatusCode::OK, "\"json\"".to_string())
+    );
+}
@@ -0,0 +1,48 @@
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{Denylist, SecurityHeaders, WarpService};
+
+fn request(uri: &str) -> AxumRequest {
+    AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_security_headers_added_to_responses() {
+    let filter =
+        warp::any().map(|| warp::reply::with_header("ok", "x-frame-options", "SAMEORIGIN"));
+    let service = WarpService::new(filter.boxed())
+        .with_security_headers(
+            SecurityHeaders::new()
+                .header("content-security-policy", "default-src 'self'")
+                .without("strict-transport-security"),
+        )
+        .with_denylist(Denylist::new().gone("/gone", ""));
+
+    for uri in ["/", "/gone"] {
+        let response = service.clone().oneshot(request(uri)).await.unwrap();
+        let headers = response.headers();
+
+        assert_eq!(headers.get("x-content-type-options").unwrap(), "nosniff");
+        assert_eq!(
+            headers.get("referrer-policy").unwrap(),
+            "strict-origin-when-cross-origin"
+        );
+        assert_eq!(
+            headers.get("content-security-policy").unwrap(),
+            "default-src 'self'"
+        );
+        assert!(headers.get("strict-transport-security").is_none());
+    }
+
+    // Headers set by the Warp filter take precedence.
+    let response = service.oneshot(request("/")).await.unwrap();
+    assert_eq!(
+        response.headers().get("x-frame-options").unwrap(),
+        "SAMEORIGIN"
+    );
+}
@@ -0,0 +1,184 @@
+use std::time::Duration;
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+use warp::Filter;
+
+use crate::WarpService;
+
+async fn get(addr: std::net::SocketAddr) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(b"GET /hello HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await
+        .unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[tokio::test]
+async fn test_into_make_service() {
+    let filter = warp::path("hello").map(|| "Hello from Warp!").boxed();
+    let service = WarpService::new(filter);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, service.into_make_service())
+            .await
+            .unwrap()
+    });
+
+    let response = get(addr).await;
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.ends_with("Hello from Warp!"));
+}
+
+#[tokio::test]
+async fn test_serve_with_graceful_shutdown() {
+    // Find a free port for the server to bind.
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    let filter = warp::path("hello").map(|| "Hello from Warp!").boxed();
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(crate::serve(WarpService::new(filter), addr, async {
+        stopped.await.ok();
+    }));
+
+    // Wait for the server to start listening.
+    let mut attempts = 0;
+    while TcpStream::connect(addr).await.is_err() && attempts < 50 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        attempts += 1;
+    }
+
+    assert!(get(addr).await.ends_with("Hello from Warp!"));
+
+    stop.send(()).unwrap();
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_into_make_service_with_connect_info() {
+    use std::net::SocketAddr;
+
+    use axum::extract::ConnectInfo;
+
+    let filter = warp::path("hello")
+        .and(crate::axum_extract::<ConnectInfo<SocketAddr>>())
+        .map(|ConnectInfo(addr): ConnectInfo<SocketAddr>| addr.ip().to_string())
+        .boxed();
+    let service = WarpService::new(filter);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            service.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap()
+    });
+
+    assert!(get(addr).await.ends_with("127.0.0.1"));
+}
+
+#[tokio::test]
+async fn test_dual_listener() {
+    use axum::{Router, routing::get};
+
+    let free_addr = || {
+        std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+    };
+    let (legacy_addr, app_addr) = (free_addr(), free_addr());
+
+    let filter = warp::path("hello").map(|| "Hello from Warp!").boxed();
+    let service = WarpService::new(filter);
+    let app = Router::new()
+        .route("/new", get(|| async { "Hello from Axum!" }))
+        .fallback_service(service.clone());
+
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    let server =
+        tokio::spawn(
+            crate::DualListener::new(legacy_addr, app_addr).serve(service, app, async {
+                stopped.await.ok();
+            }),
+        );
+
+    let mut attempts = 0;
+    while TcpStream::connect(app_addr).await.is_err() && attempts < 50 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        attempts += 1;
+    }
+
+    let request =
+        |path: &str| format!("GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n");
+    let send = |addr, path: &str| {
+        let request = request(path);
+        async move {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            stream.write_all(request.as_bytes()).await.unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        }
+    };
+
+    // The legacy port only serves the Warp routes.
+    assert!(
+        send(legacy_addr, "/hello")
+            .await
+            .ends_with("Hello from Warp!")
+    );
+    assert!(send(legacy_addr, "/new").await.starts_with("HTTP/1.1 404"));
+    // The app port serves both.
+    assert!(send(app_addr, "/hello").await.ends_with("Hello from Warp!"));
+    assert!(send(app_addr, "/new").await.ends_with("Hello from Axum!"));
+
+    stop.send(()).unwrap();
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_dual_listener_stops_both_servers_when_one_fails() {
+    let (stop, mut stopped) = tokio::sync::watch::channel(false);
+    let (finished, mut finished_rx) = tokio::sync::oneshot::channel();
+
+    let failing = async { Err(std::io::Error::other("accept failed")) };
+    let healthy = async move {
+        // Stands in for a server that shuts down gracefully once the signal resolves.
+        stopped.wait_for(|stop| *stop).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        finished.send(()).unwrap();
+        Ok(())
+    };
+
+    let result = crate::serve::join_servers(failing, healthy, || {
+        stop.send_replace(true);
+    })
+    .await;
+
+    assert_eq!(result.unwrap_err().to_string(), "accept failed");
+    // The healthy server finished before the error was returned.
+    assert!(finished_rx.try_recv().is_ok());
+}
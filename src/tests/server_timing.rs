@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::WarpService;
+
+fn request(uri: &str) -> AxumRequest {
+    AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap()
+}
+
+/// Parses the durations of a `Server-Timing` value, in milliseconds, by metric name.
+fn durations(value: &str) -> Vec<(String, f64)> {
+    value
+        .split(", ")
+        .map(|metric| {
+            let (name, duration) = metric.split_once(";dur=").unwrap();
+            (name.to_string(), duration.parse().unwrap())
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_server_timing() {
+    let filter = warp::path("slow").then(|| async {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        "done"
+    });
+    let service = WarpService::new(filter.boxed()).with_server_timing();
+
+    let response = service.oneshot(request("/slow")).await.unwrap();
+    let timings = durations(response.headers()["server-timing"].to_str().unwrap());
+
+    assert_eq!(timings.len(), 2);
+    assert_eq!(timings[0].0, "warpdrive-convert");
+    assert!(timings[0].1 >= 0.0);
+    assert_eq!(timings[1].0, "warp-filter");
+    assert!(timings[1].1 >= 20.0);
+}
+
+#[tokio::test]
+async fn test_server_timing_keeps_filter_timings() {
+    let filter = warp::any().map(|| warp::reply::with_header("ok", "server-timing", "db;dur=3"));
+    let service = WarpService::new(filter.boxed()).with_server_timing();
+
+    let response = service.oneshot(request("/")).await.unwrap();
+    let values: Vec<_> = response
+        .headers()
+        .get_all("server-timing")
+        .iter()
+        .map(|value| value.to_str().unwrap())
+        .collect();
+
+    assert_eq!(values.len(), 2);
+    assert_eq!(values[0], "db;dur=3");
+    assert!(values[1].starts_with("warpdrive-convert;dur="));
+}
+
+#[tokio::test]
+async fn test_server_timing_only_on_filter_responses() {
+    let filter = warp::any().then(|| async {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        "late"
+    });
+
+    // Off by default.
+    let service = WarpService::new(warp::any().map(|| "ok").boxed());
+    let response = service.oneshot(request("/")).await.unwrap();
+    assert!(!response.headers().contains_key("server-timing"));
+
+    // Responses added by the boundary have no timings.
+    let service = WarpService::new(filter.boxed())
+        .with_server_timing()
+        .with_timeout(Duration::from_millis(10));
+    let response = service.oneshot(request("/")).await.unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::GATEWAY_TIMEOUT);
+    assert!(!response.headers().contains_key("server-timing"));
+}
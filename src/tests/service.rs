@@ -255,3 +255,76 @@ async fn test_custom_status_and_headers() {
         .unwrap();
     assert_eq!(body, "Custom response");
 }
+
+#[tokio::test]
+async fn test_layer_applies_inside_boundary() {
+    let warp_filter = warp::path("upload")
+        .and(warp::post())
+        .and(warp::body::bytes())
+        .map(|body: warp::hyper::body::Bytes| format!("Uploaded {} bytes", body.len()));
+
+    let service = WarpService::new(warp_filter.boxed())
+        .layer(tower_http::limit::RequestBodyLimitLayer::new(8));
+
+    let request = AxumRequest::builder()
+        .method("POST")
+        .uri("/upload")
+        .body(AxumBody::from("small"))
+        .unwrap();
+
+    let response = service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    let request = AxumRequest::builder()
+        .method("POST")
+        .uri("/upload")
+        .header("content-length", "32")
+        .body(AxumBody::from("this body is far too large to go"))
+        .unwrap();
+
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), 413);
+}
+
+#[tokio::test]
+async fn test_layer_does_not_affect_router() {
+    let warp_filter = warp::path("check")
+        .and(warp::header::optional::<String>("x-layered"))
+        .map(|layered: Option<String>| layered.unwrap_or_else(|| "no".to_string()));
+    let service = WarpService::new(warp_filter.boxed()).layer(tower::util::MapRequestLayer::new(
+        |mut req: AxumRequest| {
+            req.headers_mut()
+                .insert("x-layered", axum::http::HeaderValue::from_static("yes"));
+            req
+        },
+    ));
+
+    let app = axum::Router::new()
+        .route(
+            "/axum",
+            axum::routing::get(|headers: axum::http::HeaderMap| async move {
+                headers.contains_key("x-layered").to_string()
+            }),
+        )
+        .fallback_service(service);
+
+    let request = AxumRequest::builder()
+        .uri("/axum")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "false");
+
+    let request = AxumRequest::builder()
+        .uri("/check")
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "yes");
+}
@@ -0,0 +1,95 @@
+use std::{convert::Infallible, time::Duration};
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode};
+use futures::{StreamExt, stream};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{Error, Shutdown, WarpService};
+
+fn request() -> AxumRequest {
+    AxumRequest::builder()
+        .uri("/")
+        .body(AxumBody::empty())
+        .unwrap()
+}
+
+/// A filter that responds with one chunk and then streams forever.
+fn streaming_service() -> WarpService<warp::reply::Response> {
+    let filter = warp::any().map(|| {
+        let chunks = stream::once(async { Ok::<_, Infallible>("hello") }).chain(stream::pending());
+        warp::reply::Response::new(warp::hyper::Body::wrap_stream(chunks))
+    });
+
+    WarpService::new(filter.boxed())
+}
+
+#[tokio::test]
+async fn test_shutdown_rejects_new_requests() {
+    let shutdown = Shutdown::new();
+    let service =
+        WarpService::new(warp::any().map(|| "ok").boxed()).with_shutdown(shutdown.clone());
+
+    let response = service.clone().oneshot(request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    drop(response);
+    assert_eq!(shutdown.in_flight(), 0);
+
+    shutdown.trigger();
+    assert!(shutdown.is_triggered());
+
+    let response = service.clone().oneshot(request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let err = service
+        .into_fallible()
+        .oneshot(request())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::ShuttingDown));
+
+    tokio::time::timeout(Duration::from_secs(1), shutdown.drained())
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_shutdown_waits_for_streaming_bodies() {
+    let shutdown = Shutdown::new();
+    let service = streaming_service().with_shutdown(shutdown.clone());
+
+    let response = service.oneshot(request()).await.unwrap();
+    assert_eq!(shutdown.in_flight(), 1);
+
+    shutdown.trigger();
+    let drained = tokio::time::timeout(Duration::from_millis(50), shutdown.drained()).await;
+    assert!(drained.is_err());
+
+    drop(response);
+    tokio::time::timeout(Duration::from_secs(1), shutdown.drained())
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_shutdown_terminates_streaming_bodies() {
+    let shutdown = Shutdown::new().terminate_streams();
+    let service = streaming_service().with_shutdown(shutdown.clone());
+
+    let response = service.oneshot(request()).await.unwrap();
+    let body = tokio::spawn(axum::body::to_bytes(response.into_body(), usize::MAX));
+
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    shutdown.trigger();
+
+    let body = tokio::time::timeout(Duration::from_secs(1), body)
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(body, "hello");
+
+    tokio::time::timeout(Duration::from_secs(1), shutdown.drained())
+        .await
+        .unwrap();
+}
@@ -0,0 +1,79 @@
+use std::{cell::Cell, rc::Rc, thread};
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::SingleThreadWarpService;
+
+thread_local! {
+    static VISITS: Rc<Cell<u32>> = Rc::new(Cell::new(0));
+}
+
+async fn get(service: &SingleThreadWarpService, uri: &str) -> (StatusCode, String) {
+    let req = AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.clone().oneshot(req).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn test_single_thread_service_shares_thread_local_state() {
+    let service = SingleThreadWarpService::new(|| {
+        warp::path("visits").map(|| {
+            let visits = VISITS.with(Rc::clone);
+            visits.set(visits.get() + 1);
+            format!(
+                "{} on {}",
+                visits.get(),
+                thread::current().name().unwrap_or_default()
+            )
+        })
+    });
+
+    assert_eq!(
+        get(&service, "/visits").await,
+        (StatusCode::OK, "1 on warpdrive-filter".to_string())
+    );
+    assert_eq!(
+        get(&service, "/visits").await,
+        (StatusCode::OK, "2 on warpdrive-filter".to_string())
+    );
+    assert_eq!(get(&service, "/other").await.0, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_single_thread_service_serves_filters_that_are_not_sync() {
+    // A `Cell` is `Send` but not `Sync`, so this filter could not be boxed for `WarpService`.
+    let service = SingleThreadWarpService::new(|| {
+        let greeting = Cell::new("hello");
+        warp::path("greeting").map(move || greeting.get())
+    });
+
+    assert_eq!(
+        get(&service, "/greeting").await,
+        (StatusCode::OK, "hello".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_single_thread_service_resumes_filter_panics() {
+    let service = SingleThreadWarpService::new(|| {
+        warp::path("boom").map(|| -> &'static str { panic!("boom") })
+    });
+
+    let req = AxumRequest::builder()
+        .uri("/boom")
+        .body(AxumBody::empty())
+        .unwrap();
+    let result = tokio::spawn(service.clone().oneshot(req)).await;
+    assert!(result.unwrap_err().is_panic());
+
+    assert_eq!(get(&service, "/other").await.0, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,42 @@
+use warp::Filter;
+
+use crate::{WarpService, test::snapshot_response};
+
+#[tokio::test]
+async fn test_snapshot_sorts_headers_and_formats_json() {
+    let filter = warp::path("user").map(|| {
+        let reply = warp::reply::json(&serde_json::json!({ "name": "Alice", "id": 1 }));
+        let reply = warp::reply::with_header(reply, "x-b", "2");
+        warp::reply::with_header(reply, "x-a", "1")
+    });
+
+    let request = axum::http::Request::get("/user")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let response = tower::ServiceExt::oneshot(WarpService::new(filter.boxed()), request)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        snapshot_response(response).await,
+        "200 OK\n\
+         content-type: application/json\n\
+         x-a: 1\n\
+         x-b: 2\n\
+         \n\
+         {\n  \"id\": 1,\n  \"name\": \"Alice\"\n}\n"
+    );
+}
+
+#[tokio::test]
+async fn test_snapshot_renders_binary_as_hex() {
+    let response = axum::response::IntoResponse::into_response((
+        axum::http::StatusCode::CREATED,
+        vec![0xffu8, 0x00, 0x10],
+    ));
+
+    assert_eq!(
+        snapshot_response(response).await,
+        "201 Created\ncontent-type: application/octet-stream\n\n<3 bytes>\nff 00 10\n"
+    );
+}
@@ -0,0 +1,105 @@
+use std::{convert::Infallible, time::Duration};
+
+use axum::{
+    body::Body as AxumBody, extract::Request as AxumRequest, response::sse::Event as AxumEvent,
+};
+use futures::{StreamExt, stream};
+use tower::ServiceExt;
+use warp::{Filter, filters::sse::Event as WarpEvent};
+
+use crate::{
+    sse::{SseEvent, axum_event_stream, axum_to_warp_event, warp_event_stream, warp_to_axum_event},
+    warp_service::WarpService,
+};
+
+fn full_event() -> SseEvent {
+    SseEvent::default()
+        .event("update")
+        .data("line one\nline two")
+        .id("42")
+        .retry(Duration::from_millis(1500))
+        .comment("note")
+}
+
+#[test]
+fn test_warp_event_round_trip() {
+    let event = SseEvent::from(full_event().into_warp());
+
+    assert_eq!(event, full_event());
+}
+
+#[test]
+fn test_axum_event_round_trip() {
+    let event = SseEvent::from(full_event().into_axum());
+
+    assert_eq!(event, full_event());
+}
+
+#[test]
+fn test_multi_line_comment_round_trip() {
+    let event = SseEvent::default().comment("first\nsecond").data("x");
+
+    let axum_event = SseEvent::from(event.clone().into_axum());
+    assert_eq!(axum_event, event);
+
+    let warp_event = event.into_warp();
+    assert_eq!(warp_event.to_string(), ":first second\ndata:x\n\n");
+    assert_eq!(
+        SseEvent::from(warp_event),
+        SseEvent::default().comment("first second").data("x")
+    );
+}
+
+#[test]
+fn test_direct_converters() {
+    let warp_event = WarpEvent::default().event("ping").data("hello");
+    let axum_event = warp_to_axum_event(warp_event);
+    let back = axum_to_warp_event(axum_event);
+
+    assert_eq!(back.to_string(), "event:ping\ndata:hello\n\n");
+}
+
+#[tokio::test]
+async fn test_stream_adapters() {
+    let events = || stream::iter([Ok::<_, Infallible>(SseEvent::default().data("a"))]);
+
+    let axum_events: Vec<_> = axum_event_stream(events()).collect().await;
+    assert_eq!(axum_events.len(), 1);
+
+    let warp_events: Vec<_> = warp_event_stream(events()).collect().await;
+    assert_eq!(warp_events.len(), 1);
+
+    let axum_events = axum_event_stream(stream::iter([Ok::<_, Infallible>(
+        AxumEvent::default().data("b"),
+    )]));
+    let converted: Vec<_> = warp_event_stream(axum_events.map(|r| r.map(SseEvent::from)))
+        .collect()
+        .await;
+    assert_eq!(converted[0].as_ref().unwrap().to_string(), "data:b\n\n");
+}
+
+#[tokio::test]
+async fn test_warp_sse_through_service() {
+    let warp_filter = warp::path("events").map(|| {
+        let events = stream::iter([Ok::<_, Infallible>(SseEvent::default().data("hello"))]);
+        warp::sse::reply(warp_event_stream(events))
+    });
+
+    let service = WarpService::new(warp_filter.boxed());
+
+    let request = AxumRequest::builder()
+        .uri("/events")
+        .body(AxumBody::empty())
+        .unwrap();
+
+    let response = service.oneshot(request).await.unwrap();
+
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/event-stream"
+    );
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "data:hello\n\n");
+}
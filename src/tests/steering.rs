@@ -0,0 +1,88 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, routing::get};
+use futures::future::BoxFuture;
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{FlagProvider, Steering, WarpService, admin::Percentage};
+
+async fn served_by(service: &WarpService, uri: &str, user: &str) -> String {
+    let req = AxumRequest::builder()
+        .uri(uri)
+        .header("x-user-id", user)
+        .body(AxumBody::empty())
+        .unwrap();
+    let response = service.clone().oneshot(req).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+fn service(steering: Steering) -> WarpService {
+    let filter = warp::any().map(|| Box::new("warp") as Box<dyn warp::Reply + Send + Sync>);
+    WarpService::new(filter.boxed()).with_steering(steering)
+}
+
+/// A flag service whose values can be changed while requests are served.
+#[derive(Clone, Default)]
+struct Flags {
+    values: Arc<Mutex<HashMap<String, u8>>>,
+}
+
+impl FlagProvider for Flags {
+    fn axum_percentage<'a>(&'a self, route: &'a str) -> BoxFuture<'a, Option<u8>> {
+        Box::pin(async move {
+            tokio::task::yield_now().await;
+            self.values.lock().unwrap().get(route).copied()
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_steering_follows_flags() {
+    let flags = Flags::default();
+    let service = service(Steering::new(get(|| async { "axum" }), flags.clone()));
+
+    assert_eq!(served_by(&service, "/users", "1").await, "warp");
+
+    flags
+        .values
+        .lock()
+        .unwrap()
+        .insert("/users".to_string(), 100);
+    assert_eq!(served_by(&service, "/users", "1").await, "axum");
+    assert_eq!(served_by(&service, "/orders", "1").await, "warp");
+
+    flags.values.lock().unwrap().insert("/users".to_string(), 0);
+    assert_eq!(served_by(&service, "/users", "1").await, "warp");
+}
+
+#[tokio::test]
+async fn test_sticky_steering() {
+    let percentage = Percentage::new(50);
+    let service = service(
+        Steering::new(get(|| async { "axum" }), percentage.clone()).sticky(|req| {
+            req.headers()
+                .get("x-user-id")
+                .map(|value| value.to_str().unwrap().to_string())
+        }),
+    );
+
+    let mut served = Vec::new();
+    for user in 0..50 {
+        let first = served_by(&service, "/users", &user.to_string()).await;
+        let second = served_by(&service, "/users", &user.to_string()).await;
+        assert_eq!(first, second);
+        served.push(first);
+    }
+    assert!(served.iter().any(|by| by == "warp"));
+    assert!(served.iter().any(|by| by == "axum"));
+
+    percentage.set(100);
+    assert_eq!(served_by(&service, "/users", "0").await, "axum");
+}
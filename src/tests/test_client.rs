@@ -0,0 +1,113 @@
+use axum::routing::get;
+use warp::Filter;
+
+use crate::{WarpService, test::MixedTestClient};
+
+fn client() -> MixedTestClient {
+    let warp_routes = warp::path("echo")
+        .and(warp::post())
+        .and(warp::header::<String>("x-request-id"))
+        .and(warp::body::json())
+        .map(|id: String, body: serde_json::Value| {
+            warp::reply::with_header(warp::reply::json(&body), "x-request-id", id)
+        })
+        .boxed();
+
+    let app = axum::Router::new()
+        .route("/", get(|| async { "Hello from Axum!" }))
+        .fallback_service(WarpService::new(warp_routes));
+
+    MixedTestClient::new(app)
+}
+
+#[tokio::test]
+async fn test_client_axum_route() {
+    client()
+        .get("/")
+        .send()
+        .await
+        .assert_status(200)
+        .assert_text("Hello from Axum!");
+}
+
+#[tokio::test]
+async fn test_client_warp_fallback_json() {
+    let body = serde_json::json!({ "message": "hi", "count": 1 });
+
+    let response = client()
+        .post("/echo")
+        .header("x-request-id", "abc")
+        .json(&body)
+        .send()
+        .await;
+
+    response
+        .assert_status(200)
+        .assert_header("x-request-id", "abc")
+        .assert_header("content-type", "application/json")
+        .assert_json(&body);
+    assert_eq!(response.json::<serde_json::Value>()["count"], 1);
+}
+
+#[tokio::test]
+#[should_panic(expected = "unexpected status")]
+async fn test_client_assert_status_panics() {
+    client().get("/missing").send().await.assert_status(200);
+}
+
+#[tokio::test]
+async fn test_check_parity_reports_differences() {
+    use axum::{body::Bytes, http::Request};
+
+    use crate::test::{ParityDifference, check_parity};
+
+    let warp_route = warp::path("hello")
+        .map(|| warp::reply::with_header("Hello!", "x-stack", "warp"))
+        .boxed();
+    let axum_route = axum::Router::new().route(
+        "/hello",
+        get(|| async { ([("x-stack", "axum")], "Hello!") }),
+    );
+
+    let requests = [Request::get("/hello").body(Bytes::new()).unwrap()];
+
+    let mismatches = check_parity(warp_route, axum_route, requests).await;
+
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0].uri, "/hello");
+    assert_eq!(
+        mismatches[0].differences,
+        vec![ParityDifference::Header {
+            name: axum::http::HeaderName::from_static("x-stack"),
+            warp: vec![axum::http::HeaderValue::from_static("warp")],
+            axum: vec![axum::http::HeaderValue::from_static("axum")],
+        }]
+    );
+}
+
+#[tokio::test]
+async fn test_assert_parity_passes_for_identical_routes() {
+    use axum::{body::Bytes, http::Request};
+
+    use crate::test::assert_parity;
+
+    let warp_route = warp::path!("users" / u32)
+        .map(|id: u32| format!("User {}", id))
+        .boxed();
+    let axum_route =
+        axum::Router::new().route(
+            "/users/{id}",
+            get(
+                |axum::extract::Path(id): axum::extract::Path<u32>| async move {
+                    format!("User {}", id)
+                },
+            ),
+        );
+
+    let requests = [
+        Request::get("/users/1").body(Bytes::new()).unwrap(),
+        Request::get("/users/42").body(Bytes::new()).unwrap(),
+    ];
+
+    assert_parity(warp_route, axum_route, requests).await;
+}
@@ -0,0 +1,215 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use axum::{
+    Router, body::Body as AxumBody, extract::Request as AxumRequest, http::StatusCode, routing::get,
+};
+use tower::ServiceExt;
+use tracing::{
+    Event, Id, Level, Metadata, Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Record},
+};
+use warp::Filter;
+
+use crate::{RouteOwner, UsageReport, WarpService, trace::TraceConfig};
+
+type Fields = BTreeMap<String, String>;
+type Traced = Arc<Mutex<Vec<(Fields, Vec<Fields>)>>>;
+
+/// Records the `request` spans and their events, ignoring Warp's own events.
+#[derive(Default)]
+struct Recorder {
+    next_id: AtomicU64,
+    /// The fields and handle count of each open span.
+    spans: Mutex<BTreeMap<u64, (Fields, usize)>>,
+    current: Mutex<Vec<u64>>,
+    /// The fields of each finished span, with the fields of its events.
+    traced: Traced,
+    events: Mutex<BTreeMap<u64, Vec<Fields>>>,
+}
+
+struct FieldVisitor<'a>(&'a mut Fields);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        !metadata.target().starts_with("warp::")
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut fields = Fields::new();
+        fields.insert("level".to_string(), span.metadata().level().to_string());
+        span.record(&mut FieldVisitor(&mut fields));
+        self.spans.lock().unwrap().insert(id, (fields, 1));
+        Id::from_u64(id)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        if let Some((fields, _)) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+            values.record(&mut FieldVisitor(fields));
+        }
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let Some(span) = self.current.lock().unwrap().last().copied() else {
+            return;
+        };
+        let mut fields = Fields::new();
+        fields.insert("level".to_string(), event.metadata().level().to_string());
+        event.record(&mut FieldVisitor(&mut fields));
+        fields.remove("latency_ms");
+        self.events
+            .lock()
+            .unwrap()
+            .entry(span)
+            .or_default()
+            .push(fields);
+    }
+
+    fn enter(&self, span: &Id) {
+        self.current.lock().unwrap().push(span.into_u64());
+    }
+
+    fn exit(&self, _span: &Id) {
+        self.current.lock().unwrap().pop();
+    }
+
+    fn clone_span(&self, span: &Id) -> Id {
+        if let Some((_, handles)) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+            *handles += 1;
+        }
+        span.clone()
+    }
+
+    fn try_close(&self, span: Id) -> bool {
+        let id = span.into_u64();
+        let mut spans = self.spans.lock().unwrap();
+        let Some((_, handles)) = spans.get_mut(&id) else {
+            return false;
+        };
+        *handles -= 1;
+        if *handles > 0 {
+            return false;
+        }
+
+        let (fields, _) = spans.remove(&id).unwrap();
+        let events = self.events.lock().unwrap().remove(&id).unwrap_or_default();
+        self.traced.lock().unwrap().push((fields, events));
+        true
+    }
+}
+
+#[tokio::test]
+async fn test_trace_config_traces_both_stacks_identically() {
+    let recorder = Recorder::default();
+    let traced = Arc::clone(&recorder.traced);
+    let _guard = tracing::subscriber::set_default(recorder);
+
+    let trace = TraceConfig::new().level(Level::DEBUG).include_headers(true);
+    let warp_routes = trace
+        .clone()
+        .wrap(warp::path!("items" / "warp").map(|| "warp"))
+        .boxed();
+    let app = Router::new()
+        .route("/items/axum", get(|| async { "axum" }))
+        .layer(trace.layer())
+        .fallback_service(WarpService::new(warp_routes));
+
+    for uri in ["/items/axum?page=1", "/items/warp?page=1"] {
+        let req = AxumRequest::builder()
+            .uri(uri)
+            .header("x-request-id", "abc")
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let mut traced = traced.lock().unwrap().clone();
+    assert_eq!(traced.len(), 2);
+    let (mut warp_span, warp_events) = traced.pop().unwrap();
+    let (mut axum_span, axum_events) = traced.pop().unwrap();
+    assert_eq!(axum_span.remove("path").unwrap(), "/items/axum");
+    assert_eq!(warp_span.remove("path").unwrap(), "/items/warp");
+    assert_eq!(axum_span, warp_span);
+    assert_eq!(axum_span["level"], "DEBUG");
+    assert_eq!(axum_span["method"], "GET");
+    assert_eq!(axum_span["version"], "HTTP/1.1");
+    assert!(axum_span["headers"].contains("\"x-request-id\": \"abc\""));
+
+    assert_eq!(axum_events, warp_events);
+    assert_eq!(axum_events.len(), 1);
+    assert_eq!(axum_events[0]["status"], "200");
+    assert_eq!(axum_events[0]["message"], "finished processing request");
+}
+
+#[tokio::test]
+async fn test_trace_config_records_route_owners() {
+    let recorder = Recorder::default();
+    let traced = Arc::clone(&recorder.traced);
+    let _guard = tracing::subscriber::set_default(recorder);
+
+    let report = UsageReport::new()
+        .owner(
+            "/billing",
+            RouteOwner::new("payments").target_date("2020-01-31"),
+        )
+        .owner(
+            "/users",
+            RouteOwner::new("identity").target_date("2999-12-31"),
+        );
+    let trace = TraceConfig::new().owners(&report);
+    let warp_routes = trace
+        .clone()
+        .wrap(
+            warp::path!("billing")
+                .or(warp::path!("users"))
+                .map(|_| "warp"),
+        )
+        .boxed();
+    let app = Router::new()
+        .layer(trace.layer())
+        .fallback_service(WarpService::new(warp_routes));
+
+    for uri in ["/billing", "/users"] {
+        let req = AxumRequest::builder()
+            .uri(uri)
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let traced = traced.lock().unwrap().clone();
+    assert_eq!(traced.len(), 2);
+    let (billing_span, billing_events) = &traced[0];
+    assert_eq!(billing_span["owner"], "\"payments\"");
+    assert_eq!(billing_span["target_date"], "\"2020-01-31\"");
+    let (users_span, users_events) = &traced[1];
+    assert_eq!(users_span["owner"], "\"identity\"");
+
+    // Only the route past its target date is warned about.
+    assert_eq!(billing_events.len(), 2);
+    assert_eq!(billing_events[0]["level"], "WARN");
+    assert_eq!(
+        billing_events[0]["message"],
+        "route is past its migration target date"
+    );
+    assert_eq!(users_events.len(), 1);
+}
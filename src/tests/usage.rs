@@ -0,0 +1,177 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{body::Body as AxumBody, extract::Request as AxumRequest, routing::get};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{Failover, RouteOwner, UsageExport, UsageReport, WarpService};
+
+async fn get_path(service: &WarpService, uri: &str) {
+    let req = AxumRequest::builder()
+        .uri(uri)
+        .body(AxumBody::empty())
+        .unwrap();
+    service.clone().oneshot(req).await.unwrap();
+}
+
+fn service(report: UsageReport) -> WarpService {
+    let ok = warp::path("ok").map(|| "ok");
+    let broken = warp::path("broken")
+        .map(|| warp::reply::with_status("broken", warp::http::StatusCode::INTERNAL_SERVER_ERROR));
+    let filter = ok
+        .map(|reply| Box::new(reply) as Box<dyn warp::Reply + Send + Sync>)
+        .or(broken.map(|reply| Box::new(reply) as Box<dyn warp::Reply + Send + Sync>))
+        .unify();
+
+    WarpService::new(filter.boxed()).with_usage_report(report)
+}
+
+#[tokio::test]
+async fn test_usage_report_records_routes() {
+    let report = UsageReport::new();
+    let service = service(report.clone());
+
+    get_path(&service, "/ok").await;
+    get_path(&service, "/ok").await;
+    get_path(&service, "/broken").await;
+
+    // Failed over requests are no longer served by Warp.
+    let migrated = service
+        .clone()
+        .with_failover(Failover::new(get(|| async { "axum" })));
+    get_path(&migrated, "/broken").await;
+
+    let snapshot = report.snapshot();
+    assert_eq!(snapshot.len(), 2);
+    assert_eq!(snapshot[0].route, "GET /broken");
+    assert_eq!((snapshot[0].hits, snapshot[0].errors), (2, 1));
+    assert_eq!(snapshot[0].error_rate(), 0.5);
+    assert!(!snapshot[0].on_warp);
+    assert_eq!(snapshot[1].route, "GET /ok");
+    assert_eq!((snapshot[1].hits, snapshot[1].errors), (2, 0));
+    assert!(snapshot[1].on_warp);
+
+    let csv = report.to_csv();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next().unwrap(),
+        "route,hits,errors,error_rate,last_seen,on_warp,team,target_date"
+    );
+    assert!(
+        lines
+            .next()
+            .unwrap()
+            .starts_with("\"GET /broken\",2,1,0.5000,")
+    );
+}
+
+#[tokio::test]
+async fn test_usage_report_export_and_load() {
+    let report = UsageReport::new().max_routes(1);
+    let service = service(report.clone());
+    get_path(&service, "/ok").await;
+    get_path(&service, "/broken").await;
+
+    let path = std::env::temp_dir().join(format!("warpdrive-usage-{}.json", std::process::id()));
+    report.export(&UsageExport::json(&path)).unwrap();
+
+    let loaded = UsageReport::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.snapshot().len(), 2);
+    assert_eq!(loaded.snapshot()[0].route, "(other)");
+
+    // The exporter passes the report to the callback at every interval.
+    let exported = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&exported);
+    let exporter = tokio::spawn(loaded.exporter(
+        Duration::from_millis(10),
+        UsageExport::callback(move |routes| sink.lock().unwrap().push(routes.len())),
+    ));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    exporter.abort();
+
+    assert!(exported.lock().unwrap().iter().all(|routes| *routes == 2));
+    assert!(!exported.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_route_owner_is_overdue() {
+    assert!(RouteOwner::new("a").target_date("2000-02-29").is_overdue());
+    assert!(!RouteOwner::new("a").target_date("2999-12-31").is_overdue());
+
+    // Owners without a target date, or with one in another format, are never overdue.
+    assert!(!RouteOwner::new("a").is_overdue());
+    assert!(
+        !RouteOwner::new("a")
+            .target_date("next quarter")
+            .is_overdue()
+    );
+    assert!(!RouteOwner::new("a").target_date("2000-13-01").is_overdue());
+}
+
+#[tokio::test]
+async fn test_usage_report_route_owners() {
+    let report = UsageReport::new()
+        .owner("/", RouteOwner::new("platform"))
+        .owner(
+            "/broken",
+            RouteOwner::new("payments").target_date("2026-03-31"),
+        );
+    let service = service(report.clone());
+    get_path(&service, "/ok").await;
+    get_path(&service, "/broken").await;
+
+    let snapshot = report.snapshot();
+    assert_eq!(
+        snapshot[0].owner,
+        Some(RouteOwner::new("payments").target_date("2026-03-31"))
+    );
+    assert_eq!(snapshot[1].owner, Some(RouteOwner::new("platform")));
+    assert!(
+        report
+            .to_csv()
+            .lines()
+            .nth(1)
+            .unwrap()
+            .ends_with(",\"payments\",\"2026-03-31\"")
+    );
+
+    // Owners are kept when a report is exported and loaded.
+    let path = std::env::temp_dir().join(format!("warpdrive-owners-{}.json", std::process::id()));
+    report.export(&UsageExport::json(&path)).unwrap();
+    let loaded = UsageReport::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.snapshot(), snapshot_without_nanos(&snapshot));
+}
+
+#[tokio::test]
+async fn test_usage_report_owner_prefix_ignores_trailing_slash() {
+    let report = UsageReport::new().owner("/ok/", RouteOwner::new("payments"));
+    let service = service(report.clone());
+    get_path(&service, "/ok").await;
+
+    assert_eq!(
+        report.snapshot()[0].owner,
+        Some(RouteOwner::new("payments"))
+    );
+}
+
+/// Returns the snapshot with `last_seen` truncated to seconds, as stored in exports.
+fn snapshot_without_nanos(snapshot: &[crate::RouteUsage]) -> Vec<crate::RouteUsage> {
+    snapshot
+        .iter()
+        .cloned()
+        .map(|mut usage| {
+            let secs = usage
+                .last_seen
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            usage.last_seen = std::time::UNIX_EPOCH + Duration::from_secs(secs);
+            usage
+        })
+        .collect()
+}
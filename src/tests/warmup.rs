@@ -0,0 +1,43 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use axum::{
+    body::Body as AxumBody,
+    extract::Request as AxumRequest,
+    http::{Method, StatusCode},
+};
+use tower::ServiceExt;
+use warp::Filter;
+
+use crate::{RequestLimits, WarpService};
+
+#[tokio::test]
+async fn test_warmup_reaches_the_filter_without_boundary_settings() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counted = Arc::clone(&hits);
+    let filter = warp::path("health").map(move || {
+        counted.fetch_add(1, Ordering::SeqCst);
+        "ok"
+    });
+    // No request passes the limits, so only warmup requests reach the filter.
+    let service = WarpService::new(filter.boxed())
+        .with_request_limits(RequestLimits::new().max_uri_length(0));
+
+    let warmup = |uri: &str| {
+        AxumRequest::builder()
+            .method(Method::GET)
+            .uri(uri)
+            .body(AxumBody::empty())
+            .unwrap()
+    };
+    service
+        .warmup([warmup("/health"), warmup("/missing")])
+        .await;
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+    let response = service.oneshot(warmup("/health")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::URI_TOO_LONG);
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+}
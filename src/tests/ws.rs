@@ -0,0 +1,87 @@
+use axum::extract::ws::{CloseFrame, Message as AxumMessage};
+use futures::{SinkExt, StreamExt, stream};
+use warp::filters::ws::Message as WarpMessage;
+
+use crate::ws::{WarpMessageSocket, axum_to_warp_message, warp_to_axum_message};
+
+#[test]
+fn test_warp_to_axum_message() {
+    assert_eq!(
+        warp_to_axum_message(WarpMessage::text("hello")),
+        AxumMessage::Text("hello".into())
+    );
+    assert_eq!(
+        warp_to_axum_message(WarpMessage::binary(vec![1, 2, 3])),
+        AxumMessage::Binary(vec![1, 2, 3].into())
+    );
+    assert_eq!(
+        warp_to_axum_message(WarpMessage::ping(vec![1])),
+        AxumMessage::Ping(vec![1].into())
+    );
+    assert_eq!(
+        warp_to_axum_message(WarpMessage::pong(vec![2])),
+        AxumMessage::Pong(vec![2].into())
+    );
+    assert_eq!(
+        warp_to_axum_message(WarpMessage::close_with(1000u16, "bye")),
+        AxumMessage::Close(Some(CloseFrame {
+            code: 1000,
+            reason: "bye".into(),
+        }))
+    );
+    assert_eq!(
+        warp_to_axum_message(WarpMessage::close()),
+        AxumMessage::Close(None)
+    );
+}
+
+#[test]
+fn test_axum_to_warp_message() {
+    assert_eq!(
+        axum_to_warp_message(AxumMessage::Text("hello".into())),
+        WarpMessage::text("hello")
+    );
+    assert_eq!(
+        axum_to_warp_message(AxumMessage::Binary(vec![1, 2, 3].into())),
+        WarpMessage::binary(vec![1, 2, 3])
+    );
+    assert!(axum_to_warp_message(AxumMessage::Ping(vec![1].into())).is_ping());
+    assert!(axum_to_warp_message(AxumMessage::Pong(vec![1].into())).is_pong());
+
+    let close = axum_to_warp_message(AxumMessage::Close(Some(CloseFrame {
+        code: 1001,
+        reason: "away".into(),
+    })));
+    assert_eq!(close.close_frame(), Some((1001, "away")));
+}
+
+#[tokio::test]
+async fn test_socket_stream_yields_warp_messages() {
+    let socket = stream::iter([
+        Ok::<_, ()>(AxumMessage::Text("one".into())),
+        Ok(AxumMessage::Binary(vec![2].into())),
+    ]);
+
+    let messages: Vec<_> = WarpMessageSocket::new(socket).collect().await;
+
+    assert_eq!(
+        messages,
+        vec![
+            Ok(WarpMessage::text("one")),
+            Ok(WarpMessage::binary(vec![2]))
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_socket_sink_accepts_warp_messages() {
+    let mut socket = WarpMessageSocket::new(Vec::<AxumMessage>::new());
+
+    socket.send(WarpMessage::text("reply")).await.unwrap();
+    socket.send(WarpMessage::close()).await.unwrap();
+
+    assert_eq!(
+        socket.into_inner(),
+        vec![AxumMessage::Text("reply".into()), AxumMessage::Close(None)]
+    );
+}
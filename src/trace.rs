@@ -0,0 +1,244 @@
+//! A single request tracing configuration for Axum and Warp routes.
+//!
+//! [`TraceConfig`] builds a tower-http [`TraceLayer`] for Axum routes and a matching wrapper for
+//! Warp filters, so requests to legacy and migrated routes produce the same span and the same
+//! response event, with the same fields and at the same level. Dashboards and alerts built on
+//! these logs then keep working as routes move from one stack to the other.
+//!
+//! Each request is traced with a `request` span with these fields:
+//!
+//! - `method`: the request method.
+//! - `path`: the request path, without the query.
+//! - `version`: the HTTP version, such as `HTTP/1.1`.
+//! - `headers`: the request headers, if [enabled](TraceConfig::include_headers).
+//! - `owner` and `target_date`: the owner of the route and the date it should be migrated
+//!   by, if assigned by a [`UsageReport`](crate::UsageReport) set with
+//!   [`owners`](TraceConfig::owners).
+//!
+//! Once the response is ready, a `finished processing request` event is recorded in the span
+//! with the response `status` and the `latency_ms` taken to produce it.
+//!
+//! Requests that reach a wrapped Warp filter after their route's target date has passed also
+//! record a `WARN` event, `route is past its migration target date`, so overdue routes show up
+//! in logs while they are still served by Warp.
+//!
+//! This module is available with the `tracing` feature.
+//!
+//! # Example
+//!
+//! ```rust
+//! use axum::{Router, routing::get};
+//! use tracing::Level;
+//! use warpdrive::{WarpService, trace::TraceConfig};
+//! use warp::Filter;
+//!
+//! let trace = TraceConfig::new().level(Level::DEBUG);
+//!
+//! let warp_routes = trace.clone().wrap(warp::path("legacy").map(|| "Hello from Warp!")).boxed();
+//!
+//! let app: Router = Router::new()
+//!     .route("/", get(|| async { "Hello from Axum!" }))
+//!     .layer(trace.layer())
+//!     .fallback_service(WarpService::new(warp_routes));
+//! ```
+
+use std::{fmt, sync::Arc, time::Duration};
+
+use axum::http::{Request, Response};
+use tower_http::{
+    classify::{ServerErrorsAsFailures, SharedClassifier},
+    trace::{MakeSpan, OnResponse, TraceLayer},
+};
+use tracing::{Level, Span, field::Empty};
+use warp::{Filter, Rejection, Reply};
+
+use crate::usage::{RouteOwner, UsageReport, owner_of};
+
+/// Creates a span or event at a level that is only known at runtime, as the `tracing` macros
+/// need a constant level.
+macro_rules! at_level {
+    ($level:expr, $macro:ident!($($args:tt)*)) => {
+        match $level {
+            Level::ERROR => tracing::$macro!(Level::ERROR, $($args)*),
+            Level::WARN => tracing::$macro!(Level::WARN, $($args)*),
+            Level::INFO => tracing::$macro!(Level::INFO, $($args)*),
+            Level::DEBUG => tracing::$macro!(Level::DEBUG, $($args)*),
+            Level::TRACE => tracing::$macro!(Level::TRACE, $($args)*),
+        }
+    };
+}
+
+/// The tower-http layer built by [`TraceConfig::layer`].
+pub type AxumTraceLayer = TraceLayer<
+    SharedClassifier<ServerErrorsAsFailures>,
+    RequestSpan,
+    (),
+    ResponseEvent,
+    (),
+    (),
+    (),
+>;
+
+/// Request tracing settings that generate matching instrumentation for Axum and Warp.
+#[derive(Debug, Clone)]
+pub struct TraceConfig {
+    level: Level,
+    include_headers: bool,
+    owners: Arc<Vec<(String, RouteOwner)>>,
+}
+
+impl Default for TraceConfig {
+    fn default() -> Self {
+        TraceConfig {
+            level: Level::INFO,
+            include_headers: false,
+            owners: Arc::new(Vec::new()),
+        }
+    }
+}
+
+impl TraceConfig {
+    /// Creates a configuration that traces requests at the `INFO` level, without headers.
+    pub fn new() -> Self {
+        TraceConfig::default()
+    }
+
+    /// Sets the level of the request span and response event. Defaults to `INFO`.
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Sets whether the request headers are recorded in the span. Defaults to `false`, as
+    /// headers may contain credentials.
+    pub fn include_headers(mut self, include_headers: bool) -> Self {
+        self.include_headers = include_headers;
+        self
+    }
+
+    /// Records the owner and target date of each request's route in its span, as assigned by
+    /// the [owners](UsageReport::owner) of a usage report.
+    pub fn owners(mut self, report: &UsageReport) -> Self {
+        self.owners = report.owners();
+        self
+    }
+
+    /// Builds a tower-http layer for Axum routes.
+    pub fn layer(&self) -> AxumTraceLayer {
+        TraceLayer::new_for_http()
+            .make_span_with(RequestSpan(self.clone()))
+            .on_request(())
+            .on_response(ResponseEvent(self.clone()))
+            .on_body_chunk(())
+            .on_eos(())
+            .on_failure(())
+    }
+
+    /// Wraps a Warp filter so that its requests are traced.
+    ///
+    /// Rejections are traced with the status they are answered with, so a filter that is
+    /// recovered further out may be traced with a different status than it is served with.
+    /// Warp also records its own events under the `warp::filters::trace` target, which can be
+    /// filtered out to match the Axum routes exactly.
+    pub fn wrap<F>(
+        self,
+        filter: F,
+    ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone + Send + Sync + 'static
+    where
+        F: Filter<Error = Rejection> + Clone + Send + Sync + 'static,
+        F::Extract: Reply,
+    {
+        let config = self.clone();
+        let log = warp::log::custom(move |info| {
+            config.record_response(info.status().as_u16(), info.elapsed())
+        });
+        let config = self;
+        let trace = warp::trace(move |info| {
+            let span = config.span(
+                info.method(),
+                info.path(),
+                info.version(),
+                info.request_headers(),
+            );
+            if let Some(owner) = owner_of(&config.owners, info.path())
+                && owner.is_overdue()
+            {
+                span.in_scope(|| {
+                    tracing::warn!(
+                        owner = %owner.team,
+                        target_date = owner.target_date.as_deref(),
+                        "route is past its migration target date"
+                    )
+                });
+            }
+            span
+        });
+
+        filter.with(log).with(trace)
+    }
+
+    fn span(
+        &self,
+        method: impl fmt::Display,
+        path: &str,
+        version: impl fmt::Debug,
+        headers: impl fmt::Debug,
+    ) -> Span {
+        let span = at_level!(
+            self.level,
+            span!(
+                "request",
+                method = %method,
+                path = %path,
+                version = ?version,
+                headers = Empty,
+                owner = Empty,
+                target_date = Empty,
+            )
+        );
+        if self.include_headers {
+            span.record("headers", tracing::field::debug(headers));
+        }
+        if let Some(owner) = owner_of(&self.owners, path) {
+            span.record("owner", owner.team.as_str());
+            if let Some(target_date) = &owner.target_date {
+                span.record("target_date", target_date.as_str());
+            }
+        }
+        span
+    }
+
+    /// Records the response event in the current span.
+    fn record_response(&self, status: u16, latency: Duration) {
+        let latency_ms = latency.as_millis() as u64;
+        at_level!(
+            self.level,
+            event!(status, latency_ms, "finished processing request")
+        );
+    }
+}
+
+/// Creates the request span for Axum routes.
+#[derive(Debug, Clone)]
+pub struct RequestSpan(TraceConfig);
+
+impl<B> MakeSpan<B> for RequestSpan {
+    fn make_span(&mut self, request: &Request<B>) -> Span {
+        self.0.span(
+            request.method(),
+            request.uri().path(),
+            request.version(),
+            request.headers(),
+        )
+    }
+}
+
+/// Records the response event for Axum routes.
+#[derive(Debug, Clone)]
+pub struct ResponseEvent(TraceConfig);
+
+impl<B> OnResponse<B> for ResponseEvent {
+    fn on_response(self, response: &Response<B>, latency: Duration, _span: &Span) {
+        self.0.record_response(response.status().as_u16(), latency);
+    }
+}
@@ -0,0 +1,563 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use axum::{extract::Request, http, response::Response};
+use serde_json::{Value, json};
+
+use crate::{
+    error::Error,
+    prefix::{matches_prefix, trim_prefix},
+    route::RouteKeyFn,
+};
+
+/// The number of routes tracked by default before new routes are counted together.
+const DEFAULT_MAX_ROUTES: usize = 1_000;
+
+/// The route that requests are counted under once the route limit is reached.
+const OTHER_ROUTE: &str = "(other)";
+
+/// The number of distinct response content types recorded per route.
+const MAX_CONTENT_TYPES: usize = 16;
+
+type ExportCallback = Arc<dyn Fn(&[RouteUsage]) + Send + Sync>;
+
+/// Per-route usage of [`WarpService`](crate::WarpService)s, for tracking migration progress.
+///
+/// For each route, the report counts requests and server errors, and records when the route
+/// was last seen and whether its last request was still served by Warp, rather than by an
+/// Axum service through [`Failover`](crate::Failover), [`Hedge`](crate::Hedge), or
+/// [`Steering`](crate::Steering). Routes are
+/// identified by method and path by default, such as `GET /users/42`; use
+/// [`route_key`](UsageReport::route_key) to group paths, such as by stripping IDs.
+///
+/// Routes can be assigned an [owner](UsageReport::owner), such as the team responsible for
+/// migrating them and the date they should be migrated by, which is included in exports and
+/// on the admin status endpoint so reports can be routed to the right team. With the `tracing`
+/// feature, `TraceConfig::owners` also records them in request spans and warns about requests
+/// to routes past their target date.
+///
+/// The report is a cheaply cloneable handle, so it can be shared by several services and
+/// an [`exporter`](UsageReport::exporter) that periodically writes it to a JSON or CSV file
+/// or passes it to a callback. Reports written as JSON can be [loaded](UsageReport::load)
+/// on startup, so usage accumulates across restarts over the weeks of a migration.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+///
+/// use warpdrive::{RouteOwner, UsageExport, UsageReport, WarpService};
+/// use warp::Filter;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let report = UsageReport::load("warp-usage.json")
+///     .unwrap_or_default()
+///     .route_key(|req| {
+///         // Counts `/users/42` and `/users/7` as `/users/{id}`.
+///         let path: Vec<_> = req
+///             .uri()
+///             .path()
+///             .split('/')
+///             .map(|segment| match segment.parse::<u64>() {
+///                 Ok(_) => "{id}",
+///                 Err(_) => segment,
+///             })
+///             .collect();
+///         format!("{} {}", req.method(), path.join("/"))
+///     })
+///     .owner("/users", RouteOwner::new("identity").target_date("2026-03-31"));
+///
+/// tokio::spawn(report.exporter(
+///     Duration::from_secs(60),
+///     UsageExport::json("warp-usage.json"),
+/// ));
+///
+/// let filter = warp::path("users").map(|| "Users").boxed();
+/// let service = WarpService::new(filter).with_usage_report(report);
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct UsageReport {
+    routes: Arc<Mutex<HashMap<String, RouteUsage>>>,
+    route_key: Option<RouteKeyFn>,
+    max_routes: usize,
+    owners: Arc<Vec<(String, RouteOwner)>>,
+}
+
+/// The owner of a route, as assigned with [`UsageReport::owner`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteOwner {
+    /// The team or person responsible for migrating the route.
+    pub team: String,
+    /// The date the route should be migrated by, such as `2026-03-31`.
+    pub target_date: Option<String>,
+}
+
+impl RouteOwner {
+    /// Creates an owner with no target date.
+    pub fn new(team: impl Into<String>) -> Self {
+        RouteOwner {
+            team: team.into(),
+            target_date: None,
+        }
+    }
+
+    /// Sets the date the route should be migrated by, such as `2026-03-31`.
+    pub fn target_date(mut self, target_date: impl Into<String>) -> Self {
+        self.target_date = Some(target_date.into());
+        self
+    }
+
+    /// Returns `true` if the target date, written as `YYYY-MM-DD`, has passed. Owners without
+    /// a target date, or with one in another format, are never overdue.
+    pub fn is_overdue(&self) -> bool {
+        let Some(target_day) = self.target_date.as_deref().and_then(days_since_epoch) else {
+            return false;
+        };
+        let today = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| (elapsed.as_secs() / 86_400) as i64);
+        today > target_day
+    }
+}
+
+/// Returns the number of days between the Unix epoch and a `YYYY-MM-DD` date.
+fn days_since_epoch(date: &str) -> Option<i64> {
+    let mut parts = date.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // Counts from March, so that the leap day is the last day of the year.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    Some(era * 146_097 + day_of_era - 719_468)
+}
+
+/// Returns the owner of a path, assigned to the longest matching prefix.
+pub(crate) fn owner_of<'a>(
+    owners: &'a [(String, RouteOwner)],
+    path: &str,
+) -> Option<&'a RouteOwner> {
+    owners
+        .iter()
+        .filter(|(prefix, _)| matches_prefix(prefix, path))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, owner)| owner)
+}
+
+/// The usage of a single route, as recorded by a [`UsageReport`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteUsage {
+    /// The route, such as `GET /users`.
+    pub route: String,
+    /// The number of requests.
+    pub hits: u64,
+    /// The number of requests that failed with a server error.
+    pub errors: u64,
+    /// When the route was last requested.
+    pub last_seen: SystemTime,
+    /// Whether the last request was served by Warp.
+    pub on_warp: bool,
+    /// The owner of the route, if one was assigned.
+    pub owner: Option<RouteOwner>,
+    /// The number of responses with each status code.
+    pub statuses: BTreeMap<u16, u64>,
+    /// The media types of the responses, such as `application/json`, without parameters.
+    pub content_types: BTreeSet<String>,
+}
+
+impl RouteUsage {
+    /// Returns the fraction of requests that failed, between `0.0` and `1.0`.
+    pub fn error_rate(&self) -> f64 {
+        if self.hits == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.hits as f64
+        }
+    }
+
+    fn last_seen_secs(&self) -> u64 {
+        self.last_seen
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs())
+    }
+}
+
+/// Where a [`UsageReport`] exporter writes the report.
+#[derive(Clone)]
+pub struct UsageExport {
+    target: ExportTarget,
+}
+
+#[derive(Clone)]
+enum ExportTarget {
+    Json(PathBuf),
+    Csv(PathBuf),
+    #[cfg(any(test, feature = "openapi"))]
+    OpenApi(PathBuf),
+    Callback(ExportCallback),
+}
+
+impl UsageExport {
+    /// Writes the report to a JSON file, which can be read back with [`UsageReport::load`].
+    pub fn json(path: impl Into<PathBuf>) -> Self {
+        UsageExport {
+            target: ExportTarget::Json(path.into()),
+        }
+    }
+
+    /// Writes the report to a CSV file, with a header row.
+    pub fn csv(path: impl Into<PathBuf>) -> Self {
+        UsageExport {
+            target: ExportTarget::Csv(path.into()),
+        }
+    }
+
+    /// Writes an OpenAPI skeleton of the routes to a JSON file, as built by
+    /// [`UsageReport::to_openapi`].
+    ///
+    /// Available with the `openapi` feature.
+    #[cfg(any(test, feature = "openapi"))]
+    pub fn openapi(path: impl Into<PathBuf>) -> Self {
+        UsageExport {
+            target: ExportTarget::OpenApi(path.into()),
+        }
+    }
+
+    /// Passes the report to a function.
+    pub fn callback<F>(callback: F) -> Self
+    where
+        F: Fn(&[RouteUsage]) + Send + Sync + 'static,
+    {
+        UsageExport {
+            target: ExportTarget::Callback(Arc::new(callback)),
+        }
+    }
+}
+
+impl Default for UsageReport {
+    fn default() -> Self {
+        UsageReport::new()
+    }
+}
+
+impl UsageReport {
+    /// Creates an empty report.
+    pub fn new() -> Self {
+        UsageReport {
+            routes: Arc::new(Mutex::new(HashMap::new())),
+            route_key: None,
+            max_routes: DEFAULT_MAX_ROUTES,
+            owners: Arc::new(Vec::new()),
+        }
+    }
+
+    /// Loads a report previously written with [`UsageExport::json`], to continue counting.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let value: Value = serde_json::from_slice(&fs::read(path)?)?;
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid usage report");
+
+        let mut routes = HashMap::new();
+        for entry in value["routes"].as_array().ok_or_else(invalid)? {
+            let usage = RouteUsage {
+                route: entry["route"].as_str().ok_or_else(invalid)?.to_string(),
+                hits: entry["hits"].as_u64().ok_or_else(invalid)?,
+                errors: entry["errors"].as_u64().ok_or_else(invalid)?,
+                last_seen: UNIX_EPOCH
+                    + Duration::from_secs(entry["last_seen"].as_u64().ok_or_else(invalid)?),
+                on_warp: entry["on_warp"].as_bool().ok_or_else(invalid)?,
+                owner: match &entry["owner"] {
+                    Value::Null => None,
+                    owner => Some(RouteOwner {
+                        team: owner["team"].as_str().ok_or_else(invalid)?.to_string(),
+                        target_date: owner["target_date"].as_str().map(str::to_string),
+                    }),
+                },
+                // Reports written before statuses and content types were recorded have none.
+                statuses: entry["statuses"]
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|(status, count)| Some((status.parse().ok()?, count.as_u64()?)))
+                    .collect(),
+                content_types: entry["content_types"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|content_type| content_type.as_str().map(str::to_string))
+                    .collect(),
+            };
+            routes.insert(usage.route.clone(), usage);
+        }
+
+        let report = UsageReport::new();
+        *report.routes.lock().unwrap() = routes;
+        Ok(report)
+    }
+
+    /// Identifies routes with the given function instead of by method and path.
+    pub fn route_key<F>(mut self, route_key: F) -> Self
+    where
+        F: Fn(&Request) -> String + Send + Sync + 'static,
+    {
+        self.route_key = Some(Arc::new(route_key));
+        self
+    }
+
+    /// Sets the largest number of routes tracked, after which new routes are counted
+    /// together as `(other)`. Defaults to 1000.
+    pub fn max_routes(mut self, max_routes: usize) -> Self {
+        self.max_routes = max_routes;
+        self
+    }
+
+    /// Assigns the routes under a path prefix to an owner. When several prefixes match a
+    /// request, the longest one applies.
+    ///
+    /// Owners are recorded with each route as it is requested, so routes loaded from an
+    /// earlier report keep the owner they had when they were exported.
+    pub fn owner(mut self, path_prefix: impl Into<String>, owner: RouteOwner) -> Self {
+        Arc::make_mut(&mut self.owners).push((trim_prefix(&path_prefix.into()), owner));
+        self
+    }
+
+    /// Returns the usage of every route, sorted by route.
+    pub fn snapshot(&self) -> Vec<RouteUsage> {
+        let mut routes: Vec<_> = self.routes.lock().unwrap().values().cloned().collect();
+        routes.sort_by(|a, b| a.route.cmp(&b.route));
+        routes
+    }
+
+    /// Returns the report as JSON.
+    pub fn to_json(&self) -> String {
+        let routes: Vec<_> = self
+            .snapshot()
+            .iter()
+            .map(|usage| {
+                json!({
+                    "route": usage.route,
+                    "hits": usage.hits,
+                    "errors": usage.errors,
+                    "error_rate": usage.error_rate(),
+                    "last_seen": usage.last_seen_secs(),
+                    "on_warp": usage.on_warp,
+                    "owner": usage.owner.as_ref().map(|owner| json!({
+                        "team": owner.team,
+                        "target_date": owner.target_date,
+                    })),
+                    "statuses": usage
+                        .statuses
+                        .iter()
+                        .map(|(status, count)| (status.to_string(), json!(count)))
+                        .collect::<serde_json::Map<_, _>>(),
+                    "content_types": usage.content_types,
+                })
+            })
+            .collect();
+
+        json!({ "routes": routes }).to_string()
+    }
+
+    /// Returns the report as CSV, with a header row.
+    pub fn to_csv(&self) -> String {
+        let quote = |field: &str| format!("\"{}\"", field.replace('"', "\"\""));
+
+        let mut csv =
+            String::from("route,hits,errors,error_rate,last_seen,on_warp,team,target_date\n");
+        for usage in self.snapshot() {
+            let owner = usage.owner.as_ref();
+            csv.push_str(&format!(
+                "{},{},{},{:.4},{},{},{},{}\n",
+                quote(&usage.route),
+                usage.hits,
+                usage.errors,
+                usage.error_rate(),
+                usage.last_seen_secs(),
+                usage.on_warp,
+                owner.map_or(String::new(), |owner| quote(&owner.team)),
+                owner
+                    .and_then(|owner| owner.target_date.as_deref())
+                    .map_or(String::new(), quote),
+            ));
+        }
+        csv
+    }
+
+    /// Returns an OpenAPI document with a skeleton operation for each route, built from the
+    /// methods, path shapes, statuses, and content types observed, to seed the specification
+    /// of the Axum handlers that replace them.
+    ///
+    /// Routes are expected to be keyed as `METHOD /path`, as they are by default, with path
+    /// parameters written as `{name}` by a [`route_key`](UsageReport::route_key). Routes keyed
+    /// otherwise are left out. See
+    /// [`OpenApiPaths::from_usage`](crate::openapi::OpenApiPaths::from_usage) for the
+    /// operations generated.
+    ///
+    /// Available with the `openapi` feature.
+    #[cfg(any(test, feature = "openapi"))]
+    pub fn to_openapi(&self) -> String {
+        let mut doc = json!({
+            "openapi": "3.1.0",
+            "info": {
+                "title": "Legacy routes",
+                "version": "0.0.0",
+            },
+        });
+        crate::openapi::OpenApiPaths::from_usage(&self.snapshot()).merge_into(&mut doc);
+
+        doc.to_string()
+    }
+
+    /// Exports the report once.
+    ///
+    /// Files are written to a temporary file next to the target and renamed into place, so
+    /// readers never see a partially written report.
+    pub fn export(&self, export: &UsageExport) -> io::Result<()> {
+        let (path, contents) = match &export.target {
+            ExportTarget::Json(path) => (path, self.to_json()),
+            ExportTarget::Csv(path) => (path, self.to_csv()),
+            #[cfg(any(test, feature = "openapi"))]
+            ExportTarget::OpenApi(path) => (path, self.to_openapi()),
+            ExportTarget::Callback(callback) => {
+                callback(&self.snapshot());
+                return Ok(());
+            }
+        };
+
+        let mut temporary = path.clone().into_os_string();
+        temporary.push(".tmp");
+        fs::write(&temporary, contents)?;
+        fs::rename(&temporary, path)
+    }
+
+    /// Returns a future that exports the report at every interval, to be spawned as a
+    /// background task. Failed exports are retried at the next interval.
+    pub fn exporter(
+        &self,
+        interval: Duration,
+        export: UsageExport,
+    ) -> impl Future<Output = ()> + Send + 'static {
+        let report = self.clone();
+
+        async move {
+            let mut ticks = tokio::time::interval(interval);
+            ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately.
+            ticks.tick().await;
+
+            loop {
+                ticks.tick().await;
+                let _ = report.export(&export);
+            }
+        }
+    }
+
+    /// Returns the route a request is counted under, and its owner.
+    pub(crate) fn route(&self, req: &Request) -> (String, Option<RouteOwner>) {
+        let route = match &self.route_key {
+            Some(route_key) => route_key(req),
+            None => format!("{} {}", req.method(), req.uri().path()),
+        };
+        let owner = owner_of(&self.owners, req.uri().path()).cloned();
+
+        (route, owner)
+    }
+
+    /// Returns the owners assigned to path prefixes.
+    #[cfg(any(test, feature = "tracing"))]
+    pub(crate) fn owners(&self) -> Arc<Vec<(String, RouteOwner)>> {
+        Arc::clone(&self.owners)
+    }
+
+    /// Records the outcome of a request.
+    pub(crate) fn record(
+        &self,
+        (route, owner): (String, Option<RouteOwner>),
+        result: &Result<Response, Error>,
+    ) {
+        let content_type = result.as_ref().ok().and_then(|response| {
+            let content_type = response.headers().get(http::header::CONTENT_TYPE)?;
+            let media_type = content_type.to_str().ok()?.split(';').next()?.trim();
+            Some(media_type.to_ascii_lowercase())
+        });
+        let (status, on_warp) = match result {
+            Ok(response) => (
+                response.status(),
+                !response.headers().contains_key("x-warpdrive-failover")
+                    && !response.headers().contains_key("x-warpdrive-steering")
+                    && !response.headers().contains_key("x-warpdrive-circuit")
+                    && response
+                        .headers()
+                        .get("x-warpdrive-hedge")
+                        .is_none_or(|winner| winner == "warp"),
+            ),
+            Err(err) => (err.status(), true),
+        };
+
+        let mut routes = self.routes.lock().unwrap();
+        let (route, owner) = if routes.len() >= self.max_routes && !routes.contains_key(&route) {
+            (OTHER_ROUTE.to_string(), None)
+        } else {
+            (route, owner)
+        };
+
+        let usage = routes.entry(route.clone()).or_insert(RouteUsage {
+            route,
+            hits: 0,
+            errors: 0,
+            last_seen: UNIX_EPOCH,
+            on_warp,
+            owner: None,
+            statuses: BTreeMap::new(),
+            content_types: BTreeSet::new(),
+        });
+        usage.hits += 1;
+        *usage.statuses.entry(status.as_u16()).or_default() += 1;
+        if let Some(content_type) = content_type
+            && usage.content_types.len() < MAX_CONTENT_TYPES
+        {
+            usage.content_types.insert(content_type);
+        }
+        usage.errors += u64::from(status.is_server_error());
+        usage.last_seen = SystemTime::now();
+        usage.on_warp = on_warp;
+        if owner.is_some() {
+            usage.owner = owner;
+        }
+    }
+}
+
+impl fmt::Debug for UsageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UsageReport")
+            .field("routes", &self.routes.lock().unwrap().len())
+            .field("max_routes", &self.max_routes)
+            .field("owners", &self.owners)
+            .finish()
+    }
+}
+
+impl fmt::Debug for UsageExport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.target {
+            ExportTarget::Json(path) => f.debug_tuple("Json").field(path).finish(),
+            ExportTarget::Csv(path) => f.debug_tuple("Csv").field(path).finish(),
+            #[cfg(any(test, feature = "openapi"))]
+            ExportTarget::OpenApi(path) => f.debug_tuple("OpenApi").field(path).finish(),
+            ExportTarget::Callback(_) => f.debug_tuple("Callback").finish(),
+        }
+    }
+}
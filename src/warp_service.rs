@@ -4,14 +4,61 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 
-use axum::{body::Body, extract::Request, response::Response};
-use futures::Future;
-use tower::Service;
-use warp::{Reply, filters::BoxedFilter};
+use axum::{
+    body::{Body, Bytes, HttpBody},
+    extract::{Request, connect_info::IntoMakeServiceWithConnectInfo},
+    http::{self, HeaderMap, HeaderName, HeaderValue},
+    response::{IntoResponse, Response},
+    routing::IntoMakeService,
+};
+use futures::{Future, future::BoxFuture};
+use http_body_util::Limited;
+use tower::{BoxError, Layer, Service, ServiceExt, util::BoxCloneSyncService};
+use warp::{
+    Filter as _, Reply, filters::BoxedFilter, http::Request as WarpRequest,
+    hyper::Body as WarpBody, reply::Response as WarpResponse,
+};
 
-use crate::{convert_request::into_warp_request, convert_response::into_axum_response};
+use crate::{
+    access_log::AccessLog,
+    alarm::ErrorRateAlarm,
+    audit::Audit,
+    blocking::Blocking,
+    buffer::BufferSlot,
+    cache::ResponseCache,
+    capture::Capture,
+    circuit_breaker::CircuitBreaker,
+    convert_request::{into_warp_request, normalize_absolute_form, normalize_empty_path},
+    convert_response::into_axum_response,
+    deadline::Deadline,
+    denylist::Denylist,
+    error::Error,
+    failover::Failover,
+    fault::{FaultInjection, injected_error_response, truncate_response},
+    framing::check_framing,
+    gauge::InFlightGauge,
+    header_filter::HeaderFilter,
+    header_rewrite::HeaderRewrite,
+    hedge::Hedge,
+    host::HostOverride,
+    kill_switch::KillSwitch,
+    limits::RequestLimits,
+    location::LocationRewrite,
+    migration::{Comparison, Migration},
+    normalize::PathNormalization,
+    prefix::{PrefixConfig, matches_prefix, trim_prefix},
+    probe::{Probe, ProbeReport},
+    problem::ProblemDetails,
+    query::OriginalQuery,
+    rate_limit::RateLimit,
+    security_headers::SecurityHeaders,
+    shutdown::Shutdown,
+    steering::Steering,
+    usage::UsageReport,
+};
 
 /// A Tower service that wraps Warp filters to run within Axum servers.
 ///
@@ -34,13 +81,92 @@ use crate::{convert_request::into_warp_request, convert_response::into_axum_resp
 /// ```
 pub struct WarpService<T = Box<dyn warp::Reply + Send + Sync>> {
     filter: Arc<BoxedFilter<(T,)>>,
+    inner: BoxCloneSyncService<Request, Response, Error>,
+    options: Options,
+    buffer: Option<BufferSlot>,
+    layered: bool,
     _phantom: PhantomData<T>,
 }
 
+/// Settings applied at the boundary, outside of any layers.
+#[derive(Clone, Default)]
+struct Options {
+    timeout: Option<Duration>,
+    faults: Option<Arc<FaultInjection>>,
+    rate_limit: Option<Arc<RateLimit>>,
+    failover: Option<Arc<Failover>>,
+    hedge: Option<Arc<Hedge>>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    alarm: Option<Arc<ErrorRateAlarm>>,
+    steering: Option<Arc<Steering>>,
+    migration: Option<Migration>,
+    kill_switch: Option<KillSwitch>,
+    shutdown: Option<Shutdown>,
+    in_flight: Option<InFlightGauge>,
+    deadline_header: Option<HeaderName>,
+    cache: Option<Arc<ResponseCache>>,
+    denylist: Option<Arc<Denylist>>,
+    security_headers: Option<Arc<SecurityHeaders>>,
+    header_filter: Option<Arc<HeaderFilter>>,
+    header_rewrite: Option<Arc<HeaderRewrite>>,
+    host_override: Option<Arc<HostOverride>>,
+    location_rewrite: Option<Arc<LocationRewrite>>,
+    audit: Option<Audit>,
+    capture: Option<Capture>,
+    #[cfg(any(test, feature = "error-reporting"))]
+    error_reporter: Option<crate::report::ErrorReporter>,
+    access_log: Option<AccessLog>,
+    default_request_headers: Arc<Vec<(HeaderName, HeaderValue)>>,
+    map_hooks: MapHooks,
+    path_normalization: Option<PathNormalization>,
+    original_query: bool,
+    problem_details: Option<Arc<ProblemDetails>>,
+    body_limit: Option<usize>,
+    request_limits: Option<RequestLimits>,
+    blocking: bool,
+    prefix_configs: Arc<Vec<(String, PrefixConfig)>>,
+    usage: Option<UsageReport>,
+}
+
+type MapRequest = Arc<dyn Fn(WarpRequest<WarpBody>) -> WarpRequest<WarpBody> + Send + Sync>;
+type MapResponse = Arc<dyn Fn(WarpResponse) -> WarpResponse + Send + Sync>;
+
+/// Hooks run on the Warp request and response, passed from the boundary to the innermost
+/// service in the request extensions so they apply inside any layers.
+#[derive(Clone, Default)]
+struct MapHooks {
+    request: Option<MapRequest>,
+    response: Option<MapResponse>,
+    /// The HTTP version the Warp filter sees, regardless of the version of the request.
+    version: Option<http::Version>,
+    /// Whether empty request paths are passed to the Warp filter as they are.
+    preserve_empty_path: bool,
+    /// Whether absolute-form request targets are passed to the Warp filter as they are.
+    preserve_absolute_form: bool,
+    /// Whether a `Server-Timing` header with the boundary timings is added to responses.
+    server_timing: bool,
+}
+
+impl MapHooks {
+    /// Returns `true` if any hook or setting differs from the default.
+    fn is_set(&self) -> bool {
+        self.request.is_some()
+            || self.response.is_some()
+            || self.version.is_some()
+            || self.preserve_empty_path
+            || self.preserve_absolute_form
+            || self.server_timing
+    }
+}
+
 impl<T> Clone for WarpService<T> {
     fn clone(&self) -> Self {
         WarpService {
             filter: Arc::clone(&self.filter),
+            inner: self.inner.clone(),
+            options: self.options.clone(),
+            buffer: self.buffer.clone(),
+            layered: self.layered,
             _phantom: PhantomData,
         }
     }
@@ -67,11 +193,697 @@ where
     /// let service = WarpService::new(json_filter.boxed());
     /// ```
     pub fn new(filter: BoxedFilter<(T,)>) -> Self {
+        let filter = Arc::new(filter);
+
         WarpService {
-            filter: Arc::new(filter),
+            inner: BoxCloneSyncService::new(FilterService {
+                filter: Arc::clone(&filter),
+            }),
+            filter,
+            options: Options::default(),
+            buffer: None,
+            layered: false,
             _phantom: PhantomData,
         }
     }
+
+    /// Sets a timeout for each request, including request conversion and the Warp filter.
+    ///
+    /// Requests that do not complete in time are answered with `504 Gateway Timeout`, or
+    /// fail with [`Error::Timeout`] when using [`FallibleWarpService`]. The timeout does not
+    /// apply to streaming the response body.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
+    /// Limits the size of request bodies, in bytes.
+    ///
+    /// Requests whose `Content-Length` exceeds the limit are answered with `413 Payload Too
+    /// Large` without reaching the Warp filter, or fail with [`Error::PayloadTooLarge`] when
+    /// using [`FallibleWarpService`]. Streaming bodies fail once they exceed the limit, which
+    /// the Warp filter sees as a body read error.
+    pub fn with_body_limit(mut self, limit: usize) -> Self {
+        self.options.body_limit = Some(limit);
+        self
+    }
+
+    /// Records the query of each request as it reaches the service, before any rewriting,
+    /// for Warp filters that parse the raw query with [`original_query`](crate::original_query).
+    ///
+    /// If Axum recorded the original URI, as the `Router` does for nested services, its query
+    /// is used.
+    pub fn with_original_query(mut self) -> Self {
+        self.options.original_query = true;
+        self
+    }
+
+    /// Limits the number and size of request headers and the length of request URIs.
+    ///
+    /// See [`RequestLimits`] for details.
+    pub fn with_request_limits(mut self, limits: RequestLimits) -> Self {
+        self.options.request_limits = Some(limits);
+        self
+    }
+
+    /// Runs the Warp filter on Tokio's blocking thread pool, for filters that do CPU-heavy
+    /// synchronous work, such as rendering templates, that would otherwise stall the threads
+    /// serving other requests.
+    ///
+    /// Each request is converted and run through the filter with `spawn_blocking`, so it costs
+    /// a thread handoff and should only be enabled where needed, such as with
+    /// [`PrefixConfig::blocking`] for the heavy routes. The timeout still applies, but a
+    /// request that times out keeps its blocking thread until the filter finishes.
+    pub fn with_blocking(mut self) -> Self {
+        self.options.blocking = true;
+        self
+    }
+
+    /// Overrides boundary settings, such as the timeout and body limit, for requests under
+    /// a path prefix.
+    ///
+    /// See [`PrefixConfig`] for the settings that can be overridden.
+    pub fn with_prefix_config(mut self, prefix: &str, config: PrefixConfig) -> Self {
+        let prefix = trim_prefix(prefix);
+        let prefix_configs = Arc::make_mut(&mut self.options.prefix_configs);
+        prefix_configs.retain(|(existing, _)| *existing != prefix);
+        prefix_configs.push((prefix, config));
+        self
+    }
+
+    /// Forwards the remaining budget of a [`Deadline`] request extension to the Warp filter
+    /// as a header, in whole milliseconds.
+    ///
+    /// Deadlines are honored whether or not they are forwarded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid header name.
+    pub fn with_deadline_header(mut self, name: &str) -> Self {
+        let name = HeaderName::try_from(name).expect("invalid deadline header name");
+        self.options.deadline_header = Some(name);
+        self
+    }
+
+    /// Enables fault injection, such as added latency, error responses, and truncated bodies.
+    ///
+    /// See [`FaultInjection`] for details. This should only be enabled in test and staging
+    /// environments.
+    pub fn with_fault_injection(mut self, faults: FaultInjection) -> Self {
+        self.options.faults = Some(Arc::new(faults));
+        self
+    }
+
+    /// Limits the rate of requests per key, such as per client IP or API key.
+    ///
+    /// Requests over the limit are rejected with `429 Too Many Requests` before reaching the
+    /// Warp filter. See [`RateLimit`] for details.
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.options.rate_limit = Some(Arc::new(rate_limit));
+        self
+    }
+
+    /// Normalizes request paths, such as by collapsing repeated slashes, before they reach
+    /// the Warp filter.
+    ///
+    /// See [`PathNormalization`] for the available options.
+    pub fn with_path_normalization(mut self, normalization: PathNormalization) -> Self {
+        self.options.path_normalization = Some(normalization);
+        self
+    }
+
+    /// Answers requests for denied paths, such as routes already migrated to Axum, without
+    /// calling the Warp filter.
+    ///
+    /// See [`Denylist`] for details.
+    pub fn with_denylist(mut self, denylist: Denylist) -> Self {
+        self.options.denylist = Some(Arc::new(denylist));
+        self
+    }
+
+    /// Answers requests that would reach the Warp filter with `503 Service Unavailable`
+    /// while a switch is on.
+    ///
+    /// See [`KillSwitch`] for details.
+    pub fn with_kill_switch(mut self, kill_switch: KillSwitch) -> Self {
+        self.options.kill_switch = Some(kill_switch);
+        self
+    }
+
+    /// Filters request headers before they reach the Warp filter, and response headers
+    /// before they are returned to Axum.
+    ///
+    /// See [`HeaderFilter`] for details.
+    pub fn with_header_filter(mut self, header_filter: HeaderFilter) -> Self {
+        self.options.header_filter = Some(Arc::new(header_filter));
+        self
+    }
+
+    /// Adds a header to requests that do not have it, before they reach the Warp filter.
+    ///
+    /// Legacy filters sometimes require headers that an internal proxy used to add, such as
+    /// a client ID. Defaults are added after any [`HeaderFilter`] is applied, so a header
+    /// stripped from untrusted requests can be replaced with a default. Can be called more
+    /// than once to add several headers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` or `value` is not a valid header name or value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use warpdrive::WarpService;
+    /// use warp::Filter;
+    ///
+    /// let filter = warp::header::<String>("x-client-id").map(|client: String| client).boxed();
+    ///
+    /// let service = WarpService::new(filter).with_default_request_header("x-client-id", "edge");
+    /// ```
+    pub fn with_default_request_header(mut self, name: &str, value: &str) -> Self {
+        let name = HeaderName::try_from(name).expect("invalid header name");
+        let value = HeaderValue::try_from(value).expect("invalid header value");
+
+        let defaults = Arc::make_mut(&mut self.options.default_request_headers);
+        defaults.retain(|(existing, _)| *existing != name);
+        defaults.push((name, value));
+        self
+    }
+
+    /// Overrides or normalizes the `Host` header and URI authority of requests before they
+    /// reach the Warp filter.
+    ///
+    /// See [`HostOverride`] for details.
+    pub fn with_host_override(mut self, host_override: HostOverride) -> Self {
+        self.options.host_override = Some(Arc::new(host_override));
+        self
+    }
+
+    /// Writes an access log line for each request, in the Common or Combined Log Format.
+    ///
+    /// See [`AccessLog`] for details.
+    pub fn with_access_log(mut self, access_log: AccessLog) -> Self {
+        self.options.access_log = Some(access_log);
+        self
+    }
+
+    /// Calls a hook with the details of each request served by the Warp filter, for
+    /// compliance logging.
+    ///
+    /// See [`Audit`] for details.
+    pub fn with_audit(mut self, audit: Audit) -> Self {
+        self.options.audit = Some(audit);
+        self
+    }
+
+    /// Calls a hook when the boundary fails a request, with the request metadata and error
+    /// chain, for reporting to services such as Sentry.
+    ///
+    /// See [`ErrorReporter`](crate::report::ErrorReporter) for details.
+    #[cfg(any(test, feature = "error-reporting"))]
+    pub fn with_error_reporter(mut self, reporter: crate::report::ErrorReporter) -> Self {
+        self.options.error_reporter = Some(reporter);
+        self
+    }
+
+    /// Keeps a sample of recent requests and responses under a path prefix, for debugging.
+    ///
+    /// See [`Capture`] for details.
+    pub fn with_capture(mut self, capture: Capture) -> Self {
+        self.options.capture = Some(capture);
+        self
+    }
+
+    /// Sets, removes, or renames headers on responses from the Warp filter.
+    ///
+    /// See [`HeaderRewrite`] for details.
+    pub fn with_header_rewrite(mut self, rewrite: HeaderRewrite) -> Self {
+        self.options.header_rewrite = Some(Arc::new(rewrite));
+        self
+    }
+
+    /// Rewrites the `Location` header of responses from the Warp filter, such as redirects
+    /// built with the URLs of the legacy deployment.
+    ///
+    /// See [`LocationRewrite`] for details.
+    pub fn with_location_rewrite(mut self, rewrite: LocationRewrite) -> Self {
+        self.options.location_rewrite = Some(Arc::new(rewrite));
+        self
+    }
+
+    /// Runs a function on each converted Warp request, before it reaches the Warp filter.
+    ///
+    /// This is an escape hatch for small per-service adjustments, such as adding a header
+    /// that legacy code expects.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use warpdrive::WarpService;
+    /// use warp::Filter;
+    ///
+    /// let filter = warp::header::<String>("x-tenant").map(|tenant| tenant).boxed();
+    ///
+    /// let service = WarpService::new(filter).with_map_request(|mut req| {
+    ///     req.headers_mut()
+    ///         .entry("x-tenant")
+    ///         .or_insert(warp::http::HeaderValue::from_static("default"));
+    ///     req
+    /// });
+    /// ```
+    pub fn with_map_request<F>(mut self, map: F) -> Self
+    where
+        F: Fn(WarpRequest<WarpBody>) -> WarpRequest<WarpBody> + Send + Sync + 'static,
+    {
+        self.options.map_hooks.request = Some(Arc::new(map));
+        self
+    }
+
+    /// Runs a function on each Warp response from the Warp filter, before it is converted
+    /// into an Axum response.
+    pub fn with_map_response<F>(mut self, map: F) -> Self
+    where
+        F: Fn(WarpResponse) -> WarpResponse + Send + Sync + 'static,
+    {
+        self.options.map_hooks.response = Some(Arc::new(map));
+        self
+    }
+
+    /// Presents every request to the Warp filter with the given HTTP version, such as
+    /// `HTTP/1.1` for legacy handlers that branch on the version and misbehave with the
+    /// `HTTP/2` requests of the Axum server.
+    ///
+    /// The version is set when the request is converted, inside any layers, and the response
+    /// is converted back with the version of the original request.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use axum::http::Version;
+    /// use warpdrive::WarpService;
+    /// use warp::Filter;
+    ///
+    /// let filter = warp::path("users").map(|| "Users").boxed();
+    ///
+    /// let service = WarpService::new(filter).force_version(Version::HTTP_11);
+    /// ```
+    pub fn force_version(mut self, version: http::Version) -> Self {
+        self.options.map_hooks.version = Some(version);
+        self
+    }
+
+    /// Passes empty request paths to the Warp filter as they are.
+    ///
+    /// By default, a request whose path is empty, such as one in authority-form or one left
+    /// with nothing by a layer that strips the mount prefix, is presented to the Warp filter
+    /// with the path `/`, so routes at the root of the mount match `warp::path::end()` and
+    /// see `/` from `warp::path::full()`. `CONNECT` requests are never rewritten.
+    pub fn preserve_empty_path(mut self) -> Self {
+        self.options.map_hooks.preserve_empty_path = true;
+        self
+    }
+
+    /// Passes request targets in absolute-form to the Warp filter as they are.
+    ///
+    /// By default, an HTTP/1 request whose target is in absolute-form, such as
+    /// `GET http://example.com/users` sent by a forward proxy, is presented to the Warp filter
+    /// in origin-form, as `/users`, with the authority in the `Host` header, replacing any
+    /// `Host` the request had. Filters then see the same request as if it had been sent
+    /// directly. `CONNECT` requests, and HTTP/2 requests, are never rewritten.
+    pub fn preserve_absolute_form(mut self) -> Self {
+        self.options.map_hooks.preserve_absolute_form = true;
+        self
+    }
+
+    /// Adds a `Server-Timing` header to responses from the Warp filter, with the time spent
+    /// converting the request and response and the time spent in the filter, so browser
+    /// developer tools and other frontend tooling can see what the legacy path and the bridge
+    /// contribute to each request.
+    ///
+    /// The header is appended, so any `Server-Timing` set by the filter is kept, and reads
+    /// `warpdrive-convert;dur=0.052, warp-filter;dur=12.408`, in milliseconds. The filter
+    /// duration ends when the filter returns its response, so streaming the body afterwards is
+    /// not included. Responses that do not come from the filter, such as timeouts and rate
+    /// limited requests, have no timings.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use warpdrive::WarpService;
+    /// use warp::Filter;
+    ///
+    /// let filter = warp::path("users").map(|| "Users").boxed();
+    ///
+    /// let service = WarpService::new(filter).with_server_timing();
+    /// ```
+    pub fn with_server_timing(mut self) -> Self {
+        self.options.map_hooks.server_timing = true;
+        self
+    }
+
+    /// Adds security headers, such as `Strict-Transport-Security`, to every response that
+    /// does not already set them.
+    ///
+    /// See [`SecurityHeaders`] for the defaults.
+    pub fn with_security_headers(mut self, security_headers: SecurityHeaders) -> Self {
+        self.options.security_headers = Some(Arc::new(security_headers));
+        self
+    }
+
+    /// Caches `GET` responses from the Warp filter in memory.
+    ///
+    /// Cached responses are served without calling the Warp filter. See [`ResponseCache`]
+    /// for which responses are cached.
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.options.cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Replays requests that fail in the Warp filter against an Axum service.
+    ///
+    /// Requests answered with a `5xx` status, that time out, or that panic are served by the
+    /// Axum service instead. See [`Failover`] for details.
+    pub fn with_failover(mut self, failover: Failover) -> Self {
+        self.options.failover = Some(Arc::new(failover));
+        self
+    }
+
+    /// Stops sending requests for a route to the Warp filter while it is failing.
+    ///
+    /// See [`CircuitBreaker`] for details. The circuit breaker applies inside
+    /// [failover](Self::with_failover) and outside [hedging](Self::with_hedge).
+    pub fn with_circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        self.options.circuit_breaker = Some(Arc::new(breaker));
+        self
+    }
+
+    /// Raises an alarm when the error rate of a route served by the Warp filter crosses a
+    /// threshold.
+    ///
+    /// See [`ErrorRateAlarm`] for details.
+    pub fn with_error_rate_alarm(mut self, alarm: ErrorRateAlarm) -> Self {
+        self.options.alarm = Some(Arc::new(alarm));
+        self
+    }
+
+    /// Races the Warp filter against an Axum service, serving the first successful response.
+    ///
+    /// See [`Hedge`] for details. Hedging applies inside [failover](Self::with_failover), so
+    /// a request fails over only if neither implementation succeeds.
+    pub fn with_hedge(mut self, hedge: Hedge) -> Self {
+        self.options.hedge = Some(Arc::new(hedge));
+        self
+    }
+
+    /// Steers requests between the Warp filter and an Axum service, as decided per request
+    /// by a [`FlagProvider`](crate::FlagProvider).
+    ///
+    /// See [`Steering`] for details. Requests steered to Axum skip the remaining boundary
+    /// settings, such as the cache, timeout, and failover.
+    pub fn with_steering(mut self, steering: Steering) -> Self {
+        self.options.steering = Some(Arc::new(steering));
+        self
+    }
+
+    /// Sends synthetic requests through the service, including the conversion boundary and
+    /// every configured setting, and checks their responses.
+    ///
+    /// Probes are sent one at a time, in order. Run this at startup to fail fast if the
+    /// legacy routes do not respond as expected, such as after upgrading warp. See [`Probe`]
+    /// for details.
+    pub async fn probe(&self, probes: impl IntoIterator<Item = Probe>) -> ProbeReport {
+        let mut results = Vec::new();
+        for probe in probes {
+            results.push(probe.run(self.clone()).await);
+        }
+
+        ProbeReport { results }
+    }
+
+    /// Sends requests straight to the Warp filter at startup, so that the first real
+    /// requests do not pay for initialization that large filter trees do on first use.
+    ///
+    /// Requests are sent one at a time. Each is converted and served by the filter, with the
+    /// [map hooks](Self::with_map_request), and its response body is read in full and
+    /// discarded. Warmup requests skip the boundary settings and layers, so they are not rate
+    /// limited, cached, logged, or counted in usage reports. Failed requests are ignored.
+    /// Returns how long the warmup took.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use axum::{body::Body, extract::Request};
+    /// use warpdrive::WarpService;
+    /// use warp::Filter;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let filter = warp::path("health").map(|| "ok").boxed();
+    /// let service = WarpService::new(filter);
+    ///
+    /// let warmup = Request::get("/health").body(Body::empty()).unwrap();
+    /// let elapsed = service.warmup([warmup]).await;
+    /// println!("warmed up in {:?}", elapsed);
+    /// # }
+    /// ```
+    pub async fn warmup(&self, requests: impl IntoIterator<Item = Request>) -> Duration {
+        let start = Instant::now();
+        for req in requests {
+            if let Ok(response) =
+                process_request_with_filter(req, &self.filter, &self.options.map_hooks).await
+            {
+                let _ = axum::body::to_bytes(response.into_body(), usize::MAX).await;
+            }
+        }
+        start.elapsed()
+    }
+
+    /// Moves routes from the Warp filter to an Axum service in phases, with shadowing,
+    /// canaries, and full cutover.
+    ///
+    /// See [`Migration`] for details. Requests served by Axum skip the remaining boundary
+    /// settings, such as the cache, timeout, and failover.
+    pub fn with_migration(mut self, migration: Migration) -> Self {
+        self.options.migration = Some(migration);
+        self
+    }
+
+    /// Renders errors generated at the boundary, such as timeouts and conversion failures, as
+    /// RFC 9457 `application/problem+json` documents instead of plain text.
+    ///
+    /// See [`ProblemDetails`] for the kinds of error and their `type` URIs.
+    pub fn with_problem_details(mut self, problem_details: ProblemDetails) -> Self {
+        self.options.problem_details = Some(Arc::new(problem_details));
+        self
+    }
+
+    /// Records per-route usage in a report, for tracking migration progress.
+    ///
+    /// See [`UsageReport`] for details.
+    pub fn with_usage_report(mut self, report: UsageReport) -> Self {
+        self.options.usage = Some(report);
+        self
+    }
+
+    /// Shuts this service down gracefully with the given handle.
+    ///
+    /// Once shutdown is triggered, new requests are answered with `503 Service
+    /// Unavailable`, and in-flight requests are tracked until their response bodies finish.
+    /// See [`Shutdown`] for details.
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.options.shutdown = Some(shutdown);
+        self
+    }
+
+    /// Counts the requests in flight in this service with the given gauge.
+    ///
+    /// See [`InFlightGauge`] for details.
+    pub fn with_in_flight_gauge(mut self, gauge: InFlightGauge) -> Self {
+        self.options.in_flight = Some(gauge);
+        self
+    }
+
+    /// Queues requests in a bounded buffer, and limits how many are processed at once, so
+    /// that `poll_ready` reports genuine readiness.
+    ///
+    /// Without a buffer, the service is always ready and accepts every request immediately.
+    /// With one, at most `max_concurrency` requests run through the boundary at once, up to
+    /// `bound` more wait in the queue, and `poll_ready` returns `Pending` once the queue is
+    /// full, so Tower middleware such as `LoadShedLayer` and servers that respect readiness
+    /// apply backpressure. The buffer is shared by every clone of the service, and is spawned
+    /// on the Tokio runtime when the service is first polled, so it runs with every setting
+    /// applied to the service.
+    ///
+    /// As with any buffered Tower service, `poll_ready` must return `Ready` before each call,
+    /// as it does when the service is called through `oneshot` or mounted in an Axum router.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bound` or `max_concurrency` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use warpdrive::WarpService;
+    /// use warp::Filter;
+    ///
+    /// let filter = warp::path("reports").map(|| "Reports").boxed();
+    ///
+    /// // Run at most 16 requests at once, with 64 more waiting.
+    /// let service = WarpService::new(filter).with_buffer(64, 16);
+    /// ```
+    pub fn with_buffer(mut self, bound: usize, max_concurrency: usize) -> Self {
+        self.buffer = Some(BufferSlot::new(bound, max_concurrency));
+        self
+    }
+
+    /// Converts this service into a `MakeService`, so it can be served directly with
+    /// `axum::serve` or hyper 1.x without an Axum `Router`.
+    ///
+    /// See also [`serve`](crate::serve).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use warpdrive::WarpService;
+    /// use warp::Filter;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let filter = warp::path("hello").map(|| "Hello from Warp!").boxed();
+    ///
+    /// let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
+    /// axum::serve(listener, WarpService::new(filter).into_make_service())
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn into_make_service(self) -> IntoMakeService<Self> {
+        axum::ServiceExt::<Request>::into_make_service(self)
+    }
+
+    /// Converts this service into a `MakeService` that inserts per-connection information,
+    /// such as the client address, into each request as a `ConnectInfo<C>` extension.
+    ///
+    /// This matches `Router::into_make_service_with_connect_info` for when this service is
+    /// the top-level service. The connection information can be read in Warp filters with
+    /// [`axum_extract`](crate::axum_extract), and is used by [`RateLimit::per_ip`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use std::net::SocketAddr;
+    ///
+    /// use axum::extract::ConnectInfo;
+    /// use warpdrive::{WarpService, axum_extract};
+    /// use warp::Filter;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let filter = warp::path("ip")
+    ///     .and(axum_extract::<ConnectInfo<SocketAddr>>())
+    ///     .map(|ConnectInfo(addr): ConnectInfo<SocketAddr>| addr.ip().to_string())
+    ///     .boxed();
+    ///
+    /// let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
+    /// let service = WarpService::new(filter).into_make_service_with_connect_info::<SocketAddr>();
+    /// axum::serve(listener, service).await.unwrap();
+    /// # }
+    /// ```
+    pub fn into_make_service_with_connect_info<C>(self) -> IntoMakeServiceWithConnectInfo<Self, C> {
+        axum::ServiceExt::<Request>::into_make_service_with_connect_info(self)
+    }
+
+    /// Converts this service into a [`FallibleWarpService`], which returns boundary errors
+    /// as the service error instead of converting them into responses.
+    pub fn into_fallible(self) -> FallibleWarpService<T> {
+        FallibleWarpService { inner: self }
+    }
+
+    /// Applies a Tower layer around the Warp filter, inside the conversion boundary.
+    ///
+    /// Layers applied this way only affect requests handled by this service, so
+    /// middleware such as `RequestBodyLimitLayer` can be applied to legacy routes without
+    /// affecting the rest of the Axum router. Layers are applied outside of any previously
+    /// applied layers.
+    ///
+    /// Errors returned by the layered service are reported as [`Error::Layer`], which is
+    /// converted into a `500 Internal Server Error` response.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tower_http::limit::RequestBodyLimitLayer;
+    /// use warpdrive::WarpService;
+    /// use warp::Filter;
+    ///
+    /// let filter = warp::path("api").map(|| "Hello").boxed();
+    ///
+    /// let service = WarpService::new(filter).layer(RequestBodyLimitLayer::new(1024));
+    /// ```
+    pub fn layer<L, ResBody>(mut self, layer: L) -> Self
+    where
+        L: Layer<AnyBody<BoxCloneSyncService<Request, Response, Error>>>,
+        L::Service:
+            Service<Request, Response = http::Response<ResBody>> + Clone + Send + Sync + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+        <L::Service as Service<Request>>::Error: Into<BoxError>,
+        ResBody: HttpBody<Data = Bytes> + Send + 'static,
+        ResBody::Error: Into<BoxError>,
+    {
+        let layered = layer.layer(AnyBody { inner: self.inner });
+
+        self.inner = BoxCloneSyncService::new(HandleLayerError { inner: layered });
+        self.layered = true;
+        self
+    }
+
+    /// Transforms the filter's reply before it is converted into a response.
+    ///
+    /// This adjusts legacy replies, such as adding headers or changing the envelope of a
+    /// JSON body, without editing the filter. The function receives the typed reply, so it
+    /// can inspect it before wrapping it with Warp's reply combinators. Boundary settings are
+    /// kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a layer was already applied with [`layer`](Self::layer), since layers wrap
+    /// the original filter. Call `map_reply` first.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use warpdrive::WarpService;
+    /// use warp::Filter;
+    ///
+    /// let filter = warp::path("users").map(|| warp::reply::json(&["alice", "bob"])).boxed();
+    ///
+    /// let service = WarpService::new(filter)
+    ///     .with_timeout(Duration::from_secs(5))
+    ///     .map_reply(|reply| warp::reply::with_header(reply, "x-api-version", "1"));
+    /// ```
+    pub fn map_reply<F, R>(self, map: F) -> WarpService<R>
+    where
+        F: Fn(T) -> R + Clone + Send + Sync + 'static,
+        R: warp::Reply + Send + Sync + 'static,
+    {
+        assert!(
+            !self.layered,
+            "map_reply must be called before layers are applied"
+        );
+
+        let filter = BoxedFilter::clone(&self.filter).map(map).boxed();
+        WarpService {
+            options: self.options,
+            buffer: self.buffer.map(|buffer| {
+                let (bound, max_concurrency) = buffer.config();
+                BufferSlot::new(bound, max_concurrency)
+            }),
+            ..WarpService::new(filter)
+        }
+    }
 }
 
 impl<T> Service<Request> for WarpService<T>
@@ -82,44 +894,809 @@ where
     type Error = Infallible;
     type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
 
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.poll_buffer(cx).map(Ok)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let problem_details = self
+            .options
+            .for_path(req.uri().path())
+            .problem_details
+            .clone();
+        let future = self.dispatch(req);
+
+        Box::pin(async move {
+            Ok(future.await.unwrap_or_else(|err| match &problem_details {
+                Some(problem_details) => problem_details.render(err),
+                None => err.into_response(),
+            }))
+        })
+    }
+}
+
+impl<T> WarpService<T> {
+    /// Returns a function describing the boundary settings as JSON, for the admin API.
+    pub(crate) fn config(&self) -> impl Fn() -> serde_json::Value + Send + Sync + 'static {
+        let options = self.options.clone();
+        move || options.config()
+    }
+
+    /// Returns a function that reports whether the service is ready to serve requests, with
+    /// a JSON description of its state.
+    pub(crate) fn readiness(
+        &self,
+    ) -> impl Fn() -> (bool, serde_json::Value) + Send + Sync + 'static {
+        let options = self.options.clone();
+        move || options.readiness()
+    }
+
+    /// Waits for a slot in the buffer, if enabled.
+    fn poll_buffer(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        match &mut self.buffer {
+            Some(buffer) => buffer.poll_ready(cx, || {
+                BoxCloneSyncService::new(Pipeline {
+                    inner: self.inner.clone(),
+                    options: self.options.clone(),
+                })
+            }),
+            None => Poll::Ready(()),
+        }
+    }
+
+    /// Runs a request through the boundary, through the buffer if enabled.
+    fn dispatch(&mut self, req: Request) -> BoxFuture<'static, Result<Response, Error>> {
+        match &mut self.buffer {
+            Some(buffer) => buffer.call(req),
+            None => {
+                let options = self.options.for_path(req.uri().path());
+                Box::pin(process(self.inner.clone(), options, req))
+            }
+        }
+    }
+}
+
+/// The boundary as a service, as run by a buffer.
+#[derive(Clone)]
+struct Pipeline {
+    inner: BoxCloneSyncService<Request, Response, Error>,
+    options: Options,
+}
+
+impl Service<Request> for Pipeline {
+    type Response = Response;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Response, Error>>;
+
     fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
         Poll::Ready(Ok(()))
     }
 
     fn call(&mut self, req: Request) -> Self::Future {
+        let options = self.options.for_path(req.uri().path());
+        Box::pin(process(self.inner.clone(), options, req))
+    }
+}
+
+/// Runs a request through the boundary with the settings for its path.
+fn process(
+    inner: BoxCloneSyncService<Request, Response, Error>,
+    options: Options,
+    mut req: Request,
+) -> impl Future<Output = Result<Response, Error>> + Send + 'static {
+    if options.original_query {
+        OriginalQuery::record(&mut req);
+    }
+    let counted = options.in_flight.as_ref().map(InFlightGauge::start);
+    let in_flight = options.shutdown.as_ref().map(Shutdown::start);
+    let head = req.method() == http::Method::HEAD;
+    let usage = options
+        .usage
+        .as_ref()
+        .map(|usage| (usage.clone(), usage.route(&req)));
+    let access_log = options.access_log.as_ref().map(|log| log.start(&req));
+
+    let response = async move {
+        let in_flight = match in_flight {
+            Some(Some(in_flight)) => Some(in_flight),
+            Some(None) => return Err(Error::ShuttingDown),
+            None => None,
+        };
+
+        let mut response = options.respond(inner, req).await?;
+
+        if head {
+            response = elide_body(response);
+        }
+
+        if let Some(security_headers) = &options.security_headers {
+            security_headers.apply(&mut response);
+        }
+
+        if let (Some(shutdown), Some(in_flight)) = (&options.shutdown, in_flight) {
+            response = shutdown.track(response, in_flight);
+        }
+
+        Ok(match counted {
+            Some(counted) => counted.track(response),
+            None => response,
+        })
+    };
+
+    async move {
+        let result = response.await;
+        if let Some((usage, route)) = usage {
+            usage.record(route, &result);
+        }
+        match access_log {
+            Some(access_log) => access_log.finish(result),
+            None => result,
+        }
+    }
+}
+
+impl Options {
+    /// Describes the settings as JSON.
+    fn config(&self) -> serde_json::Value {
+        let prefix_configs: serde_json::Map<_, _> = self
+            .prefix_configs
+            .iter()
+            .map(|(prefix, config)| {
+                let config = serde_json::json!({
+                    "timeout_ms": config.timeout.map(|timeout| timeout.as_millis() as u64),
+                    "body_limit": config.body_limit,
+                    "problem_details": config.problem_details.is_some(),
+                    "access_log": config.access_log,
+                    "blocking": config.blocking,
+                });
+                (prefix.clone(), config)
+            })
+            .collect();
+
+        serde_json::json!({
+            "timeout_ms": self.timeout.map(|timeout| timeout.as_millis() as u64),
+            "body_limit": self.body_limit,
+            "request_limits": self.request_limits.is_some(),
+            "blocking": self.blocking,
+            "deadline_header": self.deadline_header.as_ref().map(HeaderName::as_str),
+            "fault_injection": self.faults.is_some(),
+            "rate_limit": self.rate_limit.is_some(),
+            "failover": self.failover.is_some(),
+            "hedge": self.hedge.is_some(),
+            "circuit_breaker": self.circuit_breaker.is_some(),
+            "error_rate_alarm": self.alarm.as_ref().map(|alarm| alarm.raised_routes()),
+            "steering": self.steering.is_some(),
+            "migration": self.migration.as_ref().map(|migration| {
+                migration
+                    .phases()
+                    .into_iter()
+                    .map(|(prefix, phase)| (prefix, format!("{:?}", phase).into()))
+                    .collect::<serde_json::Map<_, _>>()
+            }),
+            "kill_switch": self.kill_switch.as_ref().map(KillSwitch::is_engaged),
+            "cache": self.cache.is_some(),
+            "denylist": self.denylist.is_some(),
+            "security_headers": self.security_headers.is_some(),
+            "header_filter": self.header_filter.is_some(),
+            "header_rewrite": self.header_rewrite.is_some(),
+            "host_override": self.host_override.is_some(),
+            "location_rewrite": self.location_rewrite.is_some(),
+            "audit": self.audit.is_some(),
+            "capture": self.capture.is_some(),
+            "access_log": self.access_log.is_some(),
+            "force_version": self
+                .map_hooks
+                .version
+                .map(|version| format!("{:?}", version)),
+            "preserve_empty_path": self.map_hooks.preserve_empty_path,
+            "preserve_absolute_form": self.map_hooks.preserve_absolute_form,
+            "server_timing": self.map_hooks.server_timing,
+            "default_request_headers": self
+                .default_request_headers
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            "path_normalization": self.path_normalization.is_some(),
+            "original_query": self.original_query,
+            "problem_details": self.problem_details.is_some(),
+            "usage_report": self.usage.is_some(),
+            "shutdown": self.shutdown.as_ref().map(|shutdown| serde_json::json!({
+                "triggered": shutdown.is_triggered(),
+                "in_flight": shutdown.in_flight(),
+            })),
+            "in_flight": self.in_flight.as_ref().map(InFlightGauge::get),
+            "prefix_configs": prefix_configs,
+        })
+    }
+
+    /// Returns whether the service is ready, with a JSON description of its state.
+    fn readiness(&self) -> (bool, serde_json::Value) {
+        let draining = self
+            .shutdown
+            .as_ref()
+            .is_some_and(|shutdown| shutdown.is_triggered());
+        let open_circuits = self
+            .circuit_breaker
+            .as_ref()
+            .map_or(Vec::new(), |breaker| breaker.open_routes());
+        let failing = !open_circuits.is_empty()
+            && self
+                .circuit_breaker
+                .as_ref()
+                .is_some_and(|breaker| !breaker.has_fallback());
+
+        let ready = !draining && !failing;
+        (
+            ready,
+            serde_json::json!({
+                "ready": ready,
+                "draining": draining,
+                "kill_switch": self.kill_switch.as_ref().is_some_and(KillSwitch::is_engaged),
+                "open_circuits": open_circuits,
+            }),
+        )
+    }
+
+    /// Returns the settings for a request path, with the longest matching prefix config
+    /// applied.
+    fn for_path(&self, path: &str) -> Options {
+        let mut options = self.clone();
+
+        let config = self
+            .prefix_configs
+            .iter()
+            .filter(|(prefix, _)| matches_prefix(prefix, path))
+            .max_by_key(|(prefix, _)| prefix.len());
+
+        if let Some((_, config)) = config {
+            options.timeout = config.timeout.or(options.timeout);
+            options.body_limit = config.body_limit.or(options.body_limit);
+            if let Some(problem_details) = &config.problem_details {
+                options.problem_details = Some(Arc::clone(problem_details));
+            }
+            if config.access_log == Some(false) {
+                options.access_log = None;
+            }
+            options.blocking = config.blocking.unwrap_or(options.blocking);
+        }
+
+        options
+    }
+
+    /// Answers a request from the denylist or cache, or runs it through the Warp filter.
+    async fn respond(
+        &self,
+        inner: BoxCloneSyncService<Request, Response, Error>,
+        mut req: Request,
+    ) -> Result<Response, Error> {
+        check_framing(req.headers())?;
+        if let Some(limits) = &self.request_limits {
+            limits.check(&req)?;
+        }
+
+        if let Some(uri) = self
+            .path_normalization
+            .and_then(|normalization| normalization.normalize(req.uri()))
+        {
+            *req.uri_mut() = uri;
+        }
+
+        if let Some(response) = self
+            .denylist
+            .as_ref()
+            .and_then(|denylist| denylist.check(req.uri().path()))
+        {
+            return Ok(response);
+        }
+
+        if let Some(limit) = self.body_limit {
+            req = limit_body(req, limit)?;
+        }
+
+        if let Some(steering) = &self.steering {
+            req = match steering.steer(req).await {
+                Ok(response) => return Ok(response),
+                Err(req) => req,
+            };
+        }
+
+        let mut comparison = None;
+        if let Some(migration) = &self.migration {
+            req = match migration.dispatch(req).await {
+                Ok(response) => return Ok(response),
+                Err((req, started)) => {
+                    comparison = started;
+                    req
+                }
+            };
+        }
+
+        if let Some(response) = self.kill_switch.as_ref().and_then(KillSwitch::check) {
+            return Ok(response);
+        }
+
+        let cache_key = match self.cache.as_ref().map(|cache| cache.lookup(&req)) {
+            Some(Ok(Some(response))) => return Ok(response),
+            Some(Err(cache_key)) => Some(cache_key),
+            Some(Ok(None)) | None => None,
+        };
+        let comparison = comparison.map(Comparison::start);
+
+        let result = match &self.failover {
+            Some(failover) => failover.run(req, |req| self.protect(inner, req)).await,
+            None => self.protect(inner, req).await,
+        };
+        let result = match comparison {
+            Some(comparison) => comparison.finish(result),
+            None => result,
+        };
+        let mut response = result?;
+
+        if let (Some(cache), Some(cache_key)) = (&self.cache, cache_key) {
+            response = cache.store(cache_key, response).await;
+        }
+
+        Ok(response)
+    }
+
+    /// Runs a request through the circuit breaker, if enabled.
+    async fn protect(
+        &self,
+        inner: BoxCloneSyncService<Request, Response, Error>,
+        req: Request,
+    ) -> Result<Response, Error> {
+        match &self.circuit_breaker {
+            Some(breaker) => breaker.run(req, |req| self.hedge(inner, req)).await,
+            None => self.hedge(inner, req).await,
+        }
+    }
+
+    /// Runs a request through the Warp filter, racing it against an Axum service if hedging
+    /// is enabled.
+    async fn hedge(
+        &self,
+        inner: BoxCloneSyncService<Request, Response, Error>,
+        req: Request,
+    ) -> Result<Response, Error> {
+        match &self.hedge {
+            Some(hedge) => hedge.run(req, |req| self.attempt(inner, req)).await,
+            None => self.attempt(inner, req).await,
+        }
+    }
+
+    fn attempt(
+        &self,
+        inner: BoxCloneSyncService<Request, Response, Error>,
+        mut req: Request,
+    ) -> impl Future<Output = Result<Response, Error>> + Send + 'static {
+        let inner = if self.blocking {
+            BoxCloneSyncService::new(Blocking::new(inner))
+        } else {
+            inner
+        };
+        let deadline = req.extensions().get::<Deadline>().map(Deadline::remaining);
+        let timeout = match (self.timeout, deadline) {
+            (Some(timeout), Some(remaining)) => Some(timeout.min(remaining)),
+            (timeout, remaining) => timeout.or(remaining),
+        };
+
+        let header_filter = self.header_filter.clone();
+        if let Some(header_filter) = &header_filter {
+            header_filter.filter_request(req.headers_mut());
+        }
+        for (name, value) in self.default_request_headers.iter() {
+            if !req.headers().contains_key(name) {
+                req.headers_mut().insert(name, value.clone());
+            }
+        }
+        if let Some(host_override) = &self.host_override {
+            host_override.apply(&mut req);
+        }
+        let header_rewrite = self
+            .header_rewrite
+            .clone()
+            .map(|rewrite| (rewrite, req.uri().path().to_string()));
+        let location_rewrite = self.location_rewrite.clone();
+        let response_headers = move |headers: &mut HeaderMap| {
+            if let Some(rewrite) = &location_rewrite {
+                rewrite.apply(headers);
+            }
+            if let Some((rewrite, path)) = &header_rewrite {
+                rewrite.apply(path, headers);
+            }
+            if let Some(header_filter) = &header_filter {
+                header_filter.filter_response(headers);
+            }
+        };
+
+        if self.map_hooks.is_set() {
+            req.extensions_mut().insert(self.map_hooks.clone());
+        }
+
+        if let (Some(name), Some(remaining)) = (&self.deadline_header, deadline) {
+            req.headers_mut().insert(
+                name.clone(),
+                HeaderValue::from(remaining.as_millis() as u64),
+            );
+        }
+
+        let rate_limited = match &self.rate_limit {
+            Some(rate_limit) => rate_limit.check(&req).err(),
+            None => None,
+        };
+        let faults = self
+            .faults
+            .as_ref()
+            .map(|faults| faults.plan(req.uri().path()));
+
+        let alarm = self
+            .alarm
+            .as_ref()
+            .map(|alarm| (Arc::clone(alarm), alarm.route(&req)));
+
+        #[cfg(any(test, feature = "error-reporting"))]
+        let reported = self
+            .error_reporter
+            .as_ref()
+            .map(|reporter| reporter.start(&req));
+
+        // Rate limited requests do not reach the Warp filter, so are not audited.
+        let (req, audited) = match &self.audit {
+            Some(audit) if rate_limited.is_none() => {
+                let (req, audited) = audit.start(req);
+                (req, Some(audited))
+            }
+            _ => (req, None),
+        };
+        let (req, captured) = match &self.capture {
+            Some(capture) if rate_limited.is_none() => capture.start(req),
+            _ => (req, None),
+        };
+
+        let response = async move {
+            if let Some(retry_after) = rate_limited {
+                return Err(Error::RateLimited(retry_after));
+            }
+
+            let Some(faults) = faults else {
+                let mut response = inner.oneshot(req).await?;
+                response_headers(response.headers_mut());
+                return Ok(response);
+            };
+
+            if let Some(latency) = faults.latency {
+                tokio::time::sleep(latency).await;
+            }
+            if let Some(status) = faults.error {
+                return Ok(injected_error_response(status));
+            }
+
+            let mut response = inner.oneshot(req).await?;
+            response_headers(response.headers_mut());
+
+            Ok(match faults.truncate_body {
+                Some(after_bytes) => truncate_response(response, after_bytes),
+                None => response,
+            })
+        };
+
+        async move {
+            #[cfg(any(test, feature = "error-reporting"))]
+            let response = async {
+                match &reported {
+                    Some(reported) => reported.catch_panic(response).await,
+                    None => response.await,
+                }
+            };
+
+            let result = match timeout {
+                // The filter may complete without yielding, so an expired deadline would
+                // otherwise not be enforced.
+                Some(Duration::ZERO) => Err(Error::Timeout(Duration::ZERO)),
+                Some(timeout) => tokio::time::timeout(timeout, response)
+                    .await
+                    .unwrap_or(Err(Error::Timeout(timeout))),
+                None => response.await,
+            };
+
+            #[cfg(any(test, feature = "error-reporting"))]
+            if let (Some(reported), Err(err)) = (&reported, &result) {
+                reported.error(err);
+            }
+
+            if let Some((alarm, route)) = alarm {
+                alarm.record(route, &result);
+            }
+
+            let result = match (captured, result) {
+                (Some(captured), Ok(response)) => Ok(captured.finish(response)),
+                (Some(captured), Err(err)) => {
+                    captured.fail(err.status());
+                    Err(err)
+                }
+                (None, result) => result,
+            };
+
+            match (audited, result) {
+                (Some(audited), Ok(response)) => Ok(audited.finish(response)),
+                (Some(audited), Err(err)) => {
+                    audited.fail(err.status());
+                    Err(err)
+                }
+                (None, result) => result,
+            }
+        }
+    }
+}
+
+/// A variant of [`WarpService`] whose service error is the typed boundary [`Error`].
+///
+/// Created with [`WarpService::into_fallible`]. Conversion failures, timeouts, and layer
+/// errors are returned as errors rather than in-band responses, so the service can be
+/// composed with `HandleErrorLayer` and other Tower error handling.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use axum::{Router, error_handling::HandleErrorLayer, http::StatusCode};
+/// use tower::ServiceBuilder;
+/// use warpdrive::WarpService;
+/// use warp::Filter;
+///
+/// let filter = warp::path("slow").map(|| "Hello").boxed();
+///
+/// let service = ServiceBuilder::new()
+///     .layer(HandleErrorLayer::new(|err: warpdrive::Error| async move {
+///         (StatusCode::SERVICE_UNAVAILABLE, err.to_string())
+///     }))
+///     .service(
+///         WarpService::new(filter)
+///             .with_timeout(Duration::from_secs(5))
+///             .into_fallible(),
+///     );
+///
+/// let app: Router = Router::new().fallback_service(service);
+/// ```
+pub struct FallibleWarpService<T = Box<dyn warp::Reply + Send + Sync>> {
+    inner: WarpService<T>,
+}
+
+impl<T> Clone for FallibleWarpService<T> {
+    fn clone(&self) -> Self {
+        FallibleWarpService {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Service<Request> for FallibleWarpService<T>
+where
+    T: warp::Reply + Send + Sync + 'static,
+{
+    type Response = Response;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_buffer(cx).map(Ok)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        self.inner.dispatch(req)
+    }
+}
+
+/// The innermost service, which converts the request and runs the Warp filter.
+struct FilterService<T> {
+    filter: Arc<BoxedFilter<(T,)>>,
+}
+
+impl<T> Clone for FilterService<T> {
+    fn clone(&self) -> Self {
+        FilterService {
+            filter: Arc::clone(&self.filter),
+        }
+    }
+}
+
+impl<T> Service<Request> for FilterService<T>
+where
+    T: warp::Reply + Send + Sync + 'static,
+{
+    type Response = Response;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut req: Request) -> Self::Future {
         let filter = Arc::clone(&self.filter);
+        let hooks = req
+            .extensions_mut()
+            .remove::<MapHooks>()
+            .unwrap_or_default();
 
         Box::pin(async move {
-            let response = match process_request_with_filter(req, &filter).await {
-                Ok(resp) => resp,
-                Err(err) => create_conversion_error_response(err),
-            };
-            Ok(response)
+            process_request_with_filter(req, &filter, &hooks)
+                .await
+                .map_err(Error::Conversion)
+        })
+    }
+}
+
+/// A service adapter that accepts requests with any body type, as used by
+/// [`WarpService::layer`].
+///
+/// Layers such as `RequestBodyLimitLayer` change the request body type before calling the
+/// inner service, so the inner service must accept any compatible body.
+#[derive(Clone)]
+pub struct AnyBody<S> {
+    inner: S,
+}
+
+impl<S, B> Service<http::Request<B>> for AnyBody<S>
+where
+    S: Service<Request>,
+    B: HttpBody<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        self.inner.call(req.map(Body::new))
+    }
+}
+
+/// Drives a layered service to completion, converting its errors into boundary errors.
+#[derive(Clone)]
+struct HandleLayerError<S> {
+    inner: S,
+}
+
+impl<S, ResBody> Service<Request> for HandleLayerError<S>
+where
+    S: Service<Request, Response = http::Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<BoxError>,
+    ResBody: HttpBody<Data = Bytes> + Send + 'static,
+    ResBody::Error: Into<BoxError>,
+{
+    type Response = Response;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let inner = self.inner.clone();
+
+        Box::pin(async move {
+            match inner.oneshot(req).await {
+                Ok(response) => Ok(response.map(Body::new)),
+                // Errors from the inner service pass through layers unchanged.
+                Err(err) => Err(match err.into().downcast::<Error>() {
+                    Ok(err) => *err,
+                    Err(err) => Error::Layer(err),
+                }),
+            }
         })
     }
 }
 
 async fn process_request_with_filter<T>(
-    req: Request,
+    mut req: Request,
     filter: &BoxedFilter<(T,)>,
+    hooks: &MapHooks,
 ) -> Result<Response, String>
 where
     T: warp::Reply + Send + Sync + 'static,
 {
-    let warp_req = into_warp_request(req).await?;
+    let version = req.version();
+    if let Some(forced) = hooks.version {
+        *req.version_mut() = forced;
+    }
+    if !hooks.preserve_absolute_form {
+        normalize_absolute_form(&mut req);
+    }
+    if !hooks.preserve_empty_path {
+        normalize_empty_path(&mut req);
+    }
+
+    let start = Instant::now();
+    let mut warp_req = into_warp_request(req).await?;
+    let mut convert = start.elapsed();
+    if let Some(map) = &hooks.request {
+        warp_req = map(warp_req);
+    }
 
     let mut service = warp::service(filter.clone());
 
-    let warp_response = match service.call(warp_req).await {
+    let start = Instant::now();
+    let mut warp_response = match service.call(warp_req).await {
         Ok(reply) => reply.into_response(),
-        Err(rejection) => rejection.into_response(),
+        Err(never) => match never {},
     };
+    let served = start.elapsed();
+    if let Some(map) = &hooks.response {
+        warp_response = map(warp_response);
+    }
+
+    let start = Instant::now();
+    let mut response = into_axum_response(warp_response)?;
+    convert += start.elapsed();
+    if hooks.version.is_some() {
+        *response.version_mut() = version;
+    }
+    if hooks.server_timing {
+        let timing = format!(
+            "warpdrive-convert;dur={:.3}, warp-filter;dur={:.3}",
+            convert.as_secs_f64() * 1000.0,
+            served.as_secs_f64() * 1000.0
+        );
+        if let Ok(value) = HeaderValue::try_from(timing) {
+            response.headers_mut().append(
+                http::header::HeaderName::from_static("server-timing"),
+                value,
+            );
+        }
+    }
+    Ok(response)
+}
+
+/// Rejects a request whose declared length exceeds the limit, and limits its body otherwise.
+fn limit_body(req: Request, limit: usize) -> Result<Request, Error> {
+    let content_length = req
+        .headers()
+        .get(http::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+
+    if content_length.is_some_and(|length| length > limit as u64) {
+        return Err(Error::PayloadTooLarge(limit));
+    }
+
+    if req
+        .body()
+        .size_hint()
+        .upper()
+        .is_some_and(|upper| upper <= limit as u64)
+    {
+        return Ok(req);
+    }
+
+    Ok(req.map(|body| Body::new(Limited::new(body, limit))))
+}
+
+/// Removes the body of a response to a `HEAD` request, keeping the `Content-Length` the
+/// body would have had so the response describes the equivalent `GET` response.
+fn elide_body(response: Response) -> Response {
+    let (mut parts, body) = response.into_parts();
+
+    if !parts.headers.contains_key(http::header::CONTENT_LENGTH)
+        && let Some(length) = body.size_hint().exact()
+    {
+        parts
+            .headers
+            .insert(http::header::CONTENT_LENGTH, HeaderValue::from(length));
+    }
 
-    into_axum_response(warp_response).await
+    Response::from_parts(parts, Body::empty())
 }
 
 // This only runs in the unlikely event of a conversion error.
-fn create_conversion_error_response(err: String) -> Response {
+pub(crate) fn create_conversion_error_response(err: String) -> Response {
     let status = axum::http::StatusCode::INTERNAL_SERVER_ERROR;
 
     Response::builder()
@@ -0,0 +1,141 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::extract::ws::{CloseFrame, Message as AxumMessage};
+use futures::{Sink, Stream};
+use warp::filters::ws::Message as WarpMessage;
+
+/// Converts a Warp WebSocket message into an Axum WebSocket message.
+///
+/// Text messages containing invalid UTF-8 are converted to binary messages.
+pub fn warp_to_axum_message(message: WarpMessage) -> AxumMessage {
+    if message.is_close() {
+        let frame = message.close_frame().map(|(code, reason)| CloseFrame {
+            code,
+            reason: reason.into(),
+        });
+        return AxumMessage::Close(frame);
+    }
+
+    if message.is_text()
+        && let Ok(text) = message.to_str()
+    {
+        return AxumMessage::Text(text.into());
+    }
+
+    let is_ping = message.is_ping();
+    let is_pong = message.is_pong();
+    let bytes = message.into_bytes().into();
+
+    if is_ping {
+        AxumMessage::Ping(bytes)
+    } else if is_pong {
+        AxumMessage::Pong(bytes)
+    } else {
+        AxumMessage::Binary(bytes)
+    }
+}
+
+/// Converts an Axum WebSocket message into a Warp WebSocket message.
+pub fn axum_to_warp_message(message: AxumMessage) -> WarpMessage {
+    match message {
+        AxumMessage::Text(text) => WarpMessage::text(text.as_str()),
+        AxumMessage::Binary(bytes) => WarpMessage::binary(bytes),
+        AxumMessage::Ping(bytes) => WarpMessage::ping(bytes),
+        AxumMessage::Pong(bytes) => WarpMessage::pong(bytes),
+        AxumMessage::Close(Some(frame)) => {
+            WarpMessage::close_with(frame.code, frame.reason.as_str().to_string())
+        }
+        AxumMessage::Close(None) => WarpMessage::close(),
+    }
+}
+
+/// Adapts an Axum WebSocket into a stream and sink of Warp messages.
+///
+/// This allows WebSocket logic written against `warp::ws::Message` to be reused unchanged
+/// in Axum WebSocket handlers. Errors are passed through from the underlying socket.
+///
+/// # Example
+///
+/// ```rust
+/// use axum::extract::ws::{WebSocket, WebSocketUpgrade};
+/// use axum::response::Response;
+/// use futures::{SinkExt, StreamExt};
+/// use warpdrive::ws::WarpMessageSocket;
+///
+/// // Existing logic written against Warp's message type.
+/// async fn echo<S, E>(mut socket: S)
+/// where
+///     S: futures::Stream<Item = Result<warp::ws::Message, E>>
+///         + futures::Sink<warp::ws::Message>
+///         + Unpin,
+/// {
+///     while let Some(Ok(message)) = socket.next().await {
+///         if socket.send(message).await.is_err() {
+///             break;
+///         }
+///     }
+/// }
+///
+/// async fn handler(ws: WebSocketUpgrade) -> Response {
+///     ws.on_upgrade(|socket: WebSocket| echo(WarpMessageSocket::new(socket)))
+/// }
+/// ```
+#[derive(Debug)]
+pub struct WarpMessageSocket<S> {
+    inner: S,
+}
+
+impl<S> WarpMessageSocket<S> {
+    /// Wraps a socket yielding and accepting Axum messages.
+    pub fn new(inner: S) -> Self {
+        WarpMessageSocket { inner }
+    }
+
+    /// Consumes the adapter, returning the wrapped socket.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, E> Stream for WarpMessageSocket<S>
+where
+    S: Stream<Item = Result<AxumMessage, E>> + Unpin,
+{
+    type Item = Result<WarpMessage, E>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.inner)
+            .poll_next(cx)
+            .map(|item| item.map(|result| result.map(axum_to_warp_message)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<S> Sink<WarpMessage> for WarpMessageSocket<S>
+where
+    S: Sink<AxumMessage> + Unpin,
+{
+    type Error = S::Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.inner).poll_ready(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: WarpMessage) -> Result<(), Self::Error> {
+        Pin::new(&mut self.inner).start_send(warp_to_axum_message(item))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
@@ -0,0 +1,370 @@
+//! Procedural macros for `warpdrive`.
+//!
+//! These are re-exported from `warpdrive` when the `macros` feature is enabled and should not
+//! be used directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DeriveInput, Error, FnArg, Ident, ItemFn, LitInt, LitStr, Pat, PatType,
+    Result, Type, parse_macro_input,
+};
+
+/// Generates a Warp filter constructor and an Axum handler from a single async function.
+///
+/// Each argument must be annotated with where it is extracted from:
+///
+/// - `#[path]`: a path segment (`warp::path::param` / `axum::extract::Path`).
+/// - `#[query]`: the deserialized query string (`warp::query` / `axum::extract::Query`).
+/// - `#[json]`: the deserialized JSON body (`warp::body::json` / `axum::Json`).
+/// - `#[header("name")]`: a header parsed with `FromStr` (`warp::header`).
+///
+/// For `async fn get_user(..)` this generates `get_user_filter()`, returning a boxed Warp
+/// filter, and `get_user_handler`, an Axum handler. The function itself is kept unchanged.
+/// The return type must implement both `warp::Reply` and `IntoResponse`, such as
+/// `warpdrive::DualReply`.
+#[proc_macro_attribute]
+pub fn dual_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return Error::new(
+            Span::call_site(),
+            "`dual_handler` does not take any arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let item = parse_macro_input!(item as ItemFn);
+    match expand(item) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+enum Source {
+    Path,
+    Query,
+    Json,
+    Header(LitStr),
+}
+
+struct Arg {
+    ident: Ident,
+    ty: Type,
+    source: Source,
+}
+
+fn expand(mut item: ItemFn) -> Result<TokenStream2> {
+    if item.sig.asyncness.is_none() {
+        return Err(Error::new_spanned(
+            item.sig.fn_token,
+            "`dual_handler` functions must be async",
+        ));
+    }
+
+    let mut args = Vec::new();
+    for input in item.sig.inputs.iter_mut() {
+        match input {
+            FnArg::Receiver(receiver) => {
+                return Err(Error::new_spanned(
+                    receiver,
+                    "`dual_handler` cannot be used on methods",
+                ));
+            }
+            FnArg::Typed(pat_type) => args.push(parse_arg(pat_type)?),
+        }
+    }
+
+    if args
+        .iter()
+        .filter(|arg| matches!(arg.source, Source::Json))
+        .count()
+        > 1
+    {
+        return Err(Error::new_spanned(
+            &item.sig.inputs,
+            "`dual_handler` supports at most one `#[json]` argument",
+        ));
+    }
+
+    let private = quote!(::warpdrive::__private);
+    let name = &item.sig.ident;
+    let vis = &item.vis;
+    let output = match &item.sig.output {
+        syn::ReturnType::Default => quote!(()),
+        syn::ReturnType::Type(_, ty) => quote!(#ty),
+    };
+    let filter_name = format_ident!("{}_filter", name);
+    let handler_name = format_ident!("{}_handler", name);
+    let idents: Vec<_> = args.iter().map(|arg| &arg.ident).collect();
+    let types: Vec<_> = args.iter().map(|arg| &arg.ty).collect();
+
+    let filters = args.iter().map(|arg| {
+        let ty = &arg.ty;
+        match &arg.source {
+            Source::Path => quote!(.and(#private::warp::path::param::<#ty>())),
+            Source::Query => quote!(.and(#private::warp::query::<#ty>())),
+            Source::Json => quote!(.and(#private::warp::body::json::<#ty>())),
+            Source::Header(name) => quote!(.and(#private::warp::header::<#ty>(#name))),
+        }
+    });
+
+    let path_args: Vec<_> = args
+        .iter()
+        .filter(|arg| matches!(arg.source, Source::Path))
+        .collect();
+    let path_extractor = match path_args.as_slice() {
+        [] => quote!(),
+        [arg] => {
+            let (ident, ty) = (&arg.ident, &arg.ty);
+            quote!(#private::axum::extract::Path(#ident): #private::axum::extract::Path<#ty>,)
+        }
+        many => {
+            let idents = many.iter().map(|arg| &arg.ident);
+            let types = many.iter().map(|arg| &arg.ty);
+            quote! {
+                #private::axum::extract::Path((#(#idents,)*)):
+                    #private::axum::extract::Path<(#(#types,)*)>,
+            }
+        }
+    };
+    let query_extractors = args
+        .iter()
+        .filter(|arg| matches!(arg.source, Source::Query))
+        .map(|arg| {
+            let (ident, ty) = (&arg.ident, &arg.ty);
+            quote!(#private::axum::extract::Query(#ident): #private::axum::extract::Query<#ty>,)
+        });
+    let header_args: Vec<_> = args
+        .iter()
+        .filter_map(|arg| match &arg.source {
+            Source::Header(name) => Some((&arg.ident, &arg.ty, name)),
+            _ => None,
+        })
+        .collect();
+    let headers_extractor = if header_args.is_empty() {
+        quote!()
+    } else {
+        quote!(__headers: #private::axum::http::HeaderMap,)
+    };
+    let header_parsing = header_args.iter().map(|(ident, ty, name)| {
+        quote! {
+            let #ident: #ty = match __headers
+                .get(#name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok())
+            {
+                Some(value) => value,
+                None => {
+                    return #private::axum::response::IntoResponse::into_response((
+                        #private::axum::http::StatusCode::BAD_REQUEST,
+                        ::std::format!("Missing or invalid request header \"{}\"", #name),
+                    ));
+                }
+            };
+        }
+    });
+    let json_extractor = args
+        .iter()
+        .filter(|arg| matches!(arg.source, Source::Json))
+        .map(|arg| {
+            let (ident, ty) = (&arg.ident, &arg.ty);
+            quote!(#private::axum::Json(#ident): #private::axum::Json<#ty>,)
+        });
+
+    Ok(quote! {
+        #item
+
+        #[doc = concat!("Warp filter generated by `dual_handler` for [`", stringify!(#name), "`].")]
+        #vis fn #filter_name() -> #private::warp::filters::BoxedFilter<(#output,)> {
+            use #private::warp::Filter as _;
+
+            #private::warp::any()
+                #(#filters)*
+                .and_then(|#(#idents: #types),*| async move {
+                    Ok::<_, ::std::convert::Infallible>(#name(#(#idents),*).await)
+                })
+                .boxed()
+        }
+
+        #[doc = concat!("Axum handler generated by `dual_handler` for [`", stringify!(#name), "`].")]
+        #vis async fn #handler_name(
+            #path_extractor
+            #(#query_extractors)*
+            #headers_extractor
+            #(#json_extractor)*
+        ) -> #private::axum::response::Response {
+            #(#header_parsing)*
+
+            #private::axum::response::IntoResponse::into_response(#name(#(#idents),*).await)
+        }
+    })
+}
+
+fn parse_arg(pat_type: &mut PatType) -> Result<Arg> {
+    let ident = match &*pat_type.pat {
+        Pat::Ident(pat_ident) => pat_ident.ident.clone(),
+        other => {
+            return Err(Error::new_spanned(
+                other,
+                "`dual_handler` arguments must be simple identifiers",
+            ));
+        }
+    };
+
+    let mut source = None;
+    let mut remaining = Vec::new();
+    for attr in pat_type.attrs.drain(..) {
+        match parse_source(&attr)? {
+            Some(parsed) if source.is_none() => source = Some(parsed),
+            Some(_) => {
+                return Err(Error::new_spanned(
+                    attr,
+                    "argument already has an extraction attribute",
+                ));
+            }
+            None => remaining.push(attr),
+        }
+    }
+    pat_type.attrs = remaining;
+
+    let source = source.ok_or_else(|| {
+        Error::new_spanned(
+            &pat_type.pat,
+            "argument must be annotated with `#[path]`, `#[query]`, `#[json]` or `#[header(\"name\")]`",
+        )
+    })?;
+
+    Ok(Arg {
+        ident,
+        ty: (*pat_type.ty).clone(),
+        source,
+    })
+}
+
+fn parse_source(attr: &Attribute) -> Result<Option<Source>> {
+    let path = attr.path();
+    if path.is_ident("path") {
+        attr.meta.require_path_only()?;
+        Ok(Some(Source::Path))
+    } else if path.is_ident("query") {
+        attr.meta.require_path_only()?;
+        Ok(Some(Source::Query))
+    } else if path.is_ident("json") {
+        attr.meta.require_path_only()?;
+        Ok(Some(Source::Json))
+    } else if path.is_ident("header") {
+        Ok(Some(Source::Header(attr.parse_args()?)))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Derives `warpdrive::RejectionMapper` for an error enum.
+///
+/// Each variant is mapped with a `#[rejection(status = 404)]` attribute, which can also be put
+/// on the enum as a default for variants without one. The message defaults to the variant's
+/// `Display` output, such as one generated by `thiserror`'s `#[error(...)]`, and can be
+/// replaced with `#[rejection(message = "...")]`, for example to avoid exposing internal
+/// details.
+#[proc_macro_derive(RejectionMapper, attributes(rejection))]
+pub fn derive_rejection_mapper(item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as DeriveInput);
+    match expand_rejection_mapper(item) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[derive(Default)]
+struct Mapping {
+    status: Option<u16>,
+    message: Option<LitStr>,
+}
+
+fn expand_rejection_mapper(item: DeriveInput) -> Result<TokenStream2> {
+    let Data::Enum(data) = &item.data else {
+        return Err(Error::new_spanned(
+            &item.ident,
+            "`RejectionMapper` can only be derived for enums",
+        ));
+    };
+
+    let default = parse_mapping(&item.attrs)?;
+
+    let private = quote!(::warpdrive::__private);
+    let mut status_arms = Vec::new();
+    let mut message_arms = Vec::new();
+    for variant in &data.variants {
+        let mapping = parse_mapping(&variant.attrs)?;
+        let ident = &variant.ident;
+
+        let status = mapping.status.or(default.status).ok_or_else(|| {
+            Error::new_spanned(
+                ident,
+                "variant must have a `#[rejection(status = ...)]` attribute, or the enum a default",
+            )
+        })?;
+        status_arms.push(quote! {
+            Self::#ident { .. } => #private::axum::http::StatusCode::from_u16(#status)
+                .expect("status code validated by `RejectionMapper`"),
+        });
+
+        let message = match mapping.message.or_else(|| default.message.clone()) {
+            Some(message) => quote!(::std::string::ToString::to_string(#message)),
+            None => quote!(::std::string::ToString::to_string(self)),
+        };
+        message_arms.push(quote!(Self::#ident { .. } => #message,));
+    }
+
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::warpdrive::RejectionMapper for #name #ty_generics #where_clause {
+            fn status(&self) -> #private::axum::http::StatusCode {
+                match *self {
+                    #(#status_arms)*
+                }
+            }
+
+            fn message(&self) -> ::std::string::String {
+                match *self {
+                    #(#message_arms)*
+                }
+            }
+        }
+    })
+}
+
+fn parse_mapping(attrs: &[Attribute]) -> Result<Mapping> {
+    let mut mapping = Mapping::default();
+
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("rejection"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("status") {
+                let lit: LitInt = meta.value()?.parse()?;
+                let status: u16 = lit.base10_parse()?;
+                if !(100..=999).contains(&status) {
+                    return Err(Error::new_spanned(
+                        lit,
+                        "status must be between 100 and 999",
+                    ));
+                }
+                mapping.status = Some(status);
+                Ok(())
+            } else if meta.path.is_ident("message") {
+                mapping.message = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `status` or `message`"))
+            }
+        })?;
+    }
+
+    Ok(mapping)
+}